@@ -4,6 +4,8 @@ use std::process::{Command, Stdio};
 use serde_json::Value;
 use thiserror::Error;
 
+use crate::adapters::process;
+
 const GITHUB_ACTIONS_JOBS_FILTER: &str = r#"
 map(
   if type != "object" then
@@ -371,7 +373,7 @@ fn run_filter_bytes(input: &[u8], filter: &str) -> Result<Value, JqError> {
         ));
     }
 
-    let output = child.wait_with_output().map_err(JqError::Spawn)?;
+    let output = process::wait_with_output(child).map_err(JqError::Spawn)?;
     if !output.status.success() {
         let stderr = String::from_utf8(output.stderr)
             .unwrap_or_else(|_| "failed to decode jq stderr".to_string());
@@ -6,6 +6,7 @@ use serde_json::Value;
 use tempfile::NamedTempFile;
 use thiserror::Error;
 
+use crate::adapters::process;
 use crate::util::sort::sort_value_keys;
 
 #[derive(Debug, Error)]
@@ -232,7 +233,7 @@ fn spawn_mlr(bin: &str, args: &[String]) -> Result<Child, MlrError> {
 }
 
 fn wait_and_collect_rows(child: Child) -> Result<Vec<Value>, MlrError> {
-    let output = child.wait_with_output().map_err(MlrError::Spawn)?;
+    let output = process::wait_with_output(child).map_err(MlrError::Spawn)?;
     if !output.status.success() {
         let stderr = String::from_utf8(output.stderr)
             .unwrap_or_else(|_| "failed to decode mlr stderr".to_string());
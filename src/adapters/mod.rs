@@ -3,6 +3,7 @@ pub mod mdbook;
 pub mod mlr;
 pub mod nb;
 pub mod pandoc;
+pub mod process;
 pub mod rg;
 pub mod xh;
 pub mod yq;
@@ -4,6 +4,8 @@ use std::process::{Command, Stdio};
 use serde_json::Value;
 use thiserror::Error;
 
+use crate::adapters::process;
+
 /// Supported document input formats accepted by pandoc.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PandocInputFormat {
@@ -87,7 +89,7 @@ pub fn to_json_ast(input: &[u8], from: PandocInputFormat) -> Result<Value, Pando
         ));
     }
 
-    let output = child.wait_with_output().map_err(PandocError::Spawn)?;
+    let output = process::wait_with_output(child).map_err(PandocError::Spawn)?;
     if !output.status.success() {
         let stderr = String::from_utf8(output.stderr)
             .unwrap_or_else(|_| "failed to decode pandoc stderr".to_string());
@@ -0,0 +1,152 @@
+//! Child-process waiting shared by the tool adapters.
+//!
+//! Long-lived MCP sessions run each tool call under a [`CancellationToken`];
+//! while one is installed on the current thread, [`wait_with_output`] kills the
+//! child as soon as the token is cancelled instead of blocking until it exits.
+
+use std::cell::RefCell;
+use std::io::{self, Read};
+use std::process::{Child, Output};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+const CANCELLATION_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+thread_local! {
+    static CURRENT_CANCELLATION: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+}
+
+/// Shared flag that aborts the external tools of one request.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Runs `run` with `token` installed as this thread's cancellation scope.
+pub fn with_cancellation<T>(token: &CancellationToken, run: impl FnOnce() -> T) -> T {
+    let previous = CURRENT_CANCELLATION.with(|current| current.replace(Some(token.clone())));
+    let result = run();
+    CURRENT_CANCELLATION.with(|current| current.replace(previous));
+    result
+}
+
+/// Whether the current thread's cancellation scope has been cancelled.
+pub fn is_current_cancelled() -> bool {
+    CURRENT_CANCELLATION.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    })
+}
+
+/// Drop-in replacement for [`Child::wait_with_output`] that honors the
+/// current cancellation scope.
+///
+/// Cancellation kills the child and returns an [`io::ErrorKind::Interrupted`]
+/// error. Without a scope this is exactly `child.wait_with_output()`.
+pub fn wait_with_output(mut child: Child) -> io::Result<Output> {
+    let Some(token) = CURRENT_CANCELLATION.with(|current| current.borrow().clone()) else {
+        return child.wait_with_output();
+    };
+
+    drop(child.stdin.take());
+    let stdout = child.stdout.take().map(spawn_pipe_reader);
+    let stderr = child.stderr.take().map(spawn_pipe_reader);
+
+    let status = loop {
+        if token.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "request was cancelled",
+            ));
+        }
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        thread::sleep(CANCELLATION_POLL_INTERVAL);
+    };
+
+    Ok(Output {
+        status,
+        stdout: join_pipe_reader(stdout)?,
+        stderr: join_pipe_reader(stderr)?,
+    })
+}
+
+fn spawn_pipe_reader<R: Read + Send + 'static>(
+    mut pipe: R,
+) -> thread::JoinHandle<io::Result<Vec<u8>>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        pipe.read_to_end(&mut buffer)?;
+        Ok(buffer)
+    })
+}
+
+fn join_pipe_reader(
+    reader: Option<thread::JoinHandle<io::Result<Vec<u8>>>>,
+) -> io::Result<Vec<u8>> {
+    match reader {
+        None => Ok(Vec::new()),
+        Some(handle) => handle
+            .join()
+            .map_err(|_| io::Error::other("pipe reader thread panicked"))?,
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::process::{Command, Stdio};
+    use std::time::Instant;
+
+    use super::*;
+
+    #[test]
+    fn wait_with_output_kills_child_when_scope_is_cancelled() {
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        let child = Command::new("sleep")
+            .arg("30")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("spawn sleep");
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            canceller.cancel();
+        });
+
+        let started = Instant::now();
+        let error = with_cancellation(&token, || wait_with_output(child)).expect_err("cancelled");
+        assert_eq!(error.kind(), io::ErrorKind::Interrupted);
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(!is_current_cancelled());
+
+        let child = Command::new("echo")
+            .arg("ok")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("spawn echo");
+        let output = with_cancellation(&CancellationToken::new(), || wait_with_output(child))
+            .expect("uncancelled output");
+        assert_eq!(output.stdout, b"ok\n");
+    }
+}
@@ -3,6 +3,8 @@ use std::process::{Command, Stdio};
 
 use thiserror::Error;
 
+use crate::adapters::process;
+
 #[derive(Debug, Error)]
 pub enum RgError {
     #[error("`rg` is not available in PATH")]
@@ -58,8 +60,8 @@ fn execute_json_with_bin(args: &RgCommandArgs<'_>, bin: &str) -> Result<String,
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
-    let output = match command.output() {
-        Ok(output) => output,
+    let output = match command.spawn() {
+        Ok(child) => process::wait_with_output(child).map_err(RgError::Spawn)?,
         Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
             return Err(RgError::Unavailable);
         }
@@ -3,6 +3,8 @@ use std::process::{Command, Stdio};
 
 use thiserror::Error;
 
+use crate::adapters::process;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HttpMethod {
     Get,
@@ -76,7 +78,7 @@ pub fn fetch(request: &XhRequest) -> Result<XhResponse, XhError> {
         .stderr(Stdio::piped())
         .spawn()
     {
-        Ok(child) => child.wait_with_output().map_err(XhError::Spawn)?,
+        Ok(child) => process::wait_with_output(child).map_err(XhError::Spawn)?,
         Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
             return Err(XhError::Unavailable);
         }
@@ -4,6 +4,8 @@ use std::process::{Command, Stdio};
 use serde_json::Value;
 use thiserror::Error;
 
+use crate::adapters::process;
+
 const GITHUB_ACTIONS_JOBS_EXTRACT_FILTER: &str =
     r#"[.[] | .jobs | to_entries | sort_by(.key) | .[] | {"job_id": .key, "job": .value}]"#;
 
@@ -77,7 +79,7 @@ fn run_filter_with_bin(values: &[Value], filter: &str, bin: &str) -> Result<Vec<
         ));
     }
 
-    let output = child.wait_with_output().map_err(YqError::Spawn)?;
+    let output = process::wait_with_output(child).map_err(YqError::Spawn)?;
     if !output.status.success() {
         let stderr = String::from_utf8(output.stderr)
             .unwrap_or_else(|_| "failed to decode yq stderr".to_string());
@@ -297,9 +297,39 @@ pub fn load_rules_from_path(path: &Path) -> Result<AssertRules, String> {
     })
 }
 
+pub fn load_schema_from_path(path: &Path) -> Result<Value, String> {
+    load_schema(path).map_err(|error| match error.kind {
+        CommandErrorKind::InputUsage(message) | CommandErrorKind::Internal(message) => message,
+    })
+}
+
+/// Loads rules like [`load_rules_from_path`], running `check_path` on every
+/// `extends` target before it is read. Returns the merged rules together with
+/// the canonical paths of the files reached through `extends`, in load order.
+pub fn load_rules_with_extends(
+    path: &Path,
+    check_path: &dyn Fn(&Path) -> Result<(), String>,
+) -> Result<(AssertRules, Vec<PathBuf>), String> {
+    let mut loader = RulesLoader {
+        stack: Vec::new(),
+        extended: Vec::new(),
+        check_path,
+    };
+    loader
+        .load(path)
+        .map(|resolved| (resolved.rules, loader.extended))
+        .map_err(|error| match error.kind {
+            CommandErrorKind::InputUsage(message) | CommandErrorKind::Internal(message) => message,
+        })
+}
+
 fn load_rules(path: &Path) -> Result<AssertRules, CommandError> {
-    let mut stack = Vec::new();
-    let resolved = load_rules_recursive(path, &mut stack)?;
+    let mut loader = RulesLoader {
+        stack: Vec::new(),
+        extended: Vec::new(),
+        check_path: &|_| Ok(()),
+    };
+    let resolved = loader.load(path)?;
     Ok(resolved.rules)
 }
 
@@ -309,62 +339,73 @@ struct ResolvedAssertRules {
     has_count: bool,
 }
 
-fn load_rules_recursive(
-    path: &Path,
-    stack: &mut Vec<PathBuf>,
-) -> Result<ResolvedAssertRules, CommandError> {
-    let (raw_rules, has_count) = load_rules_file(path)?;
-    let canonical_path = path.canonicalize().map_err(|err| {
-        CommandError::input_usage(format!(
-            "failed to canonicalize rules file `{}`: {err}",
-            path.display()
-        ))
-    })?;
+struct RulesLoader<'a> {
+    stack: Vec<PathBuf>,
+    extended: Vec<PathBuf>,
+    check_path: &'a dyn Fn(&Path) -> Result<(), String>,
+}
+
+impl RulesLoader<'_> {
+    fn load(&mut self, path: &Path) -> Result<ResolvedAssertRules, CommandError> {
+        let (raw_rules, has_count) = load_rules_file(path)?;
+        let canonical_path = path.canonicalize().map_err(|err| {
+            CommandError::input_usage(format!(
+                "failed to canonicalize rules file `{}`: {err}",
+                path.display()
+            ))
+        })?;
 
-    if let Some(cycle_start) = stack
-        .iter()
-        .position(|existing| existing == &canonical_path)
-    {
-        let mut cycle_chain: Vec<String> = stack[cycle_start..]
+        if let Some(cycle_start) = self
+            .stack
             .iter()
-            .map(|entry| entry.display().to_string())
-            .collect();
-        cycle_chain.push(canonical_path.display().to_string());
-        return Err(CommandError::input_usage(format!(
-            "rules extends cycle detected: {}",
-            cycle_chain.join(" -> ")
-        )));
-    }
+            .position(|existing| existing == &canonical_path)
+        {
+            let mut cycle_chain: Vec<String> = self.stack[cycle_start..]
+                .iter()
+                .map(|entry| entry.display().to_string())
+                .collect();
+            cycle_chain.push(canonical_path.display().to_string());
+            return Err(CommandError::input_usage(format!(
+                "rules extends cycle detected: {}",
+                cycle_chain.join(" -> ")
+            )));
+        }
 
-    stack.push(canonical_path.clone());
-    let result = (|| {
-        let mut merged = ResolvedAssertRules {
-            rules: AssertRules::default(),
-            has_count: false,
-        };
-        if let Some(extends) = raw_rules.extends.clone() {
-            for extends_entry in extends.into_paths() {
-                let extended_path =
-                    resolve_extended_rules_path(&canonical_path, extends_entry.as_str());
-                let extended = load_rules_recursive(extended_path.as_path(), stack)?;
-                merged = merge_resolved_rules(merged, extended);
+        self.stack.push(canonical_path.clone());
+        let result = (|| {
+            let mut merged = ResolvedAssertRules {
+                rules: AssertRules::default(),
+                has_count: false,
+            };
+            if let Some(extends) = raw_rules.extends.clone() {
+                for extends_entry in extends.into_paths() {
+                    let extended_path =
+                        resolve_extended_rules_path(&canonical_path, extends_entry.as_str());
+                    (self.check_path)(extended_path.as_path())
+                        .map_err(CommandError::input_usage)?;
+                    let extended = self.load(extended_path.as_path())?;
+                    merged = merge_resolved_rules(merged, extended);
+                }
             }
-        }
 
-        let current = ResolvedAssertRules {
-            rules: AssertRules {
-                extends: None,
-                required_keys: raw_rules.required_keys,
-                forbid_keys: raw_rules.forbid_keys,
-                fields: raw_rules.fields,
-                count: raw_rules.count,
-            },
-            has_count,
-        };
-        Ok(merge_resolved_rules(merged, current))
-    })();
-    stack.pop();
-    result
+            let current = ResolvedAssertRules {
+                rules: AssertRules {
+                    extends: None,
+                    required_keys: raw_rules.required_keys,
+                    forbid_keys: raw_rules.forbid_keys,
+                    fields: raw_rules.fields,
+                    count: raw_rules.count,
+                },
+                has_count,
+            };
+            Ok(merge_resolved_rules(merged, current))
+        })();
+        self.stack.pop();
+        if result.is_ok() && !self.stack.is_empty() && !self.extended.contains(&canonical_path) {
+            self.extended.push(canonical_path);
+        }
+        result
+    }
 }
 
 fn resolve_extended_rules_path(current_file_path: &Path, extends_entry: &str) -> PathBuf {
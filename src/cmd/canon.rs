@@ -1,16 +1,28 @@
 use std::io::{Read, Write};
+use std::path::Path;
 
+use crate::cmd::r#assert;
 use crate::domain::error::CanonError;
+use crate::domain::value_path::ValuePath;
+use crate::engine::canon::coerce::{CoerceMode, CoercePolicy, CoerceSchema};
+use crate::engine::canon::normalize::NormalizeOptions;
+use crate::engine::canon::time::TimeNormalization;
 use crate::engine::canon::{CanonOptions, canonicalize_values};
 use crate::io::{Format, reader, writer};
 
 /// Command-level options for canonicalization execution.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CanonCommandOptions {
     /// Sort object keys lexicographically. If `false`, preserve input key order.
     pub sort_keys: bool,
     /// Normalize RFC3339 timestamps to UTC (`Z`) when enabled.
     pub normalize_time: bool,
+    /// Which string scalars may be coerced into booleans and numbers.
+    pub coerce: CoercePolicy,
+    /// Optional key-casing, string and structural normalization passes.
+    pub normalize: NormalizeOptions,
+    /// Configurable timestamp conversion at selected paths.
+    pub time: Option<TimeNormalization>,
 }
 
 impl Default for CanonCommandOptions {
@@ -18,6 +30,9 @@ impl Default for CanonCommandOptions {
         Self {
             sort_keys: true,
             normalize_time: false,
+            coerce: CoercePolicy::default(),
+            normalize: NormalizeOptions::default(),
+            time: None,
         }
     }
 }
@@ -27,10 +42,88 @@ impl From<CanonCommandOptions> for CanonOptions {
         Self {
             sort_keys: value.sort_keys,
             normalize_time: value.normalize_time,
+            coerce: value.coerce,
+            normalize: value.normalize,
+            time: value.time,
+        }
+    }
+}
+
+/// Field type source consulted by `--coerce schema`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoerceSchemaSource<'a> {
+    JsonSchema(&'a Path),
+    Rules(&'a Path),
+}
+
+/// Build a coercion policy from command arguments.
+///
+/// `schema` mode requires a schema source, and a schema source is rejected in
+/// any other mode so that an ignored flag never goes unnoticed.
+pub fn resolve_coerce_policy(
+    mode: CoerceMode,
+    exempt_paths: &[String],
+    schema_source: Option<CoerceSchemaSource<'_>>,
+) -> Result<CoercePolicy, String> {
+    let exempt_paths = validate_coerce_args(mode, exempt_paths, schema_source.is_some())?;
+
+    let schema = match schema_source {
+        Some(CoerceSchemaSource::JsonSchema(path)) => {
+            CoerceSchema::from_json_schema(&r#assert::load_schema_from_path(path)?)
+        }
+        Some(CoerceSchemaSource::Rules(path)) => {
+            CoerceSchema::from_rules(&r#assert::load_rules_from_path(path)?)
+        }
+        None => CoerceSchema::default(),
+    };
+
+    Ok(CoercePolicy {
+        mode,
+        exempt_paths,
+        schema,
+    })
+}
+
+/// Check coercion arguments without loading the schema source.
+///
+/// Returns the parsed exempt paths; shared by [`resolve_coerce_policy`] and
+/// callers that validate arguments before any file is read.
+pub fn validate_coerce_args(
+    mode: CoerceMode,
+    exempt_paths: &[String],
+    has_schema_source: bool,
+) -> Result<Vec<ValuePath>, String> {
+    let exempt_paths = exempt_paths
+        .iter()
+        .map(|raw| {
+            ValuePath::parse_canonical(raw)
+                .map_err(|error| format!("invalid coerce exempt path `{raw}`: {error}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match (mode, has_schema_source) {
+        (CoerceMode::Schema, false) => {
+            Err("coerce mode `schema` requires a coerce schema or rules file".to_string())
         }
+        (CoerceMode::None | CoerceMode::All, true) => Err(format!(
+            "coerce schema or rules file requires coerce mode `schema`, got `{}`",
+            mode.as_str()
+        )),
+        _ => Ok(exempt_paths),
     }
 }
 
+/// Parse canonical paths selecting scalar arrays to sort.
+pub fn parse_sort_array_paths(raw_paths: &[String]) -> Result<Vec<ValuePath>, String> {
+    raw_paths
+        .iter()
+        .map(|raw| {
+            ValuePath::parse_canonical(raw)
+                .map_err(|error| format!("invalid sort array path `{raw}`: {error}"))
+        })
+        .collect()
+}
+
 /// Ordered pipeline-step names used for `--emit-pipeline` diagnostics.
 pub fn pipeline_steps() -> Vec<String> {
     vec![
@@ -41,7 +134,7 @@ pub fn pipeline_steps() -> Vec<String> {
 }
 
 /// Determinism guards applied by the `canon` command.
-pub fn deterministic_guards(options: CanonCommandOptions) -> Vec<String> {
+pub fn deterministic_guards(options: &CanonCommandOptions) -> Vec<String> {
     let mut guards = vec![
         "rust_native_execution".to_string(),
         "no_shell_interpolation_for_user_input".to_string(),
@@ -54,6 +147,39 @@ pub fn deterministic_guards(options: CanonCommandOptions) -> Vec<String> {
     if options.normalize_time {
         guards.push("timestamps_normalized_rfc3339_utc".to_string());
     }
+    match options.coerce.mode {
+        CoerceMode::All => {}
+        CoerceMode::None => guards.push("string_coercion_disabled".to_string()),
+        CoerceMode::Schema => guards.push("string_coercion_schema_driven".to_string()),
+    }
+    if !options.coerce.exempt_paths.is_empty() {
+        guards.push("string_coercion_exempt_paths".to_string());
+    }
+    let normalize = &options.normalize;
+    if let Some(form) = normalize.unicode {
+        guards.push(format!("strings_unicode_{}", form.as_str()));
+    }
+    if let Some(mode) = normalize.whitespace {
+        guards.push(format!("strings_whitespace_{}", mode.as_str()));
+    }
+    if let Some(case) = normalize.key_case {
+        guards.push(format!("object_keys_{}_case", case.as_str()));
+    }
+    if normalize.drop_nulls {
+        guards.push("null_fields_dropped".to_string());
+    }
+    if normalize.drop_empty {
+        guards.push("empty_fields_dropped".to_string());
+    }
+    if !normalize.sort_array_paths.is_empty() {
+        guards.push("scalar_arrays_sorted_at_paths".to_string());
+    }
+    if let Some(time) = options.time.as_ref() {
+        guards.push(format!(
+            "timestamps_at_paths_normalized_{}",
+            time.normalizer.target.as_str()
+        ));
+    }
     guards
 }
 
@@ -73,7 +199,7 @@ pub fn run<R: Read, W: Write>(
             format: input_format,
             source,
         })?;
-    let canonical = canonicalize_values(values, options.into());
+    let canonical = canonicalize_values(values, &options.into())?;
     writer::write_values(output, output_format, &canonical).map_err(|source| {
         CanonError::WriteOutput {
             format: output_format,
@@ -114,6 +240,7 @@ mod tests {
         let options = CanonCommandOptions {
             sort_keys: true,
             normalize_time: true,
+            ..CanonCommandOptions::default()
         };
 
         let mut first = Vec::new();
@@ -122,7 +249,7 @@ mod tests {
             &mut first,
             Format::Json,
             Format::Json,
-            options,
+            options.clone(),
         )
         .expect("first run should succeed");
 
@@ -151,6 +278,7 @@ mod tests {
             CanonCommandOptions {
                 sort_keys: false,
                 normalize_time: false,
+                ..CanonCommandOptions::default()
             },
         )
         .expect("canon run should succeed");
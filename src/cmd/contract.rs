@@ -1,8 +1,12 @@
+use std::path::Path;
+
 use serde::Serialize;
-use serde_json::{Value, json};
+use serde_json::{Map, Value, json};
+
+use crate::engine::contract_diff;
 
 /// Supported command names in deterministic order.
-pub const ORDERED_COMMANDS: [ContractCommand; 18] = [
+pub const ORDERED_COMMANDS: [ContractCommand; 19] = [
     ContractCommand::Canon,
     ContractCommand::IngestApi,
     ContractCommand::Ingest,
@@ -21,6 +25,7 @@ pub const ORDERED_COMMANDS: [ContractCommand; 18] = [
     ContractCommand::Doctor,
     ContractCommand::RecipeRun,
     ContractCommand::RecipeLock,
+    ContractCommand::RecipeReplay,
 ];
 
 /// Subcommand identifier accepted by `dataq contract --command`.
@@ -44,6 +49,7 @@ pub enum ContractCommand {
     Doctor,
     RecipeRun,
     RecipeLock,
+    RecipeReplay,
 }
 
 /// Structured command response that carries exit-code mapping and JSON payload.
@@ -57,6 +63,7 @@ pub struct ContractCommandResponse {
 struct CommandContract<'a> {
     command: &'a str,
     schema: &'a str,
+    supported_versions: &'a [&'a str],
     output_fields: &'a [&'a str],
     exit_codes: ExitCodeContract<'a>,
     notes: &'a [&'a str],
@@ -95,6 +102,7 @@ const INGEST_BOOK_FIELDS: &[&str] = &["book", "summary"];
 const SCAN_FIELDS: &[&str] = &["matches", "summary"];
 const DOCTOR_FIELDS: &[&str] = &["tools"];
 const RECIPE_RUN_FIELDS: &[&str] = &["matched", "exit_code", "steps"];
+const RECIPE_REPLAY_FIELDS: &[&str] = &["matched", "exit_code", "lock_check", "steps"];
 const RECIPE_LOCK_FIELDS: &[&str] = &[
     "version",
     "command_graph_hash",
@@ -103,6 +111,13 @@ const RECIPE_LOCK_FIELDS: &[&str] = &[
     "dataq_version",
 ];
 
+const RECIPE_RUN_OUTPUT_V1: &str = "dataq.recipe.run.output.v1";
+const RECIPE_RUN_OUTPUT_V2: &str = "dataq.recipe.run.output.v2";
+const RECIPE_REPLAY_OUTPUT_V1: &str = "dataq.recipe.replay.output.v1";
+const RECIPE_REPLAY_OUTPUT_V2: &str = "dataq.recipe.replay.output.v2";
+/// Step fields added by the `v2` recipe run/replay contracts.
+const RECIPE_RUN_V2_STEP_FIELDS: &[&str] = &["id", "skipped", "cached"];
+
 const CANON_NOTES: &[&str] = &[
     "Output is the canonicalized root JSON value.",
     "Top-level keys are input-dependent and therefore not fixed.",
@@ -168,17 +183,28 @@ const DOCTOR_NOTES: &[&str] = &[
     "`--capabilities` adds capability probes; `--profile` adds `capabilities` and `profile` with static requirement table versioning.",
     "Exit code 3 means missing/non-executable `jq|yq|mlr` without `--profile`, or unsatisfied selected profile requirements with `--profile`.",
 ];
+const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+const VALIDATION_MISMATCH_UNUSED: &str = "validation mismatch is not used by this command";
 const DOCTOR_EXIT_CODE_3: &str = "without `--profile`: missing/non-executable `jq|yq|mlr`; with `--profile`: selected profile requirements are unsatisfied";
 const INGEST_YAML_JOBS_EXIT_CODE_3: &str =
     "input/usage error (malformed YAML, unknown mode, or missing `jq`/`yq`/`mlr`)";
 const RECIPE_RUN_NOTES: &[&str] = &[
     "This contract describes `recipe run` output.",
-    "`steps` preserves recipe definition order.",
+    "`steps` follows the resolved execution order (definition order unless `inputs` reorder it).",
     "Step-level unmatched results map to exit code 2.",
+    "Steps skipped by `when` report `skipped=true`; `on_failure=continue` keeps executing after a mismatch.",
+    "`dataq.recipe.run.output.v1` (`--contract-version v1`) omits step `id`, `skipped` and `cached`.",
+];
+const RECIPE_REPLAY_NOTES: &[&str] = &[
+    "This contract describes `recipe replay` output.",
+    "`lock_check` lists lock mismatches in fixed constraint order; `--strict` skips execution on mismatch (exit code 2).",
+    "`steps` has the same shape as `recipe run`.",
+    "`dataq.recipe.replay.output.v1` (`--contract-version v1`) omits step `id`, `skipped` and `cached`.",
 ];
 const RECIPE_LOCK_NOTES: &[&str] = &[
-    "`tool_versions` keys are deterministically sorted by tool name (`jq`, `mlr`, `yq`).",
+    "`tool_versions` always records `jq`, `mlr`, `yq` and adds `pandoc`/`rg` when `ingest_doc`/`scan_text` steps are present; keys are sorted by tool name.",
     "Lock output is canonicalized before write/emit.",
+    "`input_hashes` (file content hashes keyed by recipe-relative path) is present only when steps read files.",
 ];
 
 pub fn run_for_command(command: ContractCommand) -> ContractCommandResponse {
@@ -210,18 +236,743 @@ pub fn deterministic_guards() -> Vec<String> {
     ]
 }
 
+/// Payload kind checked by `dataq contract verify`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContractPayloadKind {
+    /// stdout payload (exit code `0`, and `2` where validation mismatch is used).
+    Success,
+    /// stderr error payload (exit codes `3` and `1`).
+    Error,
+    /// stderr `--emit-pipeline` diagnostics line.
+    Pipeline,
+}
+
+impl ContractPayloadKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Error => "error",
+            Self::Pipeline => "pipeline",
+        }
+    }
+}
+
+pub fn run_json_schema_for_command(command: ContractCommand) -> ContractCommandResponse {
+    ContractCommandResponse {
+        exit_code: 0,
+        payload: json_schema_document(command),
+    }
+}
+
+pub fn run_json_schema_all() -> ContractCommandResponse {
+    ContractCommandResponse {
+        exit_code: 0,
+        payload: Value::Array(
+            ORDERED_COMMANDS
+                .into_iter()
+                .map(json_schema_document)
+                .collect(),
+        ),
+    }
+}
+
+/// Validates a captured output file against the JSON Schema of `command`.
+///
+/// The input is either one JSON document or JSON Lines, in which case every
+/// non-empty line is validated and errors carry the 1-based `line`.
+///
+/// Returns exit code `0` when valid, `2` with schema violations, and `3` when
+/// the input cannot be read as JSON or the command publishes no schema for
+/// `kind`.
+pub fn run_verify(
+    command: ContractCommand,
+    kind: ContractPayloadKind,
+    input: &Path,
+) -> ContractCommandResponse {
+    let raw = match std::fs::read(input) {
+        Ok(raw) => raw,
+        Err(error) => {
+            return input_usage_error(format!("failed to read `{}`: {error}", input.display()));
+        }
+    };
+    let documents = match parse_verify_input(&raw) {
+        Ok(documents) => documents,
+        Err(message) => {
+            return input_usage_error(format!(
+                "`{}` is not a JSON document or JSON Lines: {message}",
+                input.display()
+            ));
+        }
+    };
+    verify_documents(command, kind, &documents)
+}
+
+/// Same as [`run_verify`] for an already-parsed payload.
+pub fn verify_value(
+    command: ContractCommand,
+    kind: ContractPayloadKind,
+    value: &Value,
+) -> ContractCommandResponse {
+    verify_documents(command, kind, &[(None, value.clone())])
+}
+
+/// Parses `raw` as a single JSON document, falling back to JSON Lines.
+///
+/// Documents parsed from JSON Lines are paired with their 1-based line number.
+fn parse_verify_input(raw: &[u8]) -> Result<Vec<(Option<usize>, Value)>, String> {
+    let whole_error = match serde_json::from_slice::<Value>(raw) {
+        Ok(value) => return Ok(vec![(None, value)]),
+        Err(error) => error,
+    };
+    let text = std::str::from_utf8(raw).map_err(|_| whole_error.to_string())?;
+    let mut documents = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value =
+            serde_json::from_str(line).map_err(|error| format!("line {}: {error}", index + 1))?;
+        documents.push((Some(index + 1), value));
+    }
+    if documents.len() < 2 {
+        // A lone (or missing) line is not JSON Lines; report the original error.
+        return Err(whole_error.to_string());
+    }
+    Ok(documents)
+}
+
+fn verify_documents(
+    command: ContractCommand,
+    kind: ContractPayloadKind,
+    documents: &[(Option<usize>, Value)],
+) -> ContractCommandResponse {
+    let contract = command_contract(command);
+    if kind == ContractPayloadKind::Success && !has_success_payload_schema(command) {
+        return input_usage_error(format!(
+            "`{}` output depends on its input and has no success payload schema to verify against; use `--payload error` or `--payload pipeline`",
+            contract.command
+        ));
+    }
+    let document = json_schema_document(command);
+    let schema = json!({
+        "$schema": JSON_SCHEMA_DIALECT,
+        "$defs": document["$defs"],
+        "$ref": format!("#/$defs/{}", kind.as_str()),
+    });
+    let validator = match jsonschema::validator_for(&schema) {
+        Ok(validator) => validator,
+        Err(error) => {
+            return ContractCommandResponse {
+                exit_code: 1,
+                payload: json!({
+                    "error": "internal_error",
+                    "message": format!("failed to compile contract schema: {error}"),
+                }),
+            };
+        }
+    };
+    let mut errors = Vec::new();
+    for (line, value) in documents {
+        for error in validator.iter_errors(value) {
+            let mut entry = json!({
+                "path": error.instance_path().as_str(),
+                "schema_path": error.schema_path().as_str(),
+                "message": error.to_string(),
+            });
+            if let Some(line) = line {
+                entry["line"] = json!(line);
+            }
+            errors.push(entry);
+        }
+    }
+    ContractCommandResponse {
+        exit_code: if errors.is_empty() { 0 } else { 2 },
+        payload: json!({
+            "command": contract.command,
+            "schema": contract.schema,
+            "payload": kind.as_str(),
+            "valid": errors.is_empty(),
+            "errors": errors,
+        }),
+    }
+}
+
+/// Whether `command` publishes a success payload schema that constrains
+/// anything. `canon` and `merge` echo input-shaped data.
+fn has_success_payload_schema(command: ContractCommand) -> bool {
+    success_payload_schema(command) != json!({})
+}
+
+/// Resolves a `--contract-version` request to a schema ID supported by `command`.
+///
+/// Accepts either the full schema ID (`dataq.sdiff.output.v1`) or its version
+/// suffix (`v1`).
+pub fn resolve_contract_version(
+    command: ContractCommand,
+    requested: &str,
+) -> Result<&'static str, String> {
+    let contract = command_contract(command);
+    contract
+        .supported_versions
+        .iter()
+        .copied()
+        .find(|version| {
+            *version == requested
+                || version
+                    .rsplit_once('.')
+                    .is_some_and(|(_, suffix)| suffix == requested)
+        })
+        .ok_or_else(|| {
+            format!(
+                "contract version `{requested}` is not supported by `{}` (supported: {})",
+                contract.command,
+                contract
+                    .supported_versions
+                    .iter()
+                    .map(|version| format!("`{version}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })
+}
+
+/// Rewrites a success payload of `command` into the shape of `version`.
+///
+/// `version` is a schema ID returned by [`resolve_contract_version`]. Payloads
+/// of the default (latest) version are returned unchanged.
+pub fn shape_for_version(command: ContractCommand, version: &str, mut payload: Value) -> Value {
+    let v1_steps = match command {
+        ContractCommand::RecipeRun => version == RECIPE_RUN_OUTPUT_V1,
+        ContractCommand::RecipeReplay => version == RECIPE_REPLAY_OUTPUT_V1,
+        _ => false,
+    };
+    if v1_steps {
+        if let Some(steps) = payload.get_mut("steps").and_then(Value::as_array_mut) {
+            for step in steps.iter_mut().filter_map(Value::as_object_mut) {
+                for field in RECIPE_RUN_V2_STEP_FIELDS {
+                    step.shift_remove(*field);
+                }
+            }
+        }
+    }
+    payload
+}
+
+/// Compares a saved `contract --all` snapshot (either format) with the
+/// embedded contracts.
+///
+/// Returns exit code `0` when compatible, `2` with breaking changes, and `3`
+/// when the snapshot cannot be read.
+pub fn run_diff(from: &Path) -> ContractCommandResponse {
+    let raw = match std::fs::read(from) {
+        Ok(raw) => raw,
+        Err(error) => {
+            return input_usage_error(format!("failed to read `{}`: {error}", from.display()));
+        }
+    };
+    let snapshot: Value = match serde_json::from_slice(&raw) {
+        Ok(value) => value,
+        Err(error) => {
+            return input_usage_error(format!(
+                "`{}` is not a JSON document: {error}",
+                from.display()
+            ));
+        }
+    };
+    let entries = match snapshot {
+        Value::Array(entries) => entries,
+        entry @ Value::Object(_) => vec![entry],
+        _ => {
+            return input_usage_error(
+                "contract snapshot must be a contract object or an array of them".to_string(),
+            );
+        }
+    };
+
+    let (format, report) = if entries
+        .iter()
+        .all(|entry| entry.get("x-dataq-command").is_some_and(Value::is_string))
+    {
+        let current: Vec<Value> = ORDERED_COMMANDS
+            .into_iter()
+            .map(json_schema_document)
+            .collect();
+        (
+            "json-schema",
+            contract_diff::diff_schema_documents(&entries, &current),
+        )
+    } else if entries
+        .iter()
+        .all(|entry| entry.get("command").is_some_and(Value::is_string))
+    {
+        let current = match serde_json::to_value(
+            ORDERED_COMMANDS
+                .into_iter()
+                .map(command_contract)
+                .collect::<Vec<_>>(),
+        ) {
+            Ok(Value::Array(current)) => current,
+            _ => Vec::new(),
+        };
+        (
+            "contract",
+            contract_diff::diff_contract_entries(&entries, &current),
+        )
+    } else {
+        return input_usage_error(
+            "contract snapshot entries must all be `contract` objects or all be `--format json-schema` documents"
+                .to_string(),
+        );
+    };
+
+    ContractCommandResponse {
+        exit_code: if report.compatible { 0 } else { 2 },
+        payload: json!({
+            "from": from.display().to_string(),
+            "format": format,
+            "compatible": report.compatible,
+            "summary": report.summary,
+            "changes": report.changes,
+        }),
+    }
+}
+
+/// Full JSON Schema document for the outputs of `command`.
+///
+/// The root validates the stdout payload; `$defs` also carries the stderr
+/// error payload and the `--emit-pipeline` line, and `x-dataq-exit-codes`
+/// maps each exit code to its stream and definition.
+pub fn json_schema_document(command: ContractCommand) -> Value {
+    let contract = command_contract(command);
+    let exit_codes: Map<String, Value> = payload_schemas(command)
+        .into_iter()
+        .map(|(code, _)| {
+            let (stream, definition) =
+                if code == 0 || code == 2 || (code == 3 && command == ContractCommand::Doctor) {
+                    ("stdout", "#/$defs/success")
+                } else {
+                    ("stderr", "#/$defs/error")
+                };
+            (
+                code.to_string(),
+                json!({ "stream": stream, "$ref": definition }),
+            )
+        })
+        .collect();
+    let mut error = error_payload_schema();
+    error["properties"]["code"] = json!({ "type": "integer" });
+    error["properties"]["details"] = json!({});
+    json!({
+        "$schema": JSON_SCHEMA_DIALECT,
+        "title": contract.schema,
+        "description": format!("Outputs of `dataq {}`.", contract.command),
+        "x-dataq-command": contract.command,
+        "x-dataq-supported-versions": contract.supported_versions,
+        "x-dataq-exit-codes": exit_codes,
+        "$ref": "#/$defs/success",
+        "$defs": {
+            "success": success_payload_schema(command),
+            "error": error,
+            "pipeline": pipeline_report_schema(),
+        }
+    })
+}
+
+/// JSON Schema of the `--emit-pipeline` stderr line.
+pub fn pipeline_report_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["command", "input", "steps", "external_tools", "deterministic_guards"],
+        "properties": {
+            "command": { "type": "string" },
+            "input": object_schema(
+                &["sources"],
+                json!({
+                    "sources": {
+                        "type": "array",
+                        "items": object_schema(
+                            &["label", "source"],
+                            json!({
+                                "label": { "type": "string" },
+                                "source": { "type": "string" },
+                                "path": { "type": "string" },
+                                "format": { "type": "string" }
+                            }),
+                        )
+                    }
+                }),
+            ),
+            "steps": string_array_schema(),
+            "external_tools": {
+                "type": "array",
+                "items": object_schema(
+                    &["name", "used"],
+                    json!({
+                        "name": { "type": "string" },
+                        "used": { "type": "boolean" }
+                    }),
+                )
+            },
+            "stage_diagnostics": object_array_schema(),
+            "fingerprint": object_schema(
+                &["command", "args_hash", "tool_versions", "dataq_version"],
+                json!({
+                    "command": { "type": "string" },
+                    "args_hash": { "type": "string" },
+                    "input_hash": { "type": "string" },
+                    "tool_versions": { "type": "object", "additionalProperties": { "type": "string" } },
+                    "dataq_version": { "type": "string" }
+                }),
+            ),
+            "deterministic_guards": string_array_schema()
+        }
+    })
+}
+
+fn input_usage_error(message: String) -> ContractCommandResponse {
+    ContractCommandResponse {
+        exit_code: 3,
+        payload: json!({
+            "error": "input_usage_error",
+            "message": message,
+        }),
+    }
+}
+
+/// JSON Schema of the stdout payload for every exit code `command` can return,
+/// in `0`, `2`, `3`, `1` order.
+///
+/// Root object schemas list the contract `output_fields` as `required`; exit
+/// code `2` is present only when the contract uses validation mismatch.
+pub fn payload_schemas(command: ContractCommand) -> Vec<(i32, Value)> {
+    let contract = command_contract(command);
+    let success = success_payload_schema(command);
+    let mut schemas = vec![(0, success.clone())];
+    if contract.exit_codes.validation_mismatch != VALIDATION_MISMATCH_UNUSED {
+        schemas.push((2, success.clone()));
+    }
+    let code_three = if command == ContractCommand::Doctor {
+        json!({ "anyOf": [success, error_payload_schema()] })
+    } else {
+        error_payload_schema()
+    };
+    schemas.push((3, code_three));
+    schemas.push((1, error_payload_schema()));
+    schemas
+}
+
+/// JSON Schema shared by `{"error", "message"}` payloads (exit codes `3` and `1`).
+pub fn error_payload_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["error", "message"],
+        "properties": {
+            "error": { "type": "string" },
+            "message": { "type": "string" }
+        }
+    })
+}
+
+fn success_payload_schema(command: ContractCommand) -> Value {
+    let fields = command_contract(command).output_fields;
+    match command {
+        ContractCommand::Canon | ContractCommand::Merge => json!({}),
+        ContractCommand::Ingest | ContractCommand::TransformRowset => {
+            json!({ "type": "array", "items": { "type": "object" } })
+        }
+        ContractCommand::IngestApi => object_schema(
+            fields,
+            json!({
+                "source": { "type": "object" },
+                "status": { "type": "integer" },
+                "headers": { "type": "object", "additionalProperties": { "type": "string" } },
+                "body": {},
+                "fetched_at": { "type": "string" }
+            }),
+        ),
+        ContractCommand::Assert | ContractCommand::GateSchema => object_schema(
+            fields,
+            json!({
+                "matched": { "type": "boolean" },
+                "mismatch_count": { "type": "integer", "minimum": 0 },
+                "mismatches": {
+                    "type": "array",
+                    "items": object_schema(
+                        &["path", "rule_kind", "reason", "actual", "expected"],
+                        json!({
+                            "path": { "type": "string" },
+                            "rule_kind": { "type": "string" },
+                            "reason": { "type": "string" }
+                        }),
+                    )
+                }
+            }),
+        ),
+        ContractCommand::Gate => object_schema(
+            fields,
+            json!({
+                "matched": { "type": "boolean" },
+                "violations": { "type": "integer", "minimum": 0 },
+                "details": { "type": "array", "items": { "type": "object" } }
+            }),
+        ),
+        ContractCommand::Sdiff | ContractCommand::DiffSource => object_schema(
+            fields,
+            json!({
+                "counts": object_schema(
+                    &["left", "right", "delta", "equal"],
+                    json!({
+                        "left": { "type": "integer", "minimum": 0 },
+                        "right": { "type": "integer", "minimum": 0 },
+                        "delta": { "type": "integer" },
+                        "equal": { "type": "boolean" }
+                    }),
+                ),
+                "keys": object_schema(
+                    &["left_only", "right_only", "shared"],
+                    json!({
+                        "left_only": string_array_schema(),
+                        "right_only": string_array_schema(),
+                        "shared": string_array_schema()
+                    }),
+                ),
+                "ignored_paths": string_array_schema(),
+                "values": object_schema(
+                    &["total", "truncated", "items"],
+                    json!({
+                        "total": { "type": "integer", "minimum": 0 },
+                        "truncated": { "type": "boolean" },
+                        "items": {
+                            "type": "array",
+                            "items": object_schema(
+                                &["path", "left", "right"],
+                                json!({ "path": { "type": "string" } }),
+                            )
+                        }
+                    }),
+                ),
+                "sources": object_schema(
+                    &["left", "right"],
+                    json!({
+                        "left": { "type": "object" },
+                        "right": { "type": "object" }
+                    }),
+                )
+            }),
+        ),
+        ContractCommand::Profile => object_schema(
+            fields,
+            json!({
+                "record_count": { "type": "integer", "minimum": 0 },
+                "field_count": { "type": "integer", "minimum": 0 },
+                "fields": {
+                    "type": "object",
+                    "additionalProperties": object_schema(
+                        &["null_ratio", "unique_count", "type_distribution"],
+                        json!({
+                            "null_ratio": { "type": "number" },
+                            "unique_count": { "type": "integer", "minimum": 0 },
+                            "type_distribution": { "type": "object" },
+                            "numeric_stats": { "type": "object" }
+                        }),
+                    )
+                }
+            }),
+        ),
+        ContractCommand::IngestDoc => object_schema(
+            fields,
+            json!({
+                "meta": { "type": "object" },
+                "headings": object_array_schema(),
+                "links": object_array_schema(),
+                "tables": object_array_schema(),
+                "code_blocks": object_array_schema()
+            }),
+        ),
+        ContractCommand::IngestNotes => json!({
+            "type": "array",
+            "items": object_schema(
+                fields,
+                json!({
+                    "id": { "type": "string" },
+                    "title": { "type": "string" },
+                    "body": { "type": "string" },
+                    "tags": string_array_schema(),
+                    "created_at": { "type": "string" },
+                    "updated_at": { "type": ["string", "null"] },
+                    "metadata": { "type": "object" }
+                }),
+            )
+        }),
+        ContractCommand::IngestBook => object_schema(
+            fields,
+            json!({
+                "book": { "type": "object" },
+                "summary": object_schema(
+                    &["chapter_count", "order", "chapters"],
+                    json!({
+                        "chapter_count": { "type": "integer", "minimum": 0 },
+                        "order": object_array_schema(),
+                        "chapters": object_array_schema()
+                    }),
+                )
+            }),
+        ),
+        ContractCommand::Scan => object_schema(
+            fields,
+            json!({
+                "matches": {
+                    "type": "array",
+                    "items": object_schema(
+                        &["path", "line", "column", "text", "line_text"],
+                        json!({
+                            "path": { "type": "string" },
+                            "line": { "type": "integer", "minimum": 0 },
+                            "column": { "type": "integer", "minimum": 0 },
+                            "text": { "type": "string" },
+                            "line_text": { "type": "string" }
+                        }),
+                    )
+                },
+                "summary": object_schema(
+                    &[
+                        "total_matches",
+                        "returned_matches",
+                        "files_with_matches",
+                        "truncated",
+                        "policy_mode",
+                        "forbidden_matches",
+                    ],
+                    json!({
+                        "total_matches": { "type": "integer", "minimum": 0 },
+                        "returned_matches": { "type": "integer", "minimum": 0 },
+                        "files_with_matches": { "type": "integer", "minimum": 0 },
+                        "truncated": { "type": "boolean" },
+                        "policy_mode": { "type": "boolean" },
+                        "forbidden_matches": { "type": "integer", "minimum": 0 }
+                    }),
+                )
+            }),
+        ),
+        ContractCommand::Doctor => object_schema(
+            fields,
+            json!({
+                "tools": {
+                    "type": "array",
+                    "items": object_schema(
+                        &["name", "found", "version", "executable", "message"],
+                        json!({
+                            "name": { "type": "string" },
+                            "found": { "type": "boolean" },
+                            "version": { "type": ["string", "null"] },
+                            "executable": { "type": "boolean" },
+                            "message": { "type": "string" }
+                        }),
+                    )
+                },
+                "capabilities": object_array_schema(),
+                "profile": { "type": "object" }
+            }),
+        ),
+        ContractCommand::RecipeRun => object_schema(
+            fields,
+            json!({
+                "matched": { "type": "boolean" },
+                "exit_code": { "type": "integer" },
+                "steps": recipe_steps_schema()
+            }),
+        ),
+        ContractCommand::RecipeReplay => object_schema(
+            fields,
+            json!({
+                "matched": { "type": "boolean" },
+                "exit_code": { "type": "integer" },
+                "lock_check": object_schema(
+                    &["strict", "matched", "mismatch_count", "mismatches"],
+                    json!({
+                        "strict": { "type": "boolean" },
+                        "matched": { "type": "boolean" },
+                        "mismatch_count": { "type": "integer", "minimum": 0 },
+                        "mismatches": {
+                            "type": "array",
+                            "items": object_schema(
+                                &["constraint", "expected", "actual"],
+                                json!({
+                                    "constraint": { "type": "string" },
+                                    "expected": { "type": "string" },
+                                    "actual": { "type": "string" }
+                                }),
+                            )
+                        }
+                    }),
+                ),
+                "steps": recipe_steps_schema()
+            }),
+        ),
+        ContractCommand::RecipeLock => object_schema(
+            fields,
+            json!({
+                "version": { "type": "string" },
+                "command_graph_hash": { "type": "string" },
+                "args_hash": { "type": "string" },
+                "tool_versions": { "type": "object", "additionalProperties": { "type": "string" } },
+                "dataq_version": { "type": "string" },
+                "input_hashes": { "type": "object", "additionalProperties": { "type": "string" } }
+            }),
+        ),
+    }
+}
+
+/// JSON Schema for the `steps` array shared by recipe run/replay reports.
+pub fn recipe_steps_schema() -> Value {
+    json!({
+        "type": "array",
+        "items": object_schema(
+            &["index", "kind", "matched", "exit_code", "summary"],
+            json!({
+                "index": { "type": "integer", "minimum": 0 },
+                "id": { "type": "string" },
+                "kind": { "type": "string" },
+                "matched": { "type": "boolean" },
+                "exit_code": { "type": "integer" },
+                "skipped": { "type": "boolean" },
+                "cached": { "type": "boolean" },
+                "summary": {}
+            }),
+        )
+    })
+}
+
+fn object_schema(required: &[&str], properties: Value) -> Value {
+    json!({
+        "type": "object",
+        "required": required,
+        "properties": properties
+    })
+}
+
+fn string_array_schema() -> Value {
+    json!({ "type": "array", "items": { "type": "string" } })
+}
+
+fn object_array_schema() -> Value {
+    json!({ "type": "array", "items": { "type": "object" } })
+}
+
 fn command_contract(command: ContractCommand) -> CommandContract<'static> {
     match command {
         ContractCommand::Canon => CommandContract {
             command: "canon",
             schema: "dataq.canon.output.v1",
+            supported_versions: &["dataq.canon.output.v1"],
             output_fields: NO_FIXED_ROOT_FIELDS,
-            exit_codes: exit_codes("validation mismatch is not used by this command"),
+            exit_codes: exit_codes(VALIDATION_MISMATCH_UNUSED),
             notes: CANON_NOTES,
         },
         ContractCommand::IngestApi => CommandContract {
             command: "ingest-api",
             schema: "dataq.ingest.api.output.v1",
+            supported_versions: &["dataq.ingest.api.output.v1"],
             output_fields: INGEST_API_FIELDS,
             exit_codes: exit_codes("`--expect-status` mismatch"),
             notes: INGEST_API_NOTES,
@@ -229,9 +980,10 @@ fn command_contract(command: ContractCommand) -> CommandContract<'static> {
         ContractCommand::Ingest => CommandContract {
             command: "ingest yaml-jobs",
             schema: "dataq.ingest.yaml_jobs.output.v1",
+            supported_versions: &["dataq.ingest.yaml_jobs.output.v1"],
             output_fields: NO_FIXED_ROOT_FIELDS,
             exit_codes: exit_codes_with_code_three(
-                "validation mismatch is not used by this command",
+                VALIDATION_MISMATCH_UNUSED,
                 INGEST_YAML_JOBS_EXIT_CODE_3,
             ),
             notes: INGEST_YAML_JOBS_NOTES,
@@ -239,6 +991,7 @@ fn command_contract(command: ContractCommand) -> CommandContract<'static> {
         ContractCommand::Assert => CommandContract {
             command: "assert",
             schema: "dataq.assert.output.v1",
+            supported_versions: &["dataq.assert.output.v1"],
             output_fields: ASSERT_FIELDS,
             exit_codes: exit_codes("validation mismatch against rules or JSON Schema"),
             notes: ASSERT_NOTES,
@@ -246,6 +999,7 @@ fn command_contract(command: ContractCommand) -> CommandContract<'static> {
         ContractCommand::GateSchema => CommandContract {
             command: "gate-schema",
             schema: "dataq.gate.schema.output.v1",
+            supported_versions: &["dataq.gate.schema.output.v1"],
             output_fields: ASSERT_FIELDS,
             exit_codes: exit_codes("validation mismatch against JSON Schema"),
             notes: GATE_SCHEMA_NOTES,
@@ -253,6 +1007,7 @@ fn command_contract(command: ContractCommand) -> CommandContract<'static> {
         ContractCommand::Gate => CommandContract {
             command: "gate",
             schema: "dataq.gate.policy.output.v1",
+            supported_versions: &["dataq.gate.policy.output.v1"],
             output_fields: GATE_FIELDS,
             exit_codes: exit_codes("policy violations detected"),
             notes: GATE_NOTES,
@@ -260,6 +1015,7 @@ fn command_contract(command: ContractCommand) -> CommandContract<'static> {
         ContractCommand::Sdiff => CommandContract {
             command: "sdiff",
             schema: "dataq.sdiff.output.v1",
+            supported_versions: &["dataq.sdiff.output.v1"],
             output_fields: SDIFF_FIELDS,
             exit_codes: exit_codes("diff detected when `--fail-on-diff` is enabled"),
             notes: SDIFF_NOTES,
@@ -267,6 +1023,7 @@ fn command_contract(command: ContractCommand) -> CommandContract<'static> {
         ContractCommand::DiffSource => CommandContract {
             command: "diff-source",
             schema: "dataq.diff.source.output.v1",
+            supported_versions: &["dataq.diff.source.output.v1"],
             output_fields: DIFF_SOURCE_FIELDS,
             exit_codes: exit_codes("diff detected when `--fail-on-diff` is enabled"),
             notes: DIFF_SOURCE_NOTES,
@@ -274,16 +1031,18 @@ fn command_contract(command: ContractCommand) -> CommandContract<'static> {
         ContractCommand::Profile => CommandContract {
             command: "profile",
             schema: "dataq.profile.output.v1",
+            supported_versions: &["dataq.profile.output.v1"],
             output_fields: PROFILE_FIELDS,
-            exit_codes: exit_codes("validation mismatch is not used by this command"),
+            exit_codes: exit_codes(VALIDATION_MISMATCH_UNUSED),
             notes: PROFILE_NOTES,
         },
         ContractCommand::IngestDoc => CommandContract {
             command: "ingest.doc",
             schema: "dataq.ingest.doc.output.v1",
+            supported_versions: &["dataq.ingest.doc.output.v1"],
             output_fields: INGEST_DOC_FIELDS,
             exit_codes: exit_codes_with_code_three(
-                "validation mismatch is not used by this command",
+                VALIDATION_MISMATCH_UNUSED,
                 "input/usage error or missing `pandoc`/`jq`",
             ),
             notes: INGEST_DOC_NOTES,
@@ -291,9 +1050,10 @@ fn command_contract(command: ContractCommand) -> CommandContract<'static> {
         ContractCommand::IngestNotes => CommandContract {
             command: "ingest.notes",
             schema: "dataq.ingest.notes.output.v1",
+            supported_versions: &["dataq.ingest.notes.output.v1"],
             output_fields: INGEST_NOTES_FIELDS,
             exit_codes: exit_codes_with_code_three(
-                "validation mismatch is not used by this command",
+                VALIDATION_MISMATCH_UNUSED,
                 "input/usage error or missing `nb`/`jq`",
             ),
             notes: INGEST_NOTES_NOTES,
@@ -301,9 +1061,10 @@ fn command_contract(command: ContractCommand) -> CommandContract<'static> {
         ContractCommand::IngestBook => CommandContract {
             command: "ingest-book",
             schema: "dataq.ingest.book.output.v1",
+            supported_versions: &["dataq.ingest.book.output.v1"],
             output_fields: INGEST_BOOK_FIELDS,
             exit_codes: exit_codes_with_code_three(
-                "validation mismatch is not used by this command",
+                VALIDATION_MISMATCH_UNUSED,
                 "input/usage error or missing `jq`/`mdbook`",
             ),
             notes: INGEST_BOOK_NOTES,
@@ -311,6 +1072,7 @@ fn command_contract(command: ContractCommand) -> CommandContract<'static> {
         ContractCommand::Scan => CommandContract {
             command: "scan",
             schema: "dataq.scan.text.output.v1",
+            supported_versions: &["dataq.scan.text.output.v1"],
             output_fields: SCAN_FIELDS,
             exit_codes: exit_codes("forbidden-pattern matches when `policy_mode` is enabled"),
             notes: SCAN_NOTES,
@@ -318,30 +1080,31 @@ fn command_contract(command: ContractCommand) -> CommandContract<'static> {
         ContractCommand::TransformRowset => CommandContract {
             command: "transform-rowset",
             schema: "dataq.transform.rowset.output.v1",
+            supported_versions: &["dataq.transform.rowset.output.v1"],
             output_fields: NO_FIXED_ROOT_FIELDS,
-            exit_codes: exit_codes("validation mismatch is not used by this command"),
+            exit_codes: exit_codes(VALIDATION_MISMATCH_UNUSED),
             notes: TRANSFORM_ROWSET_NOTES,
         },
         ContractCommand::Merge => CommandContract {
             command: "merge",
             schema: "dataq.merge.output.v1",
+            supported_versions: &["dataq.merge.output.v1"],
             output_fields: NO_FIXED_ROOT_FIELDS,
-            exit_codes: exit_codes("validation mismatch is not used by this command"),
+            exit_codes: exit_codes(VALIDATION_MISMATCH_UNUSED),
             notes: MERGE_NOTES,
         },
         ContractCommand::Doctor => CommandContract {
             command: "doctor",
             schema: "dataq.doctor.output.v1",
+            supported_versions: &["dataq.doctor.output.v1"],
             output_fields: DOCTOR_FIELDS,
-            exit_codes: exit_codes_with_code_three(
-                "validation mismatch is not used by this command",
-                DOCTOR_EXIT_CODE_3,
-            ),
+            exit_codes: exit_codes_with_code_three(VALIDATION_MISMATCH_UNUSED, DOCTOR_EXIT_CODE_3),
             notes: DOCTOR_NOTES,
         },
         ContractCommand::RecipeRun => CommandContract {
             command: "recipe-run",
-            schema: "dataq.recipe.run.output.v1",
+            schema: RECIPE_RUN_OUTPUT_V2,
+            supported_versions: &[RECIPE_RUN_OUTPUT_V1, RECIPE_RUN_OUTPUT_V2],
             output_fields: RECIPE_RUN_FIELDS,
             exit_codes: exit_codes("at least one step reported `matched=false`"),
             notes: RECIPE_RUN_NOTES,
@@ -349,10 +1112,19 @@ fn command_contract(command: ContractCommand) -> CommandContract<'static> {
         ContractCommand::RecipeLock => CommandContract {
             command: "recipe-lock",
             schema: "dataq.recipe.lock.output.v1",
+            supported_versions: &["dataq.recipe.lock.output.v1"],
             output_fields: RECIPE_LOCK_FIELDS,
-            exit_codes: exit_codes("validation mismatch is not used by this command"),
+            exit_codes: exit_codes(VALIDATION_MISMATCH_UNUSED),
             notes: RECIPE_LOCK_NOTES,
         },
+        ContractCommand::RecipeReplay => CommandContract {
+            command: "recipe-replay",
+            schema: RECIPE_REPLAY_OUTPUT_V2,
+            supported_versions: &[RECIPE_REPLAY_OUTPUT_V1, RECIPE_REPLAY_OUTPUT_V2],
+            output_fields: RECIPE_REPLAY_FIELDS,
+            exit_codes: exit_codes("strict lock mismatch or a step reported `matched=false`"),
+            notes: RECIPE_REPLAY_NOTES,
+        },
     }
 }
 
@@ -0,0 +1,333 @@
+use std::any::TypeId;
+use std::path::PathBuf;
+
+use clap::{Arg, ArgAction, ArgGroup, Command};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+/// Structured command response that carries exit-code mapping and JSON payload.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HelpCommandResponse {
+    pub exit_code: i32,
+    pub payload: Value,
+}
+
+/// Describes `root` (or the subcommand at `path`) as machine-readable JSON.
+///
+/// Global arguments are always listed at the top level so a single subcommand
+/// lookup still carries every flag it accepts.
+pub fn run(root: &Command, path: &[String]) -> HelpCommandResponse {
+    let mut target = root;
+    let mut prefix = Vec::new();
+    for segment in path {
+        match target
+            .get_subcommands()
+            .find(|command| command.get_name() == segment.as_str() && !command.is_hide_set())
+        {
+            Some(subcommand) => {
+                prefix.push(target.get_name().to_string());
+                target = subcommand;
+            }
+            None => {
+                return HelpCommandResponse {
+                    exit_code: 3,
+                    payload: json!({
+                        "error": "input_usage_error",
+                        "message": format!("unknown command `{}`", path.join(" ")),
+                    }),
+                };
+            }
+        }
+    }
+
+    let commands = if path.is_empty() {
+        root.get_subcommands()
+            .filter(|command| !command.is_hide_set())
+            .map(|command| describe_command(command, &[]))
+            .collect()
+    } else {
+        let parents = &prefix[1..];
+        vec![describe_command(target, parents)]
+    };
+
+    HelpCommandResponse {
+        exit_code: 0,
+        payload: json!({
+            "name": root.get_name(),
+            "version": root.get_version(),
+            "global_arguments": describe_arguments(root, true),
+            "commands": commands,
+        }),
+    }
+}
+
+/// Ordered pipeline step names used by `help --json` pipeline reports.
+pub fn pipeline_steps() -> Vec<String> {
+    vec![
+        "resolve_help_target".to_string(),
+        "describe_cli_arguments".to_string(),
+        "write_help_output".to_string(),
+    ]
+}
+
+/// Determinism guards planned for the `help` command.
+pub fn deterministic_guards() -> Vec<String> {
+    vec![
+        "rust_native_execution".to_string(),
+        "cli_definition_order".to_string(),
+    ]
+}
+
+fn describe_command(command: &Command, parents: &[String]) -> Value {
+    let mut names = parents.to_vec();
+    names.push(command.get_name().to_string());
+    let subcommands: Vec<Value> = command
+        .get_subcommands()
+        .filter(|subcommand| !subcommand.is_hide_set())
+        .map(|subcommand| describe_command(subcommand, &names))
+        .collect();
+
+    json!({
+        "command": names.join(" "),
+        "about": command.get_about().map(ToString::to_string),
+        "arguments": describe_arguments(command, false),
+        "groups": describe_groups(command),
+        "subcommand_required": command.is_subcommand_required_set(),
+        "args_conflict_with_subcommands": command.is_args_conflicts_with_subcommands_set(),
+        "subcommands": subcommands,
+    })
+}
+
+fn describe_arguments(command: &Command, global: bool) -> Vec<Value> {
+    visible_arguments(command)
+        .filter(|arg| arg.is_global_set() == global)
+        .map(|arg| describe_argument(command, arg))
+        .collect()
+}
+
+fn visible_arguments(command: &Command) -> impl Iterator<Item = &Arg> {
+    command.get_arguments().filter(|arg| !arg.is_hide_set())
+}
+
+fn describe_argument(command: &Command, arg: &Arg) -> Value {
+    let kind = argument_type(arg);
+    let values: Option<Vec<String>> = (kind == "enum").then(|| {
+        arg.get_possible_values()
+            .iter()
+            .filter(|value| !value.is_hide_set())
+            .map(|value| value.get_name().to_string())
+            .collect()
+    });
+    let default = match arg.get_default_values() {
+        [] if kind == "flag" => Value::Bool(false),
+        [] => Value::Null,
+        [single] => typed_default(kind, &single.to_string_lossy()),
+        many => Value::Array(
+            many.iter()
+                .map(|value| typed_default(kind, &value.to_string_lossy()))
+                .collect(),
+        ),
+    };
+
+    json!({
+        "name": arg.get_id().as_str(),
+        "long": arg.get_long().map(|long| format!("--{long}")),
+        "short": arg.get_short().map(|short| format!("-{short}")),
+        "positional": arg.is_positional(),
+        "value_name": value_name(arg, kind),
+        "type": kind,
+        "values": values,
+        "multiple": is_multiple(arg),
+        "required": arg.is_required_set(),
+        "default": default,
+        "conflicts_with": conflicting_arguments(command, arg),
+        "help": arg.get_help().map(ToString::to_string),
+    })
+}
+
+/// Maps the clap value parser onto a small JSON-friendly type vocabulary.
+fn argument_type(arg: &Arg) -> &'static str {
+    if matches!(
+        arg.get_action(),
+        ArgAction::SetTrue | ArgAction::SetFalse | ArgAction::Count
+    ) {
+        return "flag";
+    }
+    let parser = arg.get_value_parser().type_id();
+    if parser == TypeId::of::<bool>() {
+        "boolean"
+    } else if parser == TypeId::of::<PathBuf>() {
+        "path"
+    } else if [
+        TypeId::of::<usize>(),
+        TypeId::of::<u64>(),
+        TypeId::of::<u32>(),
+        TypeId::of::<i64>(),
+    ]
+    .into_iter()
+    .any(|id| parser == id)
+    {
+        "integer"
+    } else if parser == TypeId::of::<f64>() {
+        "number"
+    } else if !arg.get_possible_values().is_empty() {
+        "enum"
+    } else {
+        "string"
+    }
+}
+
+fn value_name(arg: &Arg, kind: &str) -> Option<String> {
+    if kind == "flag" {
+        return None;
+    }
+    Some(match arg.get_value_names() {
+        Some(names) => names
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" "),
+        None => arg.get_id().as_str().to_uppercase(),
+    })
+}
+
+fn is_multiple(arg: &Arg) -> bool {
+    matches!(arg.get_action(), ArgAction::Append)
+        || arg
+            .get_num_args()
+            .is_some_and(|range| range.max_values() > 1)
+}
+
+fn typed_default(kind: &str, raw: &str) -> Value {
+    match kind {
+        "flag" | "boolean" => raw.parse::<bool>().map_or_else(|_| json!(raw), Value::Bool),
+        "integer" => raw.parse::<i64>().map_or_else(|_| json!(raw), Value::from),
+        "number" => raw.parse::<f64>().map_or_else(|_| json!(raw), Value::from),
+        _ => json!(raw),
+    }
+}
+
+/// clap records conflicts on one side only; report them symmetrically in declaration order.
+fn conflicting_arguments(command: &Command, arg: &Arg) -> Vec<String> {
+    if arg.is_global_set() {
+        return Vec::new();
+    }
+    let own: Vec<&str> = command
+        .get_arg_conflicts_with(arg)
+        .into_iter()
+        .map(|other| other.get_id().as_str())
+        .collect();
+    visible_arguments(command)
+        .filter(|other| !other.is_global_set() && other.get_id() != arg.get_id())
+        .filter(|other| {
+            own.contains(&other.get_id().as_str())
+                || command
+                    .get_arg_conflicts_with(other)
+                    .iter()
+                    .any(|back| back.get_id() == arg.get_id())
+        })
+        .map(|other| other.get_id().as_str().to_string())
+        .collect()
+}
+
+/// Only groups that constrain usage are reported; clap derive also emits one
+/// unconstrained group per args struct.
+fn describe_groups(command: &Command) -> Vec<Value> {
+    command
+        .get_groups()
+        .filter_map(|group| {
+            let mut group: ArgGroup = group.clone();
+            let multiple = group.is_multiple();
+            let required = group.is_required_set();
+            if multiple && !required {
+                return None;
+            }
+            Some(json!({
+                "name": group.get_id().as_str(),
+                "args": group.get_args().map(|id| id.as_str()).collect::<Vec<_>>(),
+                "required": required,
+                "multiple": multiple,
+            }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{Arg, ArgAction, ArgGroup, Command};
+    use serde_json::json;
+
+    use super::run;
+
+    fn sample() -> Command {
+        Command::new("tool")
+            .version("1.0.0")
+            .arg(
+                Arg::new("verbose")
+                    .long("verbose")
+                    .global(true)
+                    .action(ArgAction::SetTrue),
+            )
+            .subcommand(
+                Command::new("pick")
+                    .about("Pick one.")
+                    .arg(
+                        Arg::new("mode")
+                            .long("mode")
+                            .value_parser(["fast", "slow"])
+                            .default_value("fast"),
+                    )
+                    .arg(
+                        Arg::new("input")
+                            .long("input")
+                            .value_parser(clap::value_parser!(std::path::PathBuf))
+                            .conflicts_with("stdin"),
+                    )
+                    .arg(Arg::new("stdin").long("stdin").action(ArgAction::SetTrue))
+                    .arg(
+                        Arg::new("limit")
+                            .long("limit")
+                            .value_parser(clap::value_parser!(usize))
+                            .default_value("10"),
+                    )
+                    .group(
+                        ArgGroup::new("source")
+                            .args(["input", "stdin"])
+                            .required(true),
+                    ),
+            )
+    }
+
+    #[test]
+    fn describes_types_defaults_groups_and_symmetric_conflicts() {
+        let response = run(&sample(), &["pick".to_string()]);
+        assert_eq!(response.exit_code, 0);
+        let payload = response.payload;
+        assert_eq!(payload["global_arguments"][0]["name"], json!("verbose"));
+        assert_eq!(payload["global_arguments"][0]["type"], json!("flag"));
+
+        let command = &payload["commands"][0];
+        assert_eq!(command["command"], json!("pick"));
+        let args = command["arguments"].as_array().expect("arguments");
+        assert_eq!(args[0]["type"], json!("enum"));
+        assert_eq!(args[0]["values"], json!(["fast", "slow"]));
+        assert_eq!(args[0]["default"], json!("fast"));
+        assert_eq!(args[1]["type"], json!("path"));
+        assert_eq!(args[1]["conflicts_with"], json!(["stdin"]));
+        assert_eq!(args[2]["conflicts_with"], json!(["input"]));
+        assert_eq!(args[2]["default"], json!(false));
+        assert_eq!(args[3]["type"], json!("integer"));
+        assert_eq!(args[3]["default"], json!(10));
+        assert_eq!(
+            command["groups"],
+            json!([{"name": "source", "args": ["input", "stdin"], "required": true, "multiple": false}])
+        );
+    }
+
+    #[test]
+    fn unknown_command_is_usage_error() {
+        let response = run(&sample(), &["missing".to_string()]);
+        assert_eq!(response.exit_code, 3);
+        assert_eq!(response.payload["error"], json!("input_usage_error"));
+    }
+}
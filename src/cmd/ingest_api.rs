@@ -5,6 +5,7 @@ use serde::Serialize;
 use serde_json::{Value, json};
 
 use crate::adapters::{jq, xh};
+use crate::cmd::stage_trace;
 use crate::domain::report::PipelineStageDiagnostic;
 use crate::util::time::normalize_rfc3339_utc;
 
@@ -156,6 +157,7 @@ fn execute(
     })?;
 
     trace.mark_tool_used("xh");
+    stage_trace::report_stage_start("ingest_api_xh_fetch");
     let fetched = match xh::fetch(&xh::XhRequest {
         url: args.url.clone(),
         method: args.method.into(),
@@ -203,6 +205,7 @@ fn execute(
     });
 
     trace.mark_tool_used("jq");
+    stage_trace::report_stage_start("ingest_api_jq_normalize");
     let payload = match jq::normalize_ingest_api_response(&raw_payload) {
         Ok(payload) => {
             trace
@@ -6,6 +6,7 @@ use serde::Serialize;
 use serde_json::{Value, json};
 
 use crate::adapters::{jq, mlr, yq};
+use crate::cmd::stage_trace;
 use crate::domain::ingest::IngestYamlJobsMode;
 use crate::domain::report::PipelineStageDiagnostic;
 use crate::engine::ingest;
@@ -117,6 +118,7 @@ fn execute<R: Read>(
     let mut trace = IngestYamlJobsPipelineTrace::default();
 
     trace.mark_tool_used("yq");
+    stage_trace::report_stage_start("ingest_yaml_jobs_yq_extract");
     let yq_input_rows = input_values.len();
     let yq_rows = match run_yq_stage(args.mode, &input_values) {
         Ok(rows) => {
@@ -165,6 +167,7 @@ fn execute<R: Read>(
     };
 
     trace.mark_tool_used("jq");
+    stage_trace::report_stage_start("ingest_yaml_jobs_jq_normalize");
     let jq_input_rows = yq_rows.len();
     let jq_rows = match run_jq_stage(args.mode, &yq_rows) {
         Ok(rows) => {
@@ -213,6 +216,7 @@ fn execute<R: Read>(
     };
 
     trace.mark_tool_used("mlr");
+    stage_trace::report_stage_start("ingest_yaml_jobs_mlr_shape");
     let mlr_input_rows = jq_rows.len();
     let mlr_rows = match run_mlr_stage(args.mode, &jq_rows) {
         Ok(rows) => {
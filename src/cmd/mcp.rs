@@ -1,15 +1,21 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
 use std::fs::File;
-use std::io::{Cursor, Read, Write};
+use std::io::{BufRead, BufReader, Cursor, Read, Write};
+use std::net::{TcpListener, TcpStream};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
 
 use serde_json::{Map, Value, json};
 
+use crate::adapters::process::{self, CancellationToken};
 use crate::cmd::{
     aggregate,
     r#assert::{self as assert_cmd, AssertInputNormalizeMode},
     canon, contract, diff, doctor, emit, gate, ingest, ingest_api, ingest_yaml_jobs, join, merge,
-    profile, recipe, scan, sdiff, transform,
+    profile, recipe, scan, sdiff, stage_trace, transform,
 };
 use crate::domain::ingest::IngestYamlJobsMode;
 use crate::domain::report::{
@@ -18,11 +24,17 @@ use crate::domain::report::{
 use crate::domain::rules::AssertRules;
 use crate::engine::aggregate::AggregateMetric;
 use crate::engine::r#assert as assert_engine;
-use crate::engine::canon::{CanonOptions, canonicalize_values};
+use crate::engine::canon::canonicalize_values;
+use crate::engine::canon::coerce::{CoerceMode, CoercePolicy};
+use crate::engine::canon::normalize::{KeyCase, NormalizeOptions, UnicodeForm, WhitespaceMode};
+use crate::engine::canon::time::{
+    TimeNormalization, TimeNormalizationArgs, resolve_time_normalization,
+};
 use crate::engine::ingest as ingest_engine;
 use crate::engine::ingest::IngestDocInputFormat;
 use crate::engine::join::JoinHow;
 use crate::engine::merge::MergePolicy;
+use crate::engine::recipe as recipe_engine;
 use crate::io::{self, Format};
 
 const JSONRPC_VERSION: &str = "2.0";
@@ -33,6 +45,32 @@ const JSONRPC_INVALID_REQUEST: i64 = -32600;
 const JSONRPC_METHOD_NOT_FOUND: i64 = -32601;
 const JSONRPC_INVALID_PARAMS: i64 = -32602;
 const JSONRPC_INTERNAL_ERROR: i64 = -32603;
+const MCP_RESOURCE_NOT_FOUND: i64 = -32002;
+const HTTP_ENDPOINT_PATH: &str = "/mcp";
+const HTTP_MAX_HEADER_BYTES: usize = 64 * 1024;
+const HTTP_MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+/// Time a client has to deliver its whole request, and to accept the response.
+const HTTP_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+/// Connections served at once by default; more are answered with `503`.
+const HTTP_MAX_CONNECTIONS: usize = 64;
+/// Worker threads running `tools/call` in a `--serve` session; further calls queue.
+const SERVE_MAX_CONCURRENT_TOOL_CALLS: usize = 8;
+const CONTRACT_ALL_RESOURCE_URI: &str = "dataq://contract/all";
+const ASSERT_RULES_HELP_RESOURCE_URI: &str = "dataq://assert/rules-help";
+const ASSERT_SCHEMA_HELP_RESOURCE_URI: &str = "dataq://assert/schema-help";
+const WORKSPACE_RESOURCE_URI_PREFIX: &str = "dataq://workspace/";
+const WORKSPACE_RESOURCE_MAX_BYTES: u64 = 1024 * 1024;
+const WORKSPACE_SKIPPED_DIRS: &[&str] = &["node_modules", "target"];
+/// Directory levels below the workspace root that resource discovery descends.
+const WORKSPACE_RESOURCE_MAX_DEPTH: usize = 8;
+/// Files resource discovery inspects before it stops walking the workspace.
+const WORKSPACE_RESOURCE_MAX_FILES: usize = 10_000;
+const PROMPT_ORDER: [&str; 3] = [
+    "dataq.gate_ci_workflow",
+    "dataq.explain_sdiff",
+    "dataq.draft_rules",
+];
+const ASSERT_RULES_KEYS: &[&str] = &["count", "extends", "fields", "forbid_keys", "required_keys"];
 const TOOL_ORDER: [&str; 23] = [
     "dataq.canon",
     "dataq.ingest.api",
@@ -91,14 +129,54 @@ struct LoadedValues {
     format: Option<Format>,
 }
 
-pub fn run_single_request<R: Read, W: Write>(mut input: R, mut output: W) -> i32 {
+/// Server settings for `dataq mcp`.
+#[derive(Debug, Clone, Default)]
+pub struct McpServerOptions {
+    roots: Vec<PathBuf>,
+    http_max_connections: Option<usize>,
+}
+
+impl McpServerOptions {
+    /// Canonicalizes the `--root` allowlist. With no roots, file access is unrestricted.
+    pub fn with_roots(roots: &[PathBuf]) -> Result<Self, String> {
+        let mut canonical_roots = Vec::with_capacity(roots.len());
+        for root in roots {
+            let canonical = root.canonicalize().map_err(|error| {
+                format!("failed to resolve `--root` `{}`: {error}", root.display())
+            })?;
+            if !canonical.is_dir() {
+                return Err(format!(
+                    "`--root` must be a directory: `{}`",
+                    root.display()
+                ));
+            }
+            canonical_roots.push(canonical);
+        }
+        Ok(Self {
+            roots: canonical_roots,
+            http_max_connections: None,
+        })
+    }
+
+    /// Overrides how many `--http` connections are served concurrently.
+    pub fn with_http_max_connections(mut self, limit: usize) -> Self {
+        self.http_max_connections = Some(limit);
+        self
+    }
+}
+
+pub fn run_single_request<R: Read, W: Write>(
+    mut input: R,
+    mut output: W,
+    options: &McpServerOptions,
+) -> i32 {
     let mut raw = Vec::new();
     if input.read_to_end(&mut raw).is_err() {
         return 3;
     }
 
     let response = match parse_request_bytes(&raw) {
-        Ok(request) => handle_request(request),
+        Ok(request) => handle_request(request, options),
         Err(error_response) => error_response,
     };
 
@@ -111,6 +189,652 @@ pub fn run_single_request<R: Read, W: Write>(mut input: R, mut output: W) -> i32
     0
 }
 
+/// Long-lived session that handles newline-delimited JSON-RPC messages until EOF.
+///
+/// Notifications (no `id`) get no response; a batch array is answered with one
+/// response array on a single line. `tools/call` runs on at most
+/// `SERVE_MAX_CONCURRENT_TOOL_CALLS` worker threads (later calls wait in
+/// arrival order) and sends `notifications/progress` per stage when
+/// `params._meta.progressToken` is set. `notifications/cancelled` stops the
+/// external tools of the matching call, and no response is sent for it.
+pub fn serve<R: BufRead, W: Write + Send>(
+    mut input: R,
+    output: W,
+    options: &McpServerOptions,
+) -> i32 {
+    let (sender, receiver) = mpsc::channel::<Value>();
+    let (call_sender, call_receiver) = mpsc::channel::<Value>();
+    let call_receiver = Mutex::new(call_receiver);
+    let in_flight = InFlightToolCalls::default();
+    std::thread::scope(|scope| {
+        let writer = scope.spawn(move || write_session_messages(output, receiver));
+        let mut workers = 0usize;
+        let mut line = Vec::new();
+        let mut read_failed = false;
+        loop {
+            line.clear();
+            match input.read_until(b'\n', &mut line) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(_) => {
+                    read_failed = true;
+                    break;
+                }
+            }
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            let parsed: Value = match serde_json::from_slice(&line) {
+                Ok(value) => value,
+                Err(_) => {
+                    let _ = sender.send(error_response(
+                        Value::Null,
+                        JSONRPC_PARSE_ERROR,
+                        "parse error",
+                    ));
+                    continue;
+                }
+            };
+            in_flight.apply_cancellations(&parsed);
+            if !in_flight.register_tool_calls(&parsed) {
+                if let Some(response) = handle_payload(parsed, options) {
+                    let _ = sender.send(response);
+                }
+                continue;
+            }
+
+            // The reader keeps going while calls queue, so cancellations are
+            // still seen for calls that are running or waiting.
+            let _ = call_sender.send(parsed);
+            if workers < SERVE_MAX_CONCURRENT_TOOL_CALLS {
+                workers += 1;
+                let sender = sender.clone();
+                let in_flight = &in_flight;
+                let call_receiver = &call_receiver;
+                scope.spawn(move || {
+                    while let Some(parsed) = next_queued_call(call_receiver) {
+                        if let Some(response) =
+                            handle_session_payload(parsed, options, in_flight, &sender)
+                        {
+                            let _ = sender.send(response);
+                        }
+                    }
+                });
+            }
+        }
+        drop(call_sender);
+        drop(sender);
+        let written = writer.join().unwrap_or(false);
+        if read_failed || !written { 3 } else { 0 }
+    })
+}
+
+/// Takes the next queued `tools/call` payload; `None` once the session input
+/// ended and the queue is drained.
+fn next_queued_call(queue: &Mutex<mpsc::Receiver<Value>>) -> Option<Value> {
+    queue
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .recv()
+        .ok()
+}
+
+fn write_session_messages<W: Write>(mut output: W, receiver: mpsc::Receiver<Value>) -> bool {
+    for message in receiver {
+        if serde_json::to_writer(&mut output, &message).is_err()
+            || output.write_all(b"\n").is_err()
+            || output.flush().is_err()
+        {
+            return false;
+        }
+    }
+    true
+}
+
+/// In-flight `tools/call` requests, keyed by request id.
+#[derive(Default)]
+struct InFlightToolCalls {
+    tokens: Mutex<HashMap<String, CancellationToken>>,
+}
+
+impl InFlightToolCalls {
+    /// Registers the `tools/call` requests in `payload`; `true` if there was any.
+    ///
+    /// Registering on the reader thread means a cancellation that arrives right
+    /// after the call is never missed.
+    fn register_tool_calls(&self, payload: &Value) -> bool {
+        let mut registered = false;
+        let mut tokens = self.lock();
+        for message in payload_messages(payload) {
+            if message.get("method").and_then(Value::as_str) != Some("tools/call") {
+                continue;
+            }
+            let Some(id) = message.get("id") else {
+                continue;
+            };
+            tokens.entry(request_id_key(id)).or_default();
+            registered = true;
+        }
+        registered
+    }
+
+    fn apply_cancellations(&self, payload: &Value) {
+        let tokens = self.lock();
+        for message in payload_messages(payload) {
+            if !is_notification(message)
+                || message.get("method").and_then(Value::as_str) != Some("notifications/cancelled")
+            {
+                continue;
+            }
+            let Some(request_id) = message.pointer("/params/requestId") else {
+                continue;
+            };
+            if let Some(token) = tokens.get(&request_id_key(request_id)) {
+                token.cancel();
+            }
+        }
+    }
+
+    fn token(&self, id: &Value) -> CancellationToken {
+        self.lock()
+            .get(&request_id_key(id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn finish(&self, id: &Value) {
+        self.lock().remove(&request_id_key(id));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CancellationToken>> {
+        self.tokens
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+fn payload_messages(payload: &Value) -> &[Value] {
+    match payload {
+        Value::Array(messages) => messages,
+        message => std::slice::from_ref(message),
+    }
+}
+
+/// Keys by the JSON text so that `1` and `"1"` stay distinct.
+fn request_id_key(id: &Value) -> String {
+    id.to_string()
+}
+
+fn handle_session_payload(
+    parsed: Value,
+    options: &McpServerOptions,
+    in_flight: &InFlightToolCalls,
+    sender: &mpsc::Sender<Value>,
+) -> Option<Value> {
+    match parsed {
+        Value::Array(messages) => {
+            let responses: Vec<Value> = messages
+                .into_iter()
+                .filter_map(|message| handle_session_message(message, options, in_flight, sender))
+                .collect();
+            if responses.is_empty() {
+                None
+            } else {
+                Some(Value::Array(responses))
+            }
+        }
+        message => handle_session_message(message, options, in_flight, sender),
+    }
+}
+
+fn handle_session_message(
+    message: Value,
+    options: &McpServerOptions,
+    in_flight: &InFlightToolCalls,
+    sender: &mpsc::Sender<Value>,
+) -> Option<Value> {
+    if is_notification(&message) {
+        return None;
+    }
+    let request = match parse_request_value(message) {
+        Ok(request) => request,
+        Err((id, code, message)) => return Some(error_response(id, code, message.as_str())),
+    };
+    if request.method != "tools/call" {
+        return Some(handle_request(request, options));
+    }
+
+    let id = request.id.clone();
+    let token = in_flight.token(&id);
+    let progress_token = request
+        .params
+        .get("_meta")
+        .and_then(|meta| meta.get("progressToken"))
+        .filter(|token| matches!(token, Value::String(_) | Value::Number(_)))
+        .cloned();
+    let response = process::with_cancellation(&token, || match progress_token {
+        Some(progress_token) => stage_trace::with_stage_progress(
+            progress_notifier(progress_token, sender.clone()),
+            || handle_request(request, options),
+        ),
+        None => handle_request(request, options),
+    });
+    in_flight.finish(&id);
+    if token.is_cancelled() {
+        None
+    } else {
+        Some(response)
+    }
+}
+
+fn progress_notifier(progress_token: Value, sender: mpsc::Sender<Value>) -> Box<dyn FnMut(&str)> {
+    let mut progress = 0u64;
+    Box::new(move |stage| {
+        progress += 1;
+        let _ = sender.send(json!({
+            "jsonrpc": JSONRPC_VERSION,
+            "method": "notifications/progress",
+            "params": {
+                "progressToken": progress_token,
+                "progress": progress,
+                "message": stage,
+            }
+        }));
+    })
+}
+
+/// Handles a single message or a batch array; `None` when nothing is answered.
+fn handle_payload(parsed: Value, options: &McpServerOptions) -> Option<Value> {
+    match parsed {
+        Value::Array(messages) if messages.is_empty() => Some(error_response(
+            Value::Null,
+            JSONRPC_INVALID_REQUEST,
+            "batch must not be empty",
+        )),
+        Value::Array(messages) => {
+            let responses: Vec<Value> = messages
+                .into_iter()
+                .filter_map(|message| handle_message(message, options))
+                .collect();
+            if responses.is_empty() {
+                None
+            } else {
+                Some(Value::Array(responses))
+            }
+        }
+        message => handle_message(message, options),
+    }
+}
+
+/// Streamable HTTP transport. A `POST` to `/mcp` is handled as JSON-RPC on a
+/// thread per connection, which is closed after one request and response.
+///
+/// At most `HTTP_MAX_CONNECTIONS` connections (or the configured override)
+/// are served at once; further connections get `503` without being read.
+pub fn serve_http(listener: TcpListener, options: McpServerOptions) -> i32 {
+    let max_connections = options.http_max_connections.unwrap_or(HTTP_MAX_CONNECTIONS);
+    let options = Arc::new(options);
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        if active.fetch_add(1, Ordering::SeqCst) >= max_connections {
+            active.fetch_sub(1, Ordering::SeqCst);
+            let _ = stream.set_write_timeout(Some(HTTP_READ_TIMEOUT));
+            let mut response = HttpResponse::empty(503, "Service Unavailable");
+            response.headers.push(("Retry-After", "1".to_string()));
+            let _ = response.write_to(&stream);
+            continue;
+        }
+        let slot = HttpConnectionSlot(Arc::clone(&active));
+        let options = Arc::clone(&options);
+        std::thread::spawn(move || {
+            let _slot = slot;
+            handle_http_connection(stream, &options);
+        });
+    }
+    0
+}
+
+/// Releases a connection slot when its handler thread ends, even on panic.
+struct HttpConnectionSlot(Arc<AtomicUsize>);
+
+impl Drop for HttpConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Reads from a socket with a timeout that shrinks to the time left until
+/// `deadline`, so a client trickling bytes cannot hold the connection open.
+struct DeadlineReader<'a> {
+    stream: &'a TcpStream,
+    deadline: std::time::Instant,
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self
+            .deadline
+            .checked_duration_since(std::time::Instant::now())
+            .filter(|remaining| !remaining.is_zero())
+            .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::TimedOut))?;
+        self.stream.set_read_timeout(Some(remaining))?;
+        let mut stream = self.stream;
+        stream.read(buf)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HttpRequest {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl HttpRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HttpResponse {
+    status: u16,
+    reason: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl HttpResponse {
+    fn empty(status: u16, reason: &'static str) -> Self {
+        Self {
+            status,
+            reason,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    fn json(status: u16, reason: &'static str, value: &Value) -> Self {
+        Self {
+            status,
+            reason,
+            headers: vec![("Content-Type", "application/json".to_string())],
+            body: value.to_string().into_bytes(),
+        }
+    }
+
+    fn event_stream(value: &Value) -> Self {
+        Self {
+            status: 200,
+            reason: "OK",
+            headers: vec![
+                ("Content-Type", "text/event-stream".to_string()),
+                ("Cache-Control", "no-cache".to_string()),
+            ],
+            body: format!("event: message\ndata: {value}\n\n").into_bytes(),
+        }
+    }
+
+    fn write_to<W: Write>(&self, mut output: W) -> std::io::Result<()> {
+        write!(output, "HTTP/1.1 {} {}\r\n", self.status, self.reason)?;
+        for (name, value) in &self.headers {
+            write!(output, "{name}: {value}\r\n")?;
+        }
+        write!(
+            output,
+            "Content-Length: {}\r\nConnection: close\r\n\r\n",
+            self.body.len()
+        )?;
+        output.write_all(&self.body)?;
+        output.flush()
+    }
+}
+
+fn handle_http_connection(stream: TcpStream, options: &McpServerOptions) {
+    let _ = stream.set_write_timeout(Some(HTTP_READ_TIMEOUT));
+    let mut reader = BufReader::new(DeadlineReader {
+        stream: &stream,
+        deadline: std::time::Instant::now() + HTTP_READ_TIMEOUT,
+    });
+    let response = match read_http_request(&mut reader) {
+        Ok(request) => match route_http_request(&request, options) {
+            HttpReply::Complete(response) => response,
+            HttpReply::StreamProgress {
+                request,
+                progress_token,
+            } => {
+                let _ = stream_tool_call_progress(&stream, request, progress_token, options);
+                return;
+            }
+        },
+        Err(response) => response,
+    };
+    let _ = response.write_to(&stream);
+}
+
+/// How a routed HTTP request is answered.
+enum HttpReply {
+    Complete(HttpResponse),
+    /// A single `tools/call` carrying `params._meta.progressToken` from a
+    /// client that accepts `text/event-stream`.
+    StreamProgress {
+        request: JsonRpcRequest,
+        progress_token: Value,
+    },
+}
+
+/// Runs a `tools/call` and streams each `notifications/progress` as an SSE
+/// `message` event while it runs, followed by the response event. The body
+/// ends when the connection closes.
+fn stream_tool_call_progress(
+    stream: &TcpStream,
+    request: JsonRpcRequest,
+    progress_token: Value,
+    options: &McpServerOptions,
+) -> std::io::Result<()> {
+    let mut output = stream;
+    output.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+    )?;
+    output.flush()?;
+
+    let (sender, receiver) = mpsc::channel::<Value>();
+    std::thread::scope(|scope| {
+        let writer = scope.spawn(move || -> std::io::Result<()> {
+            for message in receiver {
+                write!(output, "event: message\ndata: {message}\n\n")?;
+                output.flush()?;
+            }
+            Ok(())
+        });
+        let response = stage_trace::with_stage_progress(
+            progress_notifier(progress_token, sender.clone()),
+            || handle_request(request, options),
+        );
+        let _ = sender.send(response);
+        drop(sender);
+        writer
+            .join()
+            .unwrap_or_else(|_| Err(std::io::Error::other("SSE writer panicked")))
+    })
+}
+
+fn read_http_request<R: BufRead>(reader: &mut R) -> Result<HttpRequest, HttpResponse> {
+    let bad_request = || HttpResponse::empty(400, "Bad Request");
+    let mut header_bytes = 0;
+    let mut read_line = |reader: &mut R| -> Result<String, HttpResponse> {
+        let mut line = Vec::new();
+        let read = reader
+            .take((HTTP_MAX_HEADER_BYTES - header_bytes) as u64 + 1)
+            .read_until(b'\n', &mut line)
+            .map_err(|_| bad_request())?;
+        header_bytes += read;
+        if header_bytes > HTTP_MAX_HEADER_BYTES {
+            return Err(HttpResponse::empty(431, "Request Header Fields Too Large"));
+        }
+        if !line.ends_with(b"\n") {
+            return Err(bad_request());
+        }
+        String::from_utf8(line)
+            .map(|line| line.trim_end_matches(['\r', '\n']).to_string())
+            .map_err(|_| bad_request())
+    };
+
+    let request_line = read_line(reader)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(bad_request());
+    };
+    let path = target.split('?').next().unwrap_or_default().to_string();
+    let method = method.to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(bad_request());
+        };
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+
+    let mut request = HttpRequest {
+        method,
+        path,
+        headers,
+        body: Vec::new(),
+    };
+    if request.header("Transfer-Encoding").is_some() {
+        return Err(HttpResponse::empty(411, "Length Required"));
+    }
+    let content_length = match request.header("Content-Length") {
+        None => 0,
+        Some(value) => value.parse::<usize>().map_err(|_| bad_request())?,
+    };
+    if content_length > HTTP_MAX_BODY_BYTES {
+        return Err(HttpResponse::empty(413, "Payload Too Large"));
+    }
+    request.body = vec![0; content_length];
+    reader
+        .read_exact(&mut request.body)
+        .map_err(|_| bad_request())?;
+    Ok(request)
+}
+
+fn route_http_request(request: &HttpRequest, options: &McpServerOptions) -> HttpReply {
+    if request.path != HTTP_ENDPOINT_PATH {
+        return HttpReply::Complete(HttpResponse::empty(404, "Not Found"));
+    }
+    if !is_local_origin(request.header("Origin")) {
+        return HttpReply::Complete(HttpResponse::empty(403, "Forbidden"));
+    }
+    if request.method != "POST" {
+        let mut response = HttpResponse::empty(405, "Method Not Allowed");
+        response.headers.push(("Allow", "POST".to_string()));
+        return HttpReply::Complete(response);
+    }
+    if !request
+        .header("Content-Type")
+        .is_some_and(|value| value.to_ascii_lowercase().starts_with("application/json"))
+    {
+        return HttpReply::Complete(HttpResponse::empty(415, "Unsupported Media Type"));
+    }
+    let accept = request
+        .header("Accept")
+        .unwrap_or("*/*")
+        .to_ascii_lowercase();
+    let accepts_json = ["application/json", "application/*", "*/*"]
+        .iter()
+        .any(|media| accept.contains(media));
+    let accepts_event_stream = accept.contains("text/event-stream");
+    if !accepts_json && !accepts_event_stream {
+        return HttpReply::Complete(HttpResponse::empty(406, "Not Acceptable"));
+    }
+
+    let parsed: Value = match serde_json::from_slice(&request.body) {
+        Ok(value) => value,
+        Err(_) => {
+            return HttpReply::Complete(HttpResponse::json(
+                400,
+                "Bad Request",
+                &error_response(Value::Null, JSONRPC_PARSE_ERROR, "parse error"),
+            ));
+        }
+    };
+    if accepts_event_stream {
+        if let Some(progress_token) = tool_call_progress_token(&parsed) {
+            if let Ok(request) = parse_request_value(parsed.clone()) {
+                return HttpReply::StreamProgress {
+                    request,
+                    progress_token,
+                };
+            }
+        }
+    }
+    HttpReply::Complete(match handle_payload(parsed, options) {
+        None => HttpResponse::empty(202, "Accepted"),
+        Some(response) if accepts_json => HttpResponse::json(200, "OK", &response),
+        Some(response) => HttpResponse::event_stream(&response),
+    })
+}
+
+/// `params._meta.progressToken` of a single `tools/call` request, if set.
+fn tool_call_progress_token(message: &Value) -> Option<Value> {
+    if message.get("method").and_then(Value::as_str) != Some("tools/call") {
+        return None;
+    }
+    message
+        .pointer("/params/_meta/progressToken")
+        .filter(|token| matches!(token, Value::String(_) | Value::Number(_)))
+        .cloned()
+}
+
+/// Allows only loopback browser `Origin`s, to defeat DNS rebinding.
+fn is_local_origin(origin: Option<&str>) -> bool {
+    let Some(origin) = origin else {
+        return true;
+    };
+    let Some((_, rest)) = origin.split_once("://") else {
+        return false;
+    };
+    let authority = rest.split('/').next().unwrap_or_default();
+    let host = if authority.starts_with('[') {
+        authority
+            .split_once(']')
+            .map_or(authority, |(host, _)| &host[1..])
+    } else {
+        authority.split(':').next().unwrap_or_default()
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "::1")
+}
+
+fn handle_message(message: Value, options: &McpServerOptions) -> Option<Value> {
+    if is_notification(&message) {
+        return None;
+    }
+    Some(match parse_request_value(message) {
+        Ok(request) => handle_request(request, options),
+        Err((id, code, message)) => error_response(id, code, message.as_str()),
+    })
+}
+
+fn is_notification(message: &Value) -> bool {
+    message.as_object().is_some_and(|object| {
+        !object.contains_key("id") && object.get("method").is_some_and(Value::is_string)
+    })
+}
+
 fn parse_request_bytes(raw: &[u8]) -> Result<JsonRpcRequest, Value> {
     let parsed: Value = match serde_json::from_slice(raw) {
         Ok(value) => value,
@@ -206,16 +930,21 @@ fn parse_request_value(value: Value) -> Result<JsonRpcRequest, (Value, i64, Stri
     Ok(JsonRpcRequest { id, method, params })
 }
 
-fn handle_request(request: JsonRpcRequest) -> Value {
+fn handle_request(request: JsonRpcRequest, options: &McpServerOptions) -> Value {
     match request.method.as_str() {
         "initialize" => success_response(request.id, initialize_result()),
         "tools/list" => success_response(request.id, tools_list_result()),
-        "tools/call" => handle_tools_call(request.id, request.params),
+        "tools/call" => handle_tools_call(request.id, request.params, options),
+        "resources/list" => success_response(request.id, resources_list_result(options)),
+        "resources/read" => handle_resources_read(request.id, request.params, options),
+        "prompts/list" => success_response(request.id, prompts_list_result()),
+        "prompts/get" => handle_prompts_get(request.id, request.params),
+        "ping" => success_response(request.id, json!({})),
         _ => error_response(request.id, JSONRPC_METHOD_NOT_FOUND, "method not found"),
     }
 }
 
-fn handle_tools_call(id: Value, params: Map<String, Value>) -> Value {
+fn handle_tools_call(id: Value, params: Map<String, Value>, options: &McpServerOptions) -> Value {
     let Some(name) = params.get("name").and_then(Value::as_str) else {
         return error_response(
             id,
@@ -235,7 +964,9 @@ fn handle_tools_call(id: Value, params: Map<String, Value>) -> Value {
         }
     };
     let alias_warnings = deprecated_alias_warnings(name, &arguments);
-    if let Some(execution) = validate_tool_arguments(name, &arguments) {
+    if let Some(execution) = validate_tool_arguments(name, &arguments)
+        .or_else(|| sandbox_tool_arguments(name, &arguments, &options.roots))
+    {
         return success_response(id, tool_call_result(execution, alias_warnings));
     }
 
@@ -290,12 +1021,28 @@ fn execute_canon(args: &Map<String, Value>) -> ToolExecution {
         Ok(value) => value,
         Err(message) => return input_usage_error(message),
     };
+    let coerce = match parse_canon_coerce_policy(args) {
+        Ok(value) => value,
+        Err(message) => return input_usage_error(message),
+    };
+    let normalize = match parse_canon_normalize_options(args) {
+        Ok(value) => value,
+        Err(message) => return input_usage_error(message),
+    };
+    let time = match parse_canon_time_normalization(args) {
+        Ok(value) => value,
+        Err(message) => return input_usage_error(message),
+    };
     let from = match parse_optional_format(args, &["from"], "from") {
         Ok(value) => value,
         Err(message) => return input_usage_error(message),
     };
-    if let Err(message) = parse_optional_format(args, &["to"], "to") {
-        return input_usage_error(message);
+    match parse_optional_string(args, &["to"], "to") {
+        Ok(Some(to)) if !matches!(to.as_str(), "json" | "jsonl") => {
+            return input_usage_error("`to` must be `json` or `jsonl`");
+        }
+        Ok(_) => {}
+        Err(message) => return input_usage_error(message),
     }
 
     let input = match parse_value_input(
@@ -315,13 +1062,17 @@ fn execute_canon(args: &Map<String, Value>) -> ToolExecution {
         Err(message) => return input_usage_error(message),
     };
 
-    let canonical = canonicalize_values(
-        loaded.values,
-        CanonOptions {
-            sort_keys,
-            normalize_time,
-        },
-    );
+    let options = canon::CanonCommandOptions {
+        sort_keys,
+        normalize_time,
+        coerce,
+        normalize,
+        time,
+    };
+    let canonical = match canonicalize_values(loaded.values, &options.clone().into()) {
+        Ok(canonical) => canonical,
+        Err(error) => return input_usage_error(format!("failed to normalize timestamp: {error}")),
+    };
 
     let mut execution = ToolExecution {
         exit_code: 0,
@@ -334,10 +1085,7 @@ fn execute_canon(args: &Map<String, Value>) -> ToolExecution {
             "canon",
             PipelineInput::new(vec![pipeline_source("input", &input, loaded.format)]),
             canon::pipeline_steps(),
-            canon::deterministic_guards(canon::CanonCommandOptions {
-                sort_keys,
-                normalize_time,
-            }),
+            canon::deterministic_guards(&options),
         );
         execution.pipeline = pipeline_as_value(pipeline).ok();
     }
@@ -345,6 +1093,77 @@ fn execute_canon(args: &Map<String, Value>) -> ToolExecution {
     execution
 }
 
+fn parse_canon_coerce_policy(args: &Map<String, Value>) -> Result<CoercePolicy, String> {
+    let mode = match parse_optional_string(args, &["coerce"], "coerce")? {
+        Some(raw) => CoerceMode::parse_cli_name(raw.as_str())
+            .ok_or_else(|| "`coerce` must be `none`, `all`, or `schema`".to_string())?,
+        None => CoerceMode::All,
+    };
+    let exempt_paths = parse_string_list(args, &["coerce_exempt"], "coerce_exempt")?;
+    let schema_path = parse_optional_path(args, &["coerce_schema_path"], "coerce_schema_path")?;
+    let rules_path = parse_optional_path(args, &["coerce_rules_path"], "coerce_rules_path")?;
+    let source = match (schema_path.as_deref(), rules_path.as_deref()) {
+        (Some(_), Some(_)) => {
+            return Err(
+                "`coerce_schema_path` and `coerce_rules_path` are mutually exclusive".to_string(),
+            );
+        }
+        (Some(path), None) => Some(canon::CoerceSchemaSource::JsonSchema(path)),
+        (None, Some(path)) => Some(canon::CoerceSchemaSource::Rules(path)),
+        (None, None) => None,
+    };
+    canon::resolve_coerce_policy(mode, &exempt_paths, source)
+}
+
+fn parse_canon_normalize_options(args: &Map<String, Value>) -> Result<NormalizeOptions, String> {
+    let key_case = parse_optional_string(args, &["key_case"], "key_case")?
+        .map(|raw| {
+            KeyCase::parse_cli_name(raw.as_str())
+                .ok_or_else(|| "`key_case` must be `snake`, `camel`, or `kebab`".to_string())
+        })
+        .transpose()?;
+    let whitespace = parse_optional_string(args, &["whitespace"], "whitespace")?
+        .map(|raw| {
+            WhitespaceMode::parse_cli_name(raw.as_str())
+                .ok_or_else(|| "`whitespace` must be `trim` or `collapse`".to_string())
+        })
+        .transpose()?;
+    let unicode = parse_optional_string(args, &["unicode"], "unicode")?
+        .map(|raw| {
+            UnicodeForm::parse_cli_name(raw.as_str())
+                .ok_or_else(|| "`unicode` must be `nfc` or `nfkc`".to_string())
+        })
+        .transpose()?;
+    let drop_nulls = parse_bool(args, &["drop_nulls"], false, "drop_nulls")?;
+    let drop_empty = parse_bool(args, &["drop_empty"], false, "drop_empty")?;
+    let sort_array = parse_string_list(args, &["sort_array"], "sort_array")?;
+    Ok(NormalizeOptions {
+        key_case,
+        whitespace,
+        unicode,
+        drop_nulls,
+        drop_empty,
+        sort_array_paths: canon::parse_sort_array_paths(&sort_array)?,
+    })
+}
+
+fn parse_canon_time_normalization(
+    args: &Map<String, Value>,
+) -> Result<Option<TimeNormalization>, String> {
+    let paths = parse_string_list(args, &["time_path"], "time_path")?;
+    let formats = parse_string_list(args, &["time_format"], "time_format")?;
+    let zone = parse_optional_string(args, &["time_zone"], "time_zone")?;
+    let precision = parse_optional_string(args, &["time_precision"], "time_precision")?;
+    let target = parse_optional_string(args, &["time_target"], "time_target")?;
+    resolve_time_normalization(TimeNormalizationArgs {
+        paths: &paths,
+        formats: &formats,
+        zone: zone.as_deref(),
+        precision: precision.as_deref(),
+        target: target.as_deref(),
+    })
+}
+
 fn execute_ingest_api(args: &Map<String, Value>) -> ToolExecution {
     let emit_pipeline = match parse_emit_pipeline(args) {
         Ok(value) => value,
@@ -1828,16 +2647,32 @@ fn execute_contract(args: &Map<String, Value>) -> ToolExecution {
         Err(message) => return input_usage_error(message),
     };
 
-    let response = if all || command.is_none() {
-        contract::run_all()
-    } else if let Some(command) = command {
-        let command = match contract_command_from_str(command.as_str()) {
-            Ok(command) => command,
-            Err(message) => return input_usage_error(message),
-        };
-        contract::run_for_command(command)
-    } else {
-        contract::run_all()
+    let json_schema = match parse_optional_string(args, &["format"], "format") {
+        Ok(None) => false,
+        Ok(Some(format)) => match format.as_str() {
+            "contract" => false,
+            "json-schema" => true,
+            _ => {
+                return input_usage_error("`format` must be one of `contract`, `json-schema`");
+            }
+        },
+        Err(message) => return input_usage_error(message),
+    };
+
+    let response = match command.filter(|_| !all) {
+        Some(command) => {
+            let command = match contract_command_from_str(command.as_str()) {
+                Ok(command) => command,
+                Err(message) => return input_usage_error(message),
+            };
+            if json_schema {
+                contract::run_json_schema_for_command(command)
+            } else {
+                contract::run_for_command(command)
+            }
+        }
+        None if json_schema => contract::run_json_schema_all(),
+        None => contract::run_all(),
     };
 
     let mut execution = ToolExecution {
@@ -1928,10 +2763,27 @@ fn execute_recipe_run(args: &Map<String, Value>) -> ToolExecution {
         Err(message) => return input_usage_error(message),
     };
 
+    let params = match parse_string_list(args, &["param", "params"], "param") {
+        Ok(value) => value,
+        Err(message) => return input_usage_error(message),
+    };
+
+    let cache_dir = match parse_optional_path(args, &["cache_dir"], "cache_dir") {
+        Ok(value) => value,
+        Err(message) => return input_usage_error(message),
+    };
+    let contract_version = match parse_contract_version(args, contract::ContractCommand::RecipeRun)
+    {
+        Ok(value) => value,
+        Err(message) => return input_usage_error(message),
+    };
+
     let (response, trace) = recipe::run_with_trace(&recipe::RecipeCommandArgs {
         file_path: file_path.clone(),
         recipe: inline_recipe,
         base_dir,
+        params,
+        cache_dir,
     });
 
     let mut execution = ToolExecution {
@@ -1939,6 +2791,11 @@ fn execute_recipe_run(args: &Map<String, Value>) -> ToolExecution {
         payload: response.payload,
         pipeline: None,
     };
+    shape_recipe_payload(
+        &mut execution,
+        contract::ContractCommand::RecipeRun,
+        contract_version,
+    );
 
     if emit_pipeline {
         let steps = if trace.steps.is_empty() {
@@ -1988,9 +2845,18 @@ fn execute_recipe_lock(args: &Map<String, Value>) -> ToolExecution {
         Err(message) => return input_usage_error(message),
     };
 
+    let params = match parse_string_list(args, &["param", "params"], "param") {
+        Ok(value) => value,
+        Err(message) => return input_usage_error(message),
+    };
+    if let Err(message) = parse_contract_version(args, contract::ContractCommand::RecipeLock) {
+        return input_usage_error(message);
+    }
+
     let (response, trace, serialized_lock) =
         recipe::lock_with_trace(&recipe::RecipeLockCommandArgs {
             file_path: file_path.clone(),
+            params,
         });
 
     let mut execution = ToolExecution {
@@ -2002,7 +2868,9 @@ fn execute_recipe_lock(args: &Map<String, Value>) -> ToolExecution {
     if execution.exit_code == 0 {
         if let Some(out_path) = out_path {
             if let Some(serialized_lock) = serialized_lock {
-                if let Err(error) = std::fs::write(&out_path, serialized_lock.as_slice()) {
+                if let Err(error) =
+                    io::writer::write_bytes_to_path(&out_path, serialized_lock.as_slice())
+                {
                     let message = format!(
                         "failed to write recipe lock file `{}`: {error}",
                         out_path.display()
@@ -2076,10 +2944,22 @@ fn execute_recipe_replay(args: &Map<String, Value>) -> ToolExecution {
         Err(message) => return input_usage_error(message),
     };
 
+    let params = match parse_string_list(args, &["param", "params"], "param") {
+        Ok(value) => value,
+        Err(message) => return input_usage_error(message),
+    };
+
+    let contract_version =
+        match parse_contract_version(args, contract::ContractCommand::RecipeReplay) {
+            Ok(value) => value,
+            Err(message) => return input_usage_error(message),
+        };
+
     let (response, trace) = recipe::replay_with_trace(&recipe::RecipeReplayCommandArgs {
         file_path: file_path.clone(),
         lock_path: lock_path.clone(),
         strict,
+        params,
     });
 
     let mut execution = ToolExecution {
@@ -2087,6 +2967,11 @@ fn execute_recipe_replay(args: &Map<String, Value>) -> ToolExecution {
         payload: response.payload,
         pipeline: None,
     };
+    shape_recipe_payload(
+        &mut execution,
+        contract::ContractCommand::RecipeReplay,
+        contract_version,
+    );
 
     if emit_pipeline {
         let steps = if trace.steps.is_empty() {
@@ -2131,6 +3016,13 @@ fn initialize_result() -> Value {
         "capabilities": {
             "tools": {
                 "listChanged": false
+            },
+            "resources": {
+                "subscribe": false,
+                "listChanged": false
+            },
+            "prompts": {
+                "listChanged": false
             }
         },
         "serverInfo": {
@@ -2148,6 +3040,566 @@ fn tools_list_result() -> Value {
     json!({ "tools": tools })
 }
 
+/// Static resources (contracts, help) followed by the workspace recipe and
+/// rules files in relative path order.
+fn resources_list_result(options: &McpServerOptions) -> Value {
+    let mut resources = vec![
+        resource_entry(
+            CONTRACT_ALL_RESOURCE_URI,
+            "contract --all",
+            "Output contracts for every dataq subcommand",
+            "application/json",
+        ),
+        resource_entry(
+            ASSERT_RULES_HELP_RESOURCE_URI,
+            "assert --rules-help",
+            "Machine-readable help for assert rules files",
+            "application/json",
+        ),
+        resource_entry(
+            ASSERT_SCHEMA_HELP_RESOURCE_URI,
+            "assert --schema-help",
+            "Machine-readable help for assert JSON Schema mode",
+            "application/json",
+        ),
+    ];
+
+    if let Ok(root) = workspace_resource_root(options) {
+        for file in discover_workspace_resources(
+            &root,
+            WORKSPACE_RESOURCE_MAX_DEPTH,
+            WORKSPACE_RESOURCE_MAX_FILES,
+        ) {
+            let description = match file.kind {
+                WorkspaceResourceKind::Recipe => "dataq recipe file",
+                WorkspaceResourceKind::Rules => "dataq assert rules file",
+            };
+            resources.push(resource_entry(
+                &format!("{WORKSPACE_RESOURCE_URI_PREFIX}{}", file.relative),
+                &file.relative,
+                description,
+                file.mime_type,
+            ));
+        }
+    }
+
+    json!({ "resources": resources })
+}
+
+fn resource_entry(uri: &str, name: &str, description: &str, mime_type: &str) -> Value {
+    json!({
+        "uri": uri,
+        "name": name,
+        "description": description,
+        "mimeType": mime_type,
+    })
+}
+
+fn handle_resources_read(
+    id: Value,
+    params: Map<String, Value>,
+    options: &McpServerOptions,
+) -> Value {
+    let Some(uri) = params.get("uri").and_then(Value::as_str) else {
+        return error_response(id, JSONRPC_INVALID_PARAMS, "`params.uri` must be a string");
+    };
+
+    let static_payload = match uri {
+        CONTRACT_ALL_RESOURCE_URI => Some(contract::run_all().payload),
+        ASSERT_RULES_HELP_RESOURCE_URI => Some(assert_cmd::rules_help_payload()),
+        ASSERT_SCHEMA_HELP_RESOURCE_URI => Some(assert_cmd::schema_help_payload()),
+        _ => None,
+    };
+    let (mime_type, text) = match static_payload {
+        Some(payload) => match serde_json::to_string(&payload) {
+            Ok(text) => ("application/json", text),
+            Err(error) => {
+                return error_response(
+                    id,
+                    JSONRPC_INTERNAL_ERROR,
+                    &format!("failed to serialize resource: {error}"),
+                );
+            }
+        },
+        None => match read_workspace_resource(uri, options) {
+            Some(resource) => resource,
+            None => {
+                return error_response(
+                    id,
+                    MCP_RESOURCE_NOT_FOUND,
+                    &format!("resource not found: `{uri}`"),
+                );
+            }
+        },
+    };
+
+    success_response(
+        id,
+        json!({
+            "contents": [{
+                "uri": uri,
+                "mimeType": mime_type,
+                "text": text,
+            }]
+        }),
+    )
+}
+
+/// Re-resolves and re-classifies the URI so only files `resources/list` would
+/// publish can be read.
+fn read_workspace_resource(
+    uri: &str,
+    options: &McpServerOptions,
+) -> Option<(&'static str, String)> {
+    let relative = uri.strip_prefix(WORKSPACE_RESOURCE_URI_PREFIX)?;
+    let relative_path = Path::new(relative);
+    if relative.is_empty()
+        || !relative_path
+            .components()
+            .all(|component| matches!(component, std::path::Component::Normal(_)))
+        || relative_path.components().count() > WORKSPACE_RESOURCE_MAX_DEPTH + 1
+    {
+        return None;
+    }
+
+    let root = workspace_resource_root(options).ok()?;
+    let path = root.join(relative_path);
+    if path
+        .ancestors()
+        .take_while(|ancestor| *ancestor != root.as_path())
+        .any(is_skipped_workspace_entry)
+    {
+        return None;
+    }
+    let file = classify_workspace_file(&root, &path)?;
+    let text = std::fs::read_to_string(&path).ok()?;
+    Some((file.mime_type, text))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WorkspaceResourceKind {
+    Recipe,
+    Rules,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct WorkspaceResourceFile {
+    relative: String,
+    kind: WorkspaceResourceKind,
+    mime_type: &'static str,
+}
+
+/// The workspace is the first `--root`, or the current directory without one.
+fn workspace_resource_root(options: &McpServerOptions) -> std::io::Result<PathBuf> {
+    match options.roots.first() {
+        Some(root) => Ok(root.clone()),
+        None => std::env::current_dir()?.canonicalize(),
+    }
+}
+
+/// Walks the workspace and returns its recipe and rules files in relative path
+/// order. Hidden entries, build output and symlinks are not followed.
+///
+/// The walk is breadth-first in sorted order, descends at most `max_depth`
+/// directory levels and stops after inspecting `max_files` files, so shallow
+/// files win when a large tree hits the cap.
+fn discover_workspace_resources(
+    root: &Path,
+    max_depth: usize,
+    max_files: usize,
+) -> Vec<WorkspaceResourceFile> {
+    let mut found = Vec::new();
+    let mut inspected = 0;
+    let mut pending = VecDeque::from([(root.to_path_buf(), 0)]);
+    'walk: while let Some((dir, depth)) = pending.pop_front() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect();
+        paths.sort();
+        for path in paths {
+            if is_skipped_workspace_entry(&path) {
+                continue;
+            }
+            let Ok(metadata) = std::fs::symlink_metadata(&path) else {
+                continue;
+            };
+            if metadata.is_dir() {
+                if depth < max_depth {
+                    pending.push_back((path, depth + 1));
+                }
+                continue;
+            }
+            if inspected == max_files {
+                break 'walk;
+            }
+            inspected += 1;
+            if let Some(file) = classify_workspace_file(root, &path) {
+                found.push(file);
+            }
+        }
+    }
+    found.sort_by(|left, right| left.relative.cmp(&right.relative));
+    found
+}
+
+fn is_skipped_workspace_entry(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.') || WORKSPACE_SKIPPED_DIRS.contains(&name))
+}
+
+fn classify_workspace_file(root: &Path, path: &Path) -> Option<WorkspaceResourceFile> {
+    let metadata = std::fs::symlink_metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > WORKSPACE_RESOURCE_MAX_BYTES {
+        return None;
+    }
+    let format = io::resolve_input_format(None, Some(path)).ok()?;
+    let mime_type = match format {
+        Format::Json => "application/json",
+        Format::Yaml => "application/yaml",
+        _ => return None,
+    };
+
+    let file = File::open(path).ok()?;
+    let mut values = io::reader::read_values(file, format).ok()?;
+    if values.len() != 1 {
+        return None;
+    }
+    let kind = classify_workspace_value(values.remove(0))?;
+
+    let relative = path
+        .strip_prefix(root)
+        .ok()?
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?
+        .join("/");
+    Some(WorkspaceResourceFile {
+        relative,
+        kind,
+        mime_type,
+    })
+}
+
+fn classify_workspace_value(value: Value) -> Option<WorkspaceResourceKind> {
+    let object = value.as_object()?;
+    if object.get("version").and_then(Value::as_str) == Some(recipe_engine::RECIPE_VERSION) {
+        return Some(WorkspaceResourceKind::Recipe);
+    }
+    if !object.is_empty()
+        && object
+            .keys()
+            .all(|key| ASSERT_RULES_KEYS.contains(&key.as_str()))
+        && serde_json::from_value::<AssertRules>(value).is_ok()
+    {
+        return Some(WorkspaceResourceKind::Rules);
+    }
+    None
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PromptSpec {
+    description: &'static str,
+    arguments: &'static [PromptArgumentSpec],
+    tools: &'static [&'static str],
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PromptArgumentSpec {
+    name: &'static str,
+    description: &'static str,
+    required: bool,
+}
+
+fn prompt_spec(prompt_name: &str) -> Option<PromptSpec> {
+    match prompt_name {
+        "dataq.gate_ci_workflow" => Some(PromptSpec {
+            description: "Gate a CI workflow file against a JSON Schema or assert rules",
+            arguments: &[
+                PromptArgumentSpec {
+                    name: "workflow_path",
+                    description: "Path to the CI workflow YAML file",
+                    required: true,
+                },
+                PromptArgumentSpec {
+                    name: "platform",
+                    description: "`github-actions` (default) or `gitlab-ci`",
+                    required: false,
+                },
+                PromptArgumentSpec {
+                    name: "schema_path",
+                    description: "JSON Schema the normalized jobs must satisfy",
+                    required: false,
+                },
+                PromptArgumentSpec {
+                    name: "rules_path",
+                    description: "assert rules file used when no schema is given",
+                    required: false,
+                },
+            ],
+            tools: &[
+                "dataq.ingest.yaml_jobs",
+                "dataq.gate.schema",
+                "dataq.assert",
+            ],
+        }),
+        "dataq.explain_sdiff" => Some(PromptSpec {
+            description: "Run sdiff on two datasets and explain the differences",
+            arguments: &[
+                PromptArgumentSpec {
+                    name: "left_path",
+                    description: "Path to the baseline dataset",
+                    required: true,
+                },
+                PromptArgumentSpec {
+                    name: "right_path",
+                    description: "Path to the candidate dataset",
+                    required: true,
+                },
+                PromptArgumentSpec {
+                    name: "key",
+                    description: "Canonical path used to match records (e.g. `$[\"id\"]`)",
+                    required: false,
+                },
+            ],
+            tools: &["dataq.sdiff"],
+        }),
+        "dataq.draft_rules" => Some(PromptSpec {
+            description: "Profile a dataset and draft assert rules that it satisfies",
+            arguments: &[
+                PromptArgumentSpec {
+                    name: "input_path",
+                    description: "Path to the dataset to profile",
+                    required: true,
+                },
+                PromptArgumentSpec {
+                    name: "from",
+                    description: "Input format when the extension is ambiguous",
+                    required: false,
+                },
+            ],
+            tools: &["dataq.profile", "dataq.assert"],
+        }),
+        _ => None,
+    }
+}
+
+fn prompts_list_result() -> Value {
+    let prompts: Vec<Value> = PROMPT_ORDER
+        .iter()
+        .filter_map(|name| prompt_spec(name).map(|spec| prompt_definition(name, spec)))
+        .collect();
+    json!({ "prompts": prompts })
+}
+
+fn prompt_definition(prompt_name: &str, spec: PromptSpec) -> Value {
+    let arguments: Vec<Value> = spec
+        .arguments
+        .iter()
+        .map(|argument| {
+            json!({
+                "name": argument.name,
+                "description": argument.description,
+                "required": argument.required,
+            })
+        })
+        .collect();
+    json!({
+        "name": prompt_name,
+        "description": spec.description,
+        "arguments": arguments,
+        "meta": {
+            "tools": spec.tools,
+        }
+    })
+}
+
+fn handle_prompts_get(id: Value, params: Map<String, Value>) -> Value {
+    let Some(name) = params.get("name").and_then(Value::as_str) else {
+        return error_response(id, JSONRPC_INVALID_PARAMS, "`params.name` must be a string");
+    };
+    let Some(spec) = prompt_spec(name) else {
+        return error_response(
+            id,
+            JSONRPC_INVALID_PARAMS,
+            &format!("unknown prompt `{name}`"),
+        );
+    };
+
+    let arguments = match params.get("arguments") {
+        None => Map::new(),
+        Some(Value::Object(arguments)) => arguments.clone(),
+        Some(_) => {
+            return error_response(
+                id,
+                JSONRPC_INVALID_PARAMS,
+                "`params.arguments` must be an object",
+            );
+        }
+    };
+    let bindings = match resolve_prompt_arguments(spec, &arguments) {
+        Ok(bindings) => bindings,
+        Err(message) => return error_response(id, JSONRPC_INVALID_PARAMS, &message),
+    };
+
+    let text = match render_prompt_text(name, spec, &bindings) {
+        Ok(text) => text,
+        Err(message) => return error_response(id, JSONRPC_INVALID_PARAMS, &message),
+    };
+    success_response(
+        id,
+        json!({
+            "description": spec.description,
+            "messages": [{
+                "role": "user",
+                "content": {
+                    "type": "text",
+                    "text": text,
+                }
+            }]
+        }),
+    )
+}
+
+fn resolve_prompt_arguments(
+    spec: PromptSpec,
+    arguments: &Map<String, Value>,
+) -> Result<Map<String, Value>, String> {
+    for key in arguments.keys() {
+        if !spec.arguments.iter().any(|argument| argument.name == key) {
+            return Err(format!("unknown prompt argument `{key}`"));
+        }
+    }
+
+    let mut bindings = Map::new();
+    for argument in spec.arguments {
+        match arguments.get(argument.name) {
+            Some(Value::String(value)) => {
+                bindings.insert(argument.name.to_string(), Value::String(value.clone()));
+            }
+            Some(_) => {
+                return Err(format!(
+                    "prompt argument `{}` must be a string",
+                    argument.name
+                ));
+            }
+            None if argument.required => {
+                return Err(format!(
+                    "missing required prompt argument `{}`",
+                    argument.name
+                ));
+            }
+            None => {}
+        }
+    }
+    Ok(bindings)
+}
+
+/// Renders the prompt steps followed by the verbatim `inputSchema` of each
+/// tool they reference.
+fn render_prompt_text(
+    prompt_name: &str,
+    spec: PromptSpec,
+    bindings: &Map<String, Value>,
+) -> Result<String, String> {
+    let binding = |name: &str| bindings.get(name).and_then(Value::as_str);
+    let mut lines = Vec::new();
+
+    match prompt_name {
+        "dataq.gate_ci_workflow" => {
+            let workflow_path = binding("workflow_path").unwrap_or_default();
+            let platform = binding("platform").unwrap_or("github-actions");
+            let jobs_preset = match platform {
+                "github-actions" => "github-actions-jobs",
+                "gitlab-ci" => "gitlab-ci-jobs",
+                other => {
+                    return Err(format!(
+                        "prompt argument `platform` must be `github-actions` or `gitlab-ci` (received `{other}`)"
+                    ));
+                }
+            };
+            lines.push(format!(
+                "Gate the {platform} workflow `{workflow_path}` with dataq."
+            ));
+            lines.push(format!(
+                "1. Call `dataq.ingest.yaml_jobs` with {} and summarize the normalized jobs.",
+                json!({ "mode": platform, "input_path": workflow_path })
+            ));
+            if let Some(schema_path) = binding("schema_path") {
+                lines.push(format!(
+                    "2. Call `dataq.gate.schema` with {}.",
+                    json!({ "schema_path": schema_path, "input_path": workflow_path, "from": jobs_preset })
+                ));
+            } else if let Some(rules_path) = binding("rules_path") {
+                lines.push(format!(
+                    "2. Call `dataq.assert` with {}.",
+                    json!({ "rules_path": rules_path, "input_path": workflow_path, "normalize": jobs_preset })
+                ));
+            } else {
+                lines.push(format!(
+                    "2. No schema or rules were given: draft inline assert rules for the jobs (see resource `{ASSERT_RULES_HELP_RESOURCE_URI}`) and call `dataq.assert` with `rules`, `input_path` = `{workflow_path}` and `normalize` = `{jobs_preset}`."
+                ));
+            }
+            lines.push(
+                "3. Report `structuredContent.exit_code` (0 = pass, 2 = violations, 3 = input/usage error) and explain each reported mismatch."
+                    .to_string(),
+            );
+        }
+        "dataq.explain_sdiff" => {
+            let mut call = Map::new();
+            call.insert("left_path".to_string(), json!(binding("left_path")));
+            call.insert("right_path".to_string(), json!(binding("right_path")));
+            if let Some(key) = binding("key") {
+                call.insert("key".to_string(), json!(key));
+            }
+            lines.push("Explain how the two datasets differ.".to_string());
+            lines.push(format!(
+                "1. Call `dataq.sdiff` with {}.",
+                Value::Object(call)
+            ));
+            lines.push(
+                "2. Summarize `counts` and `keys` first, then walk through `values.items` grouped by path. If `values.truncated` is true, say that `values.total` exceeds the listed items."
+                    .to_string(),
+            );
+            lines.push(
+                "3. Call out paths listed in `ignored_paths` so the reader knows what was not compared."
+                    .to_string(),
+            );
+        }
+        "dataq.draft_rules" => {
+            let mut call = Map::new();
+            call.insert("input_path".to_string(), json!(binding("input_path")));
+            if let Some(from) = binding("from") {
+                call.insert("from".to_string(), json!(from));
+            }
+            lines.push("Draft assert rules for this dataset.".to_string());
+            lines.push(format!(
+                "1. Call `dataq.profile` with {}.",
+                Value::Object(call.clone())
+            ));
+            lines.push(format!(
+                "2. Using the profiled `fields` and the rules format from resource `{ASSERT_RULES_HELP_RESOURCE_URI}`, draft `required_keys`, per-field `type`/`nullable` and `count` rules."
+            ));
+            call.insert("rules".to_string(), json!("<drafted rules>"));
+            lines.push(format!(
+                "3. Validate the draft by calling `dataq.assert` with {} and iterate until `exit_code` is 0.",
+                Value::Object(call)
+            ));
+        }
+        _ => return Err(format!("unknown prompt `{prompt_name}`")),
+    }
+
+    lines.push(String::new());
+    lines.push("Tool input schemas:".to_string());
+    for tool in spec.tools {
+        lines.push(format!("- `{tool}`: {}", tool_input_schema(tool)));
+    }
+    Ok(lines.join("\n"))
+}
+
 fn tool_definition(tool_name: &str) -> Value {
     let deprecated_aliases: Vec<Value> = deprecated_alias_pairs(tool_name)
         .iter()
@@ -2169,6 +3621,7 @@ fn tool_definition(tool_name: &str) -> Value {
         "name": tool_name,
         "description": format!("dataq MCP tool `{tool_name}`"),
         "inputSchema": tool_input_schema(tool_name),
+        "outputSchema": tool_output_schema(tool_name),
         "examples": tool_examples(tool_name),
         "meta": meta,
     })
@@ -2181,10 +3634,27 @@ fn emit_pipeline_schema() -> Value {
     })
 }
 
+fn contract_version_schema() -> Value {
+    json!({
+        "type": "string",
+        "description": "Pin the output contract version (full schema ID or `vN`)."
+    })
+}
+
 fn format_schema() -> Value {
+    let formats = [
+        "json",
+        "yaml",
+        "csv",
+        "jsonl",
+        #[cfg(feature = "columnar")]
+        "parquet",
+        #[cfg(feature = "columnar")]
+        "arrow",
+    ];
     json!({
         "type": "string",
-        "enum": ["json", "yaml", "csv", "jsonl"]
+        "enum": formats
     })
 }
 
@@ -2239,8 +3709,47 @@ fn tool_input_schema(tool_name: &str) -> Value {
                 "input_path": { "type": "string" },
                 "sort_keys": { "type": "boolean", "default": true },
                 "normalize_time": { "type": "boolean", "default": false },
+                "coerce": {
+                    "type": "string",
+                    "enum": ["none", "all", "schema"],
+                    "default": "all"
+                },
+                "coerce_exempt": string_or_array_of_strings_schema(),
+                "coerce_schema_path": { "type": "string" },
+                "coerce_rules_path": { "type": "string" },
+                "key_case": {
+                    "type": "string",
+                    "enum": ["snake", "camel", "kebab"]
+                },
+                "whitespace": {
+                    "type": "string",
+                    "enum": ["trim", "collapse"]
+                },
+                "unicode": {
+                    "type": "string",
+                    "enum": ["nfc", "nfkc"]
+                },
+                "drop_nulls": { "type": "boolean", "default": false },
+                "drop_empty": { "type": "boolean", "default": false },
+                "sort_array": string_or_array_of_strings_schema(),
+                "time_path": string_or_array_of_strings_schema(),
+                "time_format": string_or_array_of_strings_schema(),
+                "time_zone": { "type": "string" },
+                "time_precision": {
+                    "type": "string",
+                    "enum": ["auto", "s", "ms", "us", "ns"],
+                    "default": "auto"
+                },
+                "time_target": {
+                    "type": "string",
+                    "enum": ["rfc3339", "epoch_ms"],
+                    "default": "rfc3339"
+                },
                 "from": format_schema(),
-                "to": format_schema()
+                "to": {
+                    "type": "string",
+                    "enum": ["json", "jsonl"]
+                }
             },
             "additionalProperties": false,
             "oneOf": [
@@ -2255,7 +3764,8 @@ fn tool_input_schema(tool_name: &str) -> Value {
                 "url": { "type": "string" },
                 "method": {
                     "type": "string",
-                    "pattern": "^(?:[Gg][Ee][Tt]|[Pp][Oo][Ss][Tt]|[Pp][Uu][Tt]|[Pp][Aa][Tt][Cc][Hh]|[Dd][Ee][Ll][Ee][Tt][Ee])$"
+                    "pattern": "^(?:[Gg][Ee][Tt]|[Pp][Oo][Ss][Tt]|[Pp][Uu][Tt]|[Pp][Aa][Tt][Cc][Hh]|[Dd][Ee][Ll][Ee][Tt][Ee])$",
+                    "default": "get"
                 },
                 "header": string_or_array_of_strings_schema(),
                 "body": json_value_schema(),
@@ -2367,7 +3877,11 @@ fn tool_input_schema(tool_name: &str) -> Value {
                 "key": { "type": "string" },
                 "ignore_path": string_or_array_of_strings_schema(),
                 "fail_on_diff": { "type": "boolean", "default": false },
-                "value_diff_cap": { "type": "integer", "minimum": 0 }
+                "value_diff_cap": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "default": sdiff::DEFAULT_VALUE_DIFF_CAP
+                }
             },
             "additionalProperties": false,
             "allOf": [
@@ -2437,7 +3951,8 @@ fn tool_input_schema(tool_name: &str) -> Value {
                 "until": { "type": "string" },
                 "to": {
                     "type": "string",
-                    "enum": ["json", "jsonl"]
+                    "enum": ["json", "jsonl"],
+                    "default": "json"
                 }
             },
             "additionalProperties": false
@@ -2616,8 +4131,14 @@ fn tool_input_schema(tool_name: &str) -> Value {
                         "merge",
                         "doctor",
                         "recipe-run",
-                        "recipe-lock"
+                        "recipe-lock",
+                        "recipe-replay"
                     ]
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["contract", "json-schema"],
+                    "default": "contract"
                 }
             },
             "additionalProperties": false
@@ -2641,7 +4162,10 @@ fn tool_input_schema(tool_name: &str) -> Value {
                 "emit_pipeline": emit_pipeline_schema(),
                 "file_path": { "type": "string" },
                 "recipe": json_value_schema(),
-                "base_dir": { "type": "string" }
+                "base_dir": { "type": "string" },
+                "param": string_or_array_of_strings_schema(),
+                "cache_dir": { "type": "string" },
+                "contract_version": contract_version_schema()
             },
             "additionalProperties": false,
             "oneOf": [
@@ -2654,7 +4178,9 @@ fn tool_input_schema(tool_name: &str) -> Value {
             "properties": {
                 "emit_pipeline": emit_pipeline_schema(),
                 "file_path": { "type": "string" },
-                "out_path": { "type": "string" }
+                "out_path": { "type": "string" },
+                "param": string_or_array_of_strings_schema(),
+                "contract_version": contract_version_schema()
             },
             "required": ["file_path"],
             "additionalProperties": false
@@ -2668,7 +4194,9 @@ fn tool_input_schema(tool_name: &str) -> Value {
                 "strict": {
                     "type": "boolean",
                     "default": false
-                }
+                },
+                "param": string_or_array_of_strings_schema(),
+                "contract_version": contract_version_schema()
             },
             "required": ["file_path", "lock_path"],
             "additionalProperties": false
@@ -2885,6 +4413,7 @@ fn tool_contract_command(tool_name: &str) -> Option<contract::ContractCommand> {
         "dataq.doctor" => Some(contract::ContractCommand::Doctor),
         "dataq.recipe.run" => Some(contract::ContractCommand::RecipeRun),
         "dataq.recipe.lock" => Some(contract::ContractCommand::RecipeLock),
+        "dataq.recipe.replay" => Some(contract::ContractCommand::RecipeReplay),
         _ => None,
     }
 }
@@ -2921,6 +4450,82 @@ fn tool_exit_code_contract(tool_name: &str) -> Value {
         .unwrap_or_else(default_exit_code_contract)
 }
 
+/// JSON Schema of `structuredContent`; the `payload` shape is selected per
+/// `exit_code` with `if`/`then`.
+fn tool_output_schema(tool_name: &str) -> Value {
+    let payload_schemas = tool_payload_schemas(tool_name);
+    let exit_codes: Vec<i32> = payload_schemas.iter().map(|(code, _)| *code).collect();
+    let branches: Vec<Value> = payload_schemas
+        .into_iter()
+        .map(|(code, payload)| {
+            json!({
+                "if": { "properties": { "exit_code": { "const": code } } },
+                "then": { "properties": { "payload": payload } }
+            })
+        })
+        .collect();
+    json!({
+        "type": "object",
+        "required": ["exit_code", "payload"],
+        "properties": {
+            "exit_code": { "type": "integer", "enum": exit_codes },
+            "payload": {},
+            "pipeline": { "type": "object" },
+            "meta": { "type": "object" }
+        },
+        "allOf": branches
+    })
+}
+
+fn tool_payload_schemas(tool_name: &str) -> Vec<(i32, Value)> {
+    if let Some(command) = tool_contract_command(tool_name) {
+        return contract::payload_schemas(command);
+    }
+    let success = match tool_name {
+        "dataq.join" | "dataq.aggregate" => {
+            json!({ "type": "array", "items": { "type": "object" } })
+        }
+        "dataq.contract" => json!({ "type": ["object", "array"] }),
+        "dataq.emit.plan" => json!({
+            "type": "object",
+            "required": ["command", "args", "stages", "tools"],
+            "properties": {
+                "command": { "type": "string" },
+                "args": { "type": "array", "items": { "type": "string" } },
+                "stages": { "type": "array", "items": { "type": "object" } },
+                "tools": { "type": "array", "items": { "type": "object" } }
+            }
+        }),
+        "dataq.recipe.replay" => json!({
+            "type": "object",
+            "required": ["matched", "exit_code", "lock_check", "steps"],
+            "properties": {
+                "matched": { "type": "boolean" },
+                "exit_code": { "type": "integer" },
+                "lock_check": {
+                    "type": "object",
+                    "required": ["strict", "matched", "mismatch_count", "mismatches"],
+                    "properties": {
+                        "strict": { "type": "boolean" },
+                        "matched": { "type": "boolean" },
+                        "mismatch_count": { "type": "integer", "minimum": 0 },
+                        "mismatches": { "type": "array", "items": { "type": "object" } }
+                    }
+                },
+                "steps": contract::recipe_steps_schema()
+            }
+        }),
+        _ => json!({}),
+    };
+    let mut schemas = vec![(0, success.clone())];
+    if tool_name == "dataq.recipe.replay" {
+        schemas.push((2, success));
+    }
+    schemas.push((3, contract::error_payload_schema()));
+    schemas.push((1, contract::error_payload_schema()));
+    schemas
+}
+
 fn deprecated_alias_pairs(tool_name: &str) -> &'static [(&'static str, &'static str)] {
     match tool_name {
         "dataq.canon" => &[
@@ -3012,16 +4617,19 @@ fn deprecated_alias_pairs(tool_name: &str) -> &'static [(&'static str, &'static
             ("file", "file_path"),
             ("recipe_path", "file_path"),
             ("recipe_inline", "recipe"),
+            ("params", "param"),
         ],
         "dataq.recipe.lock" => &[
             ("file", "file_path"),
             ("recipe_path", "file_path"),
             ("out", "out_path"),
+            ("params", "param"),
         ],
         "dataq.recipe.replay" => &[
             ("file", "file_path"),
             ("recipe_path", "file_path"),
             ("lock", "lock_path"),
+            ("params", "param"),
         ],
         _ => &[],
     }
@@ -3095,6 +4703,302 @@ fn validate_tool_arguments(tool_name: &str, args: &Map<String, Value>) -> Option
     ))
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SandboxPathArgument {
+    Path,
+    PathList,
+    DiffSource,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SandboxViolation {
+    Traversal,
+    OutsideRoot,
+    SymlinkEscape,
+}
+
+impl SandboxViolation {
+    fn reason(self) -> &'static str {
+        match self {
+            Self::Traversal => "path_traversal",
+            Self::OutsideRoot => "outside_root",
+            Self::SymlinkEscape => "symlink_escape",
+        }
+    }
+}
+
+/// Canonical argument names that point at files or directories.
+fn sandbox_path_argument(tool_name: &str, name: &str) -> Option<SandboxPathArgument> {
+    match (tool_name, name) {
+        (
+            _,
+            "base_dir" | "base_path" | "cache_dir" | "coerce_rules_path" | "coerce_schema_path"
+            | "file_path" | "input_path" | "left_path" | "lock_path" | "out_path" | "right_path"
+            | "rules_path" | "schema_path",
+        ) => Some(SandboxPathArgument::Path),
+        (_, "overlay_paths") => Some(SandboxPathArgument::PathList),
+        ("dataq.ingest.book", "root") | ("dataq.scan.text", "path") => {
+            Some(SandboxPathArgument::Path)
+        }
+        ("dataq.diff.source", "left" | "right") => Some(SandboxPathArgument::DiffSource),
+        _ => None,
+    }
+}
+
+/// With `--root` set, checks that path arguments and the paths a recipe
+/// references stay under an allowed root.
+fn sandbox_tool_arguments(
+    tool_name: &str,
+    args: &Map<String, Value>,
+    roots: &[PathBuf],
+) -> Option<ToolExecution> {
+    if roots.is_empty() || !is_known_tool(tool_name) {
+        return None;
+    }
+
+    let aliases = deprecated_alias_pairs(tool_name);
+    let mut violations: Vec<(String, String, SandboxViolation)> = Vec::new();
+    let mut provided: Vec<&str> = Vec::new();
+    for (name, value) in args {
+        let canonical = aliases
+            .iter()
+            .find(|(alias, _)| alias == name)
+            .map_or(name.as_str(), |(_, canonical)| canonical);
+        let Some(kind) = sandbox_path_argument(tool_name, canonical) else {
+            continue;
+        };
+        if !value.is_null() {
+            provided.push(canonical);
+        }
+        let raw_paths: Vec<&str> = match value {
+            Value::String(path) => vec![path.as_str()],
+            Value::Array(items) if kind == SandboxPathArgument::PathList => {
+                items.iter().filter_map(Value::as_str).collect()
+            }
+            _ => continue,
+        };
+        for raw in raw_paths {
+            let path = match kind {
+                SandboxPathArgument::DiffSource => match diff::parse_source_locator(raw, name) {
+                    Ok(diff::DiffSourceLocator::Path(path))
+                    | Ok(diff::DiffSourceLocator::Preset { path, .. }) => path,
+                    Err(_) => continue,
+                },
+                _ => PathBuf::from(raw),
+            };
+            if is_stdin_input_path_sentinel(&path) {
+                continue;
+            }
+            if let Err(violation) = check_sandboxed_path(roots, &path) {
+                violations.push((name.clone(), raw.to_string(), violation));
+            }
+        }
+    }
+
+    for (name, default) in sandbox_default_paths(tool_name) {
+        if provided.contains(name) {
+            continue;
+        }
+        if let Err(violation) = check_sandboxed_path(roots, Path::new(default)) {
+            violations.push((name.to_string(), default.to_string(), violation));
+        }
+    }
+
+    if violations.is_empty() {
+        violations = sandbox_rules_extends(tool_name, args, roots);
+    }
+    if violations.is_empty() {
+        violations = match sandbox_recipe_paths(tool_name, args, roots) {
+            Ok(violations) => violations,
+            Err(message) => {
+                return Some(input_usage_error(format!(
+                    "failed to resolve recipe paths under the configured `--root`: {message}"
+                )));
+            }
+        };
+    }
+    if violations.is_empty() {
+        return None;
+    }
+
+    let listed = violations
+        .iter()
+        .map(|(name, path, violation)| format!("`{name}` (`{path}`: {})", violation.reason()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut invalid_params: Vec<Value> = violations
+        .iter()
+        .map(|(name, _, violation)| invalid_param_entry(name, violation.reason()))
+        .collect();
+    invalid_params.dedup();
+    Some(input_usage_error_with_invalid_params(
+        format!("path argument(s) are not allowed under the configured `--root`: {listed}"),
+        invalid_params,
+    ))
+}
+
+/// Paths a tool falls back to when the named argument is omitted; these are
+/// read just like explicit arguments and must be checked too.
+fn sandbox_default_paths(tool_name: &str) -> &'static [(&'static str, &'static str)] {
+    match tool_name {
+        "dataq.scan.text" => &[("path", ".")],
+        _ => &[],
+    }
+}
+
+/// Walks the `extends` chain of rules file arguments, checking each target
+/// before it is read. Other load errors are left to the tool itself.
+fn sandbox_rules_extends(
+    tool_name: &str,
+    args: &Map<String, Value>,
+    roots: &[PathBuf],
+) -> Vec<(String, String, SandboxViolation)> {
+    let aliases = deprecated_alias_pairs(tool_name);
+    let mut violations = Vec::new();
+    for (name, value) in args {
+        let canonical = aliases
+            .iter()
+            .find(|(alias, _)| alias == name)
+            .map_or(name.as_str(), |(_, canonical)| canonical);
+        let (Some(path), "rules_path" | "coerce_rules_path") = (value.as_str(), canonical) else {
+            continue;
+        };
+        let refused = RefCell::new(Vec::new());
+        let _ = assert_cmd::load_rules_with_extends(Path::new(path), &|target| {
+            check_sandboxed_path(roots, target).map_err(|violation| {
+                refused.borrow_mut().push((target.to_path_buf(), violation));
+                violation.reason().to_string()
+            })
+        });
+        violations.extend(
+            refused
+                .into_inner()
+                .into_iter()
+                .map(|(target, violation)| (name.clone(), target.display().to_string(), violation)),
+        );
+    }
+    violations
+}
+
+/// Recipe tools also check every file the recipe references. `include` files
+/// and rules `extends` targets are checked before they are opened, and a
+/// recipe that cannot be resolved is rejected instead of running unchecked.
+fn sandbox_recipe_paths(
+    tool_name: &str,
+    args: &Map<String, Value>,
+    roots: &[PathBuf],
+) -> Result<Vec<(String, String, SandboxViolation)>, String> {
+    if !matches!(
+        tool_name,
+        "dataq.recipe.run" | "dataq.recipe.lock" | "dataq.recipe.replay"
+    ) {
+        return Ok(Vec::new());
+    }
+    let params = parse_string_list(args, &["param", "params"], "param")?;
+    let refused = RefCell::new(Vec::new());
+    let may_read = |path: &Path| match check_sandboxed_path(roots, path) {
+        Ok(()) => true,
+        Err(violation) => {
+            refused.borrow_mut().push((path.to_path_buf(), violation));
+            false
+        }
+    };
+    let (label, referenced) =
+        match parse_optional_path(args, &["file_path", "file", "recipe_path"], "file_path")? {
+            Some(path) => (
+                "file_path",
+                recipe_engine::referenced_paths(path.as_path(), &params, &may_read),
+            ),
+            None => match parse_inline_value(args, &["recipe", "recipe_inline"], "recipe")? {
+                Some(recipe_value) => {
+                    let base_dir = parse_optional_path(args, &["base_dir"], "base_dir")?;
+                    (
+                        "recipe",
+                        recipe_engine::referenced_paths_from_value(
+                            recipe_value,
+                            base_dir.as_deref(),
+                            &params,
+                            &may_read,
+                        ),
+                    )
+                }
+                None => return Ok(Vec::new()),
+            },
+        };
+
+    let mut violations: Vec<(String, String, SandboxViolation)> = refused
+        .into_inner()
+        .into_iter()
+        .map(|(path, violation)| (label.to_string(), path.display().to_string(), violation))
+        .collect();
+    match referenced {
+        Ok(paths) => {
+            for path in paths {
+                if let Err(violation) = check_sandboxed_path(roots, &path) {
+                    let entry = (label.to_string(), path.display().to_string(), violation);
+                    if !violations.contains(&entry) {
+                        violations.push(entry);
+                    }
+                }
+            }
+        }
+        Err(_) if !violations.is_empty() => {}
+        Err(
+            recipe_engine::RecipeExecutionErrorKind::InputUsage(message)
+            | recipe_engine::RecipeExecutionErrorKind::Internal(message),
+        ) => return Err(message),
+    }
+    Ok(violations)
+}
+
+fn check_sandboxed_path(roots: &[PathBuf], path: &Path) -> Result<(), SandboxViolation> {
+    if path
+        .components()
+        .any(|component| component == std::path::Component::ParentDir)
+    {
+        return Err(SandboxViolation::Traversal);
+    }
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map_err(|_| SandboxViolation::OutsideRoot)?
+            .join(path)
+    };
+
+    let resolved = canonicalize_existing_prefix(&absolute);
+    if roots.iter().any(|root| resolved.starts_with(root)) {
+        return Ok(());
+    }
+    if roots.iter().any(|root| absolute.starts_with(root)) {
+        Err(SandboxViolation::SymlinkEscape)
+    } else {
+        Err(SandboxViolation::OutsideRoot)
+    }
+}
+
+/// Canonicalizes the longest existing ancestor and appends the missing tail
+/// (for example an output file not created yet) as is.
+fn canonicalize_existing_prefix(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut current = path;
+    loop {
+        if let Ok(canonical) = current.canonicalize() {
+            return missing
+                .iter()
+                .rev()
+                .fold(canonical, |resolved, component| resolved.join(component));
+        }
+        match (current.parent(), current.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name.to_os_string());
+                current = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
 fn tool_call_result(execution: ToolExecution, alias_warnings: Vec<Value>) -> Value {
     let ToolExecution {
         exit_code,
@@ -3209,8 +5113,15 @@ const MACHINE_PARAM_NAMES: &[&str] = &[
     "base_dir",
     "base_path",
     "body",
+    "cache_dir",
     "capabilities",
+    "coerce",
+    "coerce_exempt",
+    "coerce_rules_path",
+    "coerce_schema_path",
     "command",
+    "drop_empty",
+    "drop_nulls",
     "emit_pipeline",
     "expect_status",
     "fail_on_diff",
@@ -3227,6 +5138,7 @@ const MACHINE_PARAM_NAMES: &[&str] = &[
     "jq_filter",
     "jq_project",
     "key",
+    "key_case",
     "left",
     "left_from",
     "left_path",
@@ -3242,6 +5154,7 @@ const MACHINE_PARAM_NAMES: &[&str] = &[
     "out_path",
     "overlay_paths",
     "overlays",
+    "param",
     "path",
     "pattern",
     "policy",
@@ -3258,16 +5171,24 @@ const MACHINE_PARAM_NAMES: &[&str] = &[
     "schema",
     "schema_path",
     "since",
+    "sort_array",
     "sort_keys",
     "source",
     "strict",
     "tag",
     "target",
+    "time_format",
+    "time_path",
+    "time_precision",
+    "time_target",
+    "time_zone",
     "to",
+    "unicode",
     "url",
     "until",
     "value_diff_cap",
     "verify_mdbook_meta",
+    "whitespace",
 ];
 
 fn invalid_param_entry(name: &str, reason: &str) -> Value {
@@ -3471,6 +5392,29 @@ fn parse_optional_string_allow_empty(
     }
 }
 
+/// Resolves the optional `contract_version` argument (full schema ID or `vN`)
+/// against the versions `command` supports.
+fn parse_contract_version(
+    args: &Map<String, Value>,
+    command: contract::ContractCommand,
+) -> Result<Option<&'static str>, String> {
+    parse_optional_string(args, &["contract_version"], "contract_version")?
+        .map(|requested| contract::resolve_contract_version(command, &requested))
+        .transpose()
+}
+
+/// Rewrites a stdout payload (exit code `0` or `2`) into the pinned contract version.
+fn shape_recipe_payload(
+    execution: &mut ToolExecution,
+    command: contract::ContractCommand,
+    version: Option<&str>,
+) {
+    if let (Some(version), 0 | 2) = (version, execution.exit_code) {
+        execution.payload =
+            contract::shape_for_version(command, version, std::mem::take(&mut execution.payload));
+    }
+}
+
 fn parse_optional_path(
     args: &Map<String, Value>,
     aliases: &[&str],
@@ -3934,6 +5878,7 @@ fn contract_command_from_str(value: &str) -> Result<contract::ContractCommand, S
         "doctor" => Ok(contract::ContractCommand::Doctor),
         "recipe" | "recipe-run" => Ok(contract::ContractCommand::RecipeRun),
         "recipe-lock" => Ok(contract::ContractCommand::RecipeLock),
+        "recipe-replay" => Ok(contract::ContractCommand::RecipeReplay),
         _ => Err(format!("unsupported contract command `{value}`")),
     }
 }
@@ -3949,10 +5894,214 @@ mod tests {
         value.as_object().expect("args object").clone()
     }
 
+    #[test]
+    fn serve_skips_notifications_and_answers_batches_until_eof() {
+        let input = concat!(
+            "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"ping\"}\n",
+            "\n",
+            "{\"jsonrpc\":\"2.0\",\"method\":\"notifications/initialized\"}\n",
+            "[{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"ping\"},",
+            "{\"jsonrpc\":\"2.0\",\"method\":\"notifications/cancelled\"}]\n",
+            "[{\"jsonrpc\":\"2.0\",\"method\":\"notifications/initialized\"}]\n",
+            "[]\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":3,\"method\":\"nope\"}"
+        );
+        let mut output = Vec::new();
+        let code = serve(
+            Cursor::new(input.as_bytes()),
+            &mut output,
+            &McpServerOptions::default(),
+        );
+        assert_eq!(code, 0);
+
+        let responses: Vec<Value> = String::from_utf8(output)
+            .expect("utf8 output")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("json line"))
+            .collect();
+        assert_eq!(responses.len(), 4);
+        assert_eq!(responses[0]["id"], Value::from(1));
+        assert_eq!(responses[0]["result"], json!({}));
+        assert_eq!(
+            responses[1],
+            json!([{"jsonrpc": "2.0", "id": 2, "result": {}}])
+        );
+        assert_eq!(
+            responses[2]["error"]["code"],
+            Value::from(JSONRPC_INVALID_REQUEST)
+        );
+        assert_eq!(responses[3]["id"], Value::from(3));
+        assert_eq!(
+            responses[3]["error"]["code"],
+            Value::from(JSONRPC_METHOD_NOT_FOUND)
+        );
+    }
+
+    #[test]
+    fn discover_workspace_resources_lists_recipes_and_rules_only() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("recipes")).expect("create recipes");
+        fs::create_dir_all(root.join(".git")).expect("create hidden dir");
+        fs::create_dir_all(root.join("target")).expect("create target");
+        let recipe = r#"{"version":"dataq.recipe.v1","steps":[]}"#;
+        fs::write(root.join("recipes/check.json"), recipe).expect("write recipe");
+        fs::write(root.join(".git/recipe.json"), recipe).expect("write hidden recipe");
+        fs::write(root.join("target/recipe.json"), recipe).expect("write target recipe");
+        fs::write(root.join("rules.yaml"), "required_keys: [id]\n").expect("write rules");
+        fs::write(root.join("data.json"), r#"{"id":1}"#).expect("write data");
+        fs::write(root.join("empty.json"), "{}").expect("write empty");
+
+        let found = discover_workspace_resources(
+            root,
+            WORKSPACE_RESOURCE_MAX_DEPTH,
+            WORKSPACE_RESOURCE_MAX_FILES,
+        );
+        assert_eq!(
+            found,
+            vec![
+                WorkspaceResourceFile {
+                    relative: "recipes/check.json".to_string(),
+                    kind: WorkspaceResourceKind::Recipe,
+                    mime_type: "application/json",
+                },
+                WorkspaceResourceFile {
+                    relative: "rules.yaml".to_string(),
+                    kind: WorkspaceResourceKind::Rules,
+                    mime_type: "application/yaml",
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn discover_workspace_resources_stops_at_depth_and_file_caps() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        let rules = "required_keys: [id]\n";
+        fs::create_dir_all(root.join("a/b/c")).expect("create nested dirs");
+        fs::write(root.join("top.rules.yaml"), rules).expect("write top rules");
+        fs::write(root.join("a/one.rules.yaml"), rules).expect("write depth 1 rules");
+        fs::write(root.join("a/b/two.rules.yaml"), rules).expect("write depth 2 rules");
+        fs::write(root.join("a/b/c/three.rules.yaml"), rules).expect("write depth 3 rules");
+        let relatives = |found: Vec<WorkspaceResourceFile>| {
+            found
+                .into_iter()
+                .map(|file| file.relative)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            relatives(discover_workspace_resources(root, 1, 100)),
+            vec!["a/one.rules.yaml", "top.rules.yaml"]
+        );
+        assert_eq!(
+            relatives(discover_workspace_resources(root, 8, 3)),
+            vec!["a/b/two.rules.yaml", "a/one.rules.yaml", "top.rules.yaml"]
+        );
+    }
+
+    #[test]
+    fn prompts_get_validates_arguments_and_embeds_tool_schemas() {
+        let response = handle_prompts_get(
+            json!(1),
+            args(json!({
+                "name": "dataq.gate_ci_workflow",
+                "arguments": {"workflow_path": "ci.yml", "schema_path": "jobs.schema.json"}
+            })),
+        );
+        let text = response["result"]["messages"][0]["content"]["text"]
+            .as_str()
+            .expect("prompt text");
+        assert!(text.contains(r#"{"mode":"github-actions","input_path":"ci.yml"}"#));
+        assert!(text.contains("`dataq.gate.schema` with"));
+        assert!(text.contains(r#""from":"github-actions-jobs""#));
+        assert!(text.contains(&format!(
+            "- `dataq.ingest.yaml_jobs`: {}",
+            tool_input_schema("dataq.ingest.yaml_jobs")
+        )));
+
+        for (arguments, message) in [
+            (
+                json!({}),
+                "missing required prompt argument `workflow_path`",
+            ),
+            (
+                json!({"workflow_path": 1}),
+                "prompt argument `workflow_path` must be a string",
+            ),
+            (
+                json!({"workflow_path": "ci.yml", "extra": "x"}),
+                "unknown prompt argument `extra`",
+            ),
+            (
+                json!({"workflow_path": "ci.yml", "platform": "jenkins"}),
+                "prompt argument `platform` must be `github-actions` or `gitlab-ci` (received `jenkins`)",
+            ),
+        ] {
+            let response = handle_prompts_get(
+                json!(2),
+                args(json!({"name": "dataq.gate_ci_workflow", "arguments": arguments})),
+            );
+            assert_eq!(
+                response["error"]["code"],
+                Value::from(JSONRPC_INVALID_PARAMS)
+            );
+            assert_eq!(response["error"]["message"], Value::from(message));
+        }
+
+        let unknown = handle_prompts_get(json!(3), args(json!({"name": "dataq.nope"})));
+        assert_eq!(
+            unknown["error"]["message"],
+            Value::from("unknown prompt `dataq.nope`")
+        );
+    }
+
+    #[test]
+    fn check_sandboxed_path_classifies_traversal_outside_and_symlink_escape() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path().join("root");
+        let outside = temp.path().join("outside");
+        fs::create_dir_all(&root).expect("create root");
+        fs::create_dir_all(&outside).expect("create outside");
+        fs::write(root.join("in.json"), "[]").expect("write inside");
+        fs::write(outside.join("secret.json"), "[]").expect("write outside");
+        let roots = McpServerOptions::with_roots(std::slice::from_ref(&root))
+            .expect("roots")
+            .roots;
+
+        assert_eq!(check_sandboxed_path(&roots, &root.join("in.json")), Ok(()));
+        assert_eq!(
+            check_sandboxed_path(&roots, &root.join("new/out.json")),
+            Ok(())
+        );
+        assert_eq!(
+            check_sandboxed_path(&roots, &root.join("../outside/secret.json")),
+            Err(SandboxViolation::Traversal)
+        );
+        assert_eq!(
+            check_sandboxed_path(&roots, &outside.join("secret.json")),
+            Err(SandboxViolation::OutsideRoot)
+        );
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(outside.join("secret.json"), root.join("link.json"))
+                .expect("create symlink");
+            assert_eq!(
+                check_sandboxed_path(&roots, &roots[0].join("link.json")),
+                Err(SandboxViolation::SymlinkEscape)
+            );
+        }
+
+        let missing = McpServerOptions::with_roots(&[temp.path().join("missing")])
+            .expect_err("missing root must fail");
+        assert!(missing.starts_with("failed to resolve `--root`"));
+    }
+
     #[test]
     fn parse_request_validates_json_rpc_shape() {
         let mut output = Vec::new();
-        let code = run_single_request(Cursor::new(b"{"), &mut output);
+        let code = run_single_request(Cursor::new(b"{"), &mut output, &McpServerOptions::default());
         assert_eq!(code, 0);
         let response: Value = serde_json::from_slice(&output).expect("json response");
         assert_eq!(response["error"]["code"], Value::from(JSONRPC_PARSE_ERROR));
@@ -4464,7 +6613,7 @@ mod tests {
             method: "tools/list".to_string(),
             params: Map::new(),
         };
-        let handled = handle_request(request);
+        let handled = handle_request(request, &McpServerOptions::default());
         assert!(handled["result"]["tools"].is_array());
     }
 
@@ -4512,6 +6661,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn output_schemas_follow_contract_fields_and_exit_codes() {
+        for tool_name in TOOL_ORDER {
+            let schema = tool_output_schema(tool_name);
+            jsonschema::validator_for(&schema).expect("outputSchema compiles");
+            let exit_codes: Vec<String> = schema["properties"]["exit_code"]["enum"]
+                .as_array()
+                .expect("exit code enum")
+                .iter()
+                .map(Value::to_string)
+                .collect();
+            let mut contract_codes: Vec<String> = tool_exit_code_contract(tool_name)
+                .as_object()
+                .expect("exit code contract")
+                .iter()
+                .filter(|(_, description)| {
+                    description.as_str() != Some("validation mismatch is not used by this command")
+                })
+                .map(|(code, _)| code.clone())
+                .collect();
+            let mut sorted_codes = exit_codes.clone();
+            sorted_codes.sort();
+            contract_codes.sort();
+            assert_eq!(sorted_codes, contract_codes, "tool: {tool_name}");
+
+            let Some(command) = tool_contract_command(tool_name) else {
+                continue;
+            };
+            let contract = contract::run_for_command(command).payload;
+            let success = &schema["allOf"][0]["then"]["properties"]["payload"];
+            let required = if success["type"] == json!("array") {
+                &success["items"]["required"]
+            } else {
+                &success["required"]
+            };
+            if contract["output_fields"] != json!([]) {
+                assert_eq!(required, &contract["output_fields"], "tool: {tool_name}");
+            }
+        }
+
+        let validator = jsonschema::validator_for(&tool_output_schema("dataq.assert"))
+            .expect("assert outputSchema");
+        assert!(validator.is_valid(&json!({
+            "exit_code": 2,
+            "payload": {"matched": false, "mismatch_count": 0, "mismatches": []}
+        })));
+        assert!(!validator.is_valid(&json!({"exit_code": 0, "payload": {"matched": true}})));
+        assert!(!validator.is_valid(&json!({"exit_code": 3, "payload": {"matched": true}})));
+        assert!(
+            !jsonschema::validator_for(&tool_output_schema("dataq.canon"))
+                .expect("canon outputSchema")
+                .is_valid(&json!({"exit_code": 2, "payload": {}}))
+        );
+    }
+
     #[test]
     fn input_usage_error_payload_includes_machine_readable_invalid_params() {
         let missing = input_usage_error("missing required `input`");
@@ -4558,16 +6762,19 @@ mod tests {
 
     #[test]
     fn tools_call_structured_content_meta_includes_alias_deprecation_warning() {
-        let response = handle_request(JsonRpcRequest {
-            id: Value::from(99),
-            method: "tools/call".to_string(),
-            params: args(json!({
-                "name": "dataq.canon",
-                "arguments": {
-                    "input_inline": [{"z":"2","a":"1"}]
-                }
-            })),
-        });
+        let response = handle_request(
+            JsonRpcRequest {
+                id: Value::from(99),
+                method: "tools/call".to_string(),
+                params: args(json!({
+                    "name": "dataq.canon",
+                    "arguments": {
+                        "input_inline": [{"z":"2","a":"1"}]
+                    }
+                })),
+            },
+            &McpServerOptions::default(),
+        );
 
         assert_eq!(response["result"]["isError"], Value::Bool(false));
         let warnings = response["result"]["structuredContent"]["meta"]["warnings"]
@@ -4580,17 +6787,20 @@ mod tests {
 
     #[test]
     fn tools_call_rejects_unknown_arguments_even_for_known_tools() {
-        let response = handle_request(JsonRpcRequest {
-            id: Value::from(100),
-            method: "tools/call".to_string(),
-            params: args(json!({
-                "name": "dataq.canon",
-                "arguments": {
-                    "input": [{"id": 1}],
-                    "unexpected_arg": true
-                }
-            })),
-        });
+        let response = handle_request(
+            JsonRpcRequest {
+                id: Value::from(100),
+                method: "tools/call".to_string(),
+                params: args(json!({
+                    "name": "dataq.canon",
+                    "arguments": {
+                        "input": [{"id": 1}],
+                        "unexpected_arg": true
+                    }
+                })),
+            },
+            &McpServerOptions::default(),
+        );
 
         assert_eq!(response["result"]["isError"], Value::Bool(true));
         assert_eq!(
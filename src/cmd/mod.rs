@@ -7,6 +7,7 @@ pub mod diff;
 pub mod doctor;
 pub mod emit;
 pub mod gate;
+pub mod help;
 pub mod ingest;
 pub mod ingest_api;
 pub mod ingest_yaml_jobs;
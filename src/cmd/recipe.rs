@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use serde::Serialize;
 use serde_json::{Value, json};
 
+use crate::domain::report::RecipeGraphReport;
 use crate::engine::recipe::{self, RecipeExecutionErrorKind};
 
 /// Input arguments for recipe run command execution API.
@@ -12,6 +13,10 @@ pub struct RecipeCommandArgs {
     pub file_path: Option<PathBuf>,
     pub recipe: Option<Value>,
     pub base_dir: Option<PathBuf>,
+    /// `key=value` overrides for the recipe's `params` block.
+    pub params: Vec<String>,
+    /// Directory for the on-disk step cache; caching is disabled when `None`.
+    pub cache_dir: Option<PathBuf>,
 }
 
 /// Input arguments for `recipe replay` command execution API.
@@ -20,6 +25,7 @@ pub struct RecipeReplayCommandArgs {
     pub file_path: PathBuf,
     pub lock_path: PathBuf,
     pub strict: bool,
+    pub params: Vec<String>,
 }
 
 /// Structured command response that carries exit-code mapping and JSON payload.
@@ -39,6 +45,29 @@ pub struct RecipePipelineTrace {
 #[derive(Debug, Clone)]
 pub struct RecipeLockCommandArgs {
     pub file_path: PathBuf,
+    pub params: Vec<String>,
+}
+
+/// Input arguments for `recipe validate` command execution API.
+#[derive(Debug, Clone)]
+pub struct RecipeValidateCommandArgs {
+    pub file_path: PathBuf,
+    pub params: Vec<String>,
+}
+
+/// Output encoding for `recipe graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecipeGraphFormat {
+    Json,
+    Dot,
+}
+
+/// Input arguments for `recipe graph` command execution API.
+#[derive(Debug, Clone)]
+pub struct RecipeGraphCommandArgs {
+    pub file_path: PathBuf,
+    pub params: Vec<String>,
+    pub format: RecipeGraphFormat,
 }
 
 /// Trace details used by `--emit-pipeline` for recipe lock stages.
@@ -74,10 +103,15 @@ pub fn run_with_trace(args: &RecipeCommandArgs) -> (RecipeCommandResponse, Recip
                 RecipePipelineTrace::default(),
             );
         }
-        (Some(file_path), None) => recipe::run(file_path.as_path()),
-        (None, Some(recipe_value)) => {
-            recipe::run_from_value(recipe_value.clone(), args.base_dir.as_deref())
+        (Some(file_path), None) => {
+            recipe::run(file_path.as_path(), &args.params, args.cache_dir.as_deref())
         }
+        (None, Some(recipe_value)) => recipe::run_from_value(
+            recipe_value.clone(),
+            args.base_dir.as_deref(),
+            &args.params,
+            args.cache_dir.as_deref(),
+        ),
     };
 
     match execution {
@@ -124,7 +158,7 @@ pub fn lock_with_trace(
     RecipeLockPipelineTrace,
     Option<Vec<u8>>,
 ) {
-    match recipe::lock(args.file_path.as_path()) {
+    match recipe::lock(args.file_path.as_path(), &args.params) {
         Ok(execution) => {
             let payload = match serde_json::from_slice::<Value>(&execution.serialized) {
                 Ok(payload) => payload,
@@ -176,6 +210,7 @@ pub fn replay_with_trace(
         args.file_path.as_path(),
         args.lock_path.as_path(),
         args.strict,
+        &args.params,
     );
 
     match execution {
@@ -217,6 +252,168 @@ pub fn replay_with_trace(
     }
 }
 
+pub fn validate_with_trace(
+    args: &RecipeValidateCommandArgs,
+) -> (RecipeCommandResponse, RecipePipelineTrace) {
+    match recipe::validate(args.file_path.as_path(), &args.params) {
+        Ok(execution) => {
+            let exit_code = execution.report.exit_code;
+            let payload = match serde_json::to_value(execution.report) {
+                Ok(payload) => payload,
+                Err(error) => {
+                    return (
+                        RecipeCommandResponse {
+                            exit_code: 1,
+                            payload: json!({
+                                "error": "internal_error",
+                                "message": format!(
+                                    "failed to serialize recipe validate report: {error}"
+                                ),
+                            }),
+                        },
+                        RecipePipelineTrace {
+                            steps: execution.pipeline_steps,
+                        },
+                    );
+                }
+            };
+
+            (
+                RecipeCommandResponse { exit_code, payload },
+                RecipePipelineTrace {
+                    steps: execution.pipeline_steps,
+                },
+            )
+        }
+        Err(error) => (
+            map_execution_error_response(error.kind),
+            RecipePipelineTrace {
+                steps: error.pipeline_steps,
+            },
+        ),
+    }
+}
+
+/// Resolves the recipe step graph.
+///
+/// The JSON report is always returned as the payload; with
+/// [`RecipeGraphFormat::Dot`] the rendered Graphviz text is returned as well.
+pub fn graph_with_trace(
+    args: &RecipeGraphCommandArgs,
+) -> (RecipeCommandResponse, RecipePipelineTrace, Option<String>) {
+    match recipe::graph(args.file_path.as_path(), &args.params) {
+        Ok(execution) => {
+            let dot = match args.format {
+                RecipeGraphFormat::Json => None,
+                RecipeGraphFormat::Dot => Some(render_graph_dot(&execution.report)),
+            };
+            let payload = match serde_json::to_value(execution.report) {
+                Ok(payload) => payload,
+                Err(error) => {
+                    return (
+                        RecipeCommandResponse {
+                            exit_code: 1,
+                            payload: json!({
+                                "error": "internal_error",
+                                "message": format!(
+                                    "failed to serialize recipe graph report: {error}"
+                                ),
+                            }),
+                        },
+                        RecipePipelineTrace {
+                            steps: execution.pipeline_steps,
+                        },
+                        None,
+                    );
+                }
+            };
+
+            (
+                RecipeCommandResponse {
+                    exit_code: 0,
+                    payload,
+                },
+                RecipePipelineTrace {
+                    steps: execution.pipeline_steps,
+                },
+                dot,
+            )
+        }
+        Err(error) => (
+            map_execution_error_response(error.kind),
+            RecipePipelineTrace {
+                steps: error.pipeline_steps,
+            },
+            None,
+        ),
+    }
+}
+
+/// Renders the step graph as Graphviz DOT: one cluster per recipe step with
+/// its stages chained inside, and step edges from the last stage of the
+/// upstream step to the first stage of the downstream step.
+pub fn render_graph_dot(report: &RecipeGraphReport) -> String {
+    let mut out = String::from("digraph recipe {\n  rankdir=LR;\n  node [shape=box];\n");
+    for node in &report.nodes {
+        let label = match node.id.as_deref() {
+            Some(id) => format!("{}: {} ({id})", node.index, node.kind),
+            None => format!("{}: {}", node.index, node.kind),
+        };
+        out.push_str(&format!(
+            "  subgraph cluster_step_{} {{\n    label={};\n",
+            node.index,
+            dot_quote(&label)
+        ));
+        for stage in &node.stages {
+            out.push_str(&format!(
+                "    {} [label={}];\n",
+                dot_stage_node(node.index, stage.order),
+                dot_quote(&format!("{} [{}]", stage.step, stage.tool))
+            ));
+        }
+        for pair in node.stages.windows(2) {
+            out.push_str(&format!(
+                "    {} -> {};\n",
+                dot_stage_node(node.index, pair[0].order),
+                dot_stage_node(node.index, pair[1].order)
+            ));
+        }
+        out.push_str("  }\n");
+    }
+    for edge in &report.edges {
+        let from = report.nodes[edge.from]
+            .stages
+            .last()
+            .map_or(0, |stage| stage.order);
+        let to = report.nodes[edge.to]
+            .stages
+            .first()
+            .map_or(0, |stage| stage.order);
+        let attributes = match edge.matched {
+            Some(matched) => format!(
+                " [style=dashed, label={}]",
+                dot_quote(&format!("when matched={matched}"))
+            ),
+            None => String::new(),
+        };
+        out.push_str(&format!(
+            "  {} -> {}{attributes};\n",
+            dot_stage_node(edge.from, from),
+            dot_stage_node(edge.to, to)
+        ));
+    }
+    out.push('}');
+    out
+}
+
+fn dot_stage_node(step: usize, stage: usize) -> String {
+    format!("\"step_{step}_stage_{stage}\"")
+}
+
+fn dot_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
 fn map_execution_error_response(error: RecipeExecutionErrorKind) -> RecipeCommandResponse {
     match error {
         RecipeExecutionErrorKind::InputUsage(message) => RecipeCommandResponse {
@@ -255,6 +452,24 @@ pub fn deterministic_guards_lock() -> Vec<String> {
     ]
 }
 
+/// Determinism guards planned for the `recipe validate` command.
+pub fn deterministic_guards_validate() -> Vec<String> {
+    vec![
+        "rust_native_execution".to_string(),
+        "static_validation_without_step_execution".to_string(),
+        "recipe_validate_errors_in_step_order".to_string(),
+    ]
+}
+
+/// Determinism guards planned for the `recipe graph` command.
+pub fn deterministic_guards_graph() -> Vec<String> {
+    vec![
+        "rust_native_execution".to_string(),
+        "static_planner_without_external_execution".to_string(),
+        "deterministic_stage_dependency_order".to_string(),
+    ]
+}
+
 /// Backward-compatible alias for recipe run guards.
 pub fn deterministic_guards() -> Vec<String> {
     deterministic_guards_run()
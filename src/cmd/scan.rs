@@ -5,6 +5,7 @@ use serde::Serialize;
 use serde_json::{Value, json};
 
 use crate::adapters::{jq, rg};
+use crate::cmd::stage_trace;
 use crate::domain::report::PipelineStageDiagnostic;
 use crate::engine::scan::ScanTextMatch;
 
@@ -93,6 +94,7 @@ pub fn run_with_trace(
     };
 
     trace.mark_tool_used("rg");
+    stage_trace::report_stage_start("scan_text_rg_execute");
     let rg_args = rg::RgCommandArgs {
         pattern: args.pattern.as_str(),
         path: args.path.as_path(),
@@ -141,6 +143,7 @@ pub fn run_with_trace(
         }
     };
 
+    stage_trace::report_stage_start("scan_text_parse");
     let mut matches =
         match crate::engine::scan::parse_rg_json_stream(&raw_output, invocation_root.as_path()) {
             Ok(matches) => {
@@ -179,6 +182,7 @@ pub fn run_with_trace(
 
     if args.jq_project {
         trace.mark_tool_used("jq");
+        stage_trace::report_stage_start("scan_text_jq_project");
         let projected = match project_with_jq(&matches) {
             Ok(projected) => {
                 trace
@@ -1,7 +1,33 @@
+use std::cell::RefCell;
+
 use serde_json::Value;
 
 use crate::domain::report::{PipelineStageDiagnostic, PipelineStageMetrics};
 
+type StageProgressSink = Box<dyn FnMut(&str)>;
+
+thread_local! {
+    static STAGE_PROGRESS: RefCell<Option<StageProgressSink>> = const { RefCell::new(None) };
+}
+
+/// Routes stage-start reports to `sink` while `run` executes on this thread.
+pub(crate) fn with_stage_progress<T>(sink: StageProgressSink, run: impl FnOnce() -> T) -> T {
+    let previous = STAGE_PROGRESS.with(|current| current.replace(Some(sink)));
+    let result = run();
+    STAGE_PROGRESS.with(|current| current.replace(previous));
+    result
+}
+
+/// Reports that the pipeline stage `step` is starting. No-op outside
+/// [`with_stage_progress`].
+pub(crate) fn report_stage_start(step: &str) {
+    STAGE_PROGRESS.with(|current| {
+        if let Some(sink) = current.borrow_mut().as_mut() {
+            sink(step);
+        }
+    });
+}
+
 pub(crate) fn run_value_stage<E, F>(
     order: usize,
     step: &'static str,
@@ -12,6 +38,7 @@ pub(crate) fn run_value_stage<E, F>(
 where
     F: FnOnce() -> Result<Vec<Value>, E>,
 {
+    report_stage_start(step);
     let input_records = input_sets.iter().map(|rows| rows.len()).sum();
     let input_bytes = input_sets
         .iter()
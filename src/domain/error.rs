@@ -1,6 +1,7 @@
 use thiserror::Error;
 
 use crate::io::{Format, IoError};
+use crate::util::time::LocalTimeError;
 
 /// Errors produced by the `canon` command boundary.
 #[derive(Debug, Error)]
@@ -20,6 +21,10 @@ pub enum CanonError {
         #[source]
         source: IoError,
     },
+
+    /// A timestamp at a selected time path did not resolve to one instant.
+    #[error("failed to normalize timestamp: {0}")]
+    Time(#[from] LocalTimeError),
 }
 
 /// Errors produced by the `profile` command boundary.
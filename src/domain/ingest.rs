@@ -16,6 +16,15 @@ impl IngestYamlJobsMode {
             Self::GenericMap => "generic-map",
         }
     }
+
+    pub fn parse_cli_name(input: &str) -> Option<Self> {
+        match input {
+            "github-actions" => Some(Self::GithubActions),
+            "gitlab-ci" => Some(Self::GitlabCi),
+            "generic-map" => Some(Self::GenericMap),
+            _ => None,
+        }
+    }
 }
 
 /// Deterministic normalized row schema for `github-actions` mode.
@@ -119,12 +119,78 @@ pub struct RecipeReplayLockMismatchReport {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RecipeStepReport {
     pub index: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     pub kind: String,
     pub matched: bool,
     pub exit_code: i32,
+    /// Set when the step's `when` condition was not met.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+    /// Set when the step outcome was reused from the recipe step cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
     pub summary: Value,
 }
 
+/// Deterministic report for `recipe validate` command output.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecipeValidateReport {
+    pub valid: bool,
+    pub exit_code: i32,
+    pub step_count: usize,
+    pub errors: Vec<RecipeValidateIssueReport>,
+}
+
+/// One static validation failure, in recipe definition order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecipeValidateIssueReport {
+    /// Definition index of the failing step; absent for recipe-wide checks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<usize>,
+    pub check: String,
+    pub message: String,
+}
+
+/// Deterministic step DAG for `recipe graph` command output.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecipeGraphReport {
+    pub nodes: Vec<RecipeGraphNode>,
+    pub edges: Vec<RecipeGraphEdge>,
+    /// Step indexes in execution order.
+    pub order: Vec<usize>,
+}
+
+/// One recipe step expanded into the tool stages it runs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecipeGraphNode {
+    pub index: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub kind: String,
+    pub stages: Vec<RecipeGraphStage>,
+}
+
+/// Stage within a recipe step, modeled like `emit plan` stages.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecipeGraphStage {
+    pub order: usize,
+    pub step: String,
+    pub tool: String,
+    pub depends_on: Vec<String>,
+}
+
+/// Dependency between two recipe steps.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecipeGraphEdge {
+    pub from: usize,
+    pub to: usize,
+    /// `input` for data flow, `when` for condition references.
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched: Option<bool>,
+}
+
 /// Deterministic lock metadata for reproducible `recipe` execution.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct RecipeLockReport {
@@ -133,6 +199,9 @@ pub struct RecipeLockReport {
     pub args_hash: String,
     pub tool_versions: BTreeMap<String, String>,
     pub dataq_version: String,
+    /// Content hashes of files read by the recipe, keyed by recipe-relative path.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub input_hashes: BTreeMap<String, String>,
 }
 
 /// Diagnostics report emitted when `--emit-pipeline` is enabled.
@@ -22,6 +22,15 @@ impl AggregateMetric {
             Self::Avg => "avg",
         }
     }
+
+    pub fn parse_cli_name(input: &str) -> Option<Self> {
+        match input {
+            "count" => Some(Self::Count),
+            "sum" => Some(Self::Sum),
+            "avg" => Some(Self::Avg),
+            _ => None,
+        }
+    }
 }
 
 /// Domain errors for deterministic aggregate execution.
@@ -1,37 +1,293 @@
+use std::collections::BTreeMap;
+
 use num_bigint::BigInt;
 use serde_json::{Map, Number, Value};
 
+use crate::domain::rules::{AssertRules, RuleType};
+use crate::domain::value_path::{PathSegment, ValuePath};
 use crate::util::time::normalize_rfc3339_utc;
 
 const MAX_SAFE_NUMERIC_STRING_LEN: usize = 512;
 const MAX_SAFE_DECIMAL_EXPONENT: i32 = 2048;
 
+/// Which string scalars `canon` may turn into typed JSON values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CoerceMode {
+    /// Keep every string as-is.
+    None,
+    /// Coerce every boolean- or number-looking string.
+    #[default]
+    All,
+    /// Coerce only where a schema declares the field numeric or boolean.
+    Schema,
+}
+
+impl CoerceMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::All => "all",
+            Self::Schema => "schema",
+        }
+    }
+
+    pub fn parse_cli_name(input: &str) -> Option<Self> {
+        match input {
+            "none" => Some(Self::None),
+            "all" => Some(Self::All),
+            "schema" => Some(Self::Schema),
+            _ => None,
+        }
+    }
+}
+
+/// Scalar types a schema allows at one position.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct CoerceTargets {
+    boolean: bool,
+    number: bool,
+    integer: bool,
+    string: bool,
+}
+
+impl CoerceTargets {
+    fn merge(&mut self, other: Self) {
+        self.boolean |= other.boolean;
+        self.number |= other.number;
+        self.integer |= other.integer;
+        self.string |= other.string;
+    }
+}
+
+/// Field type tree derived from a JSON Schema or an `assert` rules file.
+///
+/// Object properties and array items are tracked separately so a schema
+/// declared for `items` applies to every element of the array.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoerceSchema {
+    targets: CoerceTargets,
+    properties: BTreeMap<String, CoerceSchema>,
+    items: Option<Box<CoerceSchema>>,
+}
+
+impl CoerceSchema {
+    /// Collect `type`, `properties` and `items` declarations, merging the
+    /// branches of `allOf`/`anyOf`/`oneOf`.
+    pub fn from_json_schema(schema: &Value) -> Self {
+        let mut out = Self::default();
+        out.merge_json_schema(schema);
+        out
+    }
+
+    /// Build a type tree from the dotted field paths of a rules file.
+    pub fn from_rules(rules: &AssertRules) -> Self {
+        let mut out = Self::default();
+        for (path, rule) in &rules.fields {
+            let Some(expected_type) = rule.expected_type.as_ref() else {
+                continue;
+            };
+            let mut node = &mut out;
+            for segment in path.split('.') {
+                node = node.properties.entry(segment.to_string()).or_default();
+            }
+            node.targets.merge(match expected_type {
+                RuleType::Boolean => CoerceTargets {
+                    boolean: true,
+                    ..CoerceTargets::default()
+                },
+                RuleType::Number => CoerceTargets {
+                    number: true,
+                    ..CoerceTargets::default()
+                },
+                RuleType::Integer => CoerceTargets {
+                    integer: true,
+                    ..CoerceTargets::default()
+                },
+                RuleType::String => CoerceTargets {
+                    string: true,
+                    ..CoerceTargets::default()
+                },
+                RuleType::Object | RuleType::Array | RuleType::Null => CoerceTargets::default(),
+            });
+        }
+        out
+    }
+
+    fn merge_json_schema(&mut self, schema: &Value) {
+        let Value::Object(map) = schema else {
+            return;
+        };
+        match map.get("type") {
+            Some(Value::String(name)) => self.targets.merge(json_schema_type_targets(name)),
+            Some(Value::Array(names)) => {
+                for name in names.iter().filter_map(Value::as_str) {
+                    self.targets.merge(json_schema_type_targets(name));
+                }
+            }
+            _ => {}
+        }
+        if let Some(Value::Object(properties)) = map.get("properties") {
+            for (key, child) in properties {
+                self.properties
+                    .entry(key.clone())
+                    .or_default()
+                    .merge_json_schema(child);
+            }
+        }
+        if let Some(items) = map.get("items") {
+            self.items
+                .get_or_insert_with(Box::default)
+                .merge_json_schema(items);
+        }
+        for keyword in ["allOf", "anyOf", "oneOf"] {
+            if let Some(Value::Array(branches)) = map.get(keyword) {
+                for branch in branches {
+                    self.merge_json_schema(branch);
+                }
+            }
+        }
+    }
+
+    fn child(&self, segment: &PathSegment) -> Option<&Self> {
+        match segment {
+            PathSegment::Key(key) => self.properties.get(key),
+            PathSegment::Index(_) => self.items.as_deref(),
+        }
+    }
+}
+
+fn json_schema_type_targets(name: &str) -> CoerceTargets {
+    let mut targets = CoerceTargets::default();
+    match name {
+        "boolean" => targets.boolean = true,
+        "number" => targets.number = true,
+        "integer" => targets.integer = true,
+        "string" => targets.string = true,
+        _ => {}
+    }
+    targets
+}
+
+/// String coercion policy applied by `canon`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoercePolicy {
+    pub mode: CoerceMode,
+    /// Canonical paths (relative to each record) whose subtrees are never
+    /// coerced, whatever the mode.
+    pub exempt_paths: Vec<ValuePath>,
+    /// Declared field types consulted in [`CoerceMode::Schema`].
+    pub schema: CoerceSchema,
+}
+
+impl CoercePolicy {
+    pub fn none() -> Self {
+        Self {
+            mode: CoerceMode::None,
+            ..Self::default()
+        }
+    }
+}
+
 /// Recursively coerce scalar string values into typed JSON values.
 pub fn coerce_value(value: Value, normalize_time: bool) -> Value {
+    coerce_value_with_policy(value, normalize_time, &CoercePolicy::default())
+}
+
+/// Recursively coerce scalar string values as allowed by `policy`.
+///
+/// Timestamp normalization is independent of the policy and still applies to
+/// strings left untouched by coercion.
+pub fn coerce_value_with_policy(
+    value: Value,
+    normalize_time: bool,
+    policy: &CoercePolicy,
+) -> Value {
+    let mut path = Vec::new();
+    let schema = match policy.mode {
+        CoerceMode::Schema => Some(&policy.schema),
+        CoerceMode::None | CoerceMode::All => None,
+    };
+    coerce_at(value, normalize_time, policy, schema, &mut path)
+}
+
+fn coerce_at(
+    value: Value,
+    normalize_time: bool,
+    policy: &CoercePolicy,
+    schema: Option<&CoerceSchema>,
+    path: &mut Vec<PathSegment>,
+) -> Value {
     match value {
         Value::Object(map) => Value::Object(
             map.into_iter()
-                .map(|(key, child)| (key, coerce_value(child, normalize_time)))
+                .map(|(key, child)| {
+                    let segment = PathSegment::Key(key);
+                    let child_schema = schema.and_then(|node| node.child(&segment));
+                    path.push(segment);
+                    let coerced = coerce_at(child, normalize_time, policy, child_schema, path);
+                    let Some(PathSegment::Key(key)) = path.pop() else {
+                        unreachable!("object key segment was pushed above");
+                    };
+                    (key, coerced)
+                })
                 .collect::<Map<String, Value>>(),
         ),
         Value::Array(items) => Value::Array(
             items
                 .into_iter()
-                .map(|item| coerce_value(item, normalize_time))
+                .enumerate()
+                .map(|(index, item)| {
+                    let segment = PathSegment::Index(index);
+                    let child_schema = schema.and_then(|node| node.child(&segment));
+                    path.push(segment);
+                    let coerced = coerce_at(item, normalize_time, policy, child_schema, path);
+                    path.pop();
+                    coerced
+                })
                 .collect(),
         ),
-        Value::String(text) => coerce_string(text, normalize_time),
+        Value::String(text) => {
+            let targets = if is_exempt(path, &policy.exempt_paths) {
+                CoerceTargets::default()
+            } else {
+                match policy.mode {
+                    CoerceMode::None => CoerceTargets::default(),
+                    CoerceMode::All => CoerceTargets {
+                        boolean: true,
+                        number: true,
+                        ..CoerceTargets::default()
+                    },
+                    CoerceMode::Schema => schema.map(|node| node.targets).unwrap_or_default(),
+                }
+            };
+            coerce_string(text, normalize_time, targets)
+        }
         primitive => primitive,
     }
 }
 
-fn coerce_string(text: String, normalize_time: bool) -> Value {
+fn is_exempt(path: &[PathSegment], exempt_paths: &[ValuePath]) -> bool {
+    exempt_paths.iter().any(|exempt| {
+        let prefix = exempt.segments();
+        path.len() >= prefix.len() && path[..prefix.len()] == *prefix
+    })
+}
+
+fn coerce_string(text: String, normalize_time: bool, targets: CoerceTargets) -> Value {
+    // A field that may legitimately hold a string keeps its text.
+    let allowed = !targets.string;
     match text.as_str() {
-        "true" => Value::Bool(true),
-        "false" => Value::Bool(false),
+        "true" if allowed && targets.boolean => Value::Bool(true),
+        "false" if allowed && targets.boolean => Value::Bool(false),
         _ => {
-            if let Some(number) = parse_json_number(text.as_str()) {
-                return Value::Number(number);
+            if allowed && targets.number {
+                if let Some(number) = parse_json_number(text.as_str()) {
+                    return Value::Number(number);
+                }
+            } else if allowed && targets.integer {
+                if let Some(number) = parse_json_integer(text.as_str()) {
+                    return Value::Number(number);
+                }
             }
             if normalize_time {
                 if let Some(normalized) = normalize_rfc3339_utc(text.as_str()) {
@@ -43,6 +299,13 @@ fn coerce_string(text: String, normalize_time: bool) -> Value {
     }
 }
 
+fn parse_json_integer(input: &str) -> Option<Number> {
+    if let Ok(parsed) = input.parse::<i64>() {
+        return Some(Number::from(parsed));
+    }
+    input.parse::<u64>().ok().map(Number::from)
+}
+
 fn parse_json_number(input: &str) -> Option<Number> {
     if let Ok(parsed) = input.parse::<i64>() {
         return Some(Number::from(parsed));
@@ -224,7 +487,9 @@ fn f64_rational(value: f64) -> Option<(BigInt, BigInt)> {
 mod tests {
     use serde_json::json;
 
-    use super::coerce_value;
+    use super::{CoerceMode, CoercePolicy, CoerceSchema, coerce_value, coerce_value_with_policy};
+    use crate::domain::rules::AssertRules;
+    use crate::domain::value_path::ValuePath;
 
     #[test]
     fn coerces_booleans_and_numbers_recursively() {
@@ -295,4 +560,103 @@ mod tests {
         let actual = coerce_value(input.clone(), false);
         assert_eq!(actual, input);
     }
+
+    #[test]
+    fn none_mode_keeps_strings_but_still_normalizes_time() {
+        let input = json!({"zip": "01234", "flag": "true", "ts": "2026-02-23T20:15:30+09:00"});
+        let actual = coerce_value_with_policy(input, true, &CoercePolicy::none());
+        assert_eq!(
+            actual,
+            json!({"zip": "01234", "flag": "true", "ts": "2026-02-23T11:15:30Z"})
+        );
+    }
+
+    #[test]
+    fn exempt_paths_skip_whole_subtrees() {
+        let policy = CoercePolicy {
+            exempt_paths: vec![
+                ValuePath::parse_canonical(r#"$["ids"]"#).expect("valid path"),
+                ValuePath::parse_canonical(r#"$["nested"]["zip"]"#).expect("valid path"),
+            ],
+            ..CoercePolicy::default()
+        };
+        let input = json!({
+            "ids": ["001", "002"],
+            "nested": {"zip": "01234", "count": "3"},
+            "count": "4"
+        });
+        let actual = coerce_value_with_policy(input, false, &policy);
+        assert_eq!(
+            actual,
+            json!({
+                "ids": ["001", "002"],
+                "nested": {"zip": "01234", "count": 3},
+                "count": 4
+            })
+        );
+    }
+
+    #[test]
+    fn schema_mode_coerces_only_declared_fields() {
+        let schema = CoerceSchema::from_json_schema(&json!({
+            "type": "object",
+            "properties": {
+                "active": {"type": "boolean"},
+                "zip": {"type": "string"},
+                "either": {"type": ["string", "number"]},
+                "items": {
+                    "type": "array",
+                    "items": {
+                        "properties": {
+                            "qty": {"type": "integer"},
+                            "price": {"anyOf": [{"type": "number"}, {"type": "null"}]}
+                        }
+                    }
+                }
+            }
+        }));
+        let policy = CoercePolicy {
+            mode: CoerceMode::Schema,
+            schema,
+            ..CoercePolicy::default()
+        };
+        let input = json!({
+            "active": "true",
+            "zip": "01234",
+            "either": "7",
+            "undeclared": "9",
+            "items": [{"qty": "2", "price": "1.5"}, {"qty": "2.5", "price": "x"}]
+        });
+        let actual = coerce_value_with_policy(input, false, &policy);
+        assert_eq!(
+            actual,
+            json!({
+                "active": true,
+                "zip": "01234",
+                "either": "7",
+                "undeclared": "9",
+                "items": [{"qty": 2, "price": 1.5}, {"qty": "2.5", "price": "x"}]
+            })
+        );
+    }
+
+    #[test]
+    fn schema_from_rules_uses_dotted_field_paths() {
+        let rules: AssertRules = serde_json::from_value(json!({
+            "required_keys": [],
+            "fields": {
+                "meta.count": {"type": "integer"},
+                "meta.code": {"type": "string"}
+            }
+        }))
+        .expect("valid rules");
+        let policy = CoercePolicy {
+            mode: CoerceMode::Schema,
+            schema: CoerceSchema::from_rules(&rules),
+            ..CoercePolicy::default()
+        };
+        let input = json!({"meta": {"count": "12", "code": "12"}});
+        let actual = coerce_value_with_policy(input, false, &policy);
+        assert_eq!(actual, json!({"meta": {"count": 12, "code": "12"}}));
+    }
 }
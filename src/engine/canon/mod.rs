@@ -1,15 +1,28 @@
 pub mod coerce;
 pub mod normalize;
+pub mod time;
 
 use serde_json::Value;
 
+use crate::util::time::LocalTimeError;
+
+use self::coerce::CoercePolicy;
+use self::normalize::NormalizeOptions;
+use self::time::TimeNormalization;
+
 /// Canonicalization options shared by `canon` command and engine layers.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CanonOptions {
     /// Sort object keys lexicographically. If `false`, preserve input key order.
     pub sort_keys: bool,
     /// Normalize RFC3339 timestamps to UTC (`Z`) when enabled.
     pub normalize_time: bool,
+    /// Which string scalars may be coerced into booleans and numbers.
+    pub coerce: CoercePolicy,
+    /// Optional key-casing, string and structural normalization passes.
+    pub normalize: NormalizeOptions,
+    /// Configurable timestamp conversion at selected paths.
+    pub time: Option<TimeNormalization>,
 }
 
 impl Default for CanonOptions {
@@ -17,22 +30,42 @@ impl Default for CanonOptions {
         Self {
             sort_keys: true,
             normalize_time: false,
+            coerce: CoercePolicy::default(),
+            normalize: NormalizeOptions::default(),
+            time: None,
         }
     }
 }
 
 /// Canonicalize a sequence of JSON values deterministically.
-pub fn canonicalize_values(values: Vec<Value>, options: CanonOptions) -> Vec<Value> {
+pub fn canonicalize_values(
+    values: Vec<Value>,
+    options: &CanonOptions,
+) -> Result<Vec<Value>, LocalTimeError> {
     values
         .into_iter()
         .map(|value| canonicalize_value(value, options))
         .collect()
 }
 
-/// Canonicalize one JSON value by applying coercion then normalization.
-pub fn canonicalize_value(value: Value, options: CanonOptions) -> Value {
-    let coerced = coerce::coerce_value(value, options.normalize_time);
-    normalize::normalize_value(coerced, options.sort_keys)
+/// Canonicalize one JSON value.
+///
+/// String passes run first, then timestamp conversion at selected paths, then
+/// coercion, then structural passes (ending with key sorting).
+///
+/// Fails only when timestamp conversion meets an ambiguous or nonexistent
+/// local time.
+pub fn canonicalize_value(value: Value, options: &CanonOptions) -> Result<Value, LocalTimeError> {
+    let mut prepared = normalize::apply_passes(value, &options.normalize.string_passes());
+    if let Some(time) = options.time.as_ref() {
+        prepared = time::normalize_times(prepared, time)?;
+    }
+    let coerced =
+        coerce::coerce_value_with_policy(prepared, options.normalize_time, &options.coerce);
+    Ok(normalize::apply_passes(
+        coerced,
+        &options.normalize.structure_passes(options.sort_keys),
+    ))
 }
 
 #[cfg(test)]
@@ -50,7 +83,7 @@ mod tests {
                 "time": "2026-02-23T20:15:30+09:00"
             }
         });
-        let actual = canonicalize_value(input, CanonOptions::default());
+        let actual = canonicalize_value(input, &CanonOptions::default()).expect("canonicalize");
         assert_eq!(
             actual,
             json!({
@@ -72,9 +105,10 @@ mod tests {
         let options = CanonOptions {
             sort_keys: true,
             normalize_time: true,
+            ..CanonOptions::default()
         };
-        let once = canonicalize_value(input.clone(), options);
-        let twice = canonicalize_value(once.clone(), options);
+        let once = canonicalize_value(input.clone(), &options).expect("canonicalize");
+        let twice = canonicalize_value(once.clone(), &options).expect("canonicalize");
         assert_eq!(once, twice);
     }
 }
@@ -1,4 +1,399 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
 use serde_json::{Map, Value};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::domain::value_path::{PathSegment, ValuePath};
+
+/// Target casing for object keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyCase {
+    Snake,
+    Camel,
+    Kebab,
+}
+
+impl KeyCase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Snake => "snake",
+            Self::Camel => "camel",
+            Self::Kebab => "kebab",
+        }
+    }
+
+    pub fn parse_cli_name(input: &str) -> Option<Self> {
+        match input {
+            "snake" => Some(Self::Snake),
+            "camel" => Some(Self::Camel),
+            "kebab" => Some(Self::Kebab),
+            _ => None,
+        }
+    }
+}
+
+/// Whitespace handling for string values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhitespaceMode {
+    /// Strip leading and trailing whitespace.
+    Trim,
+    /// Trim, then replace every inner whitespace run with one ASCII space.
+    Collapse,
+}
+
+impl WhitespaceMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Trim => "trim",
+            Self::Collapse => "collapse",
+        }
+    }
+
+    pub fn parse_cli_name(input: &str) -> Option<Self> {
+        match input {
+            "trim" => Some(Self::Trim),
+            "collapse" => Some(Self::Collapse),
+            _ => None,
+        }
+    }
+}
+
+/// Unicode normalization form applied to keys and string values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnicodeForm {
+    Nfc,
+    Nfkc,
+}
+
+impl UnicodeForm {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Nfc => "nfc",
+            Self::Nfkc => "nfkc",
+        }
+    }
+
+    pub fn parse_cli_name(input: &str) -> Option<Self> {
+        match input {
+            "nfc" => Some(Self::Nfc),
+            "nfkc" => Some(Self::Nfkc),
+            _ => None,
+        }
+    }
+}
+
+/// Optional normalization passes layered on top of key sorting.
+///
+/// Every pass is off by default, so the default value reproduces plain
+/// `normalize_value` output.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NormalizeOptions {
+    pub key_case: Option<KeyCase>,
+    pub whitespace: Option<WhitespaceMode>,
+    pub unicode: Option<UnicodeForm>,
+    /// Remove object fields whose value is `null`.
+    pub drop_nulls: bool,
+    /// Remove object fields whose value is `""`, `[]` or `{}`.
+    pub drop_empty: bool,
+    /// Canonical paths (relative to each record) of scalar arrays to sort.
+    pub sort_array_paths: Vec<ValuePath>,
+}
+
+/// One normalization pass over a whole value tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NormalizePass {
+    Unicode(UnicodeForm),
+    Whitespace(WhitespaceMode),
+    KeyCase(KeyCase),
+    DropFields { nulls: bool, empty: bool },
+    SortArrays(Vec<ValuePath>),
+    SortKeys,
+}
+
+impl NormalizeOptions {
+    /// Passes that rewrite string content.
+    ///
+    /// These run before string coercion so that, for example, `" 12 "` is
+    /// trimmed first and then coerced, which keeps `canon` idempotent.
+    pub fn string_passes(&self) -> Vec<NormalizePass> {
+        let mut passes = Vec::new();
+        if let Some(form) = self.unicode {
+            passes.push(NormalizePass::Unicode(form));
+        }
+        if let Some(mode) = self.whitespace {
+            passes.push(NormalizePass::Whitespace(mode));
+        }
+        passes
+    }
+
+    /// Passes that reshape objects and arrays, in application order.
+    ///
+    /// Array sort paths therefore refer to keys after `key_case` conversion.
+    pub fn structure_passes(&self, sort_keys: bool) -> Vec<NormalizePass> {
+        let mut passes = Vec::new();
+        if let Some(case) = self.key_case {
+            passes.push(NormalizePass::KeyCase(case));
+        }
+        if self.drop_nulls || self.drop_empty {
+            passes.push(NormalizePass::DropFields {
+                nulls: self.drop_nulls,
+                empty: self.drop_empty,
+            });
+        }
+        if !self.sort_array_paths.is_empty() {
+            passes.push(NormalizePass::SortArrays(self.sort_array_paths.clone()));
+        }
+        if sort_keys {
+            passes.push(NormalizePass::SortKeys);
+        }
+        passes
+    }
+}
+
+/// Apply `passes` in order.
+pub fn apply_passes(value: Value, passes: &[NormalizePass]) -> Value {
+    passes.iter().fold(value, apply_pass)
+}
+
+fn apply_pass(value: Value, pass: &NormalizePass) -> Value {
+    match pass {
+        NormalizePass::Unicode(form) => map_strings(value, true, &|text| match form {
+            UnicodeForm::Nfc => text.nfc().collect(),
+            UnicodeForm::Nfkc => text.nfkc().collect(),
+        }),
+        NormalizePass::Whitespace(mode) => map_strings(value, false, &|text| match mode {
+            WhitespaceMode::Trim => text.trim().to_string(),
+            WhitespaceMode::Collapse => text.split_whitespace().collect::<Vec<_>>().join(" "),
+        }),
+        NormalizePass::KeyCase(case) => convert_key_case(value, *case),
+        NormalizePass::DropFields { nulls, empty } => drop_fields(value, *nulls, *empty),
+        NormalizePass::SortArrays(paths) => {
+            let mut path = Vec::new();
+            sort_arrays_at(value, paths, &mut path)
+        }
+        NormalizePass::SortKeys => normalize_value(value, true),
+    }
+}
+
+/// Rewrite every string (and, with `include_keys`, every object key).
+///
+/// Keys that become equal after rewriting collide like `key_case`: the one
+/// whose original key sorts first wins, independent of input key order.
+fn map_strings(value: Value, include_keys: bool, apply: &dyn Fn(&str) -> String) -> Value {
+    match value {
+        Value::Object(map) if include_keys => {
+            let mut converted: Map<String, Value> = Map::new();
+            let mut origins: BTreeMap<String, String> = BTreeMap::new();
+            for (key, child) in map {
+                let renamed = apply(&key);
+                let child = map_strings(child, include_keys, apply);
+                let keep = match origins.get(&renamed) {
+                    Some(existing) => key < *existing,
+                    None => true,
+                };
+                if keep {
+                    origins.insert(renamed.clone(), key);
+                    converted.insert(renamed, child);
+                }
+            }
+            Value::Object(converted)
+        }
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, child)| (key, map_strings(child, include_keys, apply)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| map_strings(item, include_keys, apply))
+                .collect(),
+        ),
+        Value::String(text) => Value::String(apply(&text)),
+        primitive => primitive,
+    }
+}
+
+/// Convert every object key to `case`.
+///
+/// When two keys collide after conversion, the one whose original key sorts
+/// first wins, independent of input key order.
+fn convert_key_case(value: Value, case: KeyCase) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut converted: Map<String, Value> = Map::new();
+            let mut origins: BTreeMap<String, String> = BTreeMap::new();
+            for (key, child) in map {
+                let renamed = rename_key(&key, case);
+                let child = convert_key_case(child, case);
+                let keep = match origins.get(&renamed) {
+                    Some(existing) => key < *existing,
+                    None => true,
+                };
+                if keep {
+                    origins.insert(renamed.clone(), key);
+                    converted.insert(renamed, child);
+                }
+            }
+            Value::Object(converted)
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| convert_key_case(item, case))
+                .collect(),
+        ),
+        primitive => primitive,
+    }
+}
+
+fn rename_key(key: &str, case: KeyCase) -> String {
+    let words = split_key_words(key);
+    match case {
+        KeyCase::Snake => words.join("_"),
+        KeyCase::Kebab => words.join("-"),
+        KeyCase::Camel => {
+            let mut out = String::with_capacity(key.len());
+            for (index, word) in words.iter().enumerate() {
+                if index == 0 {
+                    out.push_str(word);
+                    continue;
+                }
+                let mut chars = word.chars();
+                if let Some(first) = chars.next() {
+                    out.extend(first.to_uppercase());
+                    out.push_str(chars.as_str());
+                }
+            }
+            out
+        }
+    }
+}
+
+/// Split a key into lowercase words on `_`, `-`, spaces and case boundaries
+/// (`userID` -> `user`, `id`; `HTTPServer` -> `http`, `server`).
+fn split_key_words(key: &str) -> Vec<String> {
+    let chars: Vec<char> = key.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for (index, &ch) in chars.iter().enumerate() {
+        if ch == '_' || ch == '-' || ch.is_whitespace() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if ch.is_uppercase() && !current.is_empty() {
+            let prev = chars[index - 1];
+            let next_is_lower = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.extend(ch.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn drop_fields(value: Value, nulls: bool, empty: bool) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, child)| (key, drop_fields(child, nulls, empty)))
+                .filter(|(_, child)| !(nulls && child.is_null() || empty && is_empty(child)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| drop_fields(item, nulls, empty))
+                .collect(),
+        ),
+        primitive => primitive,
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::String(text) => text.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        Value::Object(map) => map.is_empty(),
+        _ => false,
+    }
+}
+
+/// Sort arrays located exactly at one of `paths`.
+///
+/// Only arrays made entirely of scalars are sorted; arrays holding objects or
+/// arrays are left in input order.
+fn sort_arrays_at(value: Value, paths: &[ValuePath], path: &mut Vec<PathSegment>) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, child)| {
+                    path.push(PathSegment::Key(key));
+                    let child = sort_arrays_at(child, paths, path);
+                    let Some(PathSegment::Key(key)) = path.pop() else {
+                        unreachable!("object key segment was pushed above");
+                    };
+                    (key, child)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => {
+            let mut items: Vec<Value> = items
+                .into_iter()
+                .enumerate()
+                .map(|(index, item)| {
+                    path.push(PathSegment::Index(index));
+                    let item = sort_arrays_at(item, paths, path);
+                    path.pop();
+                    item
+                })
+                .collect();
+            let selected = paths
+                .iter()
+                .any(|target| target.segments() == path.as_slice());
+            if selected && items.iter().all(is_scalar) {
+                items.sort_by(compare_scalars);
+            }
+            Value::Array(items)
+        }
+        primitive => primitive,
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !matches!(value, Value::Array(_) | Value::Object(_))
+}
+
+/// Total order over scalars: `null < bool < number < string`.
+fn compare_scalars(left: &Value, right: &Value) -> Ordering {
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Null => 0,
+            Value::Bool(_) => 1,
+            Value::Number(_) => 2,
+            _ => 3,
+        }
+    }
+    match (left, right) {
+        (Value::Bool(l), Value::Bool(r)) => l.cmp(r),
+        (Value::Number(l), Value::Number(r)) => {
+            let by_value = match (l.as_f64(), r.as_f64()) {
+                (Some(l), Some(r)) => l.total_cmp(&r),
+                _ => Ordering::Equal,
+            };
+            by_value.then_with(|| l.to_string().cmp(&r.to_string()))
+        }
+        (Value::String(l), Value::String(r)) => l.cmp(r),
+        _ => rank(left).cmp(&rank(right)),
+    }
+}
 
 /// Recursively normalize JSON values for deterministic output.
 ///
@@ -38,7 +433,11 @@ fn normalize_object(map: Map<String, Value>, sort_keys: bool) -> Value {
 mod tests {
     use serde_json::json;
 
-    use super::normalize_value;
+    use super::{
+        KeyCase, NormalizeOptions, UnicodeForm, WhitespaceMode, apply_passes, normalize_value,
+        rename_key,
+    };
+    use crate::domain::value_path::ValuePath;
 
     #[test]
     fn sorts_keys_recursively_when_enabled() {
@@ -70,4 +469,92 @@ mod tests {
         let as_json = serde_json::to_string(&actual).expect("serialize normalized json");
         assert_eq!(as_json, r#"{"z":{"d":4,"a":1},"a":[{"c":3,"a":1}]}"#);
     }
+
+    #[test]
+    fn renames_keys_across_word_boundaries() {
+        assert_eq!(rename_key("userID", KeyCase::Snake), "user_id");
+        assert_eq!(rename_key("HTTPServer", KeyCase::Kebab), "http-server");
+        assert_eq!(rename_key("created_at", KeyCase::Camel), "createdAt");
+        assert_eq!(rename_key("Page-Count 2", KeyCase::Snake), "page_count_2");
+    }
+
+    #[test]
+    fn key_case_collisions_keep_lexicographically_first_original_key() {
+        let input: serde_json::Value =
+            serde_json::from_str(r#"{"user_id":1,"userId":2}"#).expect("parse json");
+        let options = NormalizeOptions {
+            key_case: Some(KeyCase::Snake),
+            ..NormalizeOptions::default()
+        };
+        let actual = apply_passes(input, &options.structure_passes(true));
+        assert_eq!(actual, json!({"user_id": 2}));
+    }
+
+    #[test]
+    fn unicode_key_collisions_keep_lexicographically_first_original_key() {
+        let options = NormalizeOptions {
+            unicode: Some(UnicodeForm::Nfkc),
+            ..NormalizeOptions::default()
+        };
+        let forward = json!({"name": 1, "ｎａｍｅ": 2});
+        let reversed: serde_json::Value =
+            serde_json::from_str(r#"{"ｎａｍｅ":2,"name":1}"#).expect("parse json");
+        assert_eq!(
+            apply_passes(forward, &options.string_passes()),
+            json!({"name": 1})
+        );
+        assert_eq!(
+            apply_passes(reversed, &options.string_passes()),
+            json!({"name": 1})
+        );
+    }
+
+    #[test]
+    fn string_passes_normalize_unicode_then_whitespace() {
+        let options = NormalizeOptions {
+            unicode: Some(UnicodeForm::Nfkc),
+            whitespace: Some(WhitespaceMode::Collapse),
+            ..NormalizeOptions::default()
+        };
+        let input = json!({"ｎａｍｅ": "  Ｔｏｋｙｏ \t  Tower ", "n": 1});
+        let actual = apply_passes(input, &options.string_passes());
+        assert_eq!(actual, json!({"name": "Tokyo Tower", "n": 1}));
+    }
+
+    #[test]
+    fn drops_null_and_empty_fields_bottom_up() {
+        let options = NormalizeOptions {
+            drop_nulls: true,
+            drop_empty: true,
+            ..NormalizeOptions::default()
+        };
+        let input = json!({"a": null, "b": "", "c": {"d": null}, "e": [null, ""], "f": 0});
+        let actual = apply_passes(input, &options.structure_passes(true));
+        assert_eq!(actual, json!({"e": [null, ""], "f": 0}));
+    }
+
+    #[test]
+    fn sorts_only_scalar_arrays_at_selected_paths() {
+        let options = NormalizeOptions {
+            sort_array_paths: vec![
+                ValuePath::parse_canonical(r#"$["tags"]"#).expect("valid path"),
+                ValuePath::parse_canonical(r#"$["rows"]"#).expect("valid path"),
+            ],
+            ..NormalizeOptions::default()
+        };
+        let input = json!({
+            "tags": ["b", 10, null, "a", 2, true],
+            "other": ["b", "a"],
+            "rows": [{"id": 2}, {"id": 1}]
+        });
+        let actual = apply_passes(input, &options.structure_passes(true));
+        assert_eq!(
+            actual,
+            json!({
+                "other": ["b", "a"],
+                "rows": [{"id": 2}, {"id": 1}],
+                "tags": [null, true, 2, 10, "a", "b"]
+            })
+        );
+    }
 }
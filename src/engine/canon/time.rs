@@ -0,0 +1,188 @@
+use serde_json::Value;
+
+use crate::domain::value_path::{PathSegment, ValuePath};
+use crate::util::time::{
+    AssumedZone, LocalTimeError, TimeInputFormat, TimeNormalizer, TimePrecision, TimeTarget,
+};
+
+/// Timestamp normalization restricted to selected subtrees.
+///
+/// Only scalars under one of `paths` are parsed, so unrelated integers are
+/// never mistaken for epoch timestamps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeNormalization {
+    /// Canonical paths (relative to each record) whose subtrees hold timestamps.
+    pub paths: Vec<ValuePath>,
+    pub normalizer: TimeNormalizer,
+}
+
+/// Raw timestamp settings shared by the CLI, MCP and recipe front-ends.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimeNormalizationArgs<'a> {
+    pub paths: &'a [String],
+    pub formats: &'a [String],
+    pub zone: Option<&'a str>,
+    pub precision: Option<&'a str>,
+    pub target: Option<&'a str>,
+}
+
+/// Validate raw settings; `None` when no timestamp path is selected.
+///
+/// Formats, zone, precision and target without any path are rejected so that
+/// an ignored setting never goes unnoticed.
+pub fn resolve_time_normalization(
+    args: TimeNormalizationArgs<'_>,
+) -> Result<Option<TimeNormalization>, String> {
+    if args.paths.is_empty() {
+        let has_settings = !args.formats.is_empty()
+            || args.zone.is_some()
+            || args.precision.is_some()
+            || args.target.is_some();
+        if has_settings {
+            return Err(
+                "time format, zone, precision and target require at least one time path"
+                    .to_string(),
+            );
+        }
+        return Ok(None);
+    }
+
+    let paths = args
+        .paths
+        .iter()
+        .map(|raw| {
+            ValuePath::parse_canonical(raw)
+                .map_err(|error| format!("invalid time path `{raw}`: {error}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut normalizer = TimeNormalizer::default();
+    if !args.formats.is_empty() {
+        normalizer.formats = args
+            .formats
+            .iter()
+            .map(|raw| TimeInputFormat::parse(raw))
+            .collect::<Result<Vec<_>, _>>()?;
+    }
+    if let Some(raw) = args.zone {
+        normalizer.assume_zone = AssumedZone::parse(raw).ok_or_else(|| {
+            format!(
+                "invalid time zone `{raw}`: expected `UTC`, a fixed offset like `+09:00`, or an IANA zone like `Asia/Tokyo`"
+            )
+        })?;
+    }
+    if let Some(raw) = args.precision {
+        normalizer.precision = TimePrecision::parse_cli_name(raw).ok_or_else(|| {
+            format!("invalid time precision `{raw}`: expected `auto`, `s`, `ms`, `us`, or `ns`")
+        })?;
+    }
+    if let Some(raw) = args.target {
+        normalizer.target = TimeTarget::parse_cli_name(raw).ok_or_else(|| {
+            format!("invalid time target `{raw}`: expected `rfc3339` or `epoch_ms`")
+        })?;
+    }
+    Ok(Some(TimeNormalization { paths, normalizer }))
+}
+
+/// Rewrite every recognized timestamp under the configured paths.
+///
+/// Values that match none of the input formats are left unchanged; a naive
+/// local time the assumed zone cannot resolve to one instant is an error.
+pub fn normalize_times(value: Value, options: &TimeNormalization) -> Result<Value, LocalTimeError> {
+    let mut path = Vec::new();
+    normalize_at(value, options, &mut path)
+}
+
+fn normalize_at(
+    value: Value,
+    options: &TimeNormalization,
+    path: &mut Vec<PathSegment>,
+) -> Result<Value, LocalTimeError> {
+    match value {
+        Value::Object(map) => {
+            let mut normalized = serde_json::Map::with_capacity(map.len());
+            for (key, child) in map {
+                path.push(PathSegment::Key(key));
+                let child = normalize_at(child, options, path);
+                let Some(PathSegment::Key(key)) = path.pop() else {
+                    unreachable!("object key segment was pushed above");
+                };
+                normalized.insert(key, child?);
+            }
+            Ok(Value::Object(normalized))
+        }
+        Value::Array(items) => {
+            let mut normalized = Vec::with_capacity(items.len());
+            for (index, item) in items.into_iter().enumerate() {
+                path.push(PathSegment::Index(index));
+                let item = normalize_at(item, options, path);
+                path.pop();
+                normalized.push(item?);
+            }
+            Ok(Value::Array(normalized))
+        }
+        scalar => {
+            let selected = options.paths.iter().any(|target| {
+                let prefix = target.segments();
+                path.len() >= prefix.len() && path[..prefix.len()] == *prefix
+            });
+            if !selected {
+                return Ok(scalar);
+            }
+            Ok(options.normalizer.normalize(&scalar)?.unwrap_or(scalar))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        TimeNormalization, TimeNormalizationArgs, normalize_times, resolve_time_normalization,
+    };
+    use crate::domain::value_path::ValuePath;
+    use crate::util::time::{TimeInputFormat, TimeNormalizer, TimeTarget};
+
+    #[test]
+    fn converts_only_selected_paths() {
+        let options = TimeNormalization {
+            paths: vec![
+                ValuePath::parse_canonical(r#"$["created"]"#).expect("valid path"),
+                ValuePath::parse_canonical(r#"$["seen"]"#).expect("valid path"),
+            ],
+            normalizer: TimeNormalizer {
+                formats: vec![TimeInputFormat::EpochMillis, TimeInputFormat::Rfc3339],
+                target: TimeTarget::Rfc3339,
+                ..TimeNormalizer::default()
+            },
+        };
+        let input = json!({
+            "created": 1_700_000_000_000_i64,
+            "seen": ["2026-02-23T20:15:30+09:00", "n/a"],
+            "count": 1_700_000_000_000_i64
+        });
+        assert_eq!(
+            normalize_times(input, &options).expect("no local-time errors"),
+            json!({
+                "created": "2023-11-14T22:13:20Z",
+                "seen": ["2026-02-23T11:15:30Z", "n/a"],
+                "count": 1_700_000_000_000_i64
+            })
+        );
+    }
+
+    #[test]
+    fn settings_without_paths_are_rejected() {
+        let formats = vec!["epoch_s".to_string()];
+        let error = resolve_time_normalization(TimeNormalizationArgs {
+            formats: &formats,
+            ..TimeNormalizationArgs::default()
+        })
+        .expect_err("formats without paths should fail");
+        assert!(error.contains("time path"));
+        assert_eq!(
+            resolve_time_normalization(TimeNormalizationArgs::default()),
+            Ok(None)
+        );
+    }
+}
@@ -0,0 +1,704 @@
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Payload definitions compared between JSON Schema contract documents.
+const DOCUMENT_PAYLOADS: [&str; 3] = ["success", "error", "pipeline"];
+
+/// Compatibility impact of one contract change for output consumers.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeSeverity {
+    Breaking,
+    Additive,
+}
+
+/// One difference between a saved contract snapshot and the current contracts.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ContractChange {
+    pub command: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+    pub path: String,
+    pub change: &'static str,
+    pub severity: ChangeSeverity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ContractDiffSummary {
+    pub breaking: usize,
+    pub additive: usize,
+}
+
+/// Deterministic compatibility report in current-contract order.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ContractDiffReport {
+    pub compatible: bool,
+    pub summary: ContractDiffSummary,
+    pub changes: Vec<ContractChange>,
+}
+
+/// Compares `contract --format json-schema` documents keyed by `x-dataq-command`.
+pub fn diff_schema_documents(before: &[Value], after: &[Value]) -> ContractDiffReport {
+    diff_keyed(before, after, "x-dataq-command", diff_schema_document)
+}
+
+/// Compares `contract` objects keyed by `command`.
+pub fn diff_contract_entries(before: &[Value], after: &[Value]) -> ContractDiffReport {
+    diff_keyed(before, after, "command", diff_contract_entry)
+}
+
+fn diff_keyed(
+    before: &[Value],
+    after: &[Value],
+    key: &str,
+    diff_entry: fn(&str, &Value, &Value, &mut Vec<ContractChange>),
+) -> ContractDiffReport {
+    let command_of = |entry: &Value| entry.get(key).and_then(Value::as_str).map(str::to_string);
+    let mut changes = Vec::new();
+    for entry in after {
+        let Some(command) = command_of(entry) else {
+            continue;
+        };
+        match before
+            .iter()
+            .find(|old| command_of(old).as_deref() == Some(command.as_str()))
+        {
+            Some(old) => diff_entry(&command, old, entry, &mut changes),
+            None => changes.push(change(
+                &command,
+                None,
+                "",
+                "command_added",
+                ChangeSeverity::Additive,
+            )),
+        }
+    }
+    for old in before {
+        let Some(command) = command_of(old) else {
+            continue;
+        };
+        if !after
+            .iter()
+            .any(|entry| command_of(entry).as_deref() == Some(command.as_str()))
+        {
+            changes.push(change(
+                &command,
+                None,
+                "",
+                "command_removed",
+                ChangeSeverity::Breaking,
+            ));
+        }
+    }
+
+    let breaking = changes
+        .iter()
+        .filter(|change| change.severity == ChangeSeverity::Breaking)
+        .count();
+    ContractDiffReport {
+        compatible: breaking == 0,
+        summary: ContractDiffSummary {
+            breaking,
+            additive: changes.len() - breaking,
+        },
+        changes,
+    }
+}
+
+fn diff_schema_document(
+    command: &str,
+    before: &Value,
+    after: &Value,
+    changes: &mut Vec<ContractChange>,
+) {
+    diff_version(
+        command,
+        before.get("title"),
+        after.get("title"),
+        after.get("x-dataq-supported-versions"),
+        changes,
+    );
+    diff_exit_codes(
+        command,
+        before.get("x-dataq-exit-codes"),
+        after.get("x-dataq-exit-codes"),
+        changes,
+    );
+    for payload in DOCUMENT_PAYLOADS {
+        let (Some(old), Some(new)) = (
+            before.pointer(&format!("/$defs/{payload}")),
+            after.pointer(&format!("/$defs/{payload}")),
+        ) else {
+            continue;
+        };
+        diff_schema(command, payload, "", old, new, changes);
+    }
+}
+
+fn diff_contract_entry(
+    command: &str,
+    before: &Value,
+    after: &Value,
+    changes: &mut Vec<ContractChange>,
+) {
+    diff_version(
+        command,
+        before.get("schema"),
+        after.get("schema"),
+        after.get("supported_versions"),
+        changes,
+    );
+    diff_exit_codes(
+        command,
+        before.get("exit_codes"),
+        after.get("exit_codes"),
+        changes,
+    );
+    let old_fields = string_set(before.get("output_fields"));
+    let new_fields = string_set(after.get("output_fields"));
+    for field in new_fields.difference(&old_fields) {
+        changes.push(change(
+            command,
+            Some("success"),
+            &format!("/{field}"),
+            "field_added",
+            ChangeSeverity::Additive,
+        ));
+    }
+    for field in old_fields.difference(&new_fields) {
+        changes.push(change(
+            command,
+            Some("success"),
+            &format!("/{field}"),
+            "field_removed",
+            ChangeSeverity::Breaking,
+        ));
+    }
+}
+
+/// A new default version is additive only while the snapshot version can
+/// still be pinned with `--contract-version`.
+fn diff_version(
+    command: &str,
+    before: Option<&Value>,
+    after: Option<&Value>,
+    supported: Option<&Value>,
+    changes: &mut Vec<ContractChange>,
+) {
+    if before == after {
+        return;
+    }
+    let still_supported = before
+        .and_then(Value::as_str)
+        .is_some_and(|version| string_set(supported).contains(version));
+    let mut entry = change(
+        command,
+        None,
+        "",
+        "version_changed",
+        if still_supported {
+            ChangeSeverity::Additive
+        } else {
+            ChangeSeverity::Breaking
+        },
+    );
+    entry.before = before.cloned();
+    entry.after = after.cloned();
+    changes.push(entry);
+}
+
+/// Consumers must handle every exit code, so a new one is breaking.
+fn diff_exit_codes(
+    command: &str,
+    before: Option<&Value>,
+    after: Option<&Value>,
+    changes: &mut Vec<ContractChange>,
+) {
+    let old_codes = object_keys(before);
+    let new_codes = object_keys(after);
+    for code in new_codes.difference(&old_codes) {
+        changes.push(change(
+            command,
+            None,
+            &format!("/exit_codes/{code}"),
+            "exit_code_added",
+            ChangeSeverity::Breaking,
+        ));
+    }
+    for code in old_codes.difference(&new_codes) {
+        changes.push(change(
+            command,
+            None,
+            &format!("/exit_codes/{code}"),
+            "exit_code_removed",
+            ChangeSeverity::Additive,
+        ));
+    }
+}
+
+fn diff_schema(
+    command: &str,
+    payload: &str,
+    path: &str,
+    before: &Value,
+    after: &Value,
+    changes: &mut Vec<ContractChange>,
+) {
+    if before == after {
+        return;
+    }
+
+    let old_types = schema_types(before);
+    let new_types = schema_types(after);
+    if old_types != new_types {
+        let narrowed = match (&old_types, &new_types) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(old), Some(new)) => new
+                .iter()
+                .all(|kind| old.contains(kind) || (kind == "integer" && old.contains("number"))),
+        };
+        let mut entry = change(
+            command,
+            Some(payload),
+            path,
+            if narrowed {
+                "type_narrowed"
+            } else {
+                "type_changed"
+            },
+            if narrowed {
+                ChangeSeverity::Additive
+            } else {
+                ChangeSeverity::Breaking
+            },
+        );
+        entry.before = Some(before.get("type").cloned().unwrap_or(Value::Null));
+        entry.after = Some(after.get("type").cloned().unwrap_or(Value::Null));
+        changes.push(entry);
+    }
+    if before.get("anyOf") != after.get("anyOf") {
+        changes.push(change(
+            command,
+            Some(payload),
+            path,
+            "variants_changed",
+            ChangeSeverity::Breaking,
+        ));
+    }
+    diff_enum(
+        command,
+        payload,
+        path,
+        before.get("enum"),
+        after.get("enum"),
+        changes,
+    );
+
+    let old_required = string_set(before.get("required"));
+    let new_required = string_set(after.get("required"));
+    let empty = Map::new();
+    let old_properties = before
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let new_properties = after
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let mut new_names: Vec<&String> = new_properties.keys().collect();
+    new_names.extend(
+        new_required
+            .iter()
+            .filter(|name| !new_properties.contains_key(*name)),
+    );
+    for name in new_names {
+        let field_path = format!("{path}/{name}");
+        let was_known = old_properties.contains_key(name) || old_required.contains(name);
+        if !was_known {
+            changes.push(change(
+                command,
+                Some(payload),
+                &field_path,
+                "field_added",
+                ChangeSeverity::Additive,
+            ));
+            continue;
+        }
+        match (old_required.contains(name), new_required.contains(name)) {
+            (true, false) => changes.push(change(
+                command,
+                Some(payload),
+                &field_path,
+                "field_now_optional",
+                ChangeSeverity::Breaking,
+            )),
+            (false, true) => changes.push(change(
+                command,
+                Some(payload),
+                &field_path,
+                "field_now_required",
+                ChangeSeverity::Additive,
+            )),
+            _ => {}
+        }
+        if let (Some(old), Some(new)) = (old_properties.get(name), new_properties.get(name)) {
+            diff_schema(command, payload, &field_path, old, new, changes);
+        }
+    }
+    let mut old_names: Vec<&String> = old_properties.keys().collect();
+    old_names.extend(
+        old_required
+            .iter()
+            .filter(|name| !old_properties.contains_key(*name)),
+    );
+    for name in old_names {
+        if !new_properties.contains_key(name) && !new_required.contains(name) {
+            changes.push(change(
+                command,
+                Some(payload),
+                &format!("{path}/{name}"),
+                "field_removed",
+                ChangeSeverity::Breaking,
+            ));
+        }
+    }
+
+    for keyword in ["items", "additionalProperties"] {
+        if let (Some(old @ Value::Object(_)), Some(new @ Value::Object(_))) =
+            (before.get(keyword), after.get(keyword))
+        {
+            diff_schema(command, payload, &format!("{path}/*"), old, new, changes);
+        }
+    }
+}
+
+/// Compares `enum` constraints. Consumers match on the listed values, so any
+/// value removed or added is breaking; only introducing the constraint narrows
+/// the output.
+fn diff_enum(
+    command: &str,
+    payload: &str,
+    path: &str,
+    before: Option<&Value>,
+    after: Option<&Value>,
+    changes: &mut Vec<ContractChange>,
+) {
+    match (
+        before.and_then(Value::as_array),
+        after.and_then(Value::as_array),
+    ) {
+        (Some(old), Some(new)) => {
+            for value in old.iter().filter(|value| !new.contains(value)) {
+                let mut entry = change(
+                    command,
+                    Some(payload),
+                    path,
+                    "enum_value_removed",
+                    ChangeSeverity::Breaking,
+                );
+                entry.before = Some(value.clone());
+                changes.push(entry);
+            }
+            for value in new.iter().filter(|value| !old.contains(value)) {
+                let mut entry = change(
+                    command,
+                    Some(payload),
+                    path,
+                    "enum_value_added",
+                    ChangeSeverity::Breaking,
+                );
+                entry.after = Some(value.clone());
+                changes.push(entry);
+            }
+        }
+        (Some(old), None) => {
+            let mut entry = change(
+                command,
+                Some(payload),
+                path,
+                "enum_constraint_removed",
+                ChangeSeverity::Breaking,
+            );
+            entry.before = Some(Value::Array(old.clone()));
+            changes.push(entry);
+        }
+        (None, Some(new)) => {
+            let mut entry = change(
+                command,
+                Some(payload),
+                path,
+                "enum_constraint_added",
+                ChangeSeverity::Additive,
+            );
+            entry.after = Some(Value::Array(new.clone()));
+            changes.push(entry);
+        }
+        (None, None) => {}
+    }
+}
+
+/// `None` means the schema accepts any JSON type.
+fn schema_types(schema: &Value) -> Option<BTreeSet<String>> {
+    match schema.get("type")? {
+        Value::String(kind) => Some(BTreeSet::from([kind.clone()])),
+        Value::Array(kinds) => Some(
+            kinds
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+fn string_set(value: Option<&Value>) -> BTreeSet<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn object_keys(value: Option<&Value>) -> BTreeSet<String> {
+    value
+        .and_then(Value::as_object)
+        .map(|object| object.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn change(
+    command: &str,
+    payload: Option<&str>,
+    path: &str,
+    kind: &'static str,
+    severity: ChangeSeverity,
+) -> ContractChange {
+    ContractChange {
+        command: command.to_string(),
+        payload: payload.map(str::to_string),
+        path: path.to_string(),
+        change: kind,
+        severity,
+        before: None,
+        after: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn document(command: &str, title: &str, success: Value) -> Value {
+        json!({
+            "title": title,
+            "x-dataq-command": command,
+            "x-dataq-supported-versions": [title],
+            "x-dataq-exit-codes": {"0": {}, "3": {}, "1": {}},
+            "$defs": {"success": success}
+        })
+    }
+
+    #[test]
+    fn classifies_breaking_and_additive_schema_changes() {
+        let before = vec![
+            document(
+                "sdiff",
+                "dataq.sdiff.output.v1",
+                json!({
+                    "type": "object",
+                    "required": ["counts", "keys"],
+                    "properties": {
+                        "counts": {"type": "object"},
+                        "keys": {"type": "array", "items": {"type": "string"}},
+                        "note": {"type": "string"}
+                    }
+                }),
+            ),
+            document("gone", "dataq.gone.output.v1", json!({})),
+        ];
+        let mut sdiff_after = document(
+            "sdiff",
+            "dataq.sdiff.output.v1",
+            json!({
+                "type": "object",
+                "required": ["counts", "total"],
+                "properties": {
+                    "counts": {"type": "integer"},
+                    "keys": {"type": "array", "items": {"type": ["string", "null"]}},
+                    "total": {"type": "integer"}
+                }
+            }),
+        );
+        sdiff_after["x-dataq-exit-codes"]["2"] = json!({});
+        let after = vec![
+            sdiff_after,
+            document("new", "dataq.new.output.v1", json!({})),
+        ];
+
+        let report = diff_schema_documents(&before, &after);
+        let summary: Vec<(String, &str, ChangeSeverity)> = report
+            .changes
+            .iter()
+            .map(|change| (change.path.clone(), change.change, change.severity))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "/exit_codes/2".to_string(),
+                    "exit_code_added",
+                    ChangeSeverity::Breaking
+                ),
+                (
+                    "/counts".to_string(),
+                    "type_changed",
+                    ChangeSeverity::Breaking
+                ),
+                (
+                    "/keys".to_string(),
+                    "field_now_optional",
+                    ChangeSeverity::Breaking
+                ),
+                (
+                    "/keys/*".to_string(),
+                    "type_changed",
+                    ChangeSeverity::Breaking
+                ),
+                (
+                    "/total".to_string(),
+                    "field_added",
+                    ChangeSeverity::Additive
+                ),
+                (
+                    "/note".to_string(),
+                    "field_removed",
+                    ChangeSeverity::Breaking
+                ),
+                (String::new(), "command_added", ChangeSeverity::Additive),
+                (String::new(), "command_removed", ChangeSeverity::Breaking),
+            ]
+        );
+        assert!(!report.compatible);
+        assert_eq!(
+            report.summary,
+            ContractDiffSummary {
+                breaking: 6,
+                additive: 2
+            }
+        );
+    }
+
+    #[test]
+    fn version_bump_is_additive_while_previous_version_stays_supported() {
+        let before = vec![document("scan", "dataq.scan.text.output.v1", json!({}))];
+        let mut after = document("scan", "dataq.scan.text.output.v2", json!({}));
+        after["x-dataq-supported-versions"] =
+            json!(["dataq.scan.text.output.v1", "dataq.scan.text.output.v2"]);
+        let report = diff_schema_documents(&before, &[after.clone()]);
+        assert!(report.compatible);
+        assert_eq!(report.changes[0].change, "version_changed");
+        assert_eq!(report.changes[0].severity, ChangeSeverity::Additive);
+
+        after["x-dataq-supported-versions"] = json!(["dataq.scan.text.output.v2"]);
+        let report = diff_schema_documents(&before, &[after]);
+        assert!(!report.compatible);
+
+        let unchanged = diff_schema_documents(&before, &before);
+        assert!(unchanged.compatible);
+        assert!(unchanged.changes.is_empty());
+    }
+
+    #[test]
+    fn enum_value_changes_are_breaking() {
+        let status = |values: Value| {
+            document(
+                "gate",
+                "dataq.gate.policy.output.v1",
+                json!({
+                    "type": "object",
+                    "properties": {"status": {"type": "string", "enum": values}}
+                }),
+            )
+        };
+        let before = vec![status(json!(["pass", "fail", "skip"]))];
+
+        let report = diff_schema_documents(&before, &[status(json!(["pass", "fail"]))]);
+        assert!(!report.compatible);
+        assert_eq!(report.changes.len(), 1);
+        assert_eq!(report.changes[0].path, "/status");
+        assert_eq!(report.changes[0].change, "enum_value_removed");
+        assert_eq!(report.changes[0].severity, ChangeSeverity::Breaking);
+        assert_eq!(report.changes[0].before, Some(json!("skip")));
+
+        let report =
+            diff_schema_documents(&before, &[status(json!(["pass", "fail", "skip", "warn"]))]);
+        assert!(!report.compatible);
+        assert_eq!(report.changes[0].change, "enum_value_added");
+        assert_eq!(report.changes[0].after, Some(json!("warn")));
+
+        let unconstrained = document(
+            "gate",
+            "dataq.gate.policy.output.v1",
+            json!({
+                "type": "object",
+                "properties": {"status": {"type": "string"}}
+            }),
+        );
+        let report = diff_schema_documents(&before, std::slice::from_ref(&unconstrained));
+        assert!(!report.compatible);
+        assert_eq!(report.changes[0].change, "enum_constraint_removed");
+
+        let report = diff_schema_documents(&[unconstrained], &before);
+        assert!(report.compatible);
+        assert_eq!(report.changes[0].change, "enum_constraint_added");
+        assert_eq!(report.changes[0].severity, ChangeSeverity::Additive);
+    }
+
+    #[test]
+    fn contract_entries_compare_output_fields_and_exit_codes() {
+        let before = vec![json!({
+            "command": "assert",
+            "schema": "dataq.assert.output.v1",
+            "supported_versions": ["dataq.assert.output.v1"],
+            "output_fields": ["matched", "mismatches"],
+            "exit_codes": {"0": "success", "2": "mismatch"}
+        })];
+        let after = vec![json!({
+            "command": "assert",
+            "schema": "dataq.assert.output.v1",
+            "supported_versions": ["dataq.assert.output.v1"],
+            "output_fields": ["matched", "mismatch_count"],
+            "exit_codes": {"0": "success"}
+        })];
+        let report = diff_contract_entries(&before, &after);
+        let summary: Vec<(&str, &str)> = report
+            .changes
+            .iter()
+            .map(|change| (change.path.as_str(), change.change))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("/exit_codes/2", "exit_code_removed"),
+                ("/mismatch_count", "field_added"),
+                ("/mismatches", "field_removed"),
+            ]
+        );
+        assert_eq!(report.summary.breaking, 1);
+    }
+}
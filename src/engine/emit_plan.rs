@@ -6,7 +6,8 @@ use serde::Serialize;
 use crate::cmd::{
     aggregate,
     r#assert::{self as assert_cmd, AssertInputNormalizeMode},
-    canon, contract, doctor, join, merge, profile, sdiff,
+    canon, contract, doctor, gate, ingest, ingest_yaml_jobs, join, merge, profile, scan, sdiff,
+    transform,
 };
 
 const TOOL_ORDER: [&str; 3] = ["jq", "yq", "mlr"];
@@ -78,6 +79,29 @@ pub fn resolve(request: &EmitPlanRequest) -> Result<EmitPlan, EmitPlanError> {
     })
 }
 
+/// Resolve the stages one recipe step kind expands into.
+pub fn resolve_recipe_step_stages(kind: &str) -> Result<Vec<EmitPlanStage>, EmitPlanError> {
+    let steps = match kind {
+        "canon" | "assert" | "profile" | "sdiff" | "join" | "aggregate" | "merge" => {
+            resolve_steps(kind, &[])?
+        }
+        "transform_rowset" => transform::pipeline_steps(),
+        "scan_text" => scan::pipeline_steps(),
+        "gate_schema" => gate::schema_pipeline_steps(),
+        "gate_policy" => gate::policy_pipeline_steps(),
+        "ingest_yaml_jobs" => ingest_yaml_jobs::pipeline_steps(),
+        "ingest_doc" => ingest::pipeline_steps(),
+        "write" => vec![
+            "encode_recipe_output".to_string(),
+            "persist_recipe_output_atomically".to_string(),
+        ],
+        other => {
+            return Err(EmitPlanError::UnknownCommand(other.to_string()));
+        }
+    };
+    Ok(build_stages(kind, &steps))
+}
+
 fn normalize_command(raw: &str) -> String {
     let normalized = raw.trim().to_ascii_lowercase();
     if normalized == "recipe run" {
@@ -233,6 +257,27 @@ fn stage_tool(command: &str, step: &str) -> &'static str {
         "assert" if step == "normalize_assert_input" => "yq+jq+mlr",
         "join" if step == "execute_join_with_mlr" => "mlr",
         "aggregate" if step == "execute_aggregate_with_mlr" => "mlr",
+        "transform_rowset" => match step {
+            "transform_rowset_jq" => "jq",
+            "transform_rowset_mlr" => "mlr",
+            _ => "rust",
+        },
+        "scan_text" => match step {
+            "scan_text_rg_execute" => "rg",
+            "scan_text_jq_project" => "jq",
+            _ => "rust",
+        },
+        "ingest_yaml_jobs" => match step {
+            "ingest_yaml_jobs_yq_extract" => "yq",
+            "ingest_yaml_jobs_jq_normalize" => "jq",
+            "ingest_yaml_jobs_mlr_shape" => "mlr",
+            _ => "rust",
+        },
+        "ingest_doc" => match step {
+            "ingest_doc_pandoc_ast" => "pandoc",
+            "ingest_doc_jq_project" => "jq",
+            _ => "rust",
+        },
         "doctor" => match step {
             "doctor_probe_jq" => "jq",
             "doctor_probe_yq" => "yq",
@@ -257,7 +302,7 @@ fn build_tool_expectations(stages: &[EmitPlanStage]) -> Vec<EmitPlanTool> {
 
 #[cfg(test)]
 mod tests {
-    use super::{EmitPlanError, EmitPlanRequest, resolve};
+    use super::{EmitPlanError, EmitPlanRequest, resolve, resolve_recipe_step_stages};
 
     #[test]
     fn resolves_canon_plan_with_runtime_stage_order() {
@@ -298,6 +343,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn resolves_recipe_step_stages_with_tools() {
+        let stages = resolve_recipe_step_stages("transform_rowset").expect("transform stages");
+        let tools: Vec<&str> = stages.iter().map(|stage| stage.tool.as_str()).collect();
+        assert_eq!(tools, vec!["jq", "mlr"]);
+        assert_eq!(stages[1].depends_on, vec!["transform_rowset_jq"]);
+
+        let error = resolve_recipe_step_stages("mystery").expect_err("unknown kind");
+        assert_eq!(error, EmitPlanError::UnknownCommand("mystery".to_string()));
+    }
+
     #[test]
     fn rejects_unknown_command() {
         let error = resolve(&EmitPlanRequest {
@@ -37,6 +37,17 @@ impl IngestDocInputFormat {
         }
     }
 
+    pub fn parse_cli_name(input: &str) -> Option<Self> {
+        match input {
+            "md" => Some(Self::Md),
+            "html" => Some(Self::Html),
+            "docx" => Some(Self::Docx),
+            "rst" => Some(Self::Rst),
+            "latex" => Some(Self::Latex),
+            _ => None,
+        }
+    }
+
     fn as_pandoc(self) -> PandocInputFormat {
         match self {
             Self::Md => PandocInputFormat::Markdown,
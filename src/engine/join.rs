@@ -13,6 +13,23 @@ pub enum JoinHow {
     Left,
 }
 
+impl JoinHow {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Inner => "inner",
+            Self::Left => "left",
+        }
+    }
+
+    pub fn parse_cli_name(input: &str) -> Option<Self> {
+        match input {
+            "inner" => Some(Self::Inner),
+            "left" => Some(Self::Left),
+            _ => None,
+        }
+    }
+}
+
 /// Domain errors for deterministic join execution.
 #[derive(Debug, Error)]
 pub enum JoinError {
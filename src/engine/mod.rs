@@ -1,6 +1,7 @@
 pub mod aggregate;
 pub mod assert;
 pub mod canon;
+pub mod contract_diff;
 pub mod emit_plan;
 pub mod ingest;
 pub mod join;
@@ -1,28 +1,48 @@
-use std::collections::BTreeMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::File;
+use std::io::{Cursor, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::rc::Rc;
 use std::str::FromStr;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value, json};
+use tempfile::NamedTempFile;
 
+use crate::adapters::process;
+use crate::cmd::{
+    aggregate, canon, gate, ingest, ingest_yaml_jobs, join, merge, scan, stage_trace, transform,
+};
+use crate::domain::ingest::IngestYamlJobsMode;
 use crate::domain::report::{
-    RecipeLockReport, RecipeReplayLockCheckReport, RecipeReplayLockMismatchReport,
-    RecipeReplayReport, RecipeRunReport, RecipeStepReport,
+    RecipeGraphEdge, RecipeGraphNode, RecipeGraphReport, RecipeGraphStage, RecipeLockReport,
+    RecipeReplayLockCheckReport, RecipeReplayLockMismatchReport, RecipeReplayReport,
+    RecipeRunReport, RecipeStepReport, RecipeValidateIssueReport, RecipeValidateReport,
 };
 use crate::domain::rules::AssertRules;
 use crate::domain::value_path::ValuePath;
+use crate::engine::aggregate::AggregateMetric;
 use crate::engine::r#assert::{self, AssertValidationError};
+use crate::engine::canon::coerce::{CoerceMode, CoercePolicy};
+use crate::engine::canon::normalize::{KeyCase, NormalizeOptions, UnicodeForm, WhitespaceMode};
+use crate::engine::canon::time::{
+    TimeNormalization, TimeNormalizationArgs, resolve_time_normalization,
+};
 use crate::engine::canon::{CanonOptions, canonicalize_value, canonicalize_values};
+use crate::engine::emit_plan;
+use crate::engine::ingest::IngestDocInputFormat;
+use crate::engine::join::JoinHow;
+use crate::engine::merge::MergePolicy;
 use crate::engine::profile;
 use crate::engine::sdiff::{self, DEFAULT_VALUE_DIFF_CAP, SdiffOptions};
 use crate::io::{self, Format};
-use crate::util::hash::DeterministicHasher;
+use crate::util::hash::{DigestHasher, HashAlgorithm};
 
 pub const RECIPE_VERSION: &str = "dataq.recipe.v1";
 const RECIPE_LOCK_VERSION: &str = "dataq.recipe.lock.v1";
-const RECIPE_LOCK_TOOLS: [&str; 3] = ["jq", "yq", "mlr"];
+/// Tools every lock records; step kinds add the other external tools they run.
 const RECIPE_LOCK_TOOL_ORDER: [&str; 3] = ["jq", "mlr", "yq"];
 const CANON_REQUIRES_INPUT_OR_PRIOR_VALUES: &str =
     "canon step requires `args.input` or prior in-memory values";
@@ -32,6 +52,26 @@ const PROFILE_REQUIRES_PRIOR_VALUES: &str =
     "profile step requires prior in-memory values (for example a preceding canon step)";
 const SDIFF_REQUIRES_PRIOR_VALUES: &str =
     "sdiff step requires prior in-memory values (for example a preceding canon step)";
+const JOIN_REQUIRES_LEFT_OR_PRIOR_VALUES: &str =
+    "join step requires `args.left` or prior in-memory values";
+const AGGREGATE_REQUIRES_INPUT_OR_PRIOR_VALUES: &str =
+    "aggregate step requires `args.input` or prior in-memory values";
+const MERGE_REQUIRES_BASE_OR_PRIOR_VALUES: &str =
+    "merge step requires `args.base` or prior in-memory values";
+const TRANSFORM_ROWSET_REQUIRES_INPUT_OR_PRIOR_VALUES: &str =
+    "transform_rowset step requires `args.input` or prior in-memory values";
+const GATE_SCHEMA_REQUIRES_INPUT_OR_PRIOR_VALUES: &str =
+    "gate_schema step requires `args.input` or prior in-memory values";
+const GATE_POLICY_REQUIRES_INPUT_OR_PRIOR_VALUES: &str =
+    "gate_policy step requires `args.input` or prior in-memory values";
+const INGEST_YAML_JOBS_REQUIRES_INPUT_OR_PRIOR_VALUES: &str =
+    "ingest_yaml_jobs step requires `args.input` or prior in-memory values";
+const WRITE_REQUIRES_PRIOR_VALUES: &str =
+    "write step requires prior in-memory values (for example a preceding canon step)";
+const JOIN_REQUIRES_RIGHT_OR_SECOND_INPUT: &str =
+    "join step requires `args.right` or a second entry in `inputs`";
+const SDIFF_REQUIRES_RIGHT_OR_SECOND_INPUT: &str =
+    "sdiff step requires `args.right` or a second entry in `inputs`";
 
 #[derive(Debug, Clone)]
 pub struct RecipeExecution {
@@ -53,6 +93,18 @@ pub struct RecipeReplayExecution {
     pub pipeline_steps: Vec<String>,
 }
 
+#[derive(Debug, Clone)]
+pub struct RecipeValidateExecution {
+    pub report: RecipeValidateReport,
+    pub pipeline_steps: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecipeGraphExecution {
+    pub report: RecipeGraphReport,
+    pub pipeline_steps: Vec<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct RecipeExecutionError {
     pub kind: RecipeExecutionErrorKind,
@@ -69,7 +121,69 @@ pub enum RecipeExecutionErrorKind {
 #[serde(deny_unknown_fields)]
 struct RecipeFile {
     version: String,
+    #[serde(default)]
+    params: BTreeMap<String, RecipeParamSpec>,
     steps: Vec<RecipeStep>,
+    /// Parameter values after applying `--param` overrides and defaults.
+    #[serde(skip)]
+    resolved_params: BTreeMap<String, Value>,
+    /// Files inlined by `include` steps, relative to the recipe directory.
+    #[serde(skip)]
+    included_files: BTreeSet<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RecipeParamSpec {
+    #[serde(rename = "type")]
+    param_type: RecipeParamType,
+    default: Option<Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum RecipeParamType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+}
+
+impl RecipeParamType {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Integer => "integer",
+            Self::Number => "number",
+            Self::Boolean => "boolean",
+        }
+    }
+
+    fn accepts(self, value: &Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::Number => value.is_number(),
+            Self::Boolean => value.is_boolean(),
+        }
+    }
+
+    fn parse_override(self, raw: &str) -> Option<Value> {
+        match self {
+            Self::String => Some(Value::String(raw.to_string())),
+            Self::Integer => raw.parse::<i64>().ok().map(Value::from),
+            Self::Number => raw
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number),
+            Self::Boolean => match raw {
+                "true" => Some(Value::Bool(true)),
+                "false" => Some(Value::Bool(false)),
+                _ => None,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -80,15 +194,60 @@ struct RecipeLockFile {
     args_hash: String,
     tool_versions: BTreeMap<String, String>,
     dataq_version: String,
+    #[serde(default)]
+    input_hashes: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(deny_unknown_fields)]
 struct RecipeStep {
+    /// Dataset name other steps reference from `inputs`.
+    id: Option<String>,
     kind: String,
+    /// Upstream step ids; omitted means the preceding step (v1 linear flow).
+    inputs: Option<Vec<String>>,
+    /// Whether a mismatch stops the recipe or lets later steps run.
+    #[serde(default)]
+    on_failure: RecipeOnFailure,
+    /// Run only when an earlier step ended with the given outcome.
+    when: Option<RecipeStepCondition>,
     args: Map<String, Value>,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum RecipeOnFailure {
+    #[default]
+    Stop,
+    Continue,
+}
+
+impl RecipeOnFailure {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Stop => "stop",
+            Self::Continue => "continue",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RecipeStepCondition {
+    /// `id` of the step whose outcome is checked.
+    step: String,
+    matched: bool,
+}
+
+/// Deterministic execution order resolved from step `inputs` and `when`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RecipePlan {
+    order: Vec<usize>,
+    inputs: Vec<Vec<usize>>,
+    /// Resolved `when` as (referenced step index, expected `matched`).
+    conditions: Vec<Option<(usize, bool)>>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(deny_unknown_fields)]
 struct CanonStepArgs {
@@ -98,6 +257,27 @@ struct CanonStepArgs {
     sort_keys: bool,
     #[serde(default)]
     normalize_time: bool,
+    coerce: Option<String>,
+    #[serde(default)]
+    coerce_exempt: Vec<String>,
+    coerce_schema: Option<PathBuf>,
+    coerce_rules: Option<PathBuf>,
+    key_case: Option<String>,
+    whitespace: Option<String>,
+    unicode: Option<String>,
+    #[serde(default)]
+    drop_nulls: bool,
+    #[serde(default)]
+    drop_empty: bool,
+    #[serde(default)]
+    sort_array: Vec<String>,
+    #[serde(default)]
+    time_path: Vec<String>,
+    #[serde(default)]
+    time_format: Vec<String>,
+    time_zone: Option<String>,
+    time_precision: Option<String>,
+    time_target: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -116,7 +296,7 @@ struct ProfileStepArgs {}
 #[derive(Debug, Deserialize)]
 #[serde(deny_unknown_fields)]
 struct SdiffStepArgs {
-    right: PathBuf,
+    right: Option<PathBuf>,
     right_from: Option<String>,
     key: Option<String>,
     #[serde(default)]
@@ -125,6 +305,107 @@ struct SdiffStepArgs {
     value_diff_cap: Option<usize>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JoinStepArgs {
+    left: Option<PathBuf>,
+    left_from: Option<String>,
+    right: Option<PathBuf>,
+    right_from: Option<String>,
+    on: String,
+    how: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AggregateStepArgs {
+    input: Option<PathBuf>,
+    from: Option<String>,
+    group_by: String,
+    metric: Option<String>,
+    target: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MergeStepArgs {
+    base: Option<PathBuf>,
+    overlays: Vec<PathBuf>,
+    policy: Option<String>,
+    #[serde(default)]
+    policy_path: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransformRowsetStepArgs {
+    input: Option<PathBuf>,
+    from: Option<String>,
+    jq_filter: String,
+    mlr: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScanTextStepArgs {
+    pattern: String,
+    path: Option<PathBuf>,
+    #[serde(default)]
+    glob: Vec<String>,
+    max_matches: Option<usize>,
+    #[serde(default)]
+    policy_mode: bool,
+    #[serde(default)]
+    jq_project: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GateSchemaStepArgs {
+    schema: PathBuf,
+    input: Option<PathBuf>,
+    from: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GatePolicyStepArgs {
+    rules: PathBuf,
+    input: Option<PathBuf>,
+    source: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IngestYamlJobsStepArgs {
+    input: Option<PathBuf>,
+    mode: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IngestDocStepArgs {
+    input: PathBuf,
+    from: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IncludeStepArgs {
+    path: PathBuf,
+    /// Values bound to the included recipe's declared params.
+    #[serde(default)]
+    params: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WriteStepArgs {
+    path: PathBuf,
+    format: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
 struct StepOutcome {
     matched: bool,
     exit_code: i32,
@@ -132,7 +413,15 @@ struct StepOutcome {
     next_values: Option<Vec<Value>>,
 }
 
-pub fn run(recipe_path: &Path) -> Result<RecipeExecution, RecipeExecutionError> {
+/// Runs a recipe file; `params` are raw `key=value` overrides for its `params` block.
+///
+/// With `cache_dir`, step outcomes are stored on disk and reused while a step's
+/// kind, args, input data and the dataq version are unchanged.
+pub fn run(
+    recipe_path: &Path,
+    params: &[String],
+    cache_dir: Option<&Path>,
+) -> Result<RecipeExecution, RecipeExecutionError> {
     let mut pipeline_steps = vec![
         "load_recipe_file".to_string(),
         "validate_recipe_schema".to_string(),
@@ -152,7 +441,13 @@ pub fn run(recipe_path: &Path) -> Result<RecipeExecution, RecipeExecutionError>
         }
     };
 
-    execute_loaded_recipe(loaded, recipe_base_dir.as_path(), &mut pipeline_steps)
+    execute_loaded_recipe(
+        loaded,
+        params,
+        recipe_base_dir.as_path(),
+        cache_dir,
+        &mut pipeline_steps,
+    )
 }
 
 /// Runs a recipe from an already-loaded JSON value.
@@ -162,6 +457,8 @@ pub fn run(recipe_path: &Path) -> Result<RecipeExecution, RecipeExecutionError>
 pub fn run_from_value(
     recipe_value: Value,
     base_dir: Option<&Path>,
+    params: &[String],
+    cache_dir: Option<&Path>,
 ) -> Result<RecipeExecution, RecipeExecutionError> {
     let mut pipeline_steps = vec![
         "load_recipe_inline".to_string(),
@@ -172,12 +469,17 @@ pub fn run_from_value(
         .unwrap_or_else(|| PathBuf::from("."));
     execute_loaded_recipe(
         recipe_value,
+        params,
         resolved_base_dir.as_path(),
+        cache_dir,
         &mut pipeline_steps,
     )
 }
 
-pub fn lock(recipe_path: &Path) -> Result<RecipeLockExecution, RecipeExecutionError> {
+pub fn lock(
+    recipe_path: &Path,
+    params: &[String],
+) -> Result<RecipeLockExecution, RecipeExecutionError> {
     let mut pipeline_steps = vec!["recipe_lock_parse".to_string()];
     let loaded = match load_recipe_value(recipe_path) {
         Ok(value) => value,
@@ -188,7 +490,11 @@ pub fn lock(recipe_path: &Path) -> Result<RecipeLockExecution, RecipeExecutionEr
             });
         }
     };
-    let recipe = match parse_loaded_recipe(loaded) {
+    let recipe_base_dir = recipe_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let recipe = match parse_loaded_recipe(loaded, params, recipe_base_dir.as_path()) {
         Ok(recipe) => recipe,
         Err(kind) => {
             return Err(RecipeExecutionError {
@@ -205,7 +511,7 @@ pub fn lock(recipe_path: &Path) -> Result<RecipeLockExecution, RecipeExecutionEr
     }
 
     pipeline_steps.push("recipe_lock_probe_tools".to_string());
-    let tool_versions = match probe_recipe_lock_tools() {
+    let tool_versions = match probe_recipe_lock_tools(&recipe) {
         Ok(versions) => versions,
         Err(kind) => {
             return Err(RecipeExecutionError {
@@ -216,7 +522,7 @@ pub fn lock(recipe_path: &Path) -> Result<RecipeLockExecution, RecipeExecutionEr
     };
 
     pipeline_steps.push("recipe_lock_fingerprint".to_string());
-    let args_hash = match hash_recipe_args(&recipe) {
+    let args_hash = match hash_recipe_args(&recipe, HashAlgorithm::Sha256) {
         Ok(hash) => hash,
         Err(kind) => {
             return Err(RecipeExecutionError {
@@ -225,12 +531,22 @@ pub fn lock(recipe_path: &Path) -> Result<RecipeLockExecution, RecipeExecutionEr
             });
         }
     };
+    let input_hashes = match hash_recipe_inputs(&recipe, recipe_base_dir.as_path()) {
+        Ok(hashes) => hashes,
+        Err(kind) => {
+            return Err(RecipeExecutionError {
+                kind,
+                pipeline_steps: pipeline_steps.clone(),
+            });
+        }
+    };
     let report = RecipeLockReport {
         version: RECIPE_LOCK_VERSION.to_string(),
-        command_graph_hash: hash_recipe_command_graph(&recipe),
+        command_graph_hash: hash_recipe_command_graph(&recipe, HashAlgorithm::Sha256),
         args_hash,
         tool_versions: tool_versions.clone(),
         dataq_version: env!("CARGO_PKG_VERSION").to_string(),
+        input_hashes,
     };
     let serialized = match serialize_recipe_lock_report(&report) {
         Ok(serialized) => serialized,
@@ -254,6 +570,7 @@ pub fn replay(
     recipe_path: &Path,
     lock_path: &Path,
     strict: bool,
+    params: &[String],
 ) -> Result<RecipeReplayExecution, RecipeExecutionError> {
     let mut pipeline_steps = vec!["recipe_replay_parse".to_string()];
     let recipe_base_dir = recipe_path
@@ -261,7 +578,7 @@ pub fn replay(
         .map(Path::to_path_buf)
         .unwrap_or_else(|| PathBuf::from("."));
 
-    let recipe = match load_and_parse_recipe(recipe_path) {
+    let recipe = match load_and_parse_recipe(recipe_path, params) {
         Ok(recipe) => recipe,
         Err(kind) => {
             return Err(RecipeExecutionError {
@@ -281,15 +598,16 @@ pub fn replay(
     };
 
     pipeline_steps.push("recipe_replay_verify_lock".to_string());
-    let lock_check = match verify_lock_constraints(&recipe, &lock, strict) {
-        Ok(report) => report,
-        Err(kind) => {
-            return Err(RecipeExecutionError {
-                kind,
-                pipeline_steps: pipeline_steps.clone(),
-            });
-        }
-    };
+    let lock_check =
+        match verify_lock_constraints(&recipe, &lock, recipe_base_dir.as_path(), strict) {
+            Ok(report) => report,
+            Err(kind) => {
+                return Err(RecipeExecutionError {
+                    kind,
+                    pipeline_steps: pipeline_steps.clone(),
+                });
+            }
+        };
 
     if strict && !lock_check.matched {
         return Ok(RecipeReplayExecution {
@@ -304,7 +622,7 @@ pub fn replay(
     }
 
     pipeline_steps.push("recipe_replay_execute".to_string());
-    let run_report = match execute_recipe_steps(recipe, recipe_base_dir.as_path(), None) {
+    let run_report = match execute_recipe_steps(recipe, recipe_base_dir.as_path(), None, None) {
         Ok(report) => report,
         Err(kind) => {
             return Err(RecipeExecutionError {
@@ -324,99 +642,668 @@ pub fn replay(
         pipeline_steps,
     })
 }
-fn execute_loaded_recipe(
-    loaded: Value,
-    recipe_base_dir: &Path,
-    pipeline_steps: &mut Vec<String>,
-) -> Result<RecipeExecution, RecipeExecutionError> {
-    let recipe = match parse_loaded_recipe(loaded) {
-        Ok(recipe) => recipe,
-        Err(kind) => {
-            return Err(RecipeExecutionError {
-                kind,
-                pipeline_steps: pipeline_steps.clone(),
-            });
-        }
-    };
 
-    execute_recipe(recipe, recipe_base_dir, pipeline_steps)
+/// Statically checks a recipe without executing any step.
+///
+/// Only failures to load the recipe file are returned as errors; every other
+/// problem is reported in [`RecipeValidateReport::errors`].
+pub fn validate(
+    recipe_path: &Path,
+    params: &[String],
+) -> Result<RecipeValidateExecution, RecipeExecutionError> {
+    let mut pipeline_steps = vec!["load_recipe_file".to_string()];
+    let recipe_base_dir = recipe_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let report = load_recipe_value(recipe_path).and_then(|loaded| {
+        validate_loaded_recipe(
+            loaded,
+            params,
+            recipe_base_dir.as_path(),
+            &mut pipeline_steps,
+        )
+    });
+    match report {
+        Ok(report) => Ok(RecipeValidateExecution {
+            report,
+            pipeline_steps,
+        }),
+        Err(kind) => Err(RecipeExecutionError {
+            kind,
+            pipeline_steps,
+        }),
+    }
 }
 
-fn execute_recipe(
-    recipe: RecipeFile,
-    recipe_base_dir: &Path,
-    pipeline_steps: &mut Vec<String>,
-) -> Result<RecipeExecution, RecipeExecutionError> {
-    let report = match execute_recipe_steps(recipe, recipe_base_dir, Some(pipeline_steps)) {
-        Ok(report) => report,
-        Err(kind) => {
-            return Err(RecipeExecutionError {
-                kind,
-                pipeline_steps: pipeline_steps.clone(),
-            });
-        }
-    };
+/// Lists every filesystem path a recipe file touches, resolved against the
+/// recipe's directory: step inputs, `write` targets, `scan_text` roots,
+/// inlined `include` files and rules files reached through `extends`.
+///
+/// `may_read` is asked before an `include` file or an `extends` target is
+/// opened. Refusing an include fails the listing; refused `extends` targets
+/// are not read and the rest of their chain is skipped.
+pub fn referenced_paths(
+    recipe_path: &Path,
+    params: &[String],
+    may_read: &dyn Fn(&Path) -> bool,
+) -> Result<Vec<PathBuf>, RecipeExecutionErrorKind> {
+    let loaded = load_recipe_value(recipe_path)?;
+    let recipe_base_dir = recipe_path.parent().unwrap_or_else(|| Path::new("."));
+    let recipe = parse_loaded_recipe_with(loaded, params, recipe_base_dir, may_read)?;
+    collect_recipe_referenced_paths(&recipe, recipe_base_dir, may_read)
+}
 
-    Ok(RecipeExecution {
-        report,
-        pipeline_steps: pipeline_steps.clone(),
-    })
+/// Same as [`referenced_paths`] for an inline recipe value.
+///
+/// `base_dir` defaults to the current directory (`.`), as in [`run_from_value`].
+pub fn referenced_paths_from_value(
+    recipe_value: Value,
+    base_dir: Option<&Path>,
+    params: &[String],
+    may_read: &dyn Fn(&Path) -> bool,
+) -> Result<Vec<PathBuf>, RecipeExecutionErrorKind> {
+    let recipe_base_dir = base_dir.unwrap_or_else(|| Path::new("."));
+    let recipe = parse_loaded_recipe_with(recipe_value, params, recipe_base_dir, may_read)?;
+    collect_recipe_referenced_paths(&recipe, recipe_base_dir, may_read)
 }
 
-fn execute_recipe_steps(
-    recipe: RecipeFile,
+fn collect_recipe_referenced_paths(
+    recipe: &RecipeFile,
     recipe_base_dir: &Path,
-    mut pipeline_steps: Option<&mut Vec<String>>,
-) -> Result<RecipeRunReport, RecipeExecutionErrorKind> {
-    let mut current_values: Option<Vec<Value>> = None;
-    let mut step_reports = Vec::with_capacity(recipe.steps.len());
+    may_read: &dyn Fn(&Path) -> bool,
+) -> Result<Vec<PathBuf>, RecipeExecutionErrorKind> {
+    let mut read_paths = BTreeSet::new();
+    let mut written_paths = BTreeSet::new();
+    for step in &recipe.steps {
+        collect_step_file_paths(step, &mut read_paths, &mut written_paths)?;
+        if step.kind == "scan_text" {
+            let args: ScanTextStepArgs = parse_step_args("scan_text", step.args.clone())?;
+            read_paths.insert(args.path.unwrap_or_else(|| PathBuf::from(".")));
+        }
+    }
+    read_paths.extend(recipe.included_files.iter().cloned());
 
-    for (index, step) in recipe.steps.into_iter().enumerate() {
-        if let Some(steps) = pipeline_steps.as_deref_mut() {
-            steps.push(format!("execute_step_{index}_{}", step.kind));
+    let mut resolved: BTreeSet<PathBuf> = read_paths
+        .union(&written_paths)
+        .map(|path| resolve_recipe_path(recipe_base_dir, path))
+        .collect();
+    let check_extends = |path: &Path| {
+        if may_read(path) {
+            Ok(())
+        } else {
+            Err(format!("reading `{}` is not allowed", path.display()))
+        }
+    };
+    for step in &recipe.steps {
+        for rules_path in step_rules_file_paths(step)? {
+            let rules_path = resolve_recipe_path(recipe_base_dir, &rules_path);
+            if !may_read(&rules_path) {
+                continue;
+            }
+            // Missing or invalid rules are reported when the step runs.
+            if let Ok((_, extended)) =
+                crate::cmd::r#assert::load_rules_with_extends(&rules_path, &check_extends)
+            {
+                resolved.extend(extended);
+            }
         }
+    }
+    Ok(resolved.into_iter().collect())
+}
 
-        let kind = step.kind.clone();
-        let outcome = execute_step(step, current_values.as_deref(), recipe_base_dir)?;
+/// Resolves the step DAG of a recipe, expanding each step into its stages.
+pub fn graph(
+    recipe_path: &Path,
+    params: &[String],
+) -> Result<RecipeGraphExecution, RecipeExecutionError> {
+    let mut pipeline_steps = vec![
+        "load_recipe_file".to_string(),
+        "validate_recipe_schema".to_string(),
+    ];
+    let recipe_base_dir = recipe_path.parent().unwrap_or_else(|| Path::new("."));
+    let report = load_recipe_value(recipe_path)
+        .and_then(|loaded| parse_loaded_recipe(loaded, params, recipe_base_dir))
+        .and_then(|recipe| {
+            pipeline_steps.push("plan_recipe_graph".to_string());
+            build_recipe_graph(&recipe)
+        });
+    match report {
+        Ok(report) => Ok(RecipeGraphExecution {
+            report,
+            pipeline_steps,
+        }),
+        Err(kind) => Err(RecipeExecutionError {
+            kind,
+            pipeline_steps,
+        }),
+    }
+}
 
-        if let Some(next_values) = outcome.next_values {
-            current_values = Some(next_values);
+fn validate_loaded_recipe(
+    loaded: Value,
+    params: &[String],
+    recipe_base_dir: &Path,
+    pipeline_steps: &mut Vec<String>,
+) -> Result<RecipeValidateReport, RecipeExecutionErrorKind> {
+    pipeline_steps.push("validate_recipe_schema".to_string());
+    let recipe = match parse_loaded_recipe(loaded, params, recipe_base_dir) {
+        Ok(recipe) => recipe,
+        Err(RecipeExecutionErrorKind::InputUsage(message)) => {
+            return Ok(recipe_validate_report(
+                0,
+                vec![validate_issue(None, "schema", message)],
+            ));
+        }
+        Err(kind) => return Err(kind),
+    };
+    let step_count = recipe.steps.len();
+
+    pipeline_steps.push("validate_recipe_steps".to_string());
+    let failures = match recipe_step_arg_failures(&recipe) {
+        Ok(failures) => failures,
+        Err(RecipeExecutionErrorKind::InputUsage(message)) => {
+            return Ok(recipe_validate_report(
+                step_count,
+                vec![validate_issue(None, "args", message)],
+            ));
         }
+        Err(kind) => return Err(kind),
+    };
+    if !failures.is_empty() {
+        let mut errors = Vec::with_capacity(failures.len());
+        for (index, kind) in failures {
+            match kind {
+                RecipeExecutionErrorKind::InputUsage(message) => {
+                    errors.push(validate_issue(Some(index), "args", message));
+                }
+                kind => return Err(kind),
+            }
+        }
+        errors.sort_by_key(|issue| issue.step);
+        return Ok(recipe_validate_report(step_count, errors));
+    }
 
-        step_reports.push(RecipeStepReport {
-            index,
-            kind,
-            matched: outcome.matched,
-            exit_code: outcome.exit_code,
-            summary: outcome.summary,
-        });
+    pipeline_steps.push("check_recipe_paths".to_string());
+    let mut written_paths = BTreeSet::new();
+    for step in &recipe.steps {
+        collect_step_file_paths(step, &mut BTreeSet::new(), &mut written_paths)?;
+    }
+    let mut errors = Vec::new();
+    let mut missing_paths = vec![false; step_count];
+    for (index, step) in recipe.steps.iter().enumerate() {
+        let mut read_paths = BTreeSet::new();
+        collect_step_file_paths(step, &mut read_paths, &mut BTreeSet::new())?;
+        for path in read_paths.difference(&written_paths) {
+            if !resolve_recipe_path(recipe_base_dir, path).exists() {
+                missing_paths[index] = true;
+                errors.push(validate_issue(
+                    Some(index),
+                    "path",
+                    format!("input file `{}` does not exist", path.display()),
+                ));
+            }
+        }
+    }
 
-        if !outcome.matched {
-            return Ok(RecipeRunReport {
-                matched: false,
-                exit_code: 2,
-                steps: step_reports,
-            });
+    pipeline_steps.push("compile_recipe_rules".to_string());
+    for (index, step) in recipe.steps.iter().enumerate() {
+        if missing_paths[index] {
+            continue;
+        }
+        match compile_recipe_step_rules(step, recipe_base_dir) {
+            Ok(()) => {}
+            Err(RecipeExecutionErrorKind::InputUsage(message)) => {
+                errors.push(validate_issue(Some(index), "compile", message));
+            }
+            Err(kind) => return Err(kind),
         }
     }
+    errors.sort_by_key(|issue| issue.step);
 
-    Ok(RecipeRunReport {
-        matched: true,
-        exit_code: 0,
-        steps: step_reports,
-    })
+    Ok(recipe_validate_report(step_count, errors))
 }
 
-fn execute_step(
-    step: RecipeStep,
+/// Loads and compiles the rules and schemas a step references, validating
+/// them against an empty dataset so no data is read.
+fn compile_recipe_step_rules(
+    step: &RecipeStep,
+    recipe_base_dir: &Path,
+) -> Result<(), RecipeExecutionErrorKind> {
+    let args = step.args.clone();
+    match step.kind.as_str() {
+        "canon" => {
+            let args: CanonStepArgs = parse_step_args("canon", args)?;
+            resolve_canon_coerce_policy(&args, recipe_base_dir)?;
+        }
+        "assert" => {
+            let args: AssertStepArgs = parse_step_args("assert", args)?;
+            match resolve_assert_source(args, recipe_base_dir)? {
+                AssertSource::Rules(rules) => {
+                    r#assert::execute_assert(&[], &rules).map_err(map_assert_error)?;
+                }
+                AssertSource::Schema(schema) => {
+                    r#assert::execute_assert_with_schema(&[], &schema).map_err(map_assert_error)?;
+                }
+            }
+        }
+        "gate_schema" => {
+            let args: GateSchemaStepArgs = parse_step_args("gate_schema", args)?;
+            let schema_path = resolve_recipe_path(recipe_base_dir, args.schema.as_path());
+            let schema = crate::cmd::r#assert::load_schema_from_path(schema_path.as_path())
+                .map_err(RecipeExecutionErrorKind::InputUsage)?;
+            r#assert::execute_assert_with_schema(&[], &schema).map_err(map_assert_error)?;
+        }
+        "gate_policy" => {
+            let args: GatePolicyStepArgs = parse_step_args("gate_policy", args)?;
+            let rules_path = resolve_recipe_path(recipe_base_dir, args.rules.as_path());
+            let rules = crate::cmd::r#assert::load_rules_from_path(rules_path.as_path())
+                .map_err(RecipeExecutionErrorKind::InputUsage)?;
+            r#assert::execute_assert(&[], &rules).map_err(map_assert_error)?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn recipe_validate_report(
+    step_count: usize,
+    errors: Vec<RecipeValidateIssueReport>,
+) -> RecipeValidateReport {
+    let valid = errors.is_empty();
+    RecipeValidateReport {
+        valid,
+        exit_code: if valid { 0 } else { 2 },
+        step_count,
+        errors,
+    }
+}
+
+fn validate_issue(
+    step: Option<usize>,
+    check: &str,
+    message: impl Into<String>,
+) -> RecipeValidateIssueReport {
+    RecipeValidateIssueReport {
+        step,
+        check: check.to_string(),
+        message: message.into(),
+    }
+}
+
+fn build_recipe_graph(recipe: &RecipeFile) -> Result<RecipeGraphReport, RecipeExecutionErrorKind> {
+    let plan = plan_recipe_steps(&recipe.steps)?;
+
+    let mut nodes = Vec::with_capacity(recipe.steps.len());
+    for (index, step) in recipe.steps.iter().enumerate() {
+        let stages = emit_plan::resolve_recipe_step_stages(step.kind.as_str()).map_err(|_| {
+            RecipeExecutionErrorKind::InputUsage(format!(
+                "unknown recipe step kind `{}`",
+                step.kind
+            ))
+        })?;
+        nodes.push(RecipeGraphNode {
+            index,
+            id: step.id.clone(),
+            kind: step.kind.clone(),
+            stages: stages
+                .into_iter()
+                .map(|stage| RecipeGraphStage {
+                    order: stage.order,
+                    step: stage.step,
+                    tool: stage.tool,
+                    depends_on: stage.depends_on,
+                })
+                .collect(),
+        });
+    }
+
+    let mut edges = Vec::new();
+    for (index, inputs) in plan.inputs.iter().enumerate() {
+        for &from in inputs {
+            edges.push(RecipeGraphEdge {
+                from,
+                to: index,
+                kind: "input".to_string(),
+                matched: None,
+            });
+        }
+        if let Some((from, matched)) = plan.conditions[index] {
+            edges.push(RecipeGraphEdge {
+                from,
+                to: index,
+                kind: "when".to_string(),
+                matched: Some(matched),
+            });
+        }
+    }
+
+    Ok(RecipeGraphReport {
+        nodes,
+        edges,
+        order: plan.order,
+    })
+}
+
+fn execute_loaded_recipe(
+    loaded: Value,
+    params: &[String],
+    recipe_base_dir: &Path,
+    cache_dir: Option<&Path>,
+    pipeline_steps: &mut Vec<String>,
+) -> Result<RecipeExecution, RecipeExecutionError> {
+    let recipe = match parse_loaded_recipe(loaded, params, recipe_base_dir) {
+        Ok(recipe) => recipe,
+        Err(kind) => {
+            return Err(RecipeExecutionError {
+                kind,
+                pipeline_steps: pipeline_steps.clone(),
+            });
+        }
+    };
+
+    execute_recipe(recipe, recipe_base_dir, cache_dir, pipeline_steps)
+}
+
+fn execute_recipe(
+    recipe: RecipeFile,
+    recipe_base_dir: &Path,
+    cache_dir: Option<&Path>,
+    pipeline_steps: &mut Vec<String>,
+) -> Result<RecipeExecution, RecipeExecutionError> {
+    let cache = match cache_dir.map(StepCache::open).transpose() {
+        Ok(cache) => cache,
+        Err(kind) => {
+            return Err(RecipeExecutionError {
+                kind,
+                pipeline_steps: pipeline_steps.clone(),
+            });
+        }
+    };
+    let report = match execute_recipe_steps(
+        recipe,
+        recipe_base_dir,
+        cache.as_ref(),
+        Some(pipeline_steps),
+    ) {
+        Ok(report) => report,
+        Err(kind) => {
+            return Err(RecipeExecutionError {
+                kind,
+                pipeline_steps: pipeline_steps.clone(),
+            });
+        }
+    };
+
+    Ok(RecipeExecution {
+        report,
+        pipeline_steps: pipeline_steps.clone(),
+    })
+}
+
+fn execute_recipe_steps(
+    recipe: RecipeFile,
+    recipe_base_dir: &Path,
+    cache: Option<&StepCache>,
+    mut pipeline_steps: Option<&mut Vec<String>>,
+) -> Result<RecipeRunReport, RecipeExecutionErrorKind> {
+    let plan = plan_recipe_steps(&recipe.steps)?;
+    let mut steps: Vec<Option<RecipeStep>> = recipe.steps.into_iter().map(Some).collect();
+    let mut outputs: Vec<Option<Rc<Vec<Value>>>> = vec![None; steps.len()];
+    let mut outcomes: Vec<Option<bool>> = vec![None; steps.len()];
+    let mut step_reports = Vec::with_capacity(steps.len());
+    let mut matched = true;
+
+    for &index in &plan.order {
+        let Some(step) = steps[index].take() else {
+            continue;
+        };
+
+        let upstream = &plan.inputs[index];
+        let primary = upstream.first().and_then(|input| outputs[*input].clone());
+        let secondary = upstream.get(1).and_then(|input| outputs[*input].clone());
+
+        let kind = step.kind.clone();
+        let id = step.id.clone();
+        let on_failure = step.on_failure;
+        let condition_met = plan.conditions[index]
+            .is_none_or(|(target, expected)| outcomes[target] == Some(expected));
+        if !condition_met {
+            if let Some(pipeline) = pipeline_steps.as_deref_mut() {
+                pipeline.push(format!("skip_step_{index}_{kind}"));
+            }
+            outputs[index] = primary;
+            step_reports.push(RecipeStepReport {
+                index,
+                id,
+                kind,
+                matched: true,
+                exit_code: 0,
+                skipped: true,
+                cached: false,
+                summary: json!({}),
+            });
+            continue;
+        }
+        let cache_key = match cache {
+            Some(cache) => step_cache_key(
+                cache,
+                &step,
+                primary.as_deref().map(Vec::as_slice),
+                secondary.as_deref().map(Vec::as_slice),
+                recipe_base_dir,
+            )?,
+            None => None,
+        };
+        let cached_outcome = cache
+            .zip(cache_key.as_deref())
+            .and_then(|(cache, key)| cache.load(key));
+        let cached = cached_outcome.is_some();
+        if let Some(pipeline) = pipeline_steps.as_deref_mut() {
+            if cached {
+                pipeline.push(format!("cached_step_{index}_{kind}"));
+            } else {
+                pipeline.push(format!("execute_step_{index}_{kind}"));
+            }
+        }
+
+        let outcome = match cached_outcome {
+            Some(outcome) => outcome,
+            None => {
+                if process::is_current_cancelled() {
+                    return Err(RecipeExecutionErrorKind::InputUsage(
+                        "recipe execution was cancelled".to_string(),
+                    ));
+                }
+                stage_trace::report_stage_start(&format!("execute_step_{index}_{kind}"));
+                let outcome = execute_step(
+                    step,
+                    primary.as_deref().map(Vec::as_slice),
+                    secondary.as_deref().map(Vec::as_slice),
+                    recipe_base_dir,
+                )?;
+                if let Some((cache, key)) = cache.zip(cache_key.as_deref()) {
+                    cache.store(key, &outcome)?;
+                }
+                outcome
+            }
+        };
+
+        outputs[index] = outcome.next_values.map(Rc::new).or(primary);
+        outcomes[index] = Some(outcome.matched);
+
+        step_reports.push(RecipeStepReport {
+            index,
+            id,
+            kind,
+            matched: outcome.matched,
+            exit_code: outcome.exit_code,
+            skipped: false,
+            cached,
+            summary: outcome.summary,
+        });
+
+        if !outcome.matched {
+            matched = false;
+            if on_failure == RecipeOnFailure::Stop {
+                break;
+            }
+        }
+    }
+
+    Ok(RecipeRunReport {
+        matched,
+        exit_code: if matched { 0 } else { 2 },
+        steps: step_reports,
+    })
+}
+
+/// On-disk store of step outcomes used by `recipe run --cache-dir`.
+///
+/// Entries are named by the step cache key, so stale entries are never read
+/// back; they are simply left behind for the user to prune.
+struct StepCache {
+    dir: PathBuf,
+    /// External tool versions probed once per run for cache keys; `None`
+    /// records a tool that could not be probed.
+    tool_versions: RefCell<BTreeMap<String, Option<String>>>,
+}
+
+impl StepCache {
+    fn open(dir: &Path) -> Result<Self, RecipeExecutionErrorKind> {
+        std::fs::create_dir_all(dir).map_err(|error| {
+            RecipeExecutionErrorKind::InputUsage(format!(
+                "failed to create recipe cache directory `{}`: {error}",
+                dir.display()
+            ))
+        })?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            tool_versions: RefCell::new(BTreeMap::new()),
+        })
+    }
+
+    fn tool_version(&self, tool_name: &str) -> Option<String> {
+        self.tool_versions
+            .borrow_mut()
+            .entry(tool_name.to_string())
+            .or_insert_with(|| probe_recipe_lock_tool_version(tool_name).ok())
+            .clone()
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        let name = key.strip_prefix("sha256:").unwrap_or(key);
+        self.dir.join(format!("{name}.json"))
+    }
+
+    /// Unreadable or corrupt entries are treated as misses and rewritten.
+    fn load(&self, key: &str) -> Option<StepOutcome> {
+        let bytes = std::fs::read(self.entry_path(key)).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    fn store(&self, key: &str, outcome: &StepOutcome) -> Result<(), RecipeExecutionErrorKind> {
+        let bytes = serde_json::to_vec(outcome).map_err(|error| {
+            RecipeExecutionErrorKind::Internal(format!(
+                "failed to serialize recipe cache entry: {error}"
+            ))
+        })?;
+        write_file_atomically(self.entry_path(key).as_path(), &bytes)
+    }
+}
+
+/// Cache key over step kind, canonical args, input datasets, the contents of
+/// files the step reads (including rules reached through `extends`), the
+/// versions of external tools the step kind runs and the dataq version.
+///
+/// Returns `None` for steps that must always execute: `write` has side effects
+/// and `scan_text` reads directory trees that are not part of the key. Steps
+/// whose input files or tools cannot be resolved also run uncached so the step
+/// reports the failure itself.
+fn step_cache_key(
+    cache: &StepCache,
+    step: &RecipeStep,
+    current_values: Option<&[Value]>,
+    secondary_values: Option<&[Value]>,
+    recipe_base_dir: &Path,
+) -> Result<Option<String>, RecipeExecutionErrorKind> {
+    if matches!(step.kind.as_str(), "write" | "scan_text") {
+        return Ok(None);
+    }
+
+    let mut hasher = DigestHasher::sha256();
+    hasher.update_len_prefixed(b"dataq.recipe.step_cache.v1");
+    hasher.update_len_prefixed(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.update_len_prefixed(step.kind.as_bytes());
+    hasher.update_len_prefixed(encode_canonical_step_args(&step.args)?.as_slice());
+    for values in [current_values, secondary_values] {
+        match values {
+            Some(values) => {
+                let encoded = serde_json::to_vec(values).map_err(|error| {
+                    RecipeExecutionErrorKind::Internal(format!(
+                        "failed to serialize recipe step input: {error}"
+                    ))
+                })?;
+                hasher.update_len_prefixed(b"values");
+                hasher.update_len_prefixed(encoded.as_slice());
+            }
+            None => hasher.update_len_prefixed(b"none"),
+        }
+    }
+
+    let mut read_paths = BTreeSet::new();
+    collect_step_file_paths(step, &mut read_paths, &mut BTreeSet::new())?;
+    for rules_path in step_rules_file_paths(step)? {
+        let Ok(extended) = rules_extends_files(recipe_base_dir, &rules_path) else {
+            return Ok(None);
+        };
+        read_paths.extend(extended);
+    }
+    for path in read_paths {
+        let resolved_path = resolve_recipe_path(recipe_base_dir, &path);
+        let Ok(hash) = hash_file_contents(resolved_path.as_path(), HashAlgorithm::Sha256) else {
+            return Ok(None);
+        };
+        hasher.update_len_prefixed(path.display().to_string().as_bytes());
+        hasher.update_len_prefixed(hash.as_bytes());
+    }
+
+    for tool in recipe_step_external_tools(step.kind.as_str()) {
+        let Some(version) = cache.tool_version(&tool) else {
+            return Ok(None);
+        };
+        hasher.update_len_prefixed(b"tool");
+        hasher.update_len_prefixed(tool.as_bytes());
+        hasher.update_len_prefixed(version.as_bytes());
+    }
+    Ok(Some(hasher.finish()))
+}
+
+fn execute_step(
+    step: RecipeStep,
     current_values: Option<&[Value]>,
+    secondary_values: Option<&[Value]>,
     recipe_base_dir: &Path,
 ) -> Result<StepOutcome, RecipeExecutionErrorKind> {
     match step.kind.as_str() {
         "canon" => execute_canon_step(step.args, current_values, recipe_base_dir),
         "assert" => execute_assert_step(step.args, current_values, recipe_base_dir),
         "profile" => execute_profile_step(step.args, current_values),
-        "sdiff" => execute_sdiff_step(step.args, current_values, recipe_base_dir),
+        "sdiff" => execute_sdiff_step(step.args, current_values, secondary_values, recipe_base_dir),
+        "join" => execute_join_step(step.args, current_values, secondary_values, recipe_base_dir),
+        "aggregate" => execute_aggregate_step(step.args, current_values, recipe_base_dir),
+        "merge" => execute_merge_step(step.args, current_values, recipe_base_dir),
+        "transform_rowset" => {
+            execute_transform_rowset_step(step.args, current_values, recipe_base_dir)
+        }
+        "scan_text" => execute_scan_text_step(step.args, recipe_base_dir),
+        "gate_schema" => execute_gate_schema_step(step.args, current_values, recipe_base_dir),
+        "gate_policy" => execute_gate_policy_step(step.args, current_values, recipe_base_dir),
+        "ingest_yaml_jobs" => {
+            execute_ingest_yaml_jobs_step(step.args, current_values, recipe_base_dir)
+        }
+        "ingest_doc" => execute_ingest_doc_step(step.args, recipe_base_dir),
+        "write" => execute_write_step(step.args, current_values, recipe_base_dir),
         other => Err(RecipeExecutionErrorKind::InputUsage(format!(
             "unknown recipe step kind `{other}`"
         ))),
@@ -446,13 +1333,23 @@ fn execute_canon_step(
         ));
     };
 
+    let coerce = resolve_canon_coerce_policy(&args, recipe_base_dir)?;
+    let coerce_mode = coerce.mode;
+    let normalize = parse_canon_normalize_options(&args)?;
+    let time = parse_canon_time_normalization(&args)?;
     let canonical = canonicalize_values(
         input_values,
-        CanonOptions {
+        &CanonOptions {
             sort_keys: args.sort_keys,
             normalize_time: args.normalize_time,
+            coerce,
+            normalize,
+            time,
         },
-    );
+    )
+    .map_err(|error| {
+        RecipeExecutionErrorKind::InputUsage(format!("failed to normalize timestamp: {error}"))
+    })?;
 
     Ok(StepOutcome {
         matched: true,
@@ -461,11 +1358,136 @@ fn execute_canon_step(
             "record_count": canonical.len(),
             "sort_keys": args.sort_keys,
             "normalize_time": args.normalize_time,
+            "coerce": coerce_mode.as_str(),
         }),
         next_values: Some(canonical),
     })
 }
 
+fn parse_canon_normalize_options(
+    args: &CanonStepArgs,
+) -> Result<NormalizeOptions, RecipeExecutionErrorKind> {
+    fn parse_named<T>(
+        raw: Option<&str>,
+        field: &str,
+        expected: &str,
+        parse: fn(&str) -> Option<T>,
+    ) -> Result<Option<T>, RecipeExecutionErrorKind> {
+        raw.map(|value| {
+            parse(value).ok_or_else(|| {
+                RecipeExecutionErrorKind::InputUsage(format!(
+                    "invalid `canon.args.{field}` value `{value}`: expected {expected}"
+                ))
+            })
+        })
+        .transpose()
+    }
+
+    let sort_array_paths = args
+        .sort_array
+        .iter()
+        .map(|raw| {
+            ValuePath::parse_canonical(raw).map_err(|error| {
+                RecipeExecutionErrorKind::InputUsage(format!(
+                    "invalid `canon.args.sort_array` path `{raw}`: {error}"
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(NormalizeOptions {
+        key_case: parse_named(
+            args.key_case.as_deref(),
+            "key_case",
+            "`snake`, `camel`, or `kebab`",
+            KeyCase::parse_cli_name,
+        )?,
+        whitespace: parse_named(
+            args.whitespace.as_deref(),
+            "whitespace",
+            "`trim` or `collapse`",
+            WhitespaceMode::parse_cli_name,
+        )?,
+        unicode: parse_named(
+            args.unicode.as_deref(),
+            "unicode",
+            "`nfc` or `nfkc`",
+            UnicodeForm::parse_cli_name,
+        )?,
+        drop_nulls: args.drop_nulls,
+        drop_empty: args.drop_empty,
+        sort_array_paths,
+    })
+}
+
+fn parse_canon_time_normalization(
+    args: &CanonStepArgs,
+) -> Result<Option<TimeNormalization>, RecipeExecutionErrorKind> {
+    resolve_time_normalization(TimeNormalizationArgs {
+        paths: &args.time_path,
+        formats: &args.time_format,
+        zone: args.time_zone.as_deref(),
+        precision: args.time_precision.as_deref(),
+        target: args.time_target.as_deref(),
+    })
+    .map_err(|message| {
+        RecipeExecutionErrorKind::InputUsage(format!("invalid canon step time args: {message}"))
+    })
+}
+
+fn parse_canon_coerce_mode(args: &CanonStepArgs) -> Result<CoerceMode, RecipeExecutionErrorKind> {
+    match args.coerce.as_deref() {
+        None => Ok(CoerceMode::All),
+        Some(raw) => CoerceMode::parse_cli_name(raw).ok_or_else(|| {
+            RecipeExecutionErrorKind::InputUsage(format!(
+                "invalid `canon.args.coerce` value `{raw}`: expected `none`, `all`, or `schema`"
+            ))
+        }),
+    }
+}
+
+fn parse_canon_coerce_source_paths(
+    args: &CanonStepArgs,
+) -> Result<(Option<&Path>, Option<&Path>), RecipeExecutionErrorKind> {
+    if args.coerce_schema.is_some() && args.coerce_rules.is_some() {
+        return Err(RecipeExecutionErrorKind::InputUsage(
+            "canon step args `coerce_schema` and `coerce_rules` are mutually exclusive".to_string(),
+        ));
+    }
+    Ok((args.coerce_schema.as_deref(), args.coerce_rules.as_deref()))
+}
+
+fn validate_canon_coerce_args(args: &CanonStepArgs) -> Result<(), RecipeExecutionErrorKind> {
+    let mode = parse_canon_coerce_mode(args)?;
+    let (schema, rules) = parse_canon_coerce_source_paths(args)?;
+    canon::validate_coerce_args(
+        mode,
+        &args.coerce_exempt,
+        schema.is_some() || rules.is_some(),
+    )
+    .map_err(RecipeExecutionErrorKind::InputUsage)?;
+    Ok(())
+}
+
+/// Resolves the coerce policy through the same resolver as `dataq canon`, so
+/// recipes accept the same inputs and report the same errors.
+fn resolve_canon_coerce_policy(
+    args: &CanonStepArgs,
+    recipe_base_dir: &Path,
+) -> Result<CoercePolicy, RecipeExecutionErrorKind> {
+    let mode = parse_canon_coerce_mode(args)?;
+    let (schema, rules) = parse_canon_coerce_source_paths(args)?;
+    let schema = schema.map(|path| resolve_recipe_path(recipe_base_dir, path));
+    let rules = rules.map(|path| resolve_recipe_path(recipe_base_dir, path));
+    let source = match (schema.as_deref(), rules.as_deref()) {
+        (Some(path), _) => Some(canon::CoerceSchemaSource::JsonSchema(path)),
+        (None, Some(path)) => Some(canon::CoerceSchemaSource::Rules(path)),
+        (None, None) => None,
+    };
+    canon::resolve_coerce_policy(mode, &args.coerce_exempt, source)
+        .map_err(RecipeExecutionErrorKind::InputUsage)
+}
+
 fn execute_assert_step(
     args: Map<String, Value>,
     current_values: Option<&[Value]>,
@@ -523,6 +1545,7 @@ fn execute_profile_step(
 fn execute_sdiff_step(
     args: Map<String, Value>,
     current_values: Option<&[Value]>,
+    secondary_values: Option<&[Value]>,
     recipe_base_dir: &Path,
 ) -> Result<StepOutcome, RecipeExecutionErrorKind> {
     let args: SdiffStepArgs = parse_step_args("sdiff", args)?;
@@ -530,13 +1553,14 @@ fn execute_sdiff_step(
         RecipeExecutionErrorKind::InputUsage(SDIFF_REQUIRES_PRIOR_VALUES.to_string())
     })?;
 
-    let right_path = resolve_recipe_path(recipe_base_dir, args.right.as_path());
-    let right_format = resolve_step_input_format(
+    let right_values = load_step_input_values(
+        args.right.as_deref(),
         args.right_from.as_deref(),
-        right_path.as_path(),
         "sdiff.args.right",
+        secondary_values,
+        recipe_base_dir,
+        SDIFF_REQUIRES_RIGHT_OR_SECOND_INPUT,
     )?;
-    let right_values = read_values_from_path(right_path.as_path(), right_format)?;
 
     let parsed_key = args
         .key
@@ -587,54 +1611,557 @@ fn execute_sdiff_step(
     })
 }
 
-fn resolve_assert_source(
-    args: AssertStepArgs,
+fn execute_join_step(
+    args: Map<String, Value>,
+    current_values: Option<&[Value]>,
+    secondary_values: Option<&[Value]>,
     recipe_base_dir: &Path,
-) -> Result<AssertSource, RecipeExecutionErrorKind> {
-    let rules_value = match (args.rules, args.rules_file) {
-        (Some(value), None) => Some(value),
-        (None, Some(path)) => {
-            let resolved_path = resolve_recipe_path(recipe_base_dir, path.as_path());
-            Some(read_single_value_from_path(
-                resolved_path.as_path(),
-                "assert.rules_file",
-            )?)
-        }
-        (Some(_), Some(_)) => {
-            return Err(RecipeExecutionErrorKind::InputUsage(
-                "assert step args `rules` and `rules_file` are mutually exclusive".to_string(),
-            ));
-        }
-        (None, None) => None,
-    };
+) -> Result<StepOutcome, RecipeExecutionErrorKind> {
+    let args: JoinStepArgs = parse_step_args("join", args)?;
+    let how = parse_join_how(&args)?;
+    let left = load_step_input_values(
+        args.left.as_deref(),
+        args.left_from.as_deref(),
+        "join.args.left",
+        current_values,
+        recipe_base_dir,
+        JOIN_REQUIRES_LEFT_OR_PRIOR_VALUES,
+    )?;
+    let right = load_step_input_values(
+        args.right.as_deref(),
+        args.right_from.as_deref(),
+        "join.args.right",
+        secondary_values,
+        recipe_base_dir,
+        JOIN_REQUIRES_RIGHT_OR_SECOND_INPUT,
+    )?;
 
-    let schema_value = match (args.schema, args.schema_file) {
-        (Some(value), None) => Some(value),
-        (None, Some(path)) => {
-            let resolved_path = resolve_recipe_path(recipe_base_dir, path.as_path());
-            Some(read_single_value_from_path(
-                resolved_path.as_path(),
-                "assert.schema_file",
-            )?)
-        }
-        (Some(_), Some(_)) => {
-            return Err(RecipeExecutionErrorKind::InputUsage(
-                "assert step args `schema` and `schema_file` are mutually exclusive".to_string(),
-            ));
-        }
-        (None, None) => None,
-    };
+    let (response, _) = join::run_with_trace(&join::JoinCommandArgs {
+        left: join::JoinCommandInput::Inline(left),
+        right: join::JoinCommandInput::Inline(right),
+        on: args.on.clone(),
+        how,
+    });
+    let rows = payload_rows(command_step_payload(
+        "join",
+        response.exit_code,
+        response.payload,
+    )?);
 
-    match (rules_value, schema_value) {
-        (Some(rules), None) => {
-            let parsed: AssertRules = serde_json::from_value(rules).map_err(|error| {
-                RecipeExecutionErrorKind::InputUsage(format!(
-                    "invalid assert rules in recipe step: {error}"
-                ))
-            })?;
-            Ok(AssertSource::Rules(parsed))
-        }
-        (None, Some(schema)) => Ok(AssertSource::Schema(schema)),
+    Ok(StepOutcome {
+        matched: true,
+        exit_code: 0,
+        summary: json!({
+            "record_count": rows.len(),
+            "on": args.on,
+            "how": how.as_str(),
+        }),
+        next_values: Some(rows),
+    })
+}
+
+fn execute_aggregate_step(
+    args: Map<String, Value>,
+    current_values: Option<&[Value]>,
+    recipe_base_dir: &Path,
+) -> Result<StepOutcome, RecipeExecutionErrorKind> {
+    let args: AggregateStepArgs = parse_step_args("aggregate", args)?;
+    let metric = parse_aggregate_metric(&args)?;
+    let input = load_step_input_values(
+        args.input.as_deref(),
+        args.from.as_deref(),
+        "aggregate.args.input",
+        current_values,
+        recipe_base_dir,
+        AGGREGATE_REQUIRES_INPUT_OR_PRIOR_VALUES,
+    )?;
+
+    let (response, _) = aggregate::run_with_trace(&aggregate::AggregateCommandArgs {
+        input: aggregate::AggregateCommandInput::Inline(input),
+        group_by: args.group_by.clone(),
+        metric,
+        target: args.target.clone(),
+    });
+    let rows = payload_rows(command_step_payload(
+        "aggregate",
+        response.exit_code,
+        response.payload,
+    )?);
+
+    Ok(StepOutcome {
+        matched: true,
+        exit_code: 0,
+        summary: json!({
+            "record_count": rows.len(),
+            "group_by": args.group_by,
+            "metric": metric.as_str(),
+            "target": args.target,
+        }),
+        next_values: Some(rows),
+    })
+}
+
+fn execute_merge_step(
+    args: Map<String, Value>,
+    current_values: Option<&[Value]>,
+    recipe_base_dir: &Path,
+) -> Result<StepOutcome, RecipeExecutionErrorKind> {
+    let args: MergeStepArgs = parse_step_args("merge", args)?;
+    let policy = parse_merge_policy(&args)?;
+    let base = match args.base.as_deref() {
+        Some(path) => merge::MergeCommandInput::Path(resolve_recipe_path(recipe_base_dir, path)),
+        None => {
+            let values = current_values.ok_or_else(|| {
+                RecipeExecutionErrorKind::InputUsage(
+                    MERGE_REQUIRES_BASE_OR_PRIOR_VALUES.to_string(),
+                )
+            })?;
+            merge::MergeCommandInput::Inline(match values {
+                [single] => single.clone(),
+                _ => Value::Array(values.to_vec()),
+            })
+        }
+    };
+    let overlays = args
+        .overlays
+        .iter()
+        .map(|path| merge::MergeCommandInput::Path(resolve_recipe_path(recipe_base_dir, path)))
+        .collect();
+
+    let response = merge::run_with_policy_paths_from_inputs(
+        &merge::MergeCommandInputArgs {
+            base,
+            overlays,
+            policy,
+        },
+        &args.policy_path,
+    );
+    let merged = command_step_payload("merge", response.exit_code, response.payload)?;
+
+    Ok(StepOutcome {
+        matched: true,
+        exit_code: 0,
+        summary: json!({
+            "record_count": 1,
+            "overlay_count": args.overlays.len(),
+            "policy": args.policy.as_deref().unwrap_or("last-wins"),
+        }),
+        next_values: Some(vec![merged]),
+    })
+}
+
+fn execute_transform_rowset_step(
+    args: Map<String, Value>,
+    current_values: Option<&[Value]>,
+    recipe_base_dir: &Path,
+) -> Result<StepOutcome, RecipeExecutionErrorKind> {
+    let args: TransformRowsetStepArgs = parse_step_args("transform_rowset", args)?;
+    validate_transform_rowset_mlr_args(&args)?;
+    let input = load_step_input_values(
+        args.input.as_deref(),
+        args.from.as_deref(),
+        "transform_rowset.args.input",
+        current_values,
+        recipe_base_dir,
+        TRANSFORM_ROWSET_REQUIRES_INPUT_OR_PRIOR_VALUES,
+    )?;
+    let input_records = input.len();
+
+    let (response, _) = transform::run_rowset_with_trace(&transform::TransformRowsetCommandArgs {
+        input: transform::TransformRowsetCommandInput::Inline(input),
+        jq_filter: args.jq_filter,
+        mlr: args.mlr,
+    });
+    let rows = payload_rows(command_step_payload(
+        "transform_rowset",
+        response.exit_code,
+        response.payload,
+    )?);
+
+    Ok(StepOutcome {
+        matched: true,
+        exit_code: 0,
+        summary: json!({
+            "input_records": input_records,
+            "record_count": rows.len(),
+        }),
+        next_values: Some(rows),
+    })
+}
+
+fn execute_scan_text_step(
+    args: Map<String, Value>,
+    recipe_base_dir: &Path,
+) -> Result<StepOutcome, RecipeExecutionErrorKind> {
+    let args: ScanTextStepArgs = parse_step_args("scan_text", args)?;
+    let root = resolve_recipe_path(
+        recipe_base_dir,
+        args.path.as_deref().unwrap_or(Path::new(".")),
+    );
+
+    let (response, _) = scan::run_with_trace(&scan::ScanTextCommandArgs {
+        pattern: args.pattern,
+        path: root,
+        glob: args.glob,
+        max_matches: args.max_matches,
+        policy_mode: args.policy_mode,
+        jq_project: args.jq_project,
+    });
+    let exit_code = response.exit_code;
+    let report = command_step_payload("scan_text", exit_code, response.payload)?;
+    let summary = report.get("summary").cloned().unwrap_or(Value::Null);
+
+    Ok(StepOutcome {
+        matched: exit_code == 0,
+        exit_code,
+        summary,
+        next_values: Some(vec![report]),
+    })
+}
+
+fn execute_gate_schema_step(
+    args: Map<String, Value>,
+    current_values: Option<&[Value]>,
+    recipe_base_dir: &Path,
+) -> Result<StepOutcome, RecipeExecutionErrorKind> {
+    let args: GateSchemaStepArgs = parse_step_args("gate_schema", args)?;
+    gate::resolve_preset(args.from.as_deref()).map_err(RecipeExecutionErrorKind::InputUsage)?;
+    let (input, stdin_payload) = step_input_path_or_prior_values(
+        args.input.as_deref(),
+        current_values,
+        recipe_base_dir,
+        GATE_SCHEMA_REQUIRES_INPUT_OR_PRIOR_VALUES,
+    )?;
+
+    let (response, _) = gate::run_schema_with_stdin_and_trace(
+        &gate::GateSchemaCommandArgs {
+            schema: resolve_recipe_path(recipe_base_dir, args.schema.as_path()),
+            input,
+            from: args.from,
+        },
+        Cursor::new(stdin_payload),
+    );
+    let exit_code = response.exit_code;
+    let summary = command_step_payload("gate_schema", exit_code, response.payload)?;
+
+    Ok(StepOutcome {
+        matched: exit_code == 0,
+        exit_code,
+        summary,
+        next_values: None,
+    })
+}
+
+fn execute_gate_policy_step(
+    args: Map<String, Value>,
+    current_values: Option<&[Value]>,
+    recipe_base_dir: &Path,
+) -> Result<StepOutcome, RecipeExecutionErrorKind> {
+    let args: GatePolicyStepArgs = parse_step_args("gate_policy", args)?;
+    let source = parse_gate_policy_source(&args)?;
+    let (input, stdin_payload) = step_input_path_or_prior_values(
+        args.input.as_deref(),
+        current_values,
+        recipe_base_dir,
+        GATE_POLICY_REQUIRES_INPUT_OR_PRIOR_VALUES,
+    )?;
+
+    let response = gate::run_policy_with_stdin(
+        &gate::GatePolicyCommandArgs {
+            rules: resolve_recipe_path(recipe_base_dir, args.rules.as_path()),
+            input,
+            source,
+        },
+        Cursor::new(stdin_payload),
+    );
+    let exit_code = response.exit_code;
+    let summary = command_step_payload("gate_policy", exit_code, response.payload)?;
+
+    Ok(StepOutcome {
+        matched: exit_code == 0,
+        exit_code,
+        summary,
+        next_values: None,
+    })
+}
+
+fn execute_ingest_yaml_jobs_step(
+    args: Map<String, Value>,
+    current_values: Option<&[Value]>,
+    recipe_base_dir: &Path,
+) -> Result<StepOutcome, RecipeExecutionErrorKind> {
+    let args: IngestYamlJobsStepArgs = parse_step_args("ingest_yaml_jobs", args)?;
+    let mode = parse_ingest_yaml_jobs_mode(&args)?;
+    let input = match args.input.as_deref() {
+        Some(path) => {
+            ingest_yaml_jobs::IngestYamlJobsInput::Path(resolve_recipe_path(recipe_base_dir, path))
+        }
+        None => ingest_yaml_jobs::IngestYamlJobsInput::Inline(
+            current_values
+                .ok_or_else(|| {
+                    RecipeExecutionErrorKind::InputUsage(
+                        INGEST_YAML_JOBS_REQUIRES_INPUT_OR_PRIOR_VALUES.to_string(),
+                    )
+                })?
+                .to_vec(),
+        ),
+    };
+
+    let (response, _) = ingest_yaml_jobs::run_with_stdin_and_trace(
+        &ingest_yaml_jobs::IngestYamlJobsCommandArgs { input, mode },
+        Cursor::new(Vec::new()),
+    );
+    let rows = payload_rows(command_step_payload(
+        "ingest_yaml_jobs",
+        response.exit_code,
+        response.payload,
+    )?);
+
+    Ok(StepOutcome {
+        matched: true,
+        exit_code: 0,
+        summary: json!({
+            "record_count": rows.len(),
+            "mode": mode.as_str(),
+        }),
+        next_values: Some(rows),
+    })
+}
+
+fn execute_ingest_doc_step(
+    args: Map<String, Value>,
+    recipe_base_dir: &Path,
+) -> Result<StepOutcome, RecipeExecutionErrorKind> {
+    let args: IngestDocStepArgs = parse_step_args("ingest_doc", args)?;
+    let from = parse_ingest_doc_format(&args)?;
+
+    let response = ingest::run_with_stdin(
+        &ingest::IngestDocCommandArgs {
+            input: Some(resolve_recipe_path(recipe_base_dir, args.input.as_path())),
+            from,
+        },
+        Cursor::new(Vec::new()),
+    );
+    let document = command_step_payload("ingest_doc", response.exit_code, response.payload)?;
+
+    Ok(StepOutcome {
+        matched: true,
+        exit_code: 0,
+        summary: json!({
+            "record_count": 1,
+            "from": from.as_str(),
+        }),
+        next_values: Some(vec![document]),
+    })
+}
+
+fn execute_write_step(
+    args: Map<String, Value>,
+    current_values: Option<&[Value]>,
+    recipe_base_dir: &Path,
+) -> Result<StepOutcome, RecipeExecutionErrorKind> {
+    let args: WriteStepArgs = parse_step_args("write", args)?;
+    let values = current_values.ok_or_else(|| {
+        RecipeExecutionErrorKind::InputUsage(WRITE_REQUIRES_PRIOR_VALUES.to_string())
+    })?;
+    let format = resolve_write_step_format(&args)?;
+    let resolved_path = resolve_recipe_path(recipe_base_dir, args.path.as_path());
+    let compression = io::resolve_output_compression(Some(resolved_path.as_path()));
+
+    let mut encoded = Vec::new();
+    io::writer::write_values_compressed(&mut encoded, format, compression, values).map_err(
+        |error| {
+            RecipeExecutionErrorKind::InputUsage(format!(
+                "failed to encode `write.args.path` as {format}: {error}"
+            ))
+        },
+    )?;
+    write_file_atomically(resolved_path.as_path(), encoded.as_slice())?;
+
+    let mut hasher = DigestHasher::sha256();
+    hasher.update(encoded.as_slice());
+    Ok(StepOutcome {
+        matched: true,
+        exit_code: 0,
+        summary: json!({
+            "record_count": values.len(),
+            "path": args.path.display().to_string(),
+            "format": format.as_str(),
+            "bytes": encoded.len(),
+            "hash": hasher.finish(),
+        }),
+        next_values: None,
+    })
+}
+
+fn resolve_write_step_format(args: &WriteStepArgs) -> Result<Format, RecipeExecutionErrorKind> {
+    if let Some(raw) = args.format.as_deref() {
+        return Format::from_str(raw).map_err(|error| {
+            RecipeExecutionErrorKind::InputUsage(format!(
+                "invalid format `{raw}` for `write.args.format`: {error}"
+            ))
+        });
+    }
+    io::resolve_output_format(None, Some(args.path.as_path())).map_err(|error| {
+        RecipeExecutionErrorKind::InputUsage(format!(
+            "failed to resolve format for `write.args.path` from `{}`: {error}",
+            args.path.display()
+        ))
+    })
+}
+
+/// Write through a temp file in the destination directory, then rename it into
+/// place so readers never observe a partially written output.
+///
+/// I/O failures are execution errors (exit `1`); bad `path`/`format` args are
+/// rejected as input usage errors before this point.
+fn write_file_atomically(path: &Path, bytes: &[u8]) -> Result<(), RecipeExecutionErrorKind> {
+    let write_error = |error: std::io::Error| {
+        RecipeExecutionErrorKind::Internal(format!(
+            "failed to write output file `{}`: {error}",
+            path.display()
+        ))
+    };
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let mut file = NamedTempFile::new_in(parent).map_err(write_error)?;
+    file.write_all(bytes).map_err(write_error)?;
+    file.as_file().sync_all().map_err(write_error)?;
+    file.persist(path)
+        .map_err(|error| write_error(error.error))?;
+    Ok(())
+}
+
+fn parse_join_how(args: &JoinStepArgs) -> Result<JoinHow, RecipeExecutionErrorKind> {
+    let Some(raw) = args.how.as_deref() else {
+        return Ok(JoinHow::Inner);
+    };
+    JoinHow::parse_cli_name(raw).ok_or_else(|| {
+        RecipeExecutionErrorKind::InputUsage(format!(
+            "invalid join how `{raw}`: expected `inner` or `left`"
+        ))
+    })
+}
+
+fn parse_aggregate_metric(
+    args: &AggregateStepArgs,
+) -> Result<AggregateMetric, RecipeExecutionErrorKind> {
+    let Some(raw) = args.metric.as_deref() else {
+        return Ok(AggregateMetric::Count);
+    };
+    AggregateMetric::parse_cli_name(raw).ok_or_else(|| {
+        RecipeExecutionErrorKind::InputUsage(format!(
+            "invalid aggregate metric `{raw}`: expected one of `count`, `sum`, `avg`"
+        ))
+    })
+}
+
+fn parse_merge_policy(args: &MergeStepArgs) -> Result<MergePolicy, RecipeExecutionErrorKind> {
+    let Some(raw) = args.policy.as_deref() else {
+        return Ok(MergePolicy::LastWins);
+    };
+    MergePolicy::parse_cli_name(raw).ok_or_else(|| {
+        RecipeExecutionErrorKind::InputUsage(format!(
+            "invalid merge policy `{raw}`: expected one of `last-wins`, `deep-merge`, `array-replace`"
+        ))
+    })
+}
+
+fn validate_transform_rowset_mlr_args(
+    args: &TransformRowsetStepArgs,
+) -> Result<(), RecipeExecutionErrorKind> {
+    if args.mlr.is_empty() {
+        return Err(RecipeExecutionErrorKind::InputUsage(
+            "transform_rowset step requires at least one `mlr` argument".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn parse_gate_policy_source(
+    args: &GatePolicyStepArgs,
+) -> Result<Option<gate::GatePolicySourcePreset>, RecipeExecutionErrorKind> {
+    args.source
+        .as_deref()
+        .map(gate::GatePolicySourcePreset::parse_cli_name)
+        .transpose()
+        .map_err(RecipeExecutionErrorKind::InputUsage)
+}
+
+fn parse_ingest_yaml_jobs_mode(
+    args: &IngestYamlJobsStepArgs,
+) -> Result<IngestYamlJobsMode, RecipeExecutionErrorKind> {
+    IngestYamlJobsMode::parse_cli_name(args.mode.as_str()).ok_or_else(|| {
+        RecipeExecutionErrorKind::InputUsage(format!(
+            "invalid ingest_yaml_jobs mode `{}`: expected one of `github-actions`, `gitlab-ci`, `generic-map`",
+            args.mode
+        ))
+    })
+}
+
+fn parse_ingest_doc_format(
+    args: &IngestDocStepArgs,
+) -> Result<IngestDocInputFormat, RecipeExecutionErrorKind> {
+    IngestDocInputFormat::parse_cli_name(args.from.as_str()).ok_or_else(|| {
+        RecipeExecutionErrorKind::InputUsage(format!(
+            "invalid ingest_doc format `{}`: expected one of `md`, `html`, `docx`, `rst`, `latex`",
+            args.from
+        ))
+    })
+}
+
+fn resolve_assert_source(
+    args: AssertStepArgs,
+    recipe_base_dir: &Path,
+) -> Result<AssertSource, RecipeExecutionErrorKind> {
+    let rules = match (args.rules, args.rules_file) {
+        (Some(value), None) => {
+            let parsed: AssertRules = serde_json::from_value(value).map_err(|error| {
+                RecipeExecutionErrorKind::InputUsage(format!(
+                    "invalid assert rules in recipe step: {error}"
+                ))
+            })?;
+            Some(parsed)
+        }
+        (None, Some(path)) => {
+            // Rules files resolve `extends` exactly like `dataq assert --rules`.
+            let resolved_path = resolve_recipe_path(recipe_base_dir, path.as_path());
+            validate_file_backed_arg_format_for_lock(resolved_path.as_path(), "assert.rules_file")?;
+            Some(
+                crate::cmd::r#assert::load_rules_from_path(resolved_path.as_path())
+                    .map_err(RecipeExecutionErrorKind::InputUsage)?,
+            )
+        }
+        (Some(_), Some(_)) => {
+            return Err(RecipeExecutionErrorKind::InputUsage(
+                "assert step args `rules` and `rules_file` are mutually exclusive".to_string(),
+            ));
+        }
+        (None, None) => None,
+    };
+
+    let schema_value = match (args.schema, args.schema_file) {
+        (Some(value), None) => Some(value),
+        (None, Some(path)) => {
+            let resolved_path = resolve_recipe_path(recipe_base_dir, path.as_path());
+            Some(read_single_value_from_path(
+                resolved_path.as_path(),
+                "assert.schema_file",
+            )?)
+        }
+        (Some(_), Some(_)) => {
+            return Err(RecipeExecutionErrorKind::InputUsage(
+                "assert step args `schema` and `schema_file` are mutually exclusive".to_string(),
+            ));
+        }
+        (None, None) => None,
+    };
+
+    match (rules, schema_value) {
+        (Some(rules), None) => Ok(AssertSource::Rules(rules)),
+        (None, Some(schema)) => Ok(AssertSource::Schema(schema)),
         (None, None) => Err(RecipeExecutionErrorKind::InputUsage(
             "assert step requires exactly one of `rules`, `rules_file`, `schema`, or `schema_file`"
                 .to_string(),
@@ -645,71 +2172,432 @@ fn resolve_assert_source(
     }
 }
 
-enum AssertSource {
-    Rules(AssertRules),
-    Schema(Value),
+enum AssertSource {
+    Rules(AssertRules),
+    Schema(Value),
+}
+
+fn load_and_parse_recipe(
+    recipe_path: &Path,
+    params: &[String],
+) -> Result<RecipeFile, RecipeExecutionErrorKind> {
+    let loaded = load_recipe_value(recipe_path)?;
+    let recipe_base_dir = recipe_path.parent().unwrap_or_else(|| Path::new("."));
+    parse_loaded_recipe(loaded, params, recipe_base_dir)
+}
+
+fn load_recipe_value(recipe_path: &Path) -> Result<Value, RecipeExecutionErrorKind> {
+    let format = io::resolve_input_format(None, Some(recipe_path)).map_err(|error| {
+        RecipeExecutionErrorKind::InputUsage(format!(
+            "failed to resolve recipe format from `{}`: {error}",
+            recipe_path.display()
+        ))
+    })?;
+
+    if !matches!(format, Format::Json | Format::Yaml) {
+        return Err(RecipeExecutionErrorKind::InputUsage(format!(
+            "recipe file must be json or yaml by extension: `{}`",
+            recipe_path.display()
+        )));
+    }
+
+    read_single_value_from_path(recipe_path, "recipe.file")
+}
+
+fn load_and_parse_lock(lock_path: &Path) -> Result<RecipeLockFile, RecipeExecutionErrorKind> {
+    let value = read_single_value_from_path(lock_path, "recipe.lock")?;
+    parse_lock(value)
+}
+
+fn read_single_value_from_path(
+    path: &Path,
+    field_label: &str,
+) -> Result<Value, RecipeExecutionErrorKind> {
+    let format = io::resolve_input_format(None, Some(path)).map_err(|error| {
+        RecipeExecutionErrorKind::InputUsage(format!(
+            "failed to resolve format for `{field_label}` from `{}`: {error}",
+            path.display()
+        ))
+    })?;
+
+    let mut values = read_values_from_path(path, format)?;
+    if values.len() != 1 {
+        return Err(RecipeExecutionErrorKind::InputUsage(format!(
+            "`{field_label}` must contain exactly one document"
+        )));
+    }
+    Ok(values.remove(0))
+}
+
+/// Parse a loaded recipe, resolve its params and inline `include` steps.
+///
+/// `recipe_base_dir` is the directory top-level include paths are resolved
+/// against.
+fn parse_loaded_recipe(
+    value: Value,
+    params: &[String],
+    recipe_base_dir: &Path,
+) -> Result<RecipeFile, RecipeExecutionErrorKind> {
+    parse_loaded_recipe_with(value, params, recipe_base_dir, &|_| true)
+}
+
+/// [`parse_loaded_recipe`] that asks `may_read` before opening each
+/// included recipe file.
+fn parse_loaded_recipe_with(
+    value: Value,
+    params: &[String],
+    recipe_base_dir: &Path,
+    may_read: &dyn Fn(&Path) -> bool,
+) -> Result<RecipeFile, RecipeExecutionErrorKind> {
+    let mut recipe = parse_recipe_file(value, params)?;
+    let steps = std::mem::take(&mut recipe.steps);
+    let mut stack = Vec::new();
+    recipe.steps = expand_recipe_includes(
+        steps,
+        recipe_base_dir,
+        Path::new(""),
+        may_read,
+        &mut stack,
+        &mut recipe.included_files,
+    )?;
+    Ok(recipe)
+}
+
+fn parse_recipe_file(
+    value: Value,
+    params: &[String],
+) -> Result<RecipeFile, RecipeExecutionErrorKind> {
+    let mut recipe: RecipeFile = serde_json::from_value(value).map_err(|error| {
+        RecipeExecutionErrorKind::InputUsage(format!("invalid recipe schema: {error}"))
+    })?;
+
+    if recipe.version != RECIPE_VERSION {
+        return Err(RecipeExecutionErrorKind::InputUsage(format!(
+            "recipe version must be `{RECIPE_VERSION}`"
+        )));
+    }
+
+    resolve_recipe_params(&mut recipe, params)?;
+    Ok(recipe)
+}
+
+/// Replace each `include` step with the steps of the recipe it names.
+///
+/// Include paths resolve relative to the including file, like `extends` in
+/// assert rules; `including_dir` is that file's directory relative to
+/// `recipe_base_dir`. Literal file args of the spliced steps are rebased the
+/// same way (see [`rebase_included_step_paths`]). `stack` holds the canonical
+/// paths being expanded and is used to detect cycles. Each included file is
+/// passed to `may_read` before it is opened.
+fn expand_recipe_includes(
+    steps: Vec<RecipeStep>,
+    recipe_base_dir: &Path,
+    including_dir: &Path,
+    may_read: &dyn Fn(&Path) -> bool,
+    stack: &mut Vec<PathBuf>,
+    included_files: &mut BTreeSet<PathBuf>,
+) -> Result<Vec<RecipeStep>, RecipeExecutionErrorKind> {
+    let mut expanded = Vec::with_capacity(steps.len());
+    for step in steps {
+        if step.kind != "include" {
+            expanded.push(step);
+            continue;
+        }
+        if step.id.is_some()
+            || step.inputs.is_some()
+            || step.when.is_some()
+            || step.on_failure != RecipeOnFailure::Stop
+        {
+            return Err(RecipeExecutionErrorKind::InputUsage(
+                "include step does not support `id`, `inputs`, `when` or `on_failure`".to_string(),
+            ));
+        }
+        let args: IncludeStepArgs = parse_step_args("include", step.args)?;
+        let include_path = including_dir.join(&args.path);
+        let resolved_path = resolve_recipe_path(recipe_base_dir, &include_path);
+        if !may_read(&resolved_path) {
+            return Err(RecipeExecutionErrorKind::InputUsage(format!(
+                "reading included recipe `{}` is not allowed",
+                include_path.display()
+            )));
+        }
+        let canonical_path = resolved_path.canonicalize().map_err(|error| {
+            RecipeExecutionErrorKind::InputUsage(format!(
+                "failed to resolve included recipe `{}`: {error}",
+                include_path.display()
+            ))
+        })?;
+        if let Some(cycle_start) = stack.iter().position(|entry| entry == &canonical_path) {
+            let mut cycle_chain: Vec<String> = stack[cycle_start..]
+                .iter()
+                .map(|entry| entry.display().to_string())
+                .collect();
+            cycle_chain.push(canonical_path.display().to_string());
+            return Err(RecipeExecutionErrorKind::InputUsage(format!(
+                "recipe include cycle detected: {}",
+                cycle_chain.join(" -> ")
+            )));
+        }
+
+        let bindings: Vec<String> = args
+            .params
+            .into_iter()
+            .map(|(name, value)| match value {
+                Value::String(text) => format!("{name}={text}"),
+                other => format!("{name}={other}"),
+            })
+            .collect();
+        let nested_dir = include_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let included = load_recipe_value(resolved_path.as_path())
+            .map(|mut value| {
+                rebase_included_step_paths(&mut value, nested_dir.as_path());
+                value
+            })
+            .and_then(|value| parse_recipe_file(value, &bindings))
+            .map_err(|kind| match kind {
+                RecipeExecutionErrorKind::InputUsage(message) => {
+                    RecipeExecutionErrorKind::InputUsage(format!(
+                        "included recipe `{}`: {message}",
+                        include_path.display()
+                    ))
+                }
+                internal => internal,
+            })?;
+
+        included_files.insert(include_path.clone());
+        stack.push(canonical_path);
+        let steps = expand_recipe_includes(
+            included.steps,
+            recipe_base_dir,
+            nested_dir.as_path(),
+            may_read,
+            stack,
+            included_files,
+        );
+        stack.pop();
+        expanded.extend(steps?);
+    }
+    Ok(expanded)
+}
+
+/// File path args per step kind. `include.path` is not listed: nested
+/// includes already resolve against their including file.
+const STEP_PATH_ARGS: &[(&str, &[&str])] = &[
+    ("canon", &["input", "coerce_schema", "coerce_rules"]),
+    ("assert", &["rules_file", "schema_file"]),
+    ("sdiff", &["right"]),
+    ("join", &["left", "right"]),
+    ("aggregate", &["input"]),
+    ("merge", &["base", "overlays"]),
+    ("transform_rowset", &["input"]),
+    ("scan_text", &["path"]),
+    ("gate_schema", &["schema", "input"]),
+    ("gate_policy", &["rules", "input"]),
+    ("ingest_yaml_jobs", &["input"]),
+    ("ingest_doc", &["input"]),
+    ("write", &["path"]),
+];
+
+/// Rebase relative file args written in an included recipe onto its
+/// directory (`included_dir`, relative to the executing recipe's directory).
+///
+/// Runs before params are resolved: values that reference `${params.<name>}`
+/// are supplied by the including recipe and stay relative to the executing
+/// recipe's directory.
+fn rebase_included_step_paths(recipe: &mut Value, included_dir: &Path) {
+    if included_dir.as_os_str().is_empty() {
+        return;
+    }
+    let Some(steps) = recipe.get_mut("steps").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for step in steps {
+        let Some(fields) = step
+            .get("kind")
+            .and_then(Value::as_str)
+            .and_then(|kind| STEP_PATH_ARGS.iter().find(|(name, _)| *name == kind))
+            .map(|(_, fields)| *fields)
+        else {
+            continue;
+        };
+        let Some(args) = step.get_mut("args").and_then(Value::as_object_mut) else {
+            continue;
+        };
+        for field in fields {
+            match args.get_mut(*field) {
+                Some(Value::Array(paths)) => {
+                    for path in paths {
+                        rebase_path_value(path, included_dir);
+                    }
+                }
+                Some(path) => rebase_path_value(path, included_dir),
+                None => {}
+            }
+        }
+    }
 }
 
-fn load_and_parse_recipe(recipe_path: &Path) -> Result<RecipeFile, RecipeExecutionErrorKind> {
-    let loaded = load_recipe_value(recipe_path)?;
-    parse_loaded_recipe(loaded)
+fn rebase_path_value(value: &mut Value, included_dir: &Path) {
+    if let Value::String(path) = value
+        && !path.contains(PARAM_REFERENCE_PREFIX)
+        && !Path::new(path.as_str()).is_absolute()
+    {
+        *path = included_dir.join(path.as_str()).display().to_string();
+    }
 }
 
-fn load_recipe_value(recipe_path: &Path) -> Result<Value, RecipeExecutionErrorKind> {
-    let format = io::resolve_input_format(None, Some(recipe_path)).map_err(|error| {
-        RecipeExecutionErrorKind::InputUsage(format!(
-            "failed to resolve recipe format from `{}`: {error}",
-            recipe_path.display()
-        ))
-    })?;
+const PARAM_REFERENCE_PREFIX: &str = "${params.";
 
-    if !matches!(format, Format::Json | Format::Yaml) {
-        return Err(RecipeExecutionErrorKind::InputUsage(format!(
-            "recipe file must be json or yaml by extension: `{}`",
-            recipe_path.display()
-        )));
+/// Resolve declared params from `key=value` overrides and defaults, then
+/// substitute `${params.<name>}` references inside every step's args.
+fn resolve_recipe_params(
+    recipe: &mut RecipeFile,
+    overrides: &[String],
+) -> Result<(), RecipeExecutionErrorKind> {
+    let mut raw_values = BTreeMap::new();
+    for assignment in overrides {
+        let (name, raw) = assignment.split_once('=').ok_or_else(|| {
+            RecipeExecutionErrorKind::InputUsage(format!(
+                "invalid `--param` `{assignment}`: expected `<name>=<value>`"
+            ))
+        })?;
+        let name = name.trim();
+        if !recipe.params.contains_key(name) {
+            return Err(RecipeExecutionErrorKind::InputUsage(format!(
+                "unknown recipe param `{name}`"
+            )));
+        }
+        if raw_values.insert(name, raw).is_some() {
+            return Err(RecipeExecutionErrorKind::InputUsage(format!(
+                "recipe param `{name}` is given more than once"
+            )));
+        }
     }
 
-    read_single_value_from_path(recipe_path, "recipe.file")
-}
+    let mut resolved = BTreeMap::new();
+    for (name, spec) in &recipe.params {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+        {
+            return Err(RecipeExecutionErrorKind::InputUsage(format!(
+                "invalid recipe param name `{name}`: use ASCII letters, digits, `_` or `-`"
+            )));
+        }
+        if let Some(default) = spec.default.as_ref()
+            && !spec.param_type.accepts(default)
+        {
+            return Err(RecipeExecutionErrorKind::InputUsage(format!(
+                "default for recipe param `{name}` must be of type `{}`",
+                spec.param_type.as_str()
+            )));
+        }
+        let value = match raw_values.get(name.as_str()) {
+            Some(raw) => spec.param_type.parse_override(raw).ok_or_else(|| {
+                RecipeExecutionErrorKind::InputUsage(format!(
+                    "invalid value `{raw}` for recipe param `{name}`: expected `{}`",
+                    spec.param_type.as_str()
+                ))
+            })?,
+            None => spec.default.clone().ok_or_else(|| {
+                RecipeExecutionErrorKind::InputUsage(format!(
+                    "recipe param `{name}` has no default; pass `--param {name}=<value>`"
+                ))
+            })?,
+        };
+        resolved.insert(name.clone(), value);
+    }
 
-fn load_and_parse_lock(lock_path: &Path) -> Result<RecipeLockFile, RecipeExecutionErrorKind> {
-    let value = read_single_value_from_path(lock_path, "recipe.lock")?;
-    parse_lock(value)
+    for step in &mut recipe.steps {
+        for value in step.args.values_mut() {
+            substitute_params(value, &resolved)?;
+        }
+    }
+    recipe.resolved_params = resolved;
+    Ok(())
 }
 
-fn read_single_value_from_path(
-    path: &Path,
-    field_label: &str,
-) -> Result<Value, RecipeExecutionErrorKind> {
-    let format = io::resolve_input_format(None, Some(path)).map_err(|error| {
-        RecipeExecutionErrorKind::InputUsage(format!(
-            "failed to resolve format for `{field_label}` from `{}`: {error}",
-            path.display()
-        ))
-    })?;
-
-    let mut values = read_values_from_path(path, format)?;
-    if values.len() != 1 {
-        return Err(RecipeExecutionErrorKind::InputUsage(format!(
-            "`{field_label}` must contain exactly one document"
-        )));
+fn substitute_params(
+    value: &mut Value,
+    params: &BTreeMap<String, Value>,
+) -> Result<(), RecipeExecutionErrorKind> {
+    match value {
+        Value::String(text) => {
+            if let Some(substituted) = substitute_params_in_string(text, params)? {
+                *value = substituted;
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                substitute_params(item, params)?;
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values_mut() {
+                substitute_params(item, params)?;
+            }
+        }
+        _ => {}
     }
-    Ok(values.remove(0))
+    Ok(())
 }
 
-fn parse_loaded_recipe(value: Value) -> Result<RecipeFile, RecipeExecutionErrorKind> {
-    let recipe: RecipeFile = serde_json::from_value(value).map_err(|error| {
-        RecipeExecutionErrorKind::InputUsage(format!("invalid recipe schema: {error}"))
-    })?;
+/// A string that is exactly one reference takes the param's JSON type;
+/// otherwise references are spliced as text. `$${params.x}` stays literal.
+fn substitute_params_in_string(
+    text: &str,
+    params: &BTreeMap<String, Value>,
+) -> Result<Option<Value>, RecipeExecutionErrorKind> {
+    if !text.contains(PARAM_REFERENCE_PREFIX) {
+        return Ok(None);
+    }
+    if let Some(name) = text
+        .strip_prefix(PARAM_REFERENCE_PREFIX)
+        .and_then(|rest| rest.strip_suffix('}'))
+        && !name.contains('}')
+    {
+        return lookup_param(name, params).map(|value| Some(value.clone()));
+    }
 
-    if recipe.version != RECIPE_VERSION {
-        return Err(RecipeExecutionErrorKind::InputUsage(format!(
-            "recipe version must be `{RECIPE_VERSION}`"
-        )));
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(PARAM_REFERENCE_PREFIX) {
+        let after = &rest[start + PARAM_REFERENCE_PREFIX.len()..];
+        if rest[..start].ends_with('$') {
+            output.push_str(&rest[..start - 1]);
+            output.push_str(PARAM_REFERENCE_PREFIX);
+            rest = after;
+            continue;
+        }
+        output.push_str(&rest[..start]);
+        let end = after.find('}').ok_or_else(|| {
+            RecipeExecutionErrorKind::InputUsage(format!(
+                "unterminated param reference in `{text}`"
+            ))
+        })?;
+        match lookup_param(&after[..end], params)? {
+            Value::String(value) => output.push_str(value),
+            other => output.push_str(&other.to_string()),
+        }
+        rest = &after[end + 1..];
     }
+    output.push_str(rest);
+    Ok(Some(Value::String(output)))
+}
 
-    Ok(recipe)
+fn lookup_param<'a>(
+    name: &str,
+    params: &'a BTreeMap<String, Value>,
+) -> Result<&'a Value, RecipeExecutionErrorKind> {
+    params.get(name).ok_or_else(|| {
+        RecipeExecutionErrorKind::InputUsage(format!(
+            "step args reference undeclared recipe param `{name}`"
+        ))
+    })
 }
 
 fn parse_lock(value: Value) -> Result<RecipeLockFile, RecipeExecutionErrorKind> {
@@ -722,12 +2610,16 @@ fn parse_lock(value: Value) -> Result<RecipeLockFile, RecipeExecutionErrorKind>
 fn verify_lock_constraints(
     recipe: &RecipeFile,
     lock: &RecipeLockFile,
+    recipe_base_dir: &Path,
     strict: bool,
 ) -> Result<RecipeReplayLockCheckReport, RecipeExecutionErrorKind> {
-    let expected_command_graph_hash = hash_recipe_command_graph(recipe);
-    let expected_args_hash = hash_recipe_args(recipe)?;
+    // Recompute with the algorithm each digest was recorded with, so locks
+    // written before SHA-256 still verify.
+    let expected_command_graph_hash =
+        hash_recipe_command_graph(recipe, HashAlgorithm::of_digest(&lock.command_graph_hash));
+    let expected_args_hash = hash_recipe_args(recipe, HashAlgorithm::of_digest(&lock.args_hash))?;
     let expected_dataq_version = env!("CARGO_PKG_VERSION").to_string();
-    let actual_tool_versions = collect_actual_tool_versions(lock);
+    let actual_tool_versions = collect_actual_tool_versions(recipe, lock);
 
     let mut mismatches = Vec::new();
     if lock.version != RECIPE_LOCK_VERSION {
@@ -759,7 +2651,7 @@ fn verify_lock_constraints(
         });
     }
 
-    for tool_name in ordered_lock_tool_names(lock) {
+    for tool_name in ordered_lock_tool_names(recipe, lock) {
         let actual = actual_tool_versions
             .get(tool_name.as_str())
             .cloned()
@@ -784,6 +2676,22 @@ fn verify_lock_constraints(
         }
     }
 
+    for (path, expected) in &lock.input_hashes {
+        let resolved_path = resolve_recipe_path(recipe_base_dir, Path::new(path));
+        let actual =
+            match hash_file_contents(resolved_path.as_path(), HashAlgorithm::of_digest(expected)) {
+                Ok(hash) => hash,
+                Err(error) => format!("error: {error}"),
+            };
+        if *expected != actual {
+            mismatches.push(RecipeReplayLockMismatchReport {
+                constraint: format!("lock.input_hashes.{path}"),
+                expected: expected.clone(),
+                actual,
+            });
+        }
+    }
+
     Ok(RecipeReplayLockCheckReport {
         strict,
         matched: mismatches.is_empty(),
@@ -792,22 +2700,22 @@ fn verify_lock_constraints(
     })
 }
 
-fn ordered_lock_tool_names(lock: &RecipeLockFile) -> Vec<String> {
-    let mut names: Vec<String> = RECIPE_LOCK_TOOL_ORDER
-        .iter()
-        .map(|tool| (*tool).to_string())
-        .collect();
+fn ordered_lock_tool_names(recipe: &RecipeFile, lock: &RecipeLockFile) -> Vec<String> {
+    let mut names = recipe_lock_tool_names(recipe);
     for tool in lock.tool_versions.keys() {
-        if !RECIPE_LOCK_TOOL_ORDER.contains(&tool.as_str()) {
+        if !names.contains(tool) {
             names.push(tool.clone());
         }
     }
     names
 }
 
-fn collect_actual_tool_versions(lock: &RecipeLockFile) -> BTreeMap<String, String> {
+fn collect_actual_tool_versions(
+    recipe: &RecipeFile,
+    lock: &RecipeLockFile,
+) -> BTreeMap<String, String> {
     let mut versions = BTreeMap::new();
-    for tool_name in ordered_lock_tool_names(lock) {
+    for tool_name in ordered_lock_tool_names(recipe, lock) {
         let value = match probe_recipe_lock_tool_version(tool_name.as_str()) {
             Ok(version) => version,
             Err(error) => lock_probe_failure_as_replay_value(error),
@@ -826,6 +2734,117 @@ fn lock_probe_failure_as_replay_value(error: RecipeExecutionErrorKind) -> String
     }
 }
 
+/// Resolve step `inputs` into a topological order.
+///
+/// Ready steps run in definition order, so recipes without `inputs` keep the
+/// v1 linear order and every DAG has exactly one execution order.
+fn plan_recipe_steps(steps: &[RecipeStep]) -> Result<RecipePlan, RecipeExecutionErrorKind> {
+    let mut ids = BTreeMap::new();
+    for (index, step) in steps.iter().enumerate() {
+        let Some(id) = step.id.as_deref() else {
+            continue;
+        };
+        if id.trim().is_empty() {
+            return Err(RecipeExecutionErrorKind::InputUsage(format!(
+                "recipe step {index} has an empty `id`"
+            )));
+        }
+        if ids.insert(id, index).is_some() {
+            return Err(RecipeExecutionErrorKind::InputUsage(format!(
+                "duplicate recipe step id `{id}`"
+            )));
+        }
+    }
+
+    let mut inputs = Vec::with_capacity(steps.len());
+    let mut conditions = Vec::with_capacity(steps.len());
+    let mut pending = Vec::with_capacity(steps.len());
+    let mut dependents = vec![Vec::new(); steps.len()];
+    for (index, step) in steps.iter().enumerate() {
+        let upstream = match step.inputs.as_deref() {
+            None => index.checked_sub(1).into_iter().collect::<Vec<_>>(),
+            Some(names) => names
+                .iter()
+                .map(|name| {
+                    ids.get(name.as_str()).copied().ok_or_else(|| {
+                        RecipeExecutionErrorKind::InputUsage(format!(
+                            "recipe step {index} references unknown input `{name}`"
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        let max_inputs = max_step_inputs(step.kind.as_str());
+        if upstream.len() > max_inputs {
+            return Err(RecipeExecutionErrorKind::InputUsage(format!(
+                "`{}` step accepts at most {max_inputs} dataset(s) in `inputs`",
+                step.kind
+            )));
+        }
+        for &input in &upstream {
+            dependents[input].push(index);
+        }
+
+        // A `when` reference orders the step after its target without feeding data.
+        let condition = match step.when.as_ref() {
+            None => None,
+            Some(condition) => {
+                let target = ids.get(condition.step.as_str()).copied().ok_or_else(|| {
+                    RecipeExecutionErrorKind::InputUsage(format!(
+                        "recipe step {index} `when` references unknown step `{}`",
+                        condition.step
+                    ))
+                })?;
+                dependents[target].push(index);
+                Some((target, condition.matched))
+            }
+        };
+        pending.push(upstream.len() + usize::from(condition.is_some()));
+        inputs.push(upstream);
+        conditions.push(condition);
+    }
+
+    let mut ready: BTreeSet<usize> = (0..steps.len()).filter(|i| pending[*i] == 0).collect();
+    let mut order = Vec::with_capacity(steps.len());
+    while let Some(index) = ready.pop_first() {
+        order.push(index);
+        for &dependent in &dependents[index] {
+            pending[dependent] -= 1;
+            if pending[dependent] == 0 {
+                ready.insert(dependent);
+            }
+        }
+    }
+
+    if order.len() != steps.len() {
+        let cyclic = (0..steps.len())
+            .filter(|index| pending[*index] > 0)
+            .map(|index| match steps[index].id.as_deref() {
+                Some(id) => format!("`{id}`"),
+                None => format!("#{index}"),
+            })
+            .collect::<Vec<_>>();
+        return Err(RecipeExecutionErrorKind::InputUsage(format!(
+            "recipe step inputs form a cycle: {}",
+            cyclic.join(", ")
+        )));
+    }
+
+    Ok(RecipePlan {
+        order,
+        inputs,
+        conditions,
+    })
+}
+
+/// `join` and `sdiff` take a second (right-hand) dataset; other steps take one.
+fn max_step_inputs(kind: &str) -> usize {
+    match kind {
+        "join" | "sdiff" => 2,
+        _ => 1,
+    }
+}
+
 fn parse_step_args<T: for<'de> Deserialize<'de>>(
     kind: &str,
     args: Map<String, Value>,
@@ -836,55 +2855,210 @@ fn parse_step_args<T: for<'de> Deserialize<'de>>(
 }
 
 fn validate_recipe_lock_steps(recipe: &RecipeFile) -> Result<(), RecipeExecutionErrorKind> {
-    let mut has_in_memory_values = false;
+    match recipe_step_arg_failures(recipe)?.into_iter().next() {
+        Some((_, kind)) => Err(kind),
+        None => Ok(()),
+    }
+}
 
-    for step in &recipe.steps {
-        match step.kind.as_str() {
-            "canon" => {
-                let args: CanonStepArgs = parse_step_args("canon", step.args.clone())?;
-                validate_canon_step_args_for_lock(&args)?;
-                if args.input.is_none() && !has_in_memory_values {
-                    return Err(RecipeExecutionErrorKind::InputUsage(
-                        CANON_REQUIRES_INPUT_OR_PRIOR_VALUES.to_string(),
-                    ));
-                }
-                has_in_memory_values = true;
+/// Checks every step's args in plan order, collecting one failure per step
+/// instead of stopping at the first.
+///
+/// A failing step is treated as producing values so its failure does not
+/// cascade into "requires prior values" errors downstream.
+fn recipe_step_arg_failures(
+    recipe: &RecipeFile,
+) -> Result<Vec<(usize, RecipeExecutionErrorKind)>, RecipeExecutionErrorKind> {
+    let plan = plan_recipe_steps(&recipe.steps)?;
+    let mut available = vec![false; recipe.steps.len()];
+    let mut failures = Vec::new();
+
+    for &index in &plan.order {
+        let upstream = &plan.inputs[index];
+        let has_in_memory_values = upstream.first().is_some_and(|input| available[*input]);
+        let has_second_input = upstream.get(1).is_some_and(|input| available[*input]);
+        available[index] = match validate_recipe_lock_step(
+            &recipe.steps[index],
+            has_in_memory_values,
+            has_second_input,
+        ) {
+            Ok(has_values) => has_values,
+            Err(kind) => {
+                failures.push((index, kind));
+                true
             }
-            "assert" => {
-                let args: AssertStepArgs = parse_step_args("assert", step.args.clone())?;
-                if !has_in_memory_values {
-                    return Err(RecipeExecutionErrorKind::InputUsage(
-                        ASSERT_REQUIRES_PRIOR_VALUES.to_string(),
-                    ));
-                }
-                validate_assert_step_args_for_lock(&args)?;
+        };
+    }
+    Ok(failures)
+}
+
+/// Validates one step's args and returns whether values are available after it.
+fn validate_recipe_lock_step(
+    step: &RecipeStep,
+    mut has_in_memory_values: bool,
+    has_second_input: bool,
+) -> Result<bool, RecipeExecutionErrorKind> {
+    match step.kind.as_str() {
+        "canon" => {
+            let args: CanonStepArgs = parse_step_args("canon", step.args.clone())?;
+            validate_canon_step_args_for_lock(&args)?;
+            if args.input.is_none() && !has_in_memory_values {
+                return Err(RecipeExecutionErrorKind::InputUsage(
+                    CANON_REQUIRES_INPUT_OR_PRIOR_VALUES.to_string(),
+                ));
             }
-            "profile" => {
-                let _: ProfileStepArgs = parse_step_args("profile", step.args.clone())?;
-                if !has_in_memory_values {
-                    return Err(RecipeExecutionErrorKind::InputUsage(
-                        PROFILE_REQUIRES_PRIOR_VALUES.to_string(),
-                    ));
-                }
+            has_in_memory_values = true;
+        }
+        "assert" => {
+            let args: AssertStepArgs = parse_step_args("assert", step.args.clone())?;
+            if !has_in_memory_values {
+                return Err(RecipeExecutionErrorKind::InputUsage(
+                    ASSERT_REQUIRES_PRIOR_VALUES.to_string(),
+                ));
             }
-            "sdiff" => {
-                let args: SdiffStepArgs = parse_step_args("sdiff", step.args.clone())?;
-                if !has_in_memory_values {
-                    return Err(RecipeExecutionErrorKind::InputUsage(
-                        SDIFF_REQUIRES_PRIOR_VALUES.to_string(),
-                    ));
-                }
-                validate_sdiff_step_args_for_lock(&args)?;
+            validate_assert_step_args_for_lock(&args)?;
+        }
+        "profile" => {
+            let _: ProfileStepArgs = parse_step_args("profile", step.args.clone())?;
+            if !has_in_memory_values {
+                return Err(RecipeExecutionErrorKind::InputUsage(
+                    PROFILE_REQUIRES_PRIOR_VALUES.to_string(),
+                ));
+            }
+        }
+        "sdiff" => {
+            let args: SdiffStepArgs = parse_step_args("sdiff", step.args.clone())?;
+            if !has_in_memory_values {
+                return Err(RecipeExecutionErrorKind::InputUsage(
+                    SDIFF_REQUIRES_PRIOR_VALUES.to_string(),
+                ));
+            }
+            validate_sdiff_step_args_for_lock(&args, has_second_input)?;
+        }
+        "join" => {
+            let args: JoinStepArgs = parse_step_args("join", step.args.clone())?;
+            validate_step_input_for_lock(
+                args.left.as_deref(),
+                args.left_from.as_deref(),
+                "join.args.left",
+                has_in_memory_values,
+                JOIN_REQUIRES_LEFT_OR_PRIOR_VALUES,
+            )?;
+            validate_step_input_for_lock(
+                args.right.as_deref(),
+                args.right_from.as_deref(),
+                "join.args.right",
+                has_second_input,
+                JOIN_REQUIRES_RIGHT_OR_SECOND_INPUT,
+            )?;
+            parse_join_how(&args)?;
+            has_in_memory_values = true;
+        }
+        "aggregate" => {
+            let args: AggregateStepArgs = parse_step_args("aggregate", step.args.clone())?;
+            validate_step_input_for_lock(
+                args.input.as_deref(),
+                args.from.as_deref(),
+                "aggregate.args.input",
+                has_in_memory_values,
+                AGGREGATE_REQUIRES_INPUT_OR_PRIOR_VALUES,
+            )?;
+            parse_aggregate_metric(&args)?;
+            has_in_memory_values = true;
+        }
+        "merge" => {
+            let args: MergeStepArgs = parse_step_args("merge", step.args.clone())?;
+            validate_merge_step_args_for_lock(&args, has_in_memory_values)?;
+            has_in_memory_values = true;
+        }
+        "transform_rowset" => {
+            let args: TransformRowsetStepArgs =
+                parse_step_args("transform_rowset", step.args.clone())?;
+            validate_step_input_for_lock(
+                args.input.as_deref(),
+                args.from.as_deref(),
+                "transform_rowset.args.input",
+                has_in_memory_values,
+                TRANSFORM_ROWSET_REQUIRES_INPUT_OR_PRIOR_VALUES,
+            )?;
+            validate_transform_rowset_mlr_args(&args)?;
+            has_in_memory_values = true;
+        }
+        "scan_text" => {
+            let args: ScanTextStepArgs = parse_step_args("scan_text", step.args.clone())?;
+            if args.pattern.trim().is_empty() {
+                return Err(RecipeExecutionErrorKind::InputUsage(
+                    "scan_text step requires a non-empty `pattern`".to_string(),
+                ));
             }
-            _ => {
-                return Err(RecipeExecutionErrorKind::InputUsage(format!(
-                    "unknown recipe step kind `{}`",
-                    step.kind
-                )));
+            has_in_memory_values = true;
+        }
+        "gate_schema" => {
+            let args: GateSchemaStepArgs = parse_step_args("gate_schema", step.args.clone())?;
+            validate_step_input_for_lock(
+                args.input.as_deref(),
+                None,
+                "gate_schema.args.input",
+                has_in_memory_values,
+                GATE_SCHEMA_REQUIRES_INPUT_OR_PRIOR_VALUES,
+            )?;
+            validate_file_backed_arg_format_for_lock(
+                args.schema.as_path(),
+                "gate_schema.args.schema",
+            )?;
+            gate::resolve_preset(args.from.as_deref())
+                .map_err(RecipeExecutionErrorKind::InputUsage)?;
+        }
+        "gate_policy" => {
+            let args: GatePolicyStepArgs = parse_step_args("gate_policy", step.args.clone())?;
+            validate_step_input_for_lock(
+                args.input.as_deref(),
+                None,
+                "gate_policy.args.input",
+                has_in_memory_values,
+                GATE_POLICY_REQUIRES_INPUT_OR_PRIOR_VALUES,
+            )?;
+            validate_file_backed_arg_format_for_lock(
+                args.rules.as_path(),
+                "gate_policy.args.rules",
+            )?;
+            parse_gate_policy_source(&args)?;
+        }
+        "ingest_yaml_jobs" => {
+            let args: IngestYamlJobsStepArgs =
+                parse_step_args("ingest_yaml_jobs", step.args.clone())?;
+            validate_step_input_for_lock(
+                args.input.as_deref(),
+                None,
+                "ingest_yaml_jobs.args.input",
+                has_in_memory_values,
+                INGEST_YAML_JOBS_REQUIRES_INPUT_OR_PRIOR_VALUES,
+            )?;
+            parse_ingest_yaml_jobs_mode(&args)?;
+            has_in_memory_values = true;
+        }
+        "ingest_doc" => {
+            let args: IngestDocStepArgs = parse_step_args("ingest_doc", step.args.clone())?;
+            parse_ingest_doc_format(&args)?;
+            has_in_memory_values = true;
+        }
+        "write" => {
+            let args: WriteStepArgs = parse_step_args("write", step.args.clone())?;
+            if !has_in_memory_values {
+                return Err(RecipeExecutionErrorKind::InputUsage(
+                    WRITE_REQUIRES_PRIOR_VALUES.to_string(),
+                ));
             }
+            resolve_write_step_format(&args)?;
+        }
+        _ => {
+            return Err(RecipeExecutionErrorKind::InputUsage(format!(
+                "unknown recipe step kind `{}`",
+                step.kind
+            )));
         }
     }
-    Ok(())
+    Ok(has_in_memory_values)
 }
 
 fn validate_canon_step_args_for_lock(args: &CanonStepArgs) -> Result<(), RecipeExecutionErrorKind> {
@@ -899,6 +3073,15 @@ fn validate_canon_step_args_for_lock(args: &CanonStepArgs) -> Result<(), RecipeE
             validate_file_backed_arg_format_for_lock(path, "canon.args.input")?;
         }
     }
+    validate_canon_coerce_args(args)?;
+    parse_canon_normalize_options(args)?;
+    parse_canon_time_normalization(args)?;
+    if let Some(path) = args.coerce_schema.as_deref() {
+        validate_file_backed_arg_format_for_lock(path, "canon.args.coerce_schema")?;
+    }
+    if let Some(path) = args.coerce_rules.as_deref() {
+        validate_file_backed_arg_format_for_lock(path, "canon.args.coerce_rules")?;
+    }
     Ok(())
 }
 
@@ -950,16 +3133,17 @@ fn validate_assert_step_args_for_lock(
     Ok(())
 }
 
-fn validate_sdiff_step_args_for_lock(args: &SdiffStepArgs) -> Result<(), RecipeExecutionErrorKind> {
-    if let Some(raw_format) = args.right_from.as_deref() {
-        Format::from_str(raw_format).map_err(|error| {
-            RecipeExecutionErrorKind::InputUsage(format!(
-                "invalid format `{raw_format}` for `sdiff.args.right`: {error}"
-            ))
-        })?;
-    } else {
-        validate_file_backed_arg_format_for_lock(args.right.as_path(), "sdiff.args.right")?;
-    }
+fn validate_sdiff_step_args_for_lock(
+    args: &SdiffStepArgs,
+    has_second_input: bool,
+) -> Result<(), RecipeExecutionErrorKind> {
+    validate_step_input_for_lock(
+        args.right.as_deref(),
+        args.right_from.as_deref(),
+        "sdiff.args.right",
+        has_second_input,
+        SDIFF_REQUIRES_RIGHT_OR_SECOND_INPUT,
+    )?;
 
     if let Some(key_path) = args.key.as_deref() {
         ValuePath::parse_canonical(key_path).map_err(|error| {
@@ -975,53 +3159,373 @@ fn validate_sdiff_step_args_for_lock(args: &SdiffStepArgs) -> Result<(), RecipeE
             ))
         })?;
     }
-
+
+    Ok(())
+}
+
+fn validate_merge_step_args_for_lock(
+    args: &MergeStepArgs,
+    has_in_memory_values: bool,
+) -> Result<(), RecipeExecutionErrorKind> {
+    match args.base.as_deref() {
+        Some(path) => validate_file_backed_arg_format_for_lock(path, "merge.args.base")?,
+        None if !has_in_memory_values => {
+            return Err(RecipeExecutionErrorKind::InputUsage(
+                MERGE_REQUIRES_BASE_OR_PRIOR_VALUES.to_string(),
+            ));
+        }
+        None => {}
+    }
+    if args.overlays.is_empty() {
+        return Err(RecipeExecutionErrorKind::InputUsage(
+            "merge step requires at least one `overlays` entry".to_string(),
+        ));
+    }
+    for overlay in &args.overlays {
+        validate_file_backed_arg_format_for_lock(overlay, "merge.args.overlays")?;
+    }
+    parse_merge_policy(args)?;
+    for raw in &args.policy_path {
+        let (path, policy) = raw.rsplit_once('=').ok_or_else(|| {
+            RecipeExecutionErrorKind::InputUsage(format!(
+                "invalid merge policy_path `{raw}`: expected `<canonical-path>=<policy>`"
+            ))
+        })?;
+        ValuePath::parse_canonical(path.trim()).map_err(|error| {
+            RecipeExecutionErrorKind::InputUsage(format!(
+                "invalid merge policy_path path `{}`: {error}",
+                path.trim()
+            ))
+        })?;
+        if MergePolicy::parse_cli_name(policy.trim()).is_none() {
+            return Err(RecipeExecutionErrorKind::InputUsage(format!(
+                "invalid merge policy_path policy `{}`",
+                policy.trim()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Lock-time counterpart of [`load_step_input_values`].
+fn validate_step_input_for_lock(
+    input: Option<&Path>,
+    explicit_format: Option<&str>,
+    field_label: &str,
+    has_in_memory_values: bool,
+    missing_message: &str,
+) -> Result<(), RecipeExecutionErrorKind> {
+    let Some(path) = input else {
+        if has_in_memory_values {
+            return Ok(());
+        }
+        return Err(RecipeExecutionErrorKind::InputUsage(
+            missing_message.to_string(),
+        ));
+    };
+    if let Some(raw) = explicit_format {
+        Format::from_str(raw).map_err(|error| {
+            RecipeExecutionErrorKind::InputUsage(format!(
+                "invalid format `{raw}` for `{field_label}`: {error}"
+            ))
+        })?;
+        return Ok(());
+    }
+    validate_file_backed_arg_format_for_lock(path, field_label)
+}
+
+fn hash_recipe_command_graph(recipe: &RecipeFile, algorithm: HashAlgorithm) -> String {
+    let mut hasher = DigestHasher::new(algorithm);
+    hasher.update_len_prefixed(b"dataq.recipe.lock.command_graph.v1");
+    hasher.update_len_prefixed(recipe.version.as_bytes());
+    for (index, step) in recipe.steps.iter().enumerate() {
+        hasher.update_len_prefixed(index.to_string().as_bytes());
+        hasher.update_len_prefixed(step.kind.as_bytes());
+        hash_recipe_step_edges(&mut hasher, step);
+    }
+    hasher.finish()
+}
+
+/// Fold `id` / `inputs` into the graph hash; absent fields keep v1 hashes unchanged.
+fn hash_recipe_step_edges(hasher: &mut DigestHasher, step: &RecipeStep) {
+    if let Some(id) = step.id.as_deref() {
+        hasher.update_len_prefixed(b"id");
+        hasher.update_len_prefixed(id.as_bytes());
+    }
+    if let Some(inputs) = step.inputs.as_deref() {
+        hasher.update_len_prefixed(b"inputs");
+        hasher.update_len_prefixed(inputs.len().to_string().as_bytes());
+        for input in inputs {
+            hasher.update_len_prefixed(input.as_bytes());
+        }
+    }
+    if step.on_failure != RecipeOnFailure::default() {
+        hasher.update_len_prefixed(b"on_failure");
+        hasher.update_len_prefixed(step.on_failure.as_str().as_bytes());
+    }
+    if let Some(condition) = step.when.as_ref() {
+        hasher.update_len_prefixed(b"when");
+        hasher.update_len_prefixed(condition.step.as_bytes());
+        hasher.update_len_prefixed(if condition.matched {
+            b"matched"
+        } else {
+            b"unmatched"
+        });
+    }
+}
+
+/// Content hashes of every file a step reads, keyed by the path as written in
+/// the recipe. Files produced by `write` steps are excluded.
+fn hash_recipe_inputs(
+    recipe: &RecipeFile,
+    recipe_base_dir: &Path,
+) -> Result<BTreeMap<String, String>, RecipeExecutionErrorKind> {
+    let mut read_paths = BTreeSet::new();
+    let mut written_paths = BTreeSet::new();
+    for step in &recipe.steps {
+        collect_step_file_paths(step, &mut read_paths, &mut written_paths)?;
+    }
+    read_paths.extend(recipe.included_files.iter().cloned());
+    let mut extended_paths = BTreeSet::new();
+    for step in &recipe.steps {
+        for rules_path in step_rules_file_paths(step)? {
+            if written_paths.contains(&rules_path) {
+                continue;
+            }
+            extended_paths.extend(rules_extends_files(recipe_base_dir, &rules_path)?);
+        }
+    }
+    read_paths.extend(extended_paths);
+
+    let mut hashes = BTreeMap::new();
+    for path in read_paths.difference(&written_paths) {
+        let resolved_path = resolve_recipe_path(recipe_base_dir, path);
+        let hash = hash_file_contents(resolved_path.as_path(), HashAlgorithm::Sha256).map_err(
+            |error| {
+                RecipeExecutionErrorKind::InputUsage(format!(
+                    "failed to hash recipe input `{}`: {error}",
+                    path.display()
+                ))
+            },
+        )?;
+        hashes.insert(path.display().to_string(), hash);
+    }
+    Ok(hashes)
+}
+
+/// Adds the recipe-relative paths a step reads and writes to the given sets.
+fn collect_step_file_paths(
+    step: &RecipeStep,
+    read_paths: &mut BTreeSet<PathBuf>,
+    written_paths: &mut BTreeSet<PathBuf>,
+) -> Result<(), RecipeExecutionErrorKind> {
+    let args = step.args.clone();
+    match step.kind.as_str() {
+        "canon" => {
+            let args: CanonStepArgs = parse_step_args("canon", args)?;
+            read_paths.extend(args.input);
+            read_paths.extend(args.coerce_schema);
+            read_paths.extend(args.coerce_rules);
+        }
+        "assert" => {
+            let args: AssertStepArgs = parse_step_args("assert", args)?;
+            read_paths.extend(args.rules_file);
+            read_paths.extend(args.schema_file);
+        }
+        "sdiff" => {
+            let args: SdiffStepArgs = parse_step_args("sdiff", args)?;
+            read_paths.extend(args.right);
+        }
+        "join" => {
+            let args: JoinStepArgs = parse_step_args("join", args)?;
+            read_paths.extend(args.left);
+            read_paths.extend(args.right);
+        }
+        "aggregate" => {
+            let args: AggregateStepArgs = parse_step_args("aggregate", args)?;
+            read_paths.extend(args.input);
+        }
+        "merge" => {
+            let args: MergeStepArgs = parse_step_args("merge", args)?;
+            read_paths.extend(args.base);
+            read_paths.extend(args.overlays);
+        }
+        "transform_rowset" => {
+            let args: TransformRowsetStepArgs = parse_step_args("transform_rowset", args)?;
+            read_paths.extend(args.input);
+        }
+        "gate_schema" => {
+            let args: GateSchemaStepArgs = parse_step_args("gate_schema", args)?;
+            read_paths.insert(args.schema);
+            read_paths.extend(args.input);
+        }
+        "gate_policy" => {
+            let args: GatePolicyStepArgs = parse_step_args("gate_policy", args)?;
+            read_paths.insert(args.rules);
+            read_paths.extend(args.input);
+        }
+        "ingest_yaml_jobs" => {
+            let args: IngestYamlJobsStepArgs = parse_step_args("ingest_yaml_jobs", args)?;
+            read_paths.extend(args.input);
+        }
+        "ingest_doc" => {
+            let args: IngestDocStepArgs = parse_step_args("ingest_doc", args)?;
+            read_paths.insert(args.input);
+        }
+        "write" => {
+            let args: WriteStepArgs = parse_step_args("write", args)?;
+            written_paths.insert(args.path);
+        }
+        _ => {}
+    }
     Ok(())
 }
 
-fn hash_recipe_command_graph(recipe: &RecipeFile) -> String {
-    let mut hasher = DeterministicHasher::new();
-    hasher.update_len_prefixed(b"dataq.recipe.lock.command_graph.v1");
-    hasher.update_len_prefixed(recipe.version.as_bytes());
-    for (index, step) in recipe.steps.iter().enumerate() {
-        hasher.update_len_prefixed(index.to_string().as_bytes());
-        hasher.update_len_prefixed(step.kind.as_bytes());
-    }
-    hasher.finish_hex()
+/// Recipe-relative rules files a step loads through the `extends`-aware
+/// rules loader.
+fn step_rules_file_paths(step: &RecipeStep) -> Result<Vec<PathBuf>, RecipeExecutionErrorKind> {
+    let args = step.args.clone();
+    Ok(match step.kind.as_str() {
+        "canon" => parse_step_args::<CanonStepArgs>("canon", args)?
+            .coerce_rules
+            .into_iter()
+            .collect(),
+        "assert" => parse_step_args::<AssertStepArgs>("assert", args)?
+            .rules_file
+            .into_iter()
+            .collect(),
+        "gate_policy" => vec![parse_step_args::<GatePolicyStepArgs>("gate_policy", args)?.rules],
+        _ => Vec::new(),
+    })
+}
+
+/// Files reached through `extends` from a rules file, keyed relative to the
+/// recipe directory when they live under it and absolute otherwise.
+fn rules_extends_files(
+    recipe_base_dir: &Path,
+    rules_path: &Path,
+) -> Result<Vec<PathBuf>, RecipeExecutionErrorKind> {
+    let resolved_path = resolve_recipe_path(recipe_base_dir, rules_path);
+    let (_, extended) =
+        crate::cmd::r#assert::load_rules_with_extends(resolved_path.as_path(), &|_| Ok(()))
+            .map_err(|error| {
+                RecipeExecutionErrorKind::InputUsage(format!(
+                    "failed to resolve `extends` of rules file `{}`: {error}",
+                    rules_path.display()
+                ))
+            })?;
+    let canonical_base_dir = if recipe_base_dir.as_os_str().is_empty() {
+        Path::new(".").canonicalize().ok()
+    } else {
+        recipe_base_dir.canonicalize().ok()
+    };
+    Ok(extended
+        .into_iter()
+        .map(|path| {
+            canonical_base_dir
+                .as_deref()
+                .and_then(|base| path.strip_prefix(base).ok())
+                .map(Path::to_path_buf)
+                .unwrap_or(path)
+        })
+        .collect())
+}
+
+fn hash_file_contents(path: &Path, algorithm: HashAlgorithm) -> Result<String, std::io::Error> {
+    let bytes = std::fs::read(path)?;
+    let mut hasher = DigestHasher::new(algorithm);
+    hasher.update(bytes.as_slice());
+    Ok(hasher.finish())
 }
 
-fn hash_recipe_args(recipe: &RecipeFile) -> Result<String, RecipeExecutionErrorKind> {
-    let mut hasher = DeterministicHasher::new();
+fn hash_recipe_args(
+    recipe: &RecipeFile,
+    algorithm: HashAlgorithm,
+) -> Result<String, RecipeExecutionErrorKind> {
+    let mut hasher = DigestHasher::new(algorithm);
     hasher.update_len_prefixed(b"dataq.recipe.lock.args.v1");
     for (index, step) in recipe.steps.iter().enumerate() {
         hasher.update_len_prefixed(index.to_string().as_bytes());
         hasher.update_len_prefixed(step.kind.as_bytes());
 
-        let canonical_args = canonicalize_value(
-            Value::Object(step.args.clone()),
-            CanonOptions {
-                sort_keys: true,
-                normalize_time: false,
-            },
-        );
-        let encoded = serde_json::to_vec(&canonical_args).map_err(|error| {
-            RecipeExecutionErrorKind::Internal(format!(
-                "failed to serialize recipe step args: {error}"
-            ))
-        })?;
-        hasher.update_len_prefixed(encoded.as_slice());
+        hasher.update_len_prefixed(encode_canonical_step_args(&step.args)?.as_slice());
+    }
+    if !recipe.resolved_params.is_empty() {
+        hasher.update_len_prefixed(b"params");
+        for (name, value) in &recipe.resolved_params {
+            hasher.update_len_prefixed(name.as_bytes());
+            hasher.update_len_prefixed(value.to_string().as_bytes());
+        }
     }
-    Ok(hasher.finish_hex())
+    Ok(hasher.finish())
+}
+
+fn encode_canonical_step_args(
+    args: &Map<String, Value>,
+) -> Result<Vec<u8>, RecipeExecutionErrorKind> {
+    let canonical_args = canonicalize_value(
+        Value::Object(args.clone()),
+        &CanonOptions {
+            sort_keys: true,
+            normalize_time: false,
+            coerce: CoercePolicy::default(),
+            normalize: NormalizeOptions::default(),
+            time: None,
+        },
+    )
+    .map_err(|error| {
+        RecipeExecutionErrorKind::Internal(format!(
+            "failed to canonicalize recipe step args: {error}"
+        ))
+    })?;
+    serde_json::to_vec(&canonical_args).map_err(|error| {
+        RecipeExecutionErrorKind::Internal(format!("failed to serialize recipe step args: {error}"))
+    })
 }
 
-fn probe_recipe_lock_tools() -> Result<BTreeMap<String, String>, RecipeExecutionErrorKind> {
+fn probe_recipe_lock_tools(
+    recipe: &RecipeFile,
+) -> Result<BTreeMap<String, String>, RecipeExecutionErrorKind> {
     let mut versions = BTreeMap::new();
-    for tool in RECIPE_LOCK_TOOLS {
-        versions.insert(tool.to_string(), probe_recipe_lock_tool_version(tool)?);
+    for tool in recipe_lock_tool_names(recipe) {
+        let version = probe_recipe_lock_tool_version(&tool)?;
+        versions.insert(tool, version);
     }
     Ok(versions)
 }
 
+/// Locked tools: the fixed `jq`/`mlr`/`yq` set plus any other external tool
+/// (for example `rg` or `pandoc`) a step kind in `recipe` invokes, sorted.
+fn recipe_lock_tool_names(recipe: &RecipeFile) -> Vec<String> {
+    let mut extra = BTreeSet::new();
+    for step in &recipe.steps {
+        for tool in recipe_step_external_tools(step.kind.as_str()) {
+            if !RECIPE_LOCK_TOOL_ORDER.contains(&tool.as_str()) {
+                extra.insert(tool);
+            }
+        }
+    }
+    RECIPE_LOCK_TOOL_ORDER
+        .iter()
+        .map(|tool| (*tool).to_string())
+        .chain(extra)
+        .collect()
+}
+
+/// External tools a step kind runs, taken from its emit-plan stages.
+fn recipe_step_external_tools(kind: &str) -> BTreeSet<String> {
+    emit_plan::resolve_recipe_step_stages(kind)
+        .map(|stages| {
+            stages
+                .iter()
+                .flat_map(|stage| stage.tool.split('+'))
+                .filter(|tool| *tool != "rust")
+                .map(ToOwned::to_owned)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 fn probe_recipe_lock_tool_version(tool_name: &str) -> Result<String, RecipeExecutionErrorKind> {
     let executable = resolve_recipe_lock_tool_executable(tool_name);
     let output = Command::new(&executable)
@@ -1061,6 +3565,8 @@ fn resolve_recipe_lock_tool_executable(tool_name: &str) -> String {
         "jq" => Some("DATAQ_JQ_BIN"),
         "yq" => Some("DATAQ_YQ_BIN"),
         "mlr" => Some("DATAQ_MLR_BIN"),
+        "rg" => Some("DATAQ_RG_BIN"),
+        "pandoc" => Some("DATAQ_PANDOC_BIN"),
         _ => None,
     };
 
@@ -1080,11 +3586,19 @@ fn serialize_recipe_lock_report(
     })?;
     let canonical = canonicalize_value(
         value,
-        CanonOptions {
+        &CanonOptions {
             sort_keys: true,
             normalize_time: false,
+            coerce: CoercePolicy::default(),
+            normalize: NormalizeOptions::default(),
+            time: None,
         },
-    );
+    )
+    .map_err(|error| {
+        RecipeExecutionErrorKind::Internal(format!(
+            "failed to canonicalize recipe lock report: {error}"
+        ))
+    })?;
     serde_json::to_vec(&canonical).map_err(|error| {
         RecipeExecutionErrorKind::Internal(format!(
             "failed to serialize canonical recipe lock: {error}"
@@ -1146,6 +3660,77 @@ fn read_values_from_path(
     })
 }
 
+/// Read `input` (recipe-relative) when set, otherwise reuse prior in-memory values.
+fn load_step_input_values(
+    input: Option<&Path>,
+    explicit_format: Option<&str>,
+    field_label: &str,
+    current_values: Option<&[Value]>,
+    recipe_base_dir: &Path,
+    missing_message: &str,
+) -> Result<Vec<Value>, RecipeExecutionErrorKind> {
+    if let Some(path) = input {
+        let resolved_path = resolve_recipe_path(recipe_base_dir, path);
+        let format =
+            resolve_step_input_format(explicit_format, resolved_path.as_path(), field_label)?;
+        return read_values_from_path(resolved_path.as_path(), format);
+    }
+    current_values
+        .map(<[Value]>::to_vec)
+        .ok_or_else(|| RecipeExecutionErrorKind::InputUsage(missing_message.to_string()))
+}
+
+/// Resolve a command input path, or serialize prior values as JSON stdin for the command API.
+fn step_input_path_or_prior_values(
+    input: Option<&Path>,
+    current_values: Option<&[Value]>,
+    recipe_base_dir: &Path,
+    missing_message: &str,
+) -> Result<(Option<PathBuf>, Vec<u8>), RecipeExecutionErrorKind> {
+    if let Some(path) = input {
+        return Ok((Some(resolve_recipe_path(recipe_base_dir, path)), Vec::new()));
+    }
+    let values = current_values
+        .ok_or_else(|| RecipeExecutionErrorKind::InputUsage(missing_message.to_string()))?;
+    let payload = serde_json::to_vec(values).map_err(|error| {
+        RecipeExecutionErrorKind::Internal(format!("failed to serialize step input: {error}"))
+    })?;
+    Ok((None, payload))
+}
+
+/// Map a command-API response onto step semantics: `0`/`2` yield the payload, `3`/`1` fail.
+fn command_step_payload(
+    kind: &str,
+    exit_code: i32,
+    payload: Value,
+) -> Result<Value, RecipeExecutionErrorKind> {
+    let message = || {
+        payload
+            .get("message")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| payload.to_string())
+    };
+    match exit_code {
+        0 | 2 => Ok(payload),
+        3 => Err(RecipeExecutionErrorKind::InputUsage(format!(
+            "{kind} step failed: {}",
+            message()
+        ))),
+        _ => Err(RecipeExecutionErrorKind::Internal(format!(
+            "{kind} step failed: {}",
+            message()
+        ))),
+    }
+}
+
+fn payload_rows(payload: Value) -> Vec<Value> {
+    match payload {
+        Value::Array(rows) => rows,
+        other => vec![other],
+    }
+}
+
 fn resolve_recipe_path(recipe_base_dir: &Path, path: &Path) -> PathBuf {
     if path.is_absolute() {
         path.to_path_buf()
@@ -1192,15 +3777,30 @@ mod tests {
 
     fn step(kind: &str, args: Value) -> RecipeStep {
         RecipeStep {
+            id: None,
             kind: kind.to_string(),
+            inputs: None,
+            on_failure: RecipeOnFailure::Stop,
+            when: None,
             args: args_map(args),
         }
     }
 
+    fn dag_step(id: &str, kind: &str, inputs: &[&str], args: Value) -> RecipeStep {
+        RecipeStep {
+            id: Some(id.to_string()),
+            inputs: Some(inputs.iter().map(|input| input.to_string()).collect()),
+            ..step(kind, args)
+        }
+    }
+
     fn recipe_with_steps(steps: Vec<RecipeStep>) -> RecipeFile {
         RecipeFile {
             version: RECIPE_VERSION.to_string(),
+            params: BTreeMap::new(),
             steps,
+            resolved_params: BTreeMap::new(),
+            included_files: BTreeSet::new(),
         }
     }
 
@@ -1230,14 +3830,209 @@ mod tests {
 
     #[test]
     fn parse_loaded_recipe_rejects_version_mismatch() {
-        let error = parse_loaded_recipe(json!({
-            "version": "dataq.recipe.v0",
-            "steps": []
-        }))
+        let error = parse_loaded_recipe(
+            json!({
+                "version": "dataq.recipe.v0",
+                "steps": []
+            }),
+            &[],
+            Path::new("."),
+        )
         .expect_err("version mismatch must fail");
         assert_input_usage_contains(error, "recipe version must be `dataq.recipe.v1`");
     }
 
+    #[test]
+    fn parse_loaded_recipe_resolves_and_substitutes_params() {
+        let value = json!({
+            "version": "dataq.recipe.v1",
+            "params": {
+                "source": {"type": "string", "default": "a.json"},
+                "limit": {"type": "integer", "default": 10},
+                "strict": {"type": "boolean"}
+            },
+            "steps": [{
+                "kind": "scan_text",
+                "args": {
+                    "pattern": "${params.source}:${params.limit}",
+                    "max_matches": "${params.limit}",
+                    "glob": ["data/${params.source}", "$${params.source}"],
+                    "policy_mode": "${params.strict}"
+                }
+            }]
+        });
+
+        let recipe = parse_loaded_recipe(
+            value.clone(),
+            &["limit=3".to_string(), "strict=true".to_string()],
+            Path::new("."),
+        )
+        .expect("params resolve");
+        assert_eq!(
+            Value::Object(recipe.steps[0].args.clone()),
+            json!({
+                "pattern": "a.json:3",
+                "max_matches": 3,
+                "glob": ["data/a.json", "${params.source}"],
+                "policy_mode": true
+            })
+        );
+        let other = parse_loaded_recipe(
+            value.clone(),
+            &["limit=4".to_string(), "strict=true".to_string()],
+            Path::new("."),
+        )
+        .expect("params resolve");
+        assert_ne!(
+            hash_recipe_args(&recipe, HashAlgorithm::Sha256).expect("hash"),
+            hash_recipe_args(&other, HashAlgorithm::Sha256).expect("hash")
+        );
+
+        let missing =
+            parse_loaded_recipe(value.clone(), &[], Path::new(".")).expect_err("missing param");
+        assert_input_usage_contains(missing, "recipe param `strict` has no default");
+        let mistyped = parse_loaded_recipe(
+            value.clone(),
+            &["limit=ten".to_string(), "strict=true".to_string()],
+            Path::new("."),
+        )
+        .expect_err("mistyped param");
+        assert_input_usage_contains(mistyped, "expected `integer`");
+        let unknown = parse_loaded_recipe(value, &["nope=1".to_string()], Path::new("."))
+            .expect_err("unknown param");
+        assert_input_usage_contains(unknown, "unknown recipe param `nope`");
+    }
+
+    #[test]
+    fn parse_loaded_recipe_inlines_includes_relative_to_including_file() {
+        let temp = tempdir().expect("tempdir");
+        fs::create_dir_all(temp.path().join("lib")).expect("create lib");
+        fs::write(
+            temp.path().join("lib/prelude.json"),
+            br#"{"version":"dataq.recipe.v1",
+                "params":{"source":{"type":"string"},"strict":{"type":"boolean","default":false}},
+                "steps":[
+                    {"kind":"canon","args":{"input":"${params.source}"}},
+                    {"kind":"include","args":{"path":"checks.json"}}
+                ]}"#,
+        )
+        .expect("write prelude");
+        fs::write(
+            temp.path().join("lib/checks.json"),
+            br#"{"version":"dataq.recipe.v1","steps":[
+                {"kind":"assert","args":{"rules":{"required_keys":["id"]}}}
+            ]}"#,
+        )
+        .expect("write checks");
+
+        let recipe = parse_loaded_recipe(
+            json!({
+                "version": "dataq.recipe.v1",
+                "params": {"input": {"type": "string", "default": "rows.json"}},
+                "steps": [
+                    {"kind": "include", "args": {"path": "lib/prelude.json", "params": {"source": "${params.input}"}}},
+                    {"kind": "profile", "args": {}}
+                ]
+            }),
+            &[],
+            temp.path(),
+        )
+        .expect("includes expand");
+        let kinds: Vec<&str> = recipe.steps.iter().map(|step| step.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["canon", "assert", "profile"]);
+        assert_eq!(recipe.steps[0].args["input"], json!("rows.json"));
+        assert_eq!(
+            recipe.included_files,
+            BTreeSet::from([
+                PathBuf::from("lib/checks.json"),
+                PathBuf::from("lib/prelude.json")
+            ])
+        );
+
+        let unbound = parse_loaded_recipe(
+            json!({
+                "version": "dataq.recipe.v1",
+                "steps": [{"kind": "include", "args": {"path": "lib/prelude.json"}}]
+            }),
+            &[],
+            temp.path(),
+        )
+        .expect_err("unbound include param");
+        assert_input_usage_contains(
+            unbound,
+            "included recipe `lib/prelude.json`: recipe param `source` has no default",
+        );
+
+        fs::write(
+            temp.path().join("lib/checks.json"),
+            br#"{"version":"dataq.recipe.v1","steps":[
+                {"kind":"include","args":{"path":"prelude.json","params":{"source":"x.json"}}}
+            ]}"#,
+        )
+        .expect("write cyclic checks");
+        let cycle = parse_loaded_recipe(
+            json!({
+                "version": "dataq.recipe.v1",
+                "steps": [{"kind": "include", "args": {"path": "lib/prelude.json", "params": {"source": "a.json"}}}]
+            }),
+            &[],
+            temp.path(),
+        )
+        .expect_err("include cycle");
+        assert_input_usage_contains(cycle, "recipe include cycle detected");
+    }
+
+    #[test]
+    fn included_steps_resolve_literal_paths_against_included_file() {
+        let temp = tempdir().expect("tempdir");
+        fs::create_dir_all(temp.path().join("lib")).expect("create lib");
+        fs::write(
+            temp.path().join("lib/checks.json"),
+            br#"{"version":"dataq.recipe.v1",
+                "params":{"source":{"type":"string"}},
+                "steps":[
+                    {"id":"rows","kind":"canon","args":{"input":"${params.source}","coerce_rules":"coerce.json"}},
+                    {"kind":"assert","args":{"rules_file":"rules/policy.json"}},
+                    {"kind":"merge","args":{"base":"/abs/base.json","overlays":["a.json","${params.source}"]}},
+                    {"kind":"write","args":{"path":"out/rows.json"}}
+                ]}"#,
+        )
+        .expect("write checks");
+        let recipe_with_include = |first_id: &str| {
+            json!({
+                "version": "dataq.recipe.v1",
+                "steps": [
+                    {"id": first_id, "kind": "canon", "args": {"input": "top.json"}},
+                    {"kind": "include", "args": {"path": "lib/checks.json", "params": {"source": "rows.json"}}}
+                ]
+            })
+        };
+
+        let recipe = parse_loaded_recipe(recipe_with_include("top"), &[], temp.path())
+            .expect("includes expand");
+        assert_eq!(recipe.steps[0].args["input"], json!("top.json"));
+        assert_eq!(recipe.steps[1].args["input"], json!("rows.json"));
+        assert_eq!(
+            recipe.steps[1].args["coerce_rules"],
+            json!("lib/coerce.json")
+        );
+        assert_eq!(
+            recipe.steps[2].args["rules_file"],
+            json!("lib/rules/policy.json")
+        );
+        assert_eq!(recipe.steps[3].args["base"], json!("/abs/base.json"));
+        assert_eq!(
+            recipe.steps[3].args["overlays"],
+            json!(["lib/a.json", "rows.json"])
+        );
+        assert_eq!(recipe.steps[4].args["path"], json!("lib/out/rows.json"));
+
+        let duplicate = parse_loaded_recipe(recipe_with_include("rows"), &[], temp.path())
+            .expect("includes expand");
+        let error = plan_recipe_steps(&duplicate.steps).expect_err("duplicate step id");
+        assert_input_usage_contains(error, "duplicate recipe step id `rows`");
+    }
+
     #[test]
     fn load_recipe_value_rejects_non_json_yaml_extension() {
         let temp = tempdir().expect("tempdir");
@@ -1271,10 +4066,11 @@ mod tests {
             args_hash: "b".to_string(),
             tool_versions: BTreeMap::from([("custom".to_string(), "1.0.0".to_string())]),
             dataq_version: env!("CARGO_PKG_VERSION").to_string(),
+            input_hashes: BTreeMap::new(),
         };
 
         assert_eq!(
-            ordered_lock_tool_names(&lock),
+            ordered_lock_tool_names(&recipe_with_steps(Vec::new()), &lock),
             vec![
                 "jq".to_string(),
                 "mlr".to_string(),
@@ -1344,14 +4140,332 @@ mod tests {
             ),
         ]);
 
-        let report =
-            execute_recipe_steps(recipe, temp.path(), None).expect("execute all recipe steps");
+        let report = execute_recipe_steps(recipe, temp.path(), None, None)
+            .expect("execute all recipe steps");
         assert!(report.matched);
         assert_eq!(report.exit_code, 0);
         assert_eq!(report.steps.len(), 4);
         assert!(report.steps.iter().all(|step| step.matched));
     }
 
+    #[test]
+    fn validate_recipe_lock_steps_checks_command_step_kinds() {
+        let recipe = recipe_with_steps(vec![step(
+            "join",
+            json!({"right": "right.json", "on": "id"}),
+        )]);
+        let error = validate_recipe_lock_steps(&recipe).expect_err("join without left");
+        assert_input_usage_contains(error, JOIN_REQUIRES_LEFT_OR_PRIOR_VALUES);
+
+        let recipe = recipe_with_steps(vec![
+            step("ingest_doc", json!({"input": "doc.md", "from": "md"})),
+            step(
+                "aggregate",
+                json!({"group_by": "team", "metric": "median", "target": "price"}),
+            ),
+        ]);
+        let error = validate_recipe_lock_steps(&recipe).expect_err("unknown metric");
+        assert_input_usage_contains(error, "invalid aggregate metric `median`");
+
+        let recipe = recipe_with_steps(vec![step(
+            "gate_policy",
+            json!({"rules": "rules.json", "input": "in.json", "severity": "high"}),
+        )]);
+        let error = validate_recipe_lock_steps(&recipe).expect_err("unknown field");
+        assert_input_usage_contains(error, "invalid `gate_policy` step args");
+
+        let recipe = recipe_with_steps(vec![
+            step("scan_text", json!({"pattern": "TODO"})),
+            step(
+                "gate_policy",
+                json!({"rules": "rules.json", "source": "scan-text"}),
+            ),
+            step(
+                "merge",
+                json!({"overlays": ["patch.yaml"], "policy": "deep-merge"}),
+            ),
+            step(
+                "transform_rowset",
+                json!({"jq_filter": ".", "mlr": ["sort", "-f", "id"]}),
+            ),
+        ]);
+        validate_recipe_lock_steps(&recipe).expect("valid command steps");
+    }
+
+    #[test]
+    fn execute_recipe_steps_runs_merge_and_gate_steps() {
+        let temp = tempdir().expect("tempdir");
+        fs::write(temp.path().join("base.json"), br#"{"id":"1","tags":["a"]}"#)
+            .expect("write base");
+        fs::write(temp.path().join("overlay.json"), br#"{"owner":"ops"}"#).expect("write overlay");
+        fs::write(
+            temp.path().join("schema.json"),
+            br#"{"type":"object","required":["id","owner"]}"#,
+        )
+        .expect("write schema");
+        fs::write(
+            temp.path().join("rules.json"),
+            br#"{"required_keys":["id"],"fields":{"id":{"type":"integer"}}}"#,
+        )
+        .expect("write rules");
+
+        let recipe = recipe_with_steps(vec![
+            step(
+                "merge",
+                json!({"base": "base.json", "overlays": ["overlay.json"]}),
+            ),
+            step("canon", json!({})),
+            step("gate_schema", json!({"schema": "schema.json"})),
+            step("gate_policy", json!({"rules": "rules.json"})),
+        ]);
+
+        let report = execute_recipe_steps(recipe, temp.path(), None, None).expect("execute recipe");
+        assert!(report.matched);
+        assert_eq!(report.steps.len(), 4);
+        assert_eq!(report.steps[0].summary["overlay_count"], json!(1));
+        assert_eq!(report.steps[3].summary["violations"], json!(0));
+    }
+
+    #[test]
+    fn execute_recipe_steps_writes_current_values_atomically() {
+        let temp = tempdir().expect("tempdir");
+        fs::write(
+            temp.path().join("input.json"),
+            br#"[{"id":"2"},{"id":"1"}]"#,
+        )
+        .expect("write input");
+
+        let recipe = recipe_with_steps(vec![
+            step("canon", json!({"input": "input.json"})),
+            step("write", json!({"path": "out.jsonl"})),
+            step("profile", json!({})),
+        ]);
+        validate_recipe_lock_steps(&recipe).expect("write step validates");
+
+        let report = execute_recipe_steps(recipe, temp.path(), None, None).expect("execute recipe");
+        let written = fs::read(temp.path().join("out.jsonl")).expect("read output");
+        assert_eq!(written, b"{\"id\":2}\n{\"id\":1}\n");
+        let mut hasher = DigestHasher::sha256();
+        hasher.update(written.as_slice());
+        assert_eq!(
+            report.steps[1].summary,
+            json!({
+                "record_count": 2,
+                "path": "out.jsonl",
+                "format": "jsonl",
+                "bytes": written.len(),
+                "hash": hasher.finish(),
+            })
+        );
+        assert_eq!(report.steps[2].summary["record_count"], json!(2));
+
+        let error = validate_recipe_lock_steps(&recipe_with_steps(vec![step(
+            "write",
+            json!({"path": "out.json"}),
+        )]))
+        .expect_err("write without prior values must fail");
+        assert_input_usage_contains(error, "write step requires prior in-memory values");
+        let error = validate_recipe_lock_steps(&recipe_with_steps(vec![
+            step("canon", json!({"input": "input.json"})),
+            step("write", json!({"path": "out.txt"})),
+        ]))
+        .expect_err("unknown output extension must fail");
+        assert_input_usage_contains(error, "failed to resolve format for `write.args.path`");
+    }
+
+    #[test]
+    fn execute_recipe_steps_reuses_cached_outcomes_until_inputs_change() {
+        let temp = tempdir().expect("tempdir");
+        fs::write(temp.path().join("input.json"), br#"[{"id":"2"}]"#).expect("write input");
+        let cache = StepCache::open(temp.path().join("cache").as_path()).expect("open cache");
+        let recipe = || {
+            recipe_with_steps(vec![
+                step("canon", json!({"input": "input.json"})),
+                step("write", json!({"path": "out.json"})),
+                step("profile", json!({})),
+            ])
+        };
+
+        let first = execute_recipe_steps(recipe(), temp.path(), Some(&cache), None)
+            .expect("execute uncached");
+        assert!(first.steps.iter().all(|step| !step.cached));
+
+        let mut pipeline = Vec::new();
+        let second = execute_recipe_steps(recipe(), temp.path(), Some(&cache), Some(&mut pipeline))
+            .expect("execute cached");
+        assert_eq!(
+            second
+                .steps
+                .iter()
+                .map(|step| step.cached)
+                .collect::<Vec<_>>(),
+            vec![true, false, true]
+        );
+        assert_eq!(second.steps[0].summary, first.steps[0].summary);
+        assert_eq!(second.steps[2].summary, first.steps[2].summary);
+        assert_eq!(pipeline[0], "cached_step_0_canon");
+        assert_eq!(pipeline[1], "execute_step_1_write");
+
+        fs::write(
+            temp.path().join("input.json"),
+            br#"[{"id":"2"},{"id":"3"}]"#,
+        )
+        .expect("rewrite input");
+        let third = execute_recipe_steps(recipe(), temp.path(), Some(&cache), None)
+            .expect("execute after input change");
+        assert!(third.steps.iter().all(|step| !step.cached));
+        assert_eq!(third.steps[2].summary["record_count"], json!(2));
+    }
+
+    #[test]
+    fn execute_recipe_steps_continues_on_failure_and_honors_when() {
+        let temp = tempdir().expect("tempdir");
+        fs::write(temp.path().join("input.json"), br#"[{"id":"a"}]"#).expect("write input");
+
+        let mut check = dag_step(
+            "check",
+            "assert",
+            &["rows"],
+            json!({"rules": {"fields": {"id": {"type": "integer"}}}}),
+        );
+        check.on_failure = RecipeOnFailure::Continue;
+        let mut second = dag_step(
+            "required",
+            "assert",
+            &["rows"],
+            json!({"rules": {"required_keys": ["name"]}}),
+        );
+        second.on_failure = RecipeOnFailure::Continue;
+        let mut only_if_matched = dag_step("profile_ok", "profile", &["rows"], json!({}));
+        only_if_matched.when = Some(RecipeStepCondition {
+            step: "check".to_string(),
+            matched: true,
+        });
+        let mut only_if_failed = dag_step("profile_failed", "profile", &["rows"], json!({}));
+        only_if_failed.when = Some(RecipeStepCondition {
+            step: "check".to_string(),
+            matched: false,
+        });
+
+        let recipe = recipe_with_steps(vec![
+            dag_step("rows", "canon", &[], json!({"input": "input.json"})),
+            check,
+            second,
+            only_if_matched,
+            only_if_failed,
+        ]);
+        let report = execute_recipe_steps(recipe, temp.path(), None, None).expect("execute recipe");
+
+        assert!(!report.matched);
+        assert_eq!(report.exit_code, 2);
+        let outcomes = report
+            .steps
+            .iter()
+            .map(|step| (step.index, step.matched, step.skipped))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            vec![
+                (0, true, false),
+                (1, false, false),
+                (2, false, false),
+                (3, true, true),
+                (4, true, false),
+            ]
+        );
+
+        let mut dangling = step("profile", json!({}));
+        dangling.when = Some(RecipeStepCondition {
+            step: "missing".to_string(),
+            matched: true,
+        });
+        let error = plan_recipe_steps(&[step("canon", json!({})), dangling])
+            .expect_err("unknown when target must fail");
+        assert_input_usage_contains(error, "`when` references unknown step `missing`");
+    }
+
+    #[test]
+    fn plan_recipe_steps_orders_dag_and_rejects_cycles() {
+        let steps = vec![
+            dag_step("report", "sdiff", &["left", "right"], json!({})),
+            dag_step("left", "canon", &[], json!({"input": "a.json"})),
+            dag_step("right", "canon", &[], json!({"input": "b.json"})),
+        ];
+        let plan = plan_recipe_steps(&steps).expect("plan dag");
+        assert_eq!(plan.order, vec![1, 2, 0]);
+        assert_eq!(plan.inputs[0], vec![1, 2]);
+
+        let linear = vec![step("canon", json!({})), step("profile", json!({}))];
+        let plan = plan_recipe_steps(&linear).expect("plan linear");
+        assert_eq!(plan.order, vec![0, 1]);
+        assert_eq!(plan.inputs, vec![Vec::<usize>::new(), vec![0]]);
+
+        let cyclic = vec![
+            dag_step("a", "canon", &["b"], json!({})),
+            dag_step("b", "canon", &["a"], json!({})),
+        ];
+        let error = plan_recipe_steps(&cyclic).expect_err("cycle");
+        assert_input_usage_contains(error, "recipe step inputs form a cycle: `a`, `b`");
+
+        let unknown = vec![dag_step("a", "profile", &["missing"], json!({}))];
+        let error = plan_recipe_steps(&unknown).expect_err("unknown input");
+        assert_input_usage_contains(error, "references unknown input `missing`");
+
+        let too_many = vec![
+            dag_step("a", "canon", &[], json!({})),
+            dag_step("b", "canon", &[], json!({})),
+            dag_step("c", "profile", &["a", "b"], json!({})),
+        ];
+        let error = plan_recipe_steps(&too_many).expect_err("too many inputs");
+        assert_input_usage_contains(error, "`profile` step accepts at most 1 dataset(s)");
+    }
+
+    #[test]
+    fn execute_recipe_steps_diffs_two_named_datasets() {
+        let temp = tempdir().expect("tempdir");
+        fs::write(temp.path().join("a.json"), br#"[{"id":"1","v":"x"}]"#).expect("write a");
+        fs::write(temp.path().join("b.yaml"), b"- id: 1\n  v: x\n").expect("write b");
+
+        let recipe = recipe_with_steps(vec![
+            dag_step("left", "canon", &[], json!({"input": "a.json"})),
+            dag_step("right", "canon", &[], json!({"input": "b.yaml"})),
+            dag_step(
+                "diff",
+                "sdiff",
+                &["left", "right"],
+                json!({"key": "$[\"id\"]"}),
+            ),
+            dag_step("check", "assert", &["right"], json!({"schema": true})),
+        ]);
+        validate_recipe_lock_steps(&recipe).expect("lock validation follows inputs");
+
+        let report = execute_recipe_steps(recipe, temp.path(), None, None).expect("execute dag");
+        assert!(report.matched);
+        assert_eq!(report.steps[2].id.as_deref(), Some("diff"));
+        assert!(report.steps[2].matched);
+    }
+
+    #[test]
+    fn command_step_payload_maps_exit_codes() {
+        assert_eq!(
+            command_step_payload("join", 0, json!([1])).expect("ok payload"),
+            json!([1])
+        );
+        assert_eq!(
+            command_step_payload("gate_policy", 2, json!({"matched": false}))
+                .expect("mismatch payload"),
+            json!({"matched": false})
+        );
+        assert_input_usage_contains(
+            command_step_payload("join", 3, json!({"message": "bad key"})).expect_err("usage"),
+            "join step failed: bad key",
+        );
+        assert!(matches!(
+            command_step_payload("scan_text", 1, json!({"message": "boom"})),
+            Err(RecipeExecutionErrorKind::Internal(_))
+        ));
+    }
+
     #[test]
     fn execute_recipe_steps_stops_after_first_mismatch() {
         let temp = tempdir().expect("tempdir");
@@ -1370,8 +4484,8 @@ mod tests {
             step("profile", json!({})),
         ]);
 
-        let report =
-            execute_recipe_steps(recipe, temp.path(), None).expect("execute mismatching recipe");
+        let report = execute_recipe_steps(recipe, temp.path(), None, None)
+            .expect("execute mismatching recipe");
         assert!(!report.matched);
         assert_eq!(report.exit_code, 2);
         assert_eq!(report.steps.len(), 2);
@@ -1396,7 +4510,8 @@ mod tests {
             ]
         });
 
-        let execution = run_from_value(recipe_value, Some(temp.path())).expect("run inline recipe");
+        let execution =
+            run_from_value(recipe_value, Some(temp.path()), &[], None).expect("run inline recipe");
         assert!(execution.report.matched);
         assert_eq!(
             execution.pipeline_steps,
@@ -1435,7 +4550,7 @@ mod tests {
         )
         .expect("write lock");
 
-        let replay_report = replay(recipe_path.as_path(), lock_path.as_path(), true)
+        let replay_report = replay(recipe_path.as_path(), lock_path.as_path(), true, &[])
             .expect("strict replay should return deterministic mismatch report");
         assert!(!replay_report.report.matched);
         assert_eq!(replay_report.report.exit_code, 2);
@@ -1449,20 +4564,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn lock_tool_names_add_external_tools_of_present_step_kinds() {
+        let plain = recipe_with_steps(vec![step("canon", json!({}))]);
+        assert_eq!(recipe_lock_tool_names(&plain), vec!["jq", "mlr", "yq"]);
+
+        let external = recipe_with_steps(vec![
+            step("scan_text", json!({"pattern": "x"})),
+            step("ingest_doc", json!({"input": "a.md", "from": "md"})),
+            step("transform_rowset", json!({})),
+        ]);
+        assert_eq!(
+            recipe_lock_tool_names(&external),
+            vec!["jq", "mlr", "yq", "pandoc", "rg"]
+        );
+    }
+
     #[test]
     fn verify_lock_constraints_can_match_with_actual_probed_values() {
         let recipe = recipe_with_steps(Vec::new());
         let mut lock = RecipeLockFile {
             version: RECIPE_LOCK_VERSION.to_string(),
-            command_graph_hash: hash_recipe_command_graph(&recipe),
-            args_hash: hash_recipe_args(&recipe).expect("args hash"),
+            command_graph_hash: hash_recipe_command_graph(&recipe, HashAlgorithm::Sha256),
+            args_hash: hash_recipe_args(&recipe, HashAlgorithm::Fnv1a64).expect("args hash"),
             tool_versions: BTreeMap::new(),
             dataq_version: env!("CARGO_PKG_VERSION").to_string(),
+            input_hashes: BTreeMap::new(),
         };
-        lock.tool_versions = collect_actual_tool_versions(&lock);
+        lock.tool_versions = collect_actual_tool_versions(&recipe, &lock);
 
-        let report =
-            verify_lock_constraints(&recipe, &lock, false).expect("verify lock constraints");
+        let report = verify_lock_constraints(&recipe, &lock, Path::new("."), false)
+            .expect("verify lock constraints");
         assert!(report.matched);
         assert_eq!(report.mismatch_count, 0);
         assert!(report.mismatches.is_empty());
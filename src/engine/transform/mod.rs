@@ -4,6 +4,7 @@ use serde_json::{Number, Value};
 use thiserror::Error;
 
 use crate::adapters::{jq, mlr};
+use crate::cmd::stage_trace;
 use crate::util::sort::sort_value_keys;
 
 /// Execution result for the fixed `jq -> mlr` rowset transform.
@@ -51,6 +52,7 @@ pub fn execute_rowset(
     }
 
     let jq_input = values.len();
+    stage_trace::report_stage_start("transform_rowset_jq");
     let jq_rows =
         jq::run_custom_filter(values, jq_filter).map_err(|source| TransformRowsetError::Jq {
             input_records: jq_input,
@@ -59,6 +61,7 @@ pub fn execute_rowset(
     let jq_rows = deterministic_rows(jq_rows);
     let jq_output = jq_rows.len();
 
+    stage_trace::report_stage_start("transform_rowset_mlr");
     let mlr_rows =
         mlr::run_verbs(&jq_rows, mlr_args).map_err(|source| TransformRowsetError::Mlr {
             jq_input_records: jq_input,
@@ -0,0 +1,121 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+
+use crate::io::IoError;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const MAX_MAGIC_LEN: usize = 4;
+
+/// Stream compression wrapped around a data format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Resolve compression from the final path extension (`.gz`, `.zst`).
+    pub fn from_path(path: &Path) -> Self {
+        path.extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+            .as_deref()
+            .and_then(Self::from_extension)
+            .unwrap_or(Self::None)
+    }
+
+    /// Detect compression from leading magic bytes.
+    pub fn detect(input: &[u8]) -> Self {
+        if input.starts_with(GZIP_MAGIC) {
+            Self::Gzip
+        } else if input.starts_with(ZSTD_MAGIC) {
+            Self::Zstd
+        } else {
+            Self::None
+        }
+    }
+
+    pub(crate) fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "gz" | "gzip" => Some(Self::Gzip),
+            "zst" | "zstd" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Wrap `reader` with a decoder chosen from its leading magic bytes.
+///
+/// Uncompressed input is passed through unchanged, so callers can apply this
+/// unconditionally regardless of where the bytes came from.
+pub fn decompressing_reader<'a, R: Read + 'a>(
+    mut reader: R,
+) -> Result<Box<dyn Read + 'a>, IoError> {
+    let mut prefix = Vec::with_capacity(MAX_MAGIC_LEN);
+    while prefix.len() < MAX_MAGIC_LEN {
+        let mut byte = [0u8; 1];
+        if reader.read(&mut byte)? == 0 {
+            break;
+        }
+        prefix.push(byte[0]);
+    }
+    let compression = Compression::detect(&prefix);
+    let chained = Cursor::new(prefix).chain(reader);
+    Ok(match compression {
+        Compression::None => Box::new(chained),
+        Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(chained)),
+        Compression::Zstd => Box::new(zstd::stream::read::Decoder::new(chained)?),
+    })
+}
+
+/// Decompress an in-memory buffer when it carries a known magic prefix.
+pub fn decompress_bytes(input: &[u8]) -> Result<Cow<'_, [u8]>, IoError> {
+    if Compression::detect(input) == Compression::None {
+        return Ok(Cow::Borrowed(input));
+    }
+    let mut out = Vec::new();
+    decompressing_reader(input)?.read_to_end(&mut out)?;
+    Ok(Cow::Owned(out))
+}
+
+/// Compress `bytes` into `writer` with fixed encoder settings.
+///
+/// gzip headers carry no timestamp or file name, so identical input always
+/// produces identical output bytes.
+pub fn write_compressed<W: Write>(
+    mut writer: W,
+    compression: Compression,
+    bytes: &[u8],
+) -> Result<(), IoError> {
+    match compression {
+        Compression::None => writer.write_all(bytes)?,
+        Compression::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+            encoder.write_all(bytes)?;
+            encoder.finish()?;
+        }
+        Compression::Zstd => {
+            let mut encoder = zstd::stream::write::Encoder::new(writer, 0)?;
+            encoder.write_all(bytes)?;
+            encoder.finish()?;
+        }
+    }
+    Ok(())
+}
@@ -28,4 +28,20 @@ pub enum IoError {
 
     #[error("csv row {index} must be an object")]
     InvalidCsvRow { index: usize },
+
+    #[cfg(feature = "columnar")]
+    #[error("arrow error: {0}")]
+    Arrow(#[from] arrow_schema::ArrowError),
+
+    #[cfg(feature = "columnar")]
+    #[error("parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    #[cfg(feature = "columnar")]
+    #[error("columnar row {index} must be an object")]
+    InvalidColumnarRow { index: usize },
+
+    #[cfg(feature = "columnar")]
+    #[error("unsupported columnar data type: {data_type}")]
+    UnsupportedColumnarType { data_type: String },
 }
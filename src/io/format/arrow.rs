@@ -0,0 +1,38 @@
+use std::io::{Cursor, Read, Write};
+
+use arrow_array::RecordBatch;
+use arrow_ipc::reader::{FileReader, StreamReader};
+use arrow_ipc::writer::FileWriter;
+use serde_json::Value;
+
+use crate::io::IoError;
+use crate::io::format::columnar;
+
+const ARROW_FILE_MAGIC: &[u8] = b"ARROW1";
+
+pub fn looks_like_arrow_ipc(input: &[u8]) -> bool {
+    input.starts_with(ARROW_FILE_MAGIC)
+}
+
+/// Read Arrow IPC data in either the file (`ARROW1`) or the streaming layout.
+pub fn read_arrow_ipc<R: Read>(mut reader: R) -> Result<Vec<Value>, IoError> {
+    let mut buffer = Vec::new();
+    reader.read_to_end(&mut buffer)?;
+    let batches = if looks_like_arrow_ipc(&buffer) {
+        FileReader::try_new(Cursor::new(buffer), None)?.collect::<Result<Vec<RecordBatch>, _>>()?
+    } else {
+        StreamReader::try_new(Cursor::new(buffer), None)?
+            .collect::<Result<Vec<RecordBatch>, _>>()?
+    };
+    columnar::record_batches_to_values(&batches)
+}
+
+pub fn write_arrow_ipc<W: Write>(writer: W, values: &[Value]) -> Result<(), IoError> {
+    let (schema, batch) = columnar::values_to_record_batch(values)?;
+    let mut ipc_writer = FileWriter::try_new(writer, &schema)?;
+    if let Some(batch) = batch {
+        ipc_writer.write(&batch)?;
+    }
+    ipc_writer.finish()?;
+    Ok(())
+}
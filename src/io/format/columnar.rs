@@ -0,0 +1,299 @@
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::temporal_conversions::{as_date, as_datetime, as_time};
+use arrow_array::types::{
+    Date32Type, Date64Type, Float16Type, Float32Type, Float64Type, Int8Type, Int16Type, Int32Type,
+    Int64Type, Time32MillisecondType, Time32SecondType, Time64MicrosecondType,
+    Time64NanosecondType, TimestampMicrosecondType, TimestampMillisecondType,
+    TimestampNanosecondType, TimestampSecondType, UInt8Type, UInt16Type, UInt32Type, UInt64Type,
+};
+use arrow_array::{Array, RecordBatch};
+use arrow_schema::{DataType, Field, Fields, Schema, SchemaRef, TimeUnit};
+use chrono::{NaiveDateTime, NaiveTime};
+use serde_json::{Map, Number, Value};
+
+use crate::io::IoError;
+
+/// Convert record batches into one JSON object per row.
+///
+/// Column order follows the Arrow schema; logical types are mapped with fixed
+/// rules so the same file always yields the same JSON values.
+pub fn record_batches_to_values(batches: &[RecordBatch]) -> Result<Vec<Value>, IoError> {
+    let mut out = Vec::new();
+    for batch in batches {
+        let schema = batch.schema();
+        for row in 0..batch.num_rows() {
+            let mut map = Map::new();
+            for (field, column) in schema.fields().iter().zip(batch.columns()) {
+                map.insert(field.name().clone(), array_value(column.as_ref(), row)?);
+            }
+            out.push(Value::Object(map));
+        }
+    }
+    Ok(out)
+}
+
+/// Build a single record batch from JSON object rows.
+///
+/// The schema is inferred from all rows and its fields are sorted by name at
+/// every nesting level, matching the header ordering used by the CSV writer.
+pub fn values_to_record_batch(
+    values: &[Value],
+) -> Result<(SchemaRef, Option<RecordBatch>), IoError> {
+    for (index, value) in values.iter().enumerate() {
+        if !value.is_object() {
+            return Err(IoError::InvalidColumnarRow { index });
+        }
+    }
+    let inferred = arrow_json::reader::infer_json_schema_from_iterator(values.iter().map(Ok))?;
+    let schema: SchemaRef = Arc::new(Schema::new(sort_fields(inferred.fields())));
+    if values.is_empty() {
+        return Ok((schema, None));
+    }
+
+    let mut decoder = arrow_json::ReaderBuilder::new(Arc::clone(&schema))
+        .with_coerce_primitive(true)
+        .build_decoder()?;
+    decoder.serialize(values)?;
+    let batch = decoder.flush()?;
+    Ok((schema, batch))
+}
+
+fn sort_fields(fields: &Fields) -> Vec<Field> {
+    let mut sorted: Vec<Field> = fields
+        .iter()
+        .map(|field| {
+            field
+                .as_ref()
+                .clone()
+                .with_data_type(sort_data_type(field.data_type()))
+        })
+        .collect();
+    sorted.sort_by(|left, right| left.name().cmp(right.name()));
+    sorted
+}
+
+fn sort_data_type(data_type: &DataType) -> DataType {
+    match data_type {
+        DataType::Struct(fields) => DataType::Struct(sort_fields(fields).into()),
+        DataType::List(item) => DataType::List(Arc::new(
+            item.as_ref()
+                .clone()
+                .with_data_type(sort_data_type(item.data_type())),
+        )),
+        other => other.clone(),
+    }
+}
+
+fn array_value(array: &dyn Array, row: usize) -> Result<Value, IoError> {
+    if array.is_null(row) {
+        return Ok(Value::Null);
+    }
+    let value = match array.data_type() {
+        DataType::Null => Value::Null,
+        DataType::Boolean => Value::Bool(array.as_boolean().value(row)),
+        DataType::Int8 => Value::from(array.as_primitive::<Int8Type>().value(row)),
+        DataType::Int16 => Value::from(array.as_primitive::<Int16Type>().value(row)),
+        DataType::Int32 => Value::from(array.as_primitive::<Int32Type>().value(row)),
+        DataType::Int64 => Value::from(array.as_primitive::<Int64Type>().value(row)),
+        DataType::UInt8 => Value::from(array.as_primitive::<UInt8Type>().value(row)),
+        DataType::UInt16 => Value::from(array.as_primitive::<UInt16Type>().value(row)),
+        DataType::UInt32 => Value::from(array.as_primitive::<UInt32Type>().value(row)),
+        DataType::UInt64 => Value::from(array.as_primitive::<UInt64Type>().value(row)),
+        DataType::Float16 => {
+            float_value(array.as_primitive::<Float16Type>().value(row).to_string())
+        }
+        DataType::Float32 => {
+            float_value(array.as_primitive::<Float32Type>().value(row).to_string())
+        }
+        DataType::Float64 => {
+            float_value(array.as_primitive::<Float64Type>().value(row).to_string())
+        }
+        DataType::Decimal128(_, _) => Value::String(
+            array
+                .as_primitive::<arrow_array::types::Decimal128Type>()
+                .value_as_string(row),
+        ),
+        DataType::Decimal256(_, _) => Value::String(
+            array
+                .as_primitive::<arrow_array::types::Decimal256Type>()
+                .value_as_string(row),
+        ),
+        DataType::Utf8 => Value::String(array.as_string::<i32>().value(row).to_string()),
+        DataType::LargeUtf8 => Value::String(array.as_string::<i64>().value(row).to_string()),
+        DataType::Utf8View => Value::String(array.as_string_view().value(row).to_string()),
+        DataType::Binary => hex_value(array.as_binary::<i32>().value(row)),
+        DataType::LargeBinary => hex_value(array.as_binary::<i64>().value(row)),
+        DataType::BinaryView => hex_value(array.as_binary_view().value(row)),
+        DataType::FixedSizeBinary(_) => hex_value(array.as_fixed_size_binary().value(row)),
+        DataType::Date32 => {
+            let days = i64::from(array.as_primitive::<Date32Type>().value(row));
+            date_value(as_date::<Date32Type>(days), array.data_type())?
+        }
+        DataType::Date64 => {
+            let millis = array.as_primitive::<Date64Type>().value(row);
+            date_value(as_date::<Date64Type>(millis), array.data_type())?
+        }
+        DataType::Time32(TimeUnit::Second) => {
+            let raw = i64::from(array.as_primitive::<Time32SecondType>().value(row));
+            time_value(as_time::<Time32SecondType>(raw), array.data_type())?
+        }
+        DataType::Time32(TimeUnit::Millisecond) => {
+            let raw = i64::from(array.as_primitive::<Time32MillisecondType>().value(row));
+            time_value(as_time::<Time32MillisecondType>(raw), array.data_type())?
+        }
+        DataType::Time64(TimeUnit::Microsecond) => {
+            let raw = array.as_primitive::<Time64MicrosecondType>().value(row);
+            time_value(as_time::<Time64MicrosecondType>(raw), array.data_type())?
+        }
+        DataType::Time64(TimeUnit::Nanosecond) => {
+            let raw = array.as_primitive::<Time64NanosecondType>().value(row);
+            time_value(as_time::<Time64NanosecondType>(raw), array.data_type())?
+        }
+        DataType::Timestamp(unit, tz) => {
+            let datetime = match unit {
+                TimeUnit::Second => as_datetime::<TimestampSecondType>(
+                    array.as_primitive::<TimestampSecondType>().value(row),
+                ),
+                TimeUnit::Millisecond => as_datetime::<TimestampMillisecondType>(
+                    array.as_primitive::<TimestampMillisecondType>().value(row),
+                ),
+                TimeUnit::Microsecond => as_datetime::<TimestampMicrosecondType>(
+                    array.as_primitive::<TimestampMicrosecondType>().value(row),
+                ),
+                TimeUnit::Nanosecond => as_datetime::<TimestampNanosecondType>(
+                    array.as_primitive::<TimestampNanosecondType>().value(row),
+                ),
+            };
+            timestamp_value(datetime, *unit, tz.is_some(), array.data_type())?
+        }
+        DataType::List(_) => list_value(array.as_list::<i32>().value(row).as_ref())?,
+        DataType::LargeList(_) => list_value(array.as_list::<i64>().value(row).as_ref())?,
+        DataType::FixedSizeList(_, _) => {
+            list_value(array.as_fixed_size_list().value(row).as_ref())?
+        }
+        DataType::Struct(fields) => {
+            let struct_array = array.as_struct();
+            let mut map = Map::new();
+            for (field, column) in fields.iter().zip(struct_array.columns()) {
+                map.insert(field.name().clone(), array_value(column.as_ref(), row)?);
+            }
+            Value::Object(map)
+        }
+        DataType::Map(_, _) => map_value(array.as_map().value(row))?,
+        DataType::Dictionary(_, _) => {
+            let dictionary = array
+                .as_any_dictionary_opt()
+                .ok_or_else(|| unsupported(array.data_type()))?;
+            let key = dictionary.normalized_keys()[row];
+            array_value(dictionary.values().as_ref(), key)?
+        }
+        other => return Err(unsupported(other)),
+    };
+    Ok(value)
+}
+
+fn list_value(items: &dyn Array) -> Result<Value, IoError> {
+    let mut out = Vec::with_capacity(items.len());
+    for index in 0..items.len() {
+        out.push(array_value(items, index)?);
+    }
+    Ok(Value::Array(out))
+}
+
+/// Maps with string keys become objects; other key types become an array of
+/// `{"key": ..., "value": ...}` entries in storage order.
+fn map_value(entries: arrow_array::StructArray) -> Result<Value, IoError> {
+    let keys = entries.column(0);
+    let values = entries.column(1);
+    let string_keys = matches!(
+        keys.data_type(),
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+    );
+    if string_keys {
+        let mut map = Map::new();
+        for index in 0..entries.len() {
+            let Value::String(key) = array_value(keys.as_ref(), index)? else {
+                return Err(unsupported(keys.data_type()));
+            };
+            map.insert(key, array_value(values.as_ref(), index)?);
+        }
+        return Ok(Value::Object(map));
+    }
+
+    let mut out = Vec::with_capacity(entries.len());
+    for index in 0..entries.len() {
+        let mut entry = Map::new();
+        entry.insert("key".to_string(), array_value(keys.as_ref(), index)?);
+        entry.insert("value".to_string(), array_value(values.as_ref(), index)?);
+        out.push(Value::Object(entry));
+    }
+    Ok(Value::Array(out))
+}
+
+/// Floats are rendered through their shortest round-trip text so `f32`
+/// values do not pick up widening noise; non-finite values become strings.
+fn float_value(text: String) -> Value {
+    text.parse::<f64>()
+        .ok()
+        .and_then(Number::from_f64)
+        .map(Value::Number)
+        .unwrap_or(Value::String(text))
+}
+
+fn hex_value(bytes: &[u8]) -> Value {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        out.push_str(&format!("{byte:02x}"));
+    }
+    Value::String(out)
+}
+
+fn date_value(date: Option<chrono::NaiveDate>, data_type: &DataType) -> Result<Value, IoError> {
+    let date = date.ok_or_else(|| out_of_range(data_type))?;
+    Ok(Value::String(date.format("%Y-%m-%d").to_string()))
+}
+
+fn time_value(time: Option<NaiveTime>, data_type: &DataType) -> Result<Value, IoError> {
+    let time = time.ok_or_else(|| out_of_range(data_type))?;
+    let text = match data_type {
+        DataType::Time32(TimeUnit::Second) => time.format("%H:%M:%S"),
+        DataType::Time32(TimeUnit::Millisecond) => time.format("%H:%M:%S%.3f"),
+        DataType::Time64(TimeUnit::Microsecond) => time.format("%H:%M:%S%.6f"),
+        _ => time.format("%H:%M:%S%.9f"),
+    };
+    Ok(Value::String(text.to_string()))
+}
+
+/// Timestamps keep the precision of their unit. Zoned timestamps are stored
+/// as UTC instants and rendered with a `Z` suffix; naive ones carry no offset.
+fn timestamp_value(
+    datetime: Option<NaiveDateTime>,
+    unit: TimeUnit,
+    zoned: bool,
+    data_type: &DataType,
+) -> Result<Value, IoError> {
+    let datetime = datetime.ok_or_else(|| out_of_range(data_type))?;
+    let fraction = match unit {
+        TimeUnit::Second => "",
+        TimeUnit::Millisecond => "%.3f",
+        TimeUnit::Microsecond => "%.6f",
+        TimeUnit::Nanosecond => "%.9f",
+    };
+    let suffix = if zoned { "Z" } else { "" };
+    let pattern = format!("%Y-%m-%dT%H:%M:%S{fraction}{suffix}");
+    Ok(Value::String(datetime.format(&pattern).to_string()))
+}
+
+fn unsupported(data_type: &DataType) -> IoError {
+    IoError::UnsupportedColumnarType {
+        data_type: data_type.to_string(),
+    }
+}
+
+fn out_of_range(data_type: &DataType) -> IoError {
+    IoError::UnsupportedColumnarType {
+        data_type: format!("{data_type} (value out of range)"),
+    }
+}
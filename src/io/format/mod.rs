@@ -1,4 +1,10 @@
+#[cfg(feature = "columnar")]
+pub mod arrow;
+#[cfg(feature = "columnar")]
+pub mod columnar;
 pub mod csv;
 pub mod json;
 pub mod jsonl;
+#[cfg(feature = "columnar")]
+pub mod parquet;
 pub mod yaml;
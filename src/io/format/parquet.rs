@@ -0,0 +1,40 @@
+use std::io::{Read, Write};
+
+use arrow_array::RecordBatch;
+use bytes::Bytes;
+use parquet::arrow::ArrowWriter;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use serde_json::Value;
+
+use crate::io::IoError;
+use crate::io::format::columnar;
+
+const PARQUET_MAGIC: &[u8] = b"PAR1";
+
+pub fn looks_like_parquet(input: &[u8]) -> bool {
+    input.len() >= PARQUET_MAGIC.len() * 2
+        && input.starts_with(PARQUET_MAGIC)
+        && input.ends_with(PARQUET_MAGIC)
+}
+
+pub fn read_parquet<R: Read>(mut reader: R) -> Result<Vec<Value>, IoError> {
+    let mut buffer = Vec::new();
+    reader.read_to_end(&mut buffer)?;
+    let batches = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(buffer))?
+        .build()?
+        .collect::<Result<Vec<RecordBatch>, _>>()?;
+    columnar::record_batches_to_values(&batches)
+}
+
+pub fn write_parquet<W: Write>(mut writer: W, values: &[Value]) -> Result<(), IoError> {
+    let (schema, batch) = columnar::values_to_record_batch(values)?;
+    // `ArrowWriter` requires `Send`; encode into an owned buffer first.
+    let mut buffer = Vec::new();
+    let mut parquet_writer = ArrowWriter::try_new(&mut buffer, schema, None)?;
+    if let Some(batch) = batch {
+        parquet_writer.write(&batch)?;
+    }
+    parquet_writer.close()?;
+    writer.write_all(&buffer)?;
+    Ok(())
+}
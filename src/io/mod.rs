@@ -1,3 +1,4 @@
+pub mod compression;
 pub mod error;
 pub mod format;
 pub mod reader;
@@ -7,6 +8,7 @@ use std::fmt;
 use std::path::Path;
 use std::str::FromStr;
 
+pub use compression::Compression;
 pub use error::IoError;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,6 +17,12 @@ pub enum Format {
     Yaml,
     Csv,
     Jsonl,
+    /// Apache Parquet (requires the `columnar` feature).
+    #[cfg(feature = "columnar")]
+    Parquet,
+    /// Arrow IPC file or stream (requires the `columnar` feature).
+    #[cfg(feature = "columnar")]
+    ArrowIpc,
 }
 
 impl Format {
@@ -24,6 +32,10 @@ impl Format {
             Self::Yaml => "yaml",
             Self::Csv => "csv",
             Self::Jsonl => "jsonl",
+            #[cfg(feature = "columnar")]
+            Self::Parquet => "parquet",
+            #[cfg(feature = "columnar")]
+            Self::ArrowIpc => "arrow",
         }
     }
 }
@@ -43,6 +55,10 @@ impl FromStr for Format {
             "yaml" | "yml" => Ok(Self::Yaml),
             "csv" => Ok(Self::Csv),
             "jsonl" | "ndjson" => Ok(Self::Jsonl),
+            #[cfg(feature = "columnar")]
+            "parquet" => Ok(Self::Parquet),
+            #[cfg(feature = "columnar")]
+            "arrow" | "ipc" | "feather" => Ok(Self::ArrowIpc),
             other => Err(IoError::UnsupportedFormat {
                 format: other.to_string(),
             }),
@@ -68,6 +84,13 @@ pub fn resolve_output_format(
     resolve_format(explicit, output, "output")
 }
 
+/// Resolve output compression from the output path extension (`.gz`, `.zst`).
+pub fn resolve_output_compression(output: Option<&Path>) -> Compression {
+    output
+        .map(Compression::from_path)
+        .unwrap_or(Compression::None)
+}
+
 fn resolve_format(
     explicit: Option<Format>,
     path: Option<&Path>,
@@ -85,13 +108,25 @@ fn resolve_format(
     })
 }
 
+/// Resolve a format from the path extension, looking through a trailing
+/// compression extension (`events.jsonl.gz` resolves to `jsonl`).
 fn format_from_path(path: &Path) -> Option<Format> {
-    let ext = path.extension()?.to_string_lossy().to_ascii_lowercase();
+    let mut ext = path.extension()?.to_string_lossy().to_ascii_lowercase();
+    if Compression::from_extension(&ext).is_some() {
+        ext = Path::new(path.file_stem()?)
+            .extension()?
+            .to_string_lossy()
+            .to_ascii_lowercase();
+    }
     match ext.as_str() {
         "json" => Some(Format::Json),
         "yaml" | "yml" => Some(Format::Yaml),
         "csv" => Some(Format::Csv),
         "jsonl" | "ndjson" => Some(Format::Jsonl),
+        #[cfg(feature = "columnar")]
+        "parquet" => Some(Format::Parquet),
+        #[cfg(feature = "columnar")]
+        "arrow" | "ipc" | "feather" => Some(Format::ArrowIpc),
         _ => None,
     }
 }
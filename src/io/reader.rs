@@ -4,21 +4,40 @@ use serde_json::Value;
 
 use crate::io::format::jsonl::JsonlStreamError;
 use crate::io::format::{csv, json, jsonl, yaml};
-use crate::io::{Format, IoError};
+use crate::io::{Format, IoError, compression};
 
+/// Read all values in `format`, decompressing gzip/zstd input transparently.
 pub fn read_values<R: Read>(reader: R, format: Format) -> Result<Vec<Value>, IoError> {
+    let reader = compression::decompressing_reader(reader)?;
     match format {
         Format::Json => json::read_json(reader),
         Format::Yaml => yaml::read_yaml(reader),
         Format::Csv => csv::read_csv(reader),
         Format::Jsonl => jsonl::read_jsonl(reader),
+        #[cfg(feature = "columnar")]
+        Format::Parquet => crate::io::format::parquet::read_parquet(reader),
+        #[cfg(feature = "columnar")]
+        Format::ArrowIpc => crate::io::format::arrow::read_arrow_ipc(reader),
     }
 }
 
+/// Detect the stdin format, looking through gzip/zstd compression first.
 pub fn autodetect_stdin_format(input: &[u8]) -> Result<Format, IoError> {
+    let decompressed =
+        compression::decompress_bytes(input).map_err(|_| IoError::StdinAutodetectFailed)?;
+    let input = decompressed.as_ref();
     if input.iter().all(u8::is_ascii_whitespace) {
         return Err(IoError::StdinAutodetectFailed);
     }
+    #[cfg(feature = "columnar")]
+    {
+        if crate::io::format::parquet::looks_like_parquet(input) {
+            return Ok(Format::Parquet);
+        }
+        if crate::io::format::arrow::looks_like_arrow_ipc(input) {
+            return Ok(Format::ArrowIpc);
+        }
+    }
     let is_json = json::read_json(Cursor::new(input)).is_ok();
     if jsonl::looks_like_jsonl(input) {
         if is_json && jsonl::non_empty_line_count(input) == 1 {
@@ -42,6 +61,7 @@ pub fn read_jsonl_stream<R: Read, F, E>(reader: R, emit: F) -> Result<(), JsonlS
 where
     F: FnMut(Value) -> Result<(), E>,
 {
+    let reader = compression::decompressing_reader(reader).map_err(JsonlStreamError::Read)?;
     jsonl::read_jsonl_stream(reader, emit)
 }
 
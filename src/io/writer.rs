@@ -1,9 +1,11 @@
+use std::fs::File;
 use std::io::Write;
+use std::path::Path;
 
 use serde_json::Value;
 
 use crate::io::format::{csv, json, jsonl, yaml};
-use crate::io::{Format, IoError};
+use crate::io::{Compression, Format, IoError, compression};
 
 pub fn write_values<W: Write>(writer: W, format: Format, values: &[Value]) -> Result<(), IoError> {
     match format {
@@ -11,5 +13,30 @@ pub fn write_values<W: Write>(writer: W, format: Format, values: &[Value]) -> Re
         Format::Yaml => yaml::write_yaml(writer, values),
         Format::Csv => csv::write_csv(writer, values),
         Format::Jsonl => jsonl::write_jsonl(writer, values),
+        #[cfg(feature = "columnar")]
+        Format::Parquet => crate::io::format::parquet::write_parquet(writer, values),
+        #[cfg(feature = "columnar")]
+        Format::ArrowIpc => crate::io::format::arrow::write_arrow_ipc(writer, values),
     }
 }
+
+/// Write values in `format`, then compress the encoded bytes.
+pub fn write_values_compressed<W: Write>(
+    writer: W,
+    format: Format,
+    compression: Compression,
+    values: &[Value],
+) -> Result<(), IoError> {
+    if compression == Compression::None {
+        return write_values(writer, format, values);
+    }
+    let mut encoded = Vec::new();
+    write_values(&mut encoded, format, values)?;
+    compression::write_compressed(writer, compression, &encoded)
+}
+
+/// Write already-encoded bytes to `path`, compressed by its extension.
+pub fn write_bytes_to_path(path: &Path, bytes: &[u8]) -> Result<(), IoError> {
+    let compression = crate::io::resolve_output_compression(Some(path));
+    compression::write_compressed(File::create(path)?, compression, bytes)
+}
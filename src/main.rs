@@ -5,10 +5,10 @@ use std::path::{Path, PathBuf};
 use std::process::{self, Command};
 
 use clap::error::ErrorKind;
-use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+use clap::{ArgGroup, CommandFactory, Parser, Subcommand, ValueEnum};
 use dataq::cmd::{
-    aggregate, r#assert, canon, codex, contract, diff, doctor, emit, gate, ingest, ingest_api,
-    ingest_yaml_jobs, join, mcp, merge, profile, recipe, scan, sdiff, transform,
+    aggregate, r#assert, canon, codex, contract, diff, doctor, emit, gate, help, ingest,
+    ingest_api, ingest_yaml_jobs, join, mcp, merge, profile, recipe, scan, sdiff, transform,
 };
 use dataq::domain::error::CanonError;
 use dataq::domain::ingest::IngestYamlJobsMode;
@@ -17,12 +17,16 @@ use dataq::domain::report::{
 };
 use dataq::engine::aggregate::AggregateMetric;
 use dataq::engine::canon::canonicalize_value;
+use dataq::engine::canon::coerce::CoerceMode;
+use dataq::engine::canon::normalize::{KeyCase, NormalizeOptions, UnicodeForm, WhitespaceMode};
+use dataq::engine::canon::time::{TimeNormalizationArgs, resolve_time_normalization};
 use dataq::engine::ingest as ingest_engine;
 use dataq::engine::join::JoinHow;
 use dataq::engine::merge::MergePolicy;
 use dataq::io::format::jsonl::JsonlStreamError;
 use dataq::io::{self as dataq_io, Format, IoError};
-use dataq::util::hash::DeterministicHasher;
+use dataq::util::hash::DigestHasher;
+use dataq::util::time::{TimePrecision, TimeTarget};
 use serde::Serialize;
 use serde_json::{Value, json};
 
@@ -30,12 +34,17 @@ use serde_json::{Value, json};
 #[command(
     name = "dataq",
     version,
-    about = "Deterministic data preprocessing CLI"
+    about = "Deterministic data preprocessing CLI",
+    disable_help_subcommand = true
 )]
 struct Cli {
     #[arg(long, global = true, default_value_t = false)]
     emit_pipeline: bool,
 
+    /// Pin the output contract version (full schema ID or `vN`).
+    #[arg(long, global = true, value_name = "VERSION")]
+    contract_version: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -76,8 +85,33 @@ enum Commands {
     Emit(EmitArgs),
     /// Install or manage dataq Codex skill assets.
     Codex(CodexArgs),
-    /// Handle a single MCP JSON-RPC request from stdin.
-    Mcp,
+    /// Handle MCP JSON-RPC requests from stdin (single request by default).
+    Mcp(McpArgs),
+    /// Print help for dataq or a subcommand (`--json` for machine-readable arguments).
+    Help(HelpArgs),
+}
+
+#[derive(Debug, clap::Args)]
+struct HelpArgs {
+    /// Emit arguments, types, enums, defaults, groups, and conflicts as JSON.
+    #[arg(long, default_value_t = false)]
+    json: bool,
+    /// Subcommand path to describe (for example `ingest api`).
+    #[arg(value_name = "COMMAND")]
+    command: Vec<String>,
+}
+
+#[derive(Debug, clap::Args)]
+struct McpArgs {
+    /// Keep a newline-delimited stdio session open until EOF.
+    #[arg(long, default_value_t = false, conflicts_with = "http")]
+    serve: bool,
+    /// Serve the streamable HTTP transport on ADDR (a bare port binds 127.0.0.1).
+    #[arg(long, value_name = "ADDR")]
+    http: Option<String>,
+    /// Restrict tool file access to this directory (repeatable).
+    #[arg(long = "root", value_name = "DIR")]
+    roots: Vec<PathBuf>,
 }
 
 #[derive(Debug, clap::Args)]
@@ -96,6 +130,66 @@ struct CanonArgs {
 
     #[arg(long, default_value_t = false)]
     normalize_time: bool,
+
+    /// Which string scalars may be coerced into booleans and numbers.
+    #[arg(long, value_enum, default_value_t = CliCoerceMode::All)]
+    coerce: CliCoerceMode,
+
+    /// Canonical path (`$["field"]`) whose subtree is never coerced.
+    #[arg(long = "coerce-exempt", value_name = "PATH")]
+    coerce_exempt: Vec<String>,
+
+    /// JSON Schema declaring field types for `--coerce schema`.
+    #[arg(long, conflicts_with = "coerce_rules")]
+    coerce_schema: Option<PathBuf>,
+
+    /// Assert rules file declaring field types for `--coerce schema`.
+    #[arg(long)]
+    coerce_rules: Option<PathBuf>,
+
+    /// Convert object keys to the given case.
+    #[arg(long, value_enum)]
+    key_case: Option<CliKeyCase>,
+
+    /// Trim or collapse whitespace in string values.
+    #[arg(long, value_enum)]
+    whitespace: Option<CliWhitespaceMode>,
+
+    /// Apply Unicode normalization to keys and string values.
+    #[arg(long, value_enum)]
+    unicode: Option<CliUnicodeForm>,
+
+    /// Drop object fields whose value is null.
+    #[arg(long, default_value_t = false)]
+    drop_nulls: bool,
+
+    /// Drop object fields whose value is an empty string, array or object.
+    #[arg(long, default_value_t = false)]
+    drop_empty: bool,
+
+    /// Canonical path (`$["tags"]`) of a scalar array to sort.
+    #[arg(long = "sort-array", value_name = "PATH")]
+    sort_array: Vec<String>,
+
+    /// Canonical path whose subtree holds timestamps to normalize.
+    #[arg(long = "time-path", value_name = "PATH")]
+    time_path: Vec<String>,
+
+    /// Accepted timestamp format (`rfc3339`, `epoch_s`, `epoch_ms`, or a strftime pattern).
+    #[arg(long = "time-format", value_name = "FORMAT")]
+    time_format: Vec<String>,
+
+    /// Zone assumed for timestamps without one (`UTC`, `+09:00`, `Asia/Tokyo`).
+    #[arg(long, value_name = "ZONE")]
+    time_zone: Option<String>,
+
+    /// Fractional-second digits for RFC 3339 output.
+    #[arg(long, value_enum)]
+    time_precision: Option<CliTimePrecision>,
+
+    /// Output representation for normalized timestamps.
+    #[arg(long, value_enum)]
+    time_target: Option<CliTimeTarget>,
 }
 
 #[derive(Debug, clap::Args)]
@@ -426,12 +520,22 @@ enum RecipeSubcommand {
     Lock(RecipeLockArgs),
     /// Replay a recipe under lock constraints.
     Replay(RecipeReplayArgs),
+    /// Statically check a recipe file without executing it.
+    Validate(RecipeValidateArgs),
+    /// Emit the recipe step graph as JSON or Graphviz DOT.
+    Graph(RecipeGraphArgs),
 }
 
 #[derive(Debug, clap::Args)]
 struct RecipeRunArgs {
     #[arg(long)]
     file: PathBuf,
+
+    #[arg(long = "param", value_name = "key=value")]
+    param: Vec<String>,
+
+    #[arg(long = "cache-dir")]
+    cache_dir: Option<PathBuf>,
 }
 
 #[derive(Debug, clap::Args)]
@@ -441,6 +545,9 @@ struct RecipeLockArgs {
 
     #[arg(long)]
     out: Option<PathBuf>,
+
+    #[arg(long = "param", value_name = "key=value")]
+    param: Vec<String>,
 }
 
 #[derive(Debug, clap::Args)]
@@ -453,6 +560,30 @@ struct RecipeReplayArgs {
 
     #[arg(long, default_value_t = false)]
     strict: bool,
+
+    #[arg(long = "param", value_name = "key=value")]
+    param: Vec<String>,
+}
+
+#[derive(Debug, clap::Args)]
+struct RecipeValidateArgs {
+    #[arg(long)]
+    file: PathBuf,
+
+    #[arg(long = "param", value_name = "key=value")]
+    param: Vec<String>,
+}
+
+#[derive(Debug, clap::Args)]
+struct RecipeGraphArgs {
+    #[arg(long)]
+    file: PathBuf,
+
+    #[arg(long = "param", value_name = "key=value")]
+    param: Vec<String>,
+
+    #[arg(long, value_enum, default_value_t = CliRecipeGraphFormat::Json)]
+    format: CliRecipeGraphFormat,
 }
 
 #[derive(Debug, clap::Args)]
@@ -465,18 +596,54 @@ struct DoctorArgs {
 }
 
 #[derive(Debug, clap::Args)]
-#[command(group(
-    ArgGroup::new("contract_target")
-        .args(["command", "all"])
-        .required(true)
-        .multiple(false)
-))]
+#[command(
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true,
+    group(
+        ArgGroup::new("contract_target")
+            .args(["command", "all"])
+            .required(true)
+            .multiple(false)
+    )
+)]
 struct ContractArgs {
+    #[command(subcommand)]
+    action: Option<ContractSubcommand>,
+
     #[arg(long, value_enum)]
     command: Option<CliContractCommand>,
 
     #[arg(long, default_value_t = false)]
     all: bool,
+
+    #[arg(long, value_enum, default_value_t = CliContractFormat::Contract)]
+    format: CliContractFormat,
+}
+
+#[derive(Debug, Subcommand)]
+enum ContractSubcommand {
+    /// Validate a captured output against the command's JSON Schema.
+    Verify(ContractVerifyArgs),
+    /// Report breaking and additive changes against a saved contract snapshot.
+    Diff(ContractDiffArgs),
+}
+
+#[derive(Debug, clap::Args)]
+struct ContractDiffArgs {
+    #[arg(long)]
+    from: PathBuf,
+}
+
+#[derive(Debug, clap::Args)]
+struct ContractVerifyArgs {
+    #[arg(long, value_enum)]
+    command: CliContractCommand,
+
+    #[arg(long)]
+    input: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = CliContractPayload::Success)]
+    payload: CliContractPayload,
 }
 
 #[derive(Debug, clap::Args)]
@@ -527,6 +694,11 @@ enum CliInputFormat {
     Yaml,
     Csv,
     Jsonl,
+    #[cfg(feature = "columnar")]
+    Parquet,
+    #[cfg(feature = "columnar")]
+    #[value(name = "arrow")]
+    ArrowIpc,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -535,6 +707,48 @@ enum CanonOutputFormat {
     Jsonl,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliCoerceMode {
+    None,
+    All,
+    Schema,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliKeyCase {
+    Snake,
+    Camel,
+    Kebab,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliWhitespaceMode {
+    Trim,
+    Collapse,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliUnicodeForm {
+    Nfc,
+    Nfkc,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliTimePrecision {
+    Auto,
+    S,
+    Ms,
+    Us,
+    Ns,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliTimeTarget {
+    Rfc3339,
+    #[value(name = "epoch_ms")]
+    EpochMs,
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 enum CliMergePolicy {
     LastWins,
@@ -570,6 +784,25 @@ enum CliIngestApiMethod {
     Delete,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliContractFormat {
+    Contract,
+    JsonSchema,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliContractPayload {
+    Success,
+    Error,
+    Pipeline,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliRecipeGraphFormat {
+    Json,
+    Dot,
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 enum CliIngestYamlJobsMode {
     GithubActions,
@@ -635,6 +868,7 @@ enum CliContractCommand {
     #[value(name = "recipe-run", alias = "recipe")]
     RecipeRun,
     RecipeLock,
+    RecipeReplay,
 }
 
 impl From<CliInputFormat> for Format {
@@ -644,6 +878,10 @@ impl From<CliInputFormat> for Format {
             CliInputFormat::Yaml => Self::Yaml,
             CliInputFormat::Csv => Self::Csv,
             CliInputFormat::Jsonl => Self::Jsonl,
+            #[cfg(feature = "columnar")]
+            CliInputFormat::Parquet => Self::Parquet,
+            #[cfg(feature = "columnar")]
+            CliInputFormat::ArrowIpc => Self::ArrowIpc,
         }
     }
 }
@@ -667,6 +905,15 @@ impl From<CliMergePolicy> for MergePolicy {
     }
 }
 
+impl From<CliRecipeGraphFormat> for recipe::RecipeGraphFormat {
+    fn from(value: CliRecipeGraphFormat) -> Self {
+        match value {
+            CliRecipeGraphFormat::Json => Self::Json,
+            CliRecipeGraphFormat::Dot => Self::Dot,
+        }
+    }
+}
+
 impl From<CliJoinHow> for JoinHow {
     fn from(value: CliJoinHow) -> Self {
         match value {
@@ -686,6 +933,65 @@ impl From<CliAggregateMetric> for AggregateMetric {
     }
 }
 
+impl From<CliCoerceMode> for CoerceMode {
+    fn from(value: CliCoerceMode) -> Self {
+        match value {
+            CliCoerceMode::None => Self::None,
+            CliCoerceMode::All => Self::All,
+            CliCoerceMode::Schema => Self::Schema,
+        }
+    }
+}
+
+impl From<CliKeyCase> for KeyCase {
+    fn from(value: CliKeyCase) -> Self {
+        match value {
+            CliKeyCase::Snake => Self::Snake,
+            CliKeyCase::Camel => Self::Camel,
+            CliKeyCase::Kebab => Self::Kebab,
+        }
+    }
+}
+
+impl From<CliWhitespaceMode> for WhitespaceMode {
+    fn from(value: CliWhitespaceMode) -> Self {
+        match value {
+            CliWhitespaceMode::Trim => Self::Trim,
+            CliWhitespaceMode::Collapse => Self::Collapse,
+        }
+    }
+}
+
+impl From<CliUnicodeForm> for UnicodeForm {
+    fn from(value: CliUnicodeForm) -> Self {
+        match value {
+            CliUnicodeForm::Nfc => Self::Nfc,
+            CliUnicodeForm::Nfkc => Self::Nfkc,
+        }
+    }
+}
+
+impl From<CliTimePrecision> for TimePrecision {
+    fn from(value: CliTimePrecision) -> Self {
+        match value {
+            CliTimePrecision::Auto => Self::Auto,
+            CliTimePrecision::S => Self::Seconds,
+            CliTimePrecision::Ms => Self::Millis,
+            CliTimePrecision::Us => Self::Micros,
+            CliTimePrecision::Ns => Self::Nanos,
+        }
+    }
+}
+
+impl From<CliTimeTarget> for TimeTarget {
+    fn from(value: CliTimeTarget) -> Self {
+        match value {
+            CliTimeTarget::Rfc3339 => Self::Rfc3339,
+            CliTimeTarget::EpochMs => Self::EpochMillis,
+        }
+    }
+}
+
 impl From<CliAssertNormalizeMode> for r#assert::AssertInputNormalizeMode {
     fn from(value: CliAssertNormalizeMode) -> Self {
         match value {
@@ -707,6 +1013,16 @@ impl From<CliGatePolicySource> for gate::GatePolicySourcePreset {
     }
 }
 
+impl From<CliContractPayload> for contract::ContractPayloadKind {
+    fn from(value: CliContractPayload) -> Self {
+        match value {
+            CliContractPayload::Success => Self::Success,
+            CliContractPayload::Error => Self::Error,
+            CliContractPayload::Pipeline => Self::Pipeline,
+        }
+    }
+}
+
 impl From<CliContractCommand> for contract::ContractCommand {
     fn from(value: CliContractCommand) -> Self {
         match value {
@@ -728,6 +1044,7 @@ impl From<CliContractCommand> for contract::ContractCommand {
             CliContractCommand::Doctor => Self::Doctor,
             CliContractCommand::RecipeRun => Self::RecipeRun,
             CliContractCommand::RecipeLock => Self::RecipeLock,
+            CliContractCommand::RecipeReplay => Self::RecipeReplay,
         }
     }
 }
@@ -799,6 +1116,21 @@ fn run() -> i32 {
     };
 
     let emit_pipeline = cli.emit_pipeline;
+    let contract_version = match cli.contract_version.as_deref() {
+        Some(requested) => match resolve_requested_contract_version(&cli.command, requested) {
+            Ok(version) => Some(version),
+            Err(message) => {
+                emit_error(
+                    "input_usage_error",
+                    message,
+                    json!({"contract_version": requested}),
+                    3,
+                );
+                return 3;
+            }
+        },
+        None => None,
+    };
     match cli.command {
         Commands::Canon(args) => run_canon(args, emit_pipeline),
         Commands::Ingest(args) => run_ingest(args, emit_pipeline),
@@ -812,12 +1144,64 @@ fn run() -> i32 {
         Commands::Transform(args) => run_transform(args, emit_pipeline),
         Commands::Scan(args) => run_scan(args, emit_pipeline),
         Commands::Merge(args) => run_merge(args, emit_pipeline),
-        Commands::Recipe(args) => run_recipe(args, emit_pipeline),
+        Commands::Recipe(args) => run_recipe(args, emit_pipeline, contract_version),
         Commands::Doctor(args) => run_doctor(args, emit_pipeline),
         Commands::Contract(args) => run_contract(args, emit_pipeline),
         Commands::Emit(args) => run_emit(args, emit_pipeline),
         Commands::Codex(args) => run_codex(args, emit_pipeline),
-        Commands::Mcp => run_mcp(),
+        Commands::Mcp(args) => run_mcp(args),
+        Commands::Help(args) => run_help(args, emit_pipeline),
+    }
+}
+
+/// Resolves `--contract-version` to a schema ID supported by the subcommand.
+///
+/// Commands with a single supported version only validate the request; the
+/// resolved ID selects the emitted shape where older versions are kept.
+fn resolve_requested_contract_version(
+    command: &Commands,
+    requested: &str,
+) -> Result<&'static str, String> {
+    let Some(contract_command) = contract_command_for(command) else {
+        return Err(
+            "`--contract-version` requires a subcommand with a versioned output contract"
+                .to_string(),
+        );
+    };
+    contract::resolve_contract_version(contract_command, requested)
+}
+
+fn contract_command_for(command: &Commands) -> Option<contract::ContractCommand> {
+    use contract::ContractCommand;
+
+    match command {
+        Commands::Canon(_) => Some(ContractCommand::Canon),
+        Commands::Ingest(args) => Some(match args.command {
+            IngestSubcommand::Api(_) => ContractCommand::IngestApi,
+            IngestSubcommand::YamlJobs(_) => ContractCommand::Ingest,
+            IngestSubcommand::Notes(_) => ContractCommand::IngestNotes,
+            IngestSubcommand::Doc(_) => ContractCommand::IngestDoc,
+            IngestSubcommand::Book(_) => ContractCommand::IngestBook,
+        }),
+        Commands::Assert(_) => Some(ContractCommand::Assert),
+        Commands::Gate(args) => Some(match args.command {
+            GateSubcommand::Schema(_) => ContractCommand::GateSchema,
+            GateSubcommand::Policy(_) => ContractCommand::Gate,
+        }),
+        Commands::Sdiff(_) => Some(ContractCommand::Sdiff),
+        Commands::Diff(_) => Some(ContractCommand::DiffSource),
+        Commands::Profile(_) => Some(ContractCommand::Profile),
+        Commands::Scan(_) => Some(ContractCommand::Scan),
+        Commands::Transform(_) => Some(ContractCommand::TransformRowset),
+        Commands::Merge(_) => Some(ContractCommand::Merge),
+        Commands::Doctor(_) => Some(ContractCommand::Doctor),
+        Commands::Recipe(args) => match args.command {
+            RecipeSubcommand::Run(_) => Some(ContractCommand::RecipeRun),
+            RecipeSubcommand::Lock(_) => Some(ContractCommand::RecipeLock),
+            RecipeSubcommand::Replay(_) => Some(ContractCommand::RecipeReplay),
+            _ => None,
+        },
+        _ => None,
     }
 }
 
@@ -839,11 +1223,63 @@ fn handle_parse_error(error: clap::Error) -> i32 {
     }
 }
 
-fn run_canon(args: CanonArgs, emit_pipeline: bool) -> i32 {
-    let output_format = args.to.map(Into::into).unwrap_or(Format::Json);
-    let options = canon::CanonCommandOptions {
+fn resolve_canon_options(args: &CanonArgs) -> Result<canon::CanonCommandOptions, String> {
+    let coerce_schema_source = match (&args.coerce_schema, &args.coerce_rules) {
+        (Some(path), _) => Some(canon::CoerceSchemaSource::JsonSchema(path.as_path())),
+        (None, Some(path)) => Some(canon::CoerceSchemaSource::Rules(path.as_path())),
+        (None, None) => None,
+    };
+    let coerce = canon::resolve_coerce_policy(
+        args.coerce.into(),
+        &args.coerce_exempt,
+        coerce_schema_source,
+    )?;
+    let normalize = NormalizeOptions {
+        key_case: args.key_case.map(Into::into),
+        whitespace: args.whitespace.map(Into::into),
+        unicode: args.unicode.map(Into::into),
+        drop_nulls: args.drop_nulls,
+        drop_empty: args.drop_empty,
+        sort_array_paths: canon::parse_sort_array_paths(&args.sort_array)?,
+    };
+    let time = resolve_time_normalization(TimeNormalizationArgs {
+        paths: &args.time_path,
+        formats: &args.time_format,
+        zone: args.time_zone.as_deref(),
+        precision: args
+            .time_precision
+            .map(|precision| TimePrecision::from(precision).as_str()),
+        target: args
+            .time_target
+            .map(|target| TimeTarget::from(target).as_str()),
+    })?;
+    Ok(canon::CanonCommandOptions {
         sort_keys: args.sort_keys,
         normalize_time: args.normalize_time,
+        coerce,
+        normalize,
+        time,
+    })
+}
+
+fn run_canon(args: CanonArgs, emit_pipeline: bool) -> i32 {
+    let output_format = args.to.map(Into::into).unwrap_or(Format::Json);
+    let options = match resolve_canon_options(&args) {
+        Ok(options) => options,
+        Err(message) => {
+            emit_error("input_usage_error", message, json!({"command": "canon"}), 3);
+            if emit_pipeline {
+                let options = canon::CanonCommandOptions {
+                    sort_keys: args.sort_keys,
+                    normalize_time: args.normalize_time,
+                    ..canon::CanonCommandOptions::default()
+                };
+                let pipeline_report =
+                    build_canon_pipeline_report(&args, args.from.map(Into::into), &options);
+                emit_pipeline_report_with_context(&pipeline_report, &FingerprintContext::default());
+            }
+            return 3;
+        }
     };
     let mut input_format = args.from.map(Into::into);
 
@@ -863,7 +1299,7 @@ fn run_canon(args: CanonArgs, emit_pipeline: bool) -> i32 {
                     );
                     if emit_pipeline {
                         let pipeline_report =
-                            build_canon_pipeline_report(&args, input_format, options);
+                            build_canon_pipeline_report(&args, input_format, &options);
                         emit_pipeline_report_with_context(&pipeline_report, &fingerprint_context);
                     }
                     return 3;
@@ -888,7 +1324,7 @@ fn run_canon(args: CanonArgs, emit_pipeline: bool) -> i32 {
                     &mut output,
                     resolved_input_format,
                     output_format,
-                    options,
+                    &options,
                 ) {
                     Ok(()) => 0,
                     Err(error) => {
@@ -922,7 +1358,7 @@ fn run_canon(args: CanonArgs, emit_pipeline: bool) -> i32 {
                     &mut output,
                     resolved_input_format,
                     output_format,
-                    options,
+                    &options,
                 ) {
                     Ok(()) => 0,
                     Err(error) => {
@@ -939,7 +1375,7 @@ fn run_canon(args: CanonArgs, emit_pipeline: bool) -> i32 {
             }
             None => {
                 if output_format == Format::Jsonl {
-                    match run_canon_jsonl_autodetect_stdin(&mut output, options) {
+                    match run_canon_jsonl_autodetect_stdin(&mut output, &options) {
                         Ok(detected) => {
                             input_format = Some(detected);
                             0
@@ -992,7 +1428,7 @@ fn run_canon(args: CanonArgs, emit_pipeline: bool) -> i32 {
                                     &mut output,
                                     detected,
                                     output_format,
-                                    options,
+                                    &options,
                                 ) {
                                     Ok(()) => 0,
                                     Err(error) => {
@@ -1024,7 +1460,7 @@ fn run_canon(args: CanonArgs, emit_pipeline: bool) -> i32 {
     };
 
     if emit_pipeline {
-        let pipeline_report = build_canon_pipeline_report(&args, input_format, options);
+        let pipeline_report = build_canon_pipeline_report(&args, input_format, &options);
         emit_pipeline_report_with_context(&pipeline_report, &fingerprint_context);
     }
     exit_code
@@ -2417,25 +2853,42 @@ fn run_doctor(args: DoctorArgs, emit_pipeline: bool) -> i32 {
     exit_code
 }
 
-fn run_recipe(args: RecipeArgs, emit_pipeline: bool) -> i32 {
+fn run_recipe(args: RecipeArgs, emit_pipeline: bool, contract_version: Option<&str>) -> i32 {
     match args.command {
-        RecipeSubcommand::Run(run_args) => run_recipe_run(run_args, emit_pipeline),
+        RecipeSubcommand::Run(run_args) => {
+            run_recipe_run(run_args, emit_pipeline, contract_version)
+        }
         RecipeSubcommand::Lock(lock_args) => run_recipe_lock(lock_args, emit_pipeline),
-        RecipeSubcommand::Replay(replay_args) => run_recipe_replay(replay_args, emit_pipeline),
+        RecipeSubcommand::Replay(replay_args) => {
+            run_recipe_replay(replay_args, emit_pipeline, contract_version)
+        }
+        RecipeSubcommand::Validate(validate_args) => {
+            run_recipe_validate(validate_args, emit_pipeline)
+        }
+        RecipeSubcommand::Graph(graph_args) => run_recipe_graph(graph_args, emit_pipeline),
     }
 }
 
-fn run_recipe_run(args: RecipeRunArgs, emit_pipeline: bool) -> i32 {
+fn run_recipe_run(args: RecipeRunArgs, emit_pipeline: bool, contract_version: Option<&str>) -> i32 {
     let recipe_format = dataq_io::resolve_input_format(None, Some(args.file.as_path())).ok();
     let command_args = recipe::RecipeCommandArgs {
         file_path: Some(args.file.clone()),
         recipe: None,
         base_dir: None,
+        params: args.param.clone(),
+        cache_dir: args.cache_dir.clone(),
     };
-    let (response, trace) = recipe::run_with_trace(&command_args);
+    let (mut response, trace) = recipe::run_with_trace(&command_args);
 
     let exit_code = match response.exit_code {
         0 | 2 => {
+            if let Some(version) = contract_version {
+                response.payload = contract::shape_for_version(
+                    contract::ContractCommand::RecipeRun,
+                    version,
+                    response.payload,
+                );
+            }
             if emit_json_stdout(&response.payload) {
                 response.exit_code
             } else {
@@ -2483,6 +2936,7 @@ fn run_recipe_lock(args: RecipeLockArgs, emit_pipeline: bool) -> i32 {
     let recipe_format = dataq_io::resolve_input_format(None, Some(args.file.as_path())).ok();
     let command_args = recipe::RecipeLockCommandArgs {
         file_path: args.file.clone(),
+        params: args.param.clone(),
     };
     let (response, trace, serialized_lock) = recipe::lock_with_trace(&command_args);
 
@@ -2490,7 +2944,10 @@ fn run_recipe_lock(args: RecipeLockArgs, emit_pipeline: bool) -> i32 {
         0 => {
             if let Some(serialized_lock) = serialized_lock {
                 if let Some(out_path) = args.out.as_ref() {
-                    match fs::write(out_path, serialized_lock.as_slice()) {
+                    match dataq_io::writer::write_bytes_to_path(
+                        out_path,
+                        serialized_lock.as_slice(),
+                    ) {
                         Ok(()) => 0,
                         Err(error) => {
                             emit_error(
@@ -2566,18 +3023,30 @@ fn run_recipe_lock(args: RecipeLockArgs, emit_pipeline: bool) -> i32 {
     exit_code
 }
 
-fn run_recipe_replay(args: RecipeReplayArgs, emit_pipeline: bool) -> i32 {
+fn run_recipe_replay(
+    args: RecipeReplayArgs,
+    emit_pipeline: bool,
+    contract_version: Option<&str>,
+) -> i32 {
     let recipe_format = dataq_io::resolve_input_format(None, Some(args.file.as_path())).ok();
     let lock_format = dataq_io::resolve_input_format(None, Some(args.lock.as_path())).ok();
     let command_args = recipe::RecipeReplayCommandArgs {
         file_path: args.file.clone(),
         lock_path: args.lock.clone(),
         strict: args.strict,
+        params: args.param.clone(),
     };
-    let (response, trace) = recipe::replay_with_trace(&command_args);
+    let (mut response, trace) = recipe::replay_with_trace(&command_args);
 
     let exit_code = match response.exit_code {
         0 | 2 => {
+            if let Some(version) = contract_version {
+                response.payload = contract::shape_for_version(
+                    contract::ContractCommand::RecipeReplay,
+                    version,
+                    response.payload,
+                );
+            }
             if emit_json_stdout(&response.payload) {
                 response.exit_code
             } else {
@@ -2622,17 +3091,194 @@ fn run_recipe_replay(args: RecipeReplayArgs, emit_pipeline: bool) -> i32 {
     exit_code
 }
 
-fn run_mcp() -> i32 {
+fn run_recipe_validate(args: RecipeValidateArgs, emit_pipeline: bool) -> i32 {
+    let recipe_format = dataq_io::resolve_input_format(None, Some(args.file.as_path())).ok();
+    let command_args = recipe::RecipeValidateCommandArgs {
+        file_path: args.file.clone(),
+        params: args.param.clone(),
+    };
+    let (response, trace) = recipe::validate_with_trace(&command_args);
+
+    let exit_code = match response.exit_code {
+        0 | 2 => {
+            if emit_json_stdout(&response.payload) {
+                response.exit_code
+            } else {
+                emit_error(
+                    "internal_error",
+                    "failed to serialize recipe validate response".to_string(),
+                    json!({"command": "recipe", "subcommand": "validate"}),
+                    1,
+                );
+                1
+            }
+        }
+        3 | 1 => {
+            if emit_json_stderr(&response.payload) {
+                response.exit_code
+            } else {
+                emit_error(
+                    "internal_error",
+                    "failed to serialize recipe validate error".to_string(),
+                    json!({"command": "recipe", "subcommand": "validate"}),
+                    1,
+                );
+                1
+            }
+        }
+        other => {
+            emit_error(
+                "internal_error",
+                format!("unexpected recipe validate exit code: {other}"),
+                json!({"command": "recipe", "subcommand": "validate"}),
+                1,
+            );
+            1
+        }
+    };
+
+    if emit_pipeline {
+        let pipeline_report = build_recipe_static_pipeline_report(
+            &args.file,
+            recipe_format,
+            trace.steps,
+            recipe::deterministic_guards_validate(),
+        );
+        emit_pipeline_report(&pipeline_report);
+    }
+    exit_code
+}
+
+fn run_recipe_graph(args: RecipeGraphArgs, emit_pipeline: bool) -> i32 {
+    let recipe_format = dataq_io::resolve_input_format(None, Some(args.file.as_path())).ok();
+    let command_args = recipe::RecipeGraphCommandArgs {
+        file_path: args.file.clone(),
+        params: args.param.clone(),
+        format: args.format.into(),
+    };
+    let (response, trace, dot) = recipe::graph_with_trace(&command_args);
+
+    let exit_code = match response.exit_code {
+        0 => {
+            let emitted = match dot {
+                Some(dot) => emit_bytes_stdout(dot.as_bytes()),
+                None => emit_json_stdout(&response.payload),
+            };
+            if emitted {
+                0
+            } else {
+                emit_error(
+                    "internal_error",
+                    "failed to emit recipe graph response".to_string(),
+                    json!({"command": "recipe", "subcommand": "graph"}),
+                    1,
+                );
+                1
+            }
+        }
+        3 | 1 => {
+            if emit_json_stderr(&response.payload) {
+                response.exit_code
+            } else {
+                emit_error(
+                    "internal_error",
+                    "failed to serialize recipe graph error".to_string(),
+                    json!({"command": "recipe", "subcommand": "graph"}),
+                    1,
+                );
+                1
+            }
+        }
+        other => {
+            emit_error(
+                "internal_error",
+                format!("unexpected recipe graph exit code: {other}"),
+                json!({"command": "recipe", "subcommand": "graph"}),
+                1,
+            );
+            1
+        }
+    };
+
+    if emit_pipeline {
+        let pipeline_report = build_recipe_static_pipeline_report(
+            &args.file,
+            recipe_format,
+            trace.steps,
+            recipe::deterministic_guards_graph(),
+        );
+        emit_pipeline_report(&pipeline_report);
+    }
+    exit_code
+}
+
+fn run_mcp(args: McpArgs) -> i32 {
+    let options = match mcp::McpServerOptions::with_roots(&args.roots) {
+        Ok(options) => options,
+        Err(message) => {
+            emit_error("input_usage_error", message, json!({"command": "mcp"}), 3);
+            return 3;
+        }
+    };
+    if let Some(addr) = args.http {
+        let addr = if addr.parse::<u16>().is_ok() {
+            format!("127.0.0.1:{addr}")
+        } else {
+            addr
+        };
+        let listener = match std::net::TcpListener::bind(addr.as_str()) {
+            Ok(listener) => listener,
+            Err(error) => {
+                emit_error(
+                    "input_usage_error",
+                    format!("failed to bind MCP HTTP listener `{addr}`: {error}"),
+                    json!({"command": "mcp", "http": addr}),
+                    3,
+                );
+                return 3;
+            }
+        };
+        let local_addr = listener
+            .local_addr()
+            .map(|local| local.to_string())
+            .unwrap_or(addr);
+        eprintln!(
+            "{}",
+            json!({"event": "mcp_http_listening", "addr": local_addr, "endpoint": "/mcp"})
+        );
+        return mcp::serve_http(listener, options);
+    }
+
     let stdin = io::stdin();
     let stdout = io::stdout();
-    mcp::run_single_request(stdin.lock(), stdout.lock())
+    if args.serve {
+        mcp::serve(stdin.lock(), stdout, &options)
+    } else {
+        mcp::run_single_request(stdin.lock(), stdout.lock(), &options)
+    }
 }
 
 fn run_contract(args: ContractArgs, emit_pipeline: bool) -> i32 {
+    match args.action {
+        Some(ContractSubcommand::Verify(verify)) => {
+            return run_contract_verify(verify, emit_pipeline);
+        }
+        Some(ContractSubcommand::Diff(diff)) => return run_contract_diff(diff, emit_pipeline),
+        None => {}
+    }
+    let json_schema = matches!(args.format, CliContractFormat::JsonSchema);
     let response = if args.all {
-        contract::run_all()
+        if json_schema {
+            contract::run_json_schema_all()
+        } else {
+            contract::run_all()
+        }
     } else if let Some(command) = args.command {
-        contract::run_for_command(command.into())
+        if json_schema {
+            contract::run_json_schema_for_command(command.into())
+        } else {
+            contract::run_for_command(command.into())
+        }
     } else {
         emit_error(
             "input_usage_error",
@@ -2689,6 +3335,132 @@ fn run_contract(args: ContractArgs, emit_pipeline: bool) -> i32 {
     exit_code
 }
 
+fn run_help(args: HelpArgs, emit_pipeline: bool) -> i32 {
+    if !args.json {
+        // Reuse clap's own rendering so `dataq help <command>` matches `<command> --help`.
+        let argv = std::iter::once("dataq".to_string())
+            .chain(args.command.iter().cloned())
+            .chain(std::iter::once("--help".to_string()));
+        return match Cli::command().try_get_matches_from(argv) {
+            Ok(_) => 0,
+            Err(error) => handle_parse_error(error),
+        };
+    }
+
+    let response = help::run(&Cli::command(), &args.command);
+    let exit_code = match response.exit_code {
+        0 => {
+            if emit_json_stdout(&response.payload) {
+                0
+            } else {
+                emit_error(
+                    "internal_error",
+                    "failed to serialize help response".to_string(),
+                    json!({"command": "help"}),
+                    1,
+                );
+                1
+            }
+        }
+        other => {
+            if emit_json_stderr(&response.payload) {
+                other
+            } else {
+                emit_error(
+                    "internal_error",
+                    "failed to serialize help error".to_string(),
+                    json!({"command": "help"}),
+                    1,
+                );
+                1
+            }
+        }
+    };
+
+    if emit_pipeline {
+        emit_pipeline_report(&PipelineReport::new(
+            "help",
+            PipelineInput::new(Vec::new()),
+            help::pipeline_steps(),
+            help::deterministic_guards(),
+        ));
+    }
+
+    exit_code
+}
+
+fn run_contract_verify(args: ContractVerifyArgs, emit_pipeline: bool) -> i32 {
+    let response = contract::run_verify(args.command.into(), args.payload.into(), &args.input);
+    emit_contract_check_response(response, "contract verify", &args.input, emit_pipeline)
+}
+
+fn run_contract_diff(args: ContractDiffArgs, emit_pipeline: bool) -> i32 {
+    let response = contract::run_diff(&args.from);
+    emit_contract_check_response(response, "contract diff", &args.from, emit_pipeline)
+}
+
+/// Writes `contract verify`/`contract diff` results: exit `0`/`2` to stdout, `3`/`1` to stderr.
+fn emit_contract_check_response(
+    response: contract::ContractCommandResponse,
+    command: &str,
+    input: &Path,
+    emit_pipeline: bool,
+) -> i32 {
+    let exit_code = match response.exit_code {
+        0 | 2 => {
+            if emit_json_stdout(&response.payload) {
+                response.exit_code
+            } else {
+                emit_error(
+                    "internal_error",
+                    format!("failed to serialize {command} response"),
+                    json!({"command": command}),
+                    1,
+                );
+                1
+            }
+        }
+        3 | 1 => {
+            if emit_json_stderr(&response.payload) {
+                response.exit_code
+            } else {
+                emit_error(
+                    "internal_error",
+                    format!("failed to serialize {command} error"),
+                    json!({"command": command}),
+                    1,
+                );
+                1
+            }
+        }
+        other => {
+            emit_error(
+                "internal_error",
+                format!("unexpected {command} exit code: {other}"),
+                json!({"command": command}),
+                1,
+            );
+            1
+        }
+    };
+
+    if emit_pipeline {
+        let pipeline_report = PipelineReport::new(
+            "contract",
+            PipelineInput::new(vec![PipelineInputSource::path(
+                "input",
+                input.display().to_string(),
+                Some("json"),
+            )]),
+            contract::pipeline_steps(),
+            contract::deterministic_guards(),
+        );
+        emit_pipeline_report(&pipeline_report);
+    }
+
+    exit_code
+}
+
 fn run_emit(args: EmitArgs, emit_pipeline: bool) -> i32 {
     match args.command {
         EmitSubcommand::Plan(plan_args) => run_emit_plan(plan_args, emit_pipeline),
@@ -2835,12 +3607,12 @@ fn run_canon_with_format<R: Read, W: io::Write>(
     output: W,
     input_format: Format,
     output_format: Format,
-    options: canon::CanonCommandOptions,
+    options: &canon::CanonCommandOptions,
 ) -> Result<(), CanonError> {
     if output_format == Format::Jsonl {
         return run_canon_jsonl_stream(input, output, input_format, options);
     }
-    canon::run(input, output, input_format, output_format, options)
+    canon::run(input, output, input_format, output_format, options.clone())
 }
 
 #[derive(Debug)]
@@ -2851,7 +3623,7 @@ enum CanonStdinAutodetectError {
 
 fn run_canon_jsonl_autodetect_stdin<W: io::Write>(
     mut output: W,
-    options: canon::CanonCommandOptions,
+    options: &canon::CanonCommandOptions,
 ) -> Result<Format, CanonStdinAutodetectError> {
     let stdin = io::stdin();
     let mut reader = io::BufReader::new(stdin.lock());
@@ -2888,14 +3660,15 @@ fn run_canon_jsonl_autodetect_stdin<W: io::Write>(
     }
 
     if non_empty_lines >= 2 {
-        let canon_options = options.into();
+        let canon_options = options.clone().into();
         for value in prefetched_values {
-            let canonical = canonicalize_value(value, canon_options);
+            let canonical = canonicalize_value(value, &canon_options)
+                .map_err(|error| CanonStdinAutodetectError::Canon(error.into()))?;
             write_jsonl_stream_value(&mut output, &canonical)
                 .map_err(CanonStdinAutodetectError::Canon)?;
         }
         dataq_io::reader::read_jsonl_stream(reader, |value| {
-            let canonical = canonicalize_value(value, canon_options);
+            let canonical = canonicalize_value(value, &canon_options)?;
             write_jsonl_stream_value(&mut output, &canonical)
         })
         .map_err(|error| match error {
@@ -2917,7 +3690,7 @@ fn run_canon_jsonl_with_buffered_stdin<R: Read, W: io::Write>(
     mut reader: R,
     mut buffered_input: Vec<u8>,
     output: W,
-    options: canon::CanonCommandOptions,
+    options: &canon::CanonCommandOptions,
 ) -> Result<Format, CanonStdinAutodetectError> {
     reader
         .read_to_end(&mut buffered_input)
@@ -2952,12 +3725,12 @@ fn run_canon_jsonl_stream<R: Read, W: io::Write>(
     input: R,
     mut output: W,
     input_format: Format,
-    options: canon::CanonCommandOptions,
+    options: &canon::CanonCommandOptions,
 ) -> Result<(), CanonError> {
-    let canon_options = options.into();
+    let canon_options = options.clone().into();
     if input_format == Format::Jsonl {
         return dataq_io::reader::read_jsonl_stream(input, |value| {
-            let canonical = canonicalize_value(value, canon_options);
+            let canonical = canonicalize_value(value, &canon_options)?;
             write_jsonl_stream_value(&mut output, &canonical)
         })
         .map_err(|error| match error {
@@ -2976,7 +3749,7 @@ fn run_canon_jsonl_stream<R: Read, W: io::Write>(
         }
     })?;
     for value in values {
-        let canonical = canonicalize_value(value, canon_options);
+        let canonical = canonicalize_value(value, &canon_options)?;
         write_jsonl_stream_value(&mut output, &canonical)?;
     }
     Ok(())
@@ -2997,7 +3770,7 @@ fn write_jsonl_stream_value<W: io::Write>(output: &mut W, value: &Value) -> Resu
 
 fn map_canon_error(error: &CanonError) -> (i32, &'static str) {
     match error {
-        CanonError::ReadInput { .. } => (3, "input_usage_error"),
+        CanonError::ReadInput { .. } | CanonError::Time(_) => (3, "input_usage_error"),
         CanonError::WriteOutput { source, .. } => match source {
             IoError::Io(_) => (1, "internal_error"),
             _ => (3, "input_usage_error"),
@@ -3008,7 +3781,7 @@ fn map_canon_error(error: &CanonError) -> (i32, &'static str) {
 fn build_canon_pipeline_report(
     args: &CanonArgs,
     input_format: Option<Format>,
-    options: canon::CanonCommandOptions,
+    options: &canon::CanonCommandOptions,
 ) -> PipelineReport {
     let source = if let Some(path) = &args.input {
         PipelineInputSource::path(
@@ -3653,6 +4426,25 @@ fn build_recipe_replay_pipeline_report(
     )
 }
 
+/// Pipeline report for recipe subcommands that never execute steps.
+fn build_recipe_static_pipeline_report(
+    file: &Path,
+    recipe_format: Option<Format>,
+    steps: Vec<String>,
+    deterministic_guards: Vec<String>,
+) -> PipelineReport {
+    PipelineReport::new(
+        "recipe",
+        PipelineInput::new(vec![PipelineInputSource::path(
+            "recipe",
+            file.display().to_string(),
+            format_label(recipe_format),
+        )]),
+        steps,
+        deterministic_guards,
+    )
+}
+
 fn format_label(format: Option<Format>) -> Option<&'static str> {
     format.map(Format::as_str)
 }
@@ -3677,12 +4469,12 @@ fn hash_normalized_args() -> String {
         .filter(|arg| arg != "--emit-pipeline")
         .collect();
 
-    let mut hasher = DeterministicHasher::new();
+    let mut hasher = DigestHasher::sha256();
     hasher.update_len_prefixed(b"dataq.execution_fingerprint.args.v1");
     for arg in &args {
         hasher.update_len_prefixed(arg.as_bytes());
     }
-    hasher.finish_hex()
+    hasher.finish()
 }
 
 fn hash_consumed_input_entries(entries: &[ConsumedInputHashEntry<'_>]) -> Option<String> {
@@ -3690,7 +4482,7 @@ fn hash_consumed_input_entries(entries: &[ConsumedInputHashEntry<'_>]) -> Option
         return None;
     }
 
-    let mut hasher = DeterministicHasher::new();
+    let mut hasher = DigestHasher::sha256();
     hasher.update_len_prefixed(b"dataq.execution_fingerprint.input.v1");
     for entry in entries {
         hasher.update_len_prefixed(entry.label.as_bytes());
@@ -3705,7 +4497,7 @@ fn hash_consumed_input_entries(entries: &[ConsumedInputHashEntry<'_>]) -> Option
             hasher.update_len_prefixed(&[]);
         }
     }
-    Some(hasher.finish_hex())
+    Some(hasher.finish())
 }
 
 fn collect_used_tool_versions(
@@ -3941,6 +4733,10 @@ mod tests {
             contract::ContractCommand::from(CliContractCommand::RecipeLock),
             contract::ContractCommand::RecipeLock
         );
+        assert_eq!(
+            contract::ContractCommand::from(CliContractCommand::RecipeReplay),
+            contract::ContractCommand::RecipeReplay
+        );
 
         assert_eq!(
             doctor::DoctorProfile::from(CliDoctorProfile::Core),
@@ -4061,7 +4857,8 @@ mod tests {
         assert_eq!(format_label(None), None);
 
         let hash = hash_normalized_args();
-        assert_eq!(hash.len(), 16);
+        assert!(hash.starts_with("sha256:"));
+        assert_eq!(hash.len(), "sha256:".len() + 64);
 
         let empty_hash = hash_consumed_input_entries(&[]);
         assert_eq!(empty_hash, None);
@@ -4075,7 +4872,10 @@ mod tests {
         }];
         let input_hash = hash_consumed_input_entries(&entries);
         assert!(input_hash.is_some());
-        assert_eq!(input_hash.as_ref().map(String::len), Some(16));
+        assert_eq!(
+            input_hash.as_ref().map(String::len),
+            Some("sha256:".len() + 64)
+        );
 
         let preferred_versions =
             BTreeMap::from([(String::from("jq"), String::from("jq-test 1.0.0"))]);
@@ -4101,7 +4901,7 @@ mod tests {
             fingerprint.tool_versions.get("jq"),
             Some(&"jq-test 1.0.0".to_string())
         );
-        assert_eq!(fingerprint.args_hash.len(), 16);
+        assert!(fingerprint.args_hash.starts_with("sha256:"));
 
         let missing_tool_version = detect_tool_version("__dataq_missing_tool_for_test__");
         assert_eq!(missing_tool_version, "error: unavailable in PATH");
@@ -4134,11 +4934,26 @@ mod tests {
             to: Some(CanonOutputFormat::Json),
             sort_keys: true,
             normalize_time: false,
+            coerce: CliCoerceMode::All,
+            coerce_exempt: Vec::new(),
+            coerce_schema: None,
+            coerce_rules: None,
+            key_case: None,
+            whitespace: None,
+            unicode: None,
+            drop_nulls: false,
+            drop_empty: false,
+            sort_array: Vec::new(),
+            time_path: Vec::new(),
+            time_format: Vec::new(),
+            time_zone: None,
+            time_precision: None,
+            time_target: None,
         };
         let canon_report = build_canon_pipeline_report(
             &canon_args,
             Some(Format::Json),
-            canon::CanonCommandOptions::default(),
+            &canon::CanonCommandOptions::default(),
         );
         assert_eq!(canon_report.command, "canon");
         assert_eq!(canon_report.input.sources.len(), 1);
@@ -4364,6 +5179,8 @@ mod tests {
 
         let recipe_run_args = RecipeRunArgs {
             file: PathBuf::from("recipe.json"),
+            param: Vec::new(),
+            cache_dir: None,
         };
         let recipe_report =
             build_recipe_pipeline_report(&recipe_run_args, Some(Format::Json), Vec::new());
@@ -4372,6 +5189,7 @@ mod tests {
         let recipe_lock_args = RecipeLockArgs {
             file: PathBuf::from("recipe.json"),
             out: Some(PathBuf::from("recipe.lock.json")),
+            param: Vec::new(),
         };
         let tool_versions = BTreeMap::from([("jq".to_string(), "jq-1.8.1".to_string())]);
         let recipe_lock_report = build_recipe_lock_pipeline_report(
@@ -4386,6 +5204,7 @@ mod tests {
             file: PathBuf::from("recipe.json"),
             lock: PathBuf::from("recipe.lock.json"),
             strict: true,
+            param: Vec::new(),
         };
         let recipe_replay_report = build_recipe_replay_pipeline_report(
             &recipe_replay_args,
@@ -4413,7 +5232,7 @@ mod tests {
             Cursor::new(br#"[{"b":"2","a":"1"}]"#),
             &mut out,
             Format::Json,
-            canon::CanonCommandOptions::default(),
+            &canon::CanonCommandOptions::default(),
         )
         .expect("json input to jsonl stream");
         let output_text = String::from_utf8(out).expect("utf8");
@@ -4424,7 +5243,7 @@ mod tests {
             Cursor::new(b"{\"b\":\"2\",\"a\":\"1\"}\n{\"x\":\"true\"}\n"),
             &mut out,
             Format::Jsonl,
-            canon::CanonCommandOptions::default(),
+            &canon::CanonCommandOptions::default(),
         )
         .expect("jsonl input to jsonl stream");
         let output_text = String::from_utf8(out).expect("utf8");
@@ -4435,7 +5254,7 @@ mod tests {
             Cursor::new(Vec::<u8>::new()),
             br#"[{"x":"1"}]"#.to_vec(),
             &mut out,
-            canon::CanonCommandOptions::default(),
+            &canon::CanonCommandOptions::default(),
         )
         .expect("buffered stdin fallback");
         assert_eq!(detected, Format::Json);
@@ -4628,8 +5447,11 @@ mod tests {
             run_recipe_run(
                 RecipeRunArgs {
                     file: PathBuf::from("/definitely-missing/recipe.txt"),
+                    param: Vec::new(),
+                    cache_dir: None,
                 },
                 true,
+                None,
             ),
             3
         );
@@ -4639,6 +5461,7 @@ mod tests {
                 RecipeLockArgs {
                     file: PathBuf::from("/definitely-missing/recipe.txt"),
                     out: None,
+                    param: Vec::new(),
                 },
                 true,
             ),
@@ -4651,8 +5474,10 @@ mod tests {
                     file: PathBuf::from("/definitely-missing/recipe.json"),
                     lock: PathBuf::from("/definitely-missing/recipe.lock.json"),
                     strict: true,
+                    param: Vec::new(),
                 },
                 true,
+                None,
             ),
             3
         );
@@ -4660,8 +5485,10 @@ mod tests {
         assert_eq!(
             run_contract(
                 ContractArgs {
+                    action: None,
                     command: None,
                     all: false,
+                    format: CliContractFormat::Contract,
                 },
                 true,
             ),
@@ -4760,9 +5587,12 @@ mod tests {
             RecipeArgs {
                 command: RecipeSubcommand::Run(RecipeRunArgs {
                     file: PathBuf::from("/definitely-missing/recipe.txt"),
+                    param: Vec::new(),
+                    cache_dir: None,
                 }),
             },
             false,
+            None,
         );
         assert_eq!(recipe_exit, 3);
 
@@ -1,4 +1,6 @@
-/// Deterministic FNV-1a hasher for execution fingerprint metadata.
+use sha2::{Digest, Sha256};
+
+/// Deterministic 64-bit FNV-1a hasher for cache keys and legacy digests.
 #[derive(Debug, Clone)]
 pub struct DeterministicHasher {
     state: u64,
@@ -37,3 +39,107 @@ impl Default for DeterministicHasher {
         Self::new()
     }
 }
+
+const SHA256_PREFIX: &str = "sha256:";
+
+/// Digest algorithm, recorded in output as a `<algorithm>:` prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    /// FNV-1a 64-bit. Emitted as bare hex, as in lock files written before SHA-256.
+    Fnv1a64,
+    Sha256,
+}
+
+impl HashAlgorithm {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Fnv1a64 => "fnv1a64",
+            Self::Sha256 => "sha256",
+        }
+    }
+
+    /// Algorithm that produced a recorded digest; unprefixed values are legacy FNV-1a.
+    pub fn of_digest(digest: &str) -> Self {
+        if digest.starts_with(SHA256_PREFIX) {
+            Self::Sha256
+        } else {
+            Self::Fnv1a64
+        }
+    }
+}
+
+/// Hasher for audit digests (locks, fingerprints, content hashes).
+#[derive(Debug, Clone)]
+pub enum DigestHasher {
+    Fnv1a64(DeterministicHasher),
+    Sha256(Sha256),
+}
+
+impl DigestHasher {
+    pub fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Fnv1a64 => Self::Fnv1a64(DeterministicHasher::new()),
+            HashAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+        }
+    }
+
+    pub fn sha256() -> Self {
+        Self::new(HashAlgorithm::Sha256)
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Self::Fnv1a64(hasher) => hasher.update(bytes),
+            Self::Sha256(hasher) => hasher.update(bytes),
+        }
+    }
+
+    pub fn update_len_prefixed(&mut self, bytes: &[u8]) {
+        let len = bytes.len() as u64;
+        self.update(&len.to_le_bytes());
+        self.update(bytes);
+    }
+
+    /// Finish as `sha256:<hex>`, or bare hex for FNV-1a.
+    pub fn finish(self) -> String {
+        match self {
+            Self::Fnv1a64(hasher) => hasher.finish_hex(),
+            Self::Sha256(hasher) => {
+                let digest = hasher.finalize();
+                let mut out = String::with_capacity(SHA256_PREFIX.len() + digest.len() * 2);
+                out.push_str(SHA256_PREFIX);
+                for byte in digest {
+                    out.push_str(&format!("{byte:02x}"));
+                }
+                out
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeterministicHasher, DigestHasher, HashAlgorithm};
+
+    #[test]
+    fn sha256_digest_is_prefixed() {
+        let mut hasher = DigestHasher::sha256();
+        hasher.update(b"abc");
+        assert_eq!(
+            hasher.finish(),
+            "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn fnv_digest_keeps_legacy_bare_hex() {
+        let mut legacy = DeterministicHasher::new();
+        legacy.update_len_prefixed(b"abc");
+        let mut hasher = DigestHasher::new(HashAlgorithm::Fnv1a64);
+        hasher.update_len_prefixed(b"abc");
+        let digest = hasher.finish();
+        assert_eq!(digest, legacy.finish_hex());
+        assert_eq!(HashAlgorithm::of_digest(&digest), HashAlgorithm::Fnv1a64);
+        assert_eq!(HashAlgorithm::of_digest("sha256:00"), HashAlgorithm::Sha256);
+    }
+}
@@ -1,4 +1,13 @@
-use chrono::{DateTime, SecondsFormat, Utc};
+use std::fmt;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{
+    DateTime, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat,
+    TimeZone, Utc,
+};
+use chrono_tz::Tz;
+use serde_json::{Number, Value};
+use thiserror::Error;
 
 pub fn normalize_rfc3339_utc(input: &str) -> Option<String> {
     let dt = DateTime::parse_from_rfc3339(input).ok()?;
@@ -7,3 +16,394 @@ pub fn normalize_rfc3339_utc(input: &str) -> Option<String> {
             .to_rfc3339_opts(SecondsFormat::AutoSi, true),
     )
 }
+
+/// Accepted timestamp input representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeInputFormat {
+    Rfc3339,
+    EpochSeconds,
+    EpochMillis,
+    /// chrono `strftime` pattern, with or without an offset (`%Y/%m/%d %H:%M`).
+    Pattern(String),
+}
+
+impl TimeInputFormat {
+    /// Parse `rfc3339`, `epoch_s`, `epoch_ms` or a `%`-pattern.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        match input {
+            "rfc3339" => Ok(Self::Rfc3339),
+            "epoch_s" => Ok(Self::EpochSeconds),
+            "epoch_ms" => Ok(Self::EpochMillis),
+            pattern if pattern.contains('%') => {
+                if StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error)) {
+                    return Err(format!("invalid time format pattern `{pattern}`"));
+                }
+                Ok(Self::Pattern(pattern.to_string()))
+            }
+            other => Err(format!(
+                "unknown time format `{other}`: expected `rfc3339`, `epoch_s`, `epoch_ms`, or a strftime pattern"
+            )),
+        }
+    }
+}
+
+/// Timestamp output representation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeTarget {
+    #[default]
+    Rfc3339,
+    EpochMillis,
+}
+
+impl TimeTarget {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Rfc3339 => "rfc3339",
+            Self::EpochMillis => "epoch_ms",
+        }
+    }
+
+    pub fn parse_cli_name(input: &str) -> Option<Self> {
+        match input {
+            "rfc3339" => Some(Self::Rfc3339),
+            "epoch_ms" => Some(Self::EpochMillis),
+            _ => None,
+        }
+    }
+}
+
+/// Fractional-second digits for RFC 3339 output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimePrecision {
+    /// Emit only as many digits (0, 3, 6 or 9) as needed.
+    #[default]
+    Auto,
+    Seconds,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl TimePrecision {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Seconds => "s",
+            Self::Millis => "ms",
+            Self::Micros => "us",
+            Self::Nanos => "ns",
+        }
+    }
+
+    pub fn parse_cli_name(input: &str) -> Option<Self> {
+        match input {
+            "auto" => Some(Self::Auto),
+            "s" => Some(Self::Seconds),
+            "ms" => Some(Self::Millis),
+            "us" => Some(Self::Micros),
+            "ns" => Some(Self::Nanos),
+            _ => None,
+        }
+    }
+
+    fn seconds_format(self) -> SecondsFormat {
+        match self {
+            Self::Auto => SecondsFormat::AutoSi,
+            Self::Seconds => SecondsFormat::Secs,
+            Self::Millis => SecondsFormat::Millis,
+            Self::Micros => SecondsFormat::Micros,
+            Self::Nanos => SecondsFormat::Nanos,
+        }
+    }
+}
+
+/// Parse `UTC`, `Z` or a fixed offset such as `+09:00` / `-0530`.
+pub fn parse_fixed_offset(input: &str) -> Option<FixedOffset> {
+    if matches!(input, "UTC" | "utc" | "Z" | "z") {
+        return FixedOffset::east_opt(0);
+    }
+    let (sign, rest) = match input.as_bytes().first()? {
+        b'+' => (1, &input[1..]),
+        b'-' => (-1, &input[1..]),
+        _ => return None,
+    };
+    let digits: String = rest.chars().filter(|ch| *ch != ':').collect();
+    if digits.len() != 4 || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits[2..].parse().ok()?;
+    if minutes >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Zone assumed for timestamps that carry no offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssumedZone {
+    Fixed(FixedOffset),
+    /// IANA zone such as `Asia/Tokyo`; the offset follows the zone's DST rules.
+    Named(Tz),
+}
+
+impl Default for AssumedZone {
+    fn default() -> Self {
+        Self::Fixed(FixedOffset::east_opt(0).expect("zero offset is valid"))
+    }
+}
+
+impl AssumedZone {
+    /// Parse `UTC`, `Z`, a fixed offset (`+09:00`) or an IANA zone name.
+    pub fn parse(input: &str) -> Option<Self> {
+        parse_fixed_offset(input)
+            .map(Self::Fixed)
+            .or_else(|| input.parse::<Tz>().ok().map(Self::Named))
+    }
+
+    /// Map a local time to the single instant it denotes in this zone.
+    pub fn resolve(self, local: &NaiveDateTime) -> Result<DateTime<Utc>, LocalTimeError> {
+        let resolved = match self {
+            Self::Fixed(offset) => offset
+                .from_local_datetime(local)
+                .map(|dt| dt.with_timezone(&Utc)),
+            Self::Named(zone) => zone
+                .from_local_datetime(local)
+                .map(|dt| dt.with_timezone(&Utc)),
+        };
+        match resolved {
+            LocalResult::Single(instant) => Ok(instant),
+            LocalResult::Ambiguous(..) => Err(LocalTimeError::Ambiguous {
+                local: local.to_string(),
+                zone: self.to_string(),
+            }),
+            LocalResult::None => Err(LocalTimeError::Nonexistent {
+                local: local.to_string(),
+                zone: self.to_string(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for AssumedZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fixed(offset) => write!(f, "{offset}"),
+            Self::Named(zone) => f.write_str(zone.name()),
+        }
+    }
+}
+
+/// A local time without an offset that does not map to exactly one instant.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LocalTimeError {
+    #[error(
+        "local time `{local}` is ambiguous in time zone `{zone}` (repeated by a DST transition)"
+    )]
+    Ambiguous { local: String, zone: String },
+
+    #[error(
+        "local time `{local}` does not exist in time zone `{zone}` (skipped by a DST transition)"
+    )]
+    Nonexistent { local: String, zone: String },
+}
+
+/// Configurable timestamp parser and formatter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeNormalizer {
+    /// Input representations, tried in order.
+    pub formats: Vec<TimeInputFormat>,
+    /// Zone assumed for patterns without an offset.
+    pub assume_zone: AssumedZone,
+    pub precision: TimePrecision,
+    pub target: TimeTarget,
+}
+
+impl Default for TimeNormalizer {
+    fn default() -> Self {
+        Self {
+            formats: vec![TimeInputFormat::Rfc3339],
+            assume_zone: AssumedZone::default(),
+            precision: TimePrecision::Auto,
+            target: TimeTarget::Rfc3339,
+        }
+    }
+}
+
+impl TimeNormalizer {
+    /// Convert a scalar timestamp, or return `None` when no format matches.
+    ///
+    /// A naive local time that is ambiguous or nonexistent in the assumed
+    /// zone is an error rather than a silent non-match.
+    pub fn normalize(&self, value: &Value) -> Result<Option<Value>, LocalTimeError> {
+        let mut instant = None;
+        for format in &self.formats {
+            instant = parse_with_format(value, format, self.assume_zone)?;
+            if instant.is_some() {
+                break;
+            }
+        }
+        let Some(instant) = instant else {
+            return Ok(None);
+        };
+        Ok(Some(match self.target {
+            TimeTarget::Rfc3339 => {
+                Value::String(instant.to_rfc3339_opts(self.precision.seconds_format(), true))
+            }
+            TimeTarget::EpochMillis => Value::Number(Number::from(instant.timestamp_millis())),
+        }))
+    }
+}
+
+fn parse_with_format(
+    value: &Value,
+    format: &TimeInputFormat,
+    assume_zone: AssumedZone,
+) -> Result<Option<DateTime<Utc>>, LocalTimeError> {
+    let instant = match format {
+        TimeInputFormat::Rfc3339 => value
+            .as_str()
+            .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+            .map(|dt| dt.with_timezone(&Utc)),
+        TimeInputFormat::EpochSeconds => epoch_to_datetime(value, 1_000_000_000),
+        TimeInputFormat::EpochMillis => epoch_to_datetime(value, 1_000_000),
+        TimeInputFormat::Pattern(pattern) => {
+            let Some(text) = value.as_str() else {
+                return Ok(None);
+            };
+            if let Ok(dt) = DateTime::parse_from_str(text, pattern) {
+                return Ok(Some(dt.with_timezone(&Utc)));
+            }
+            let naive = NaiveDateTime::parse_from_str(text, pattern)
+                .ok()
+                .or_else(|| {
+                    NaiveDate::parse_from_str(text, pattern)
+                        .ok()
+                        .map(|date| date.and_time(NaiveTime::MIN))
+                });
+            match naive {
+                Some(naive) => Some(assume_zone.resolve(&naive)?),
+                None => None,
+            }
+        }
+    };
+    Ok(instant)
+}
+
+/// Interpret an integer (or integral string) as `unit_nanos` nanoseconds per unit.
+fn epoch_to_datetime(value: &Value, unit_nanos: i64) -> Option<DateTime<Utc>> {
+    let units = match value {
+        Value::Number(number) => number.as_i64()?,
+        Value::String(text) => text.parse::<i64>().ok()?,
+        _ => return None,
+    };
+    let nanos = i128::from(units) * i128::from(unit_nanos);
+    let seconds = i64::try_from(nanos.div_euclid(1_000_000_000)).ok()?;
+    let subsec = u32::try_from(nanos.rem_euclid(1_000_000_000)).ok()?;
+    DateTime::from_timestamp(seconds, subsec)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        AssumedZone, LocalTimeError, TimeInputFormat, TimeNormalizer, TimePrecision, TimeTarget,
+        parse_fixed_offset,
+    };
+
+    #[test]
+    fn parses_fixed_offsets() {
+        assert_eq!(
+            parse_fixed_offset("UTC").map(|o| o.local_minus_utc()),
+            Some(0)
+        );
+        assert_eq!(
+            parse_fixed_offset("+09:00").map(|o| o.local_minus_utc()),
+            Some(9 * 3600)
+        );
+        assert_eq!(
+            parse_fixed_offset("-0530").map(|o| o.local_minus_utc()),
+            Some(-(5 * 3600 + 30 * 60))
+        );
+        assert_eq!(parse_fixed_offset("Asia/Tokyo"), None);
+    }
+
+    #[test]
+    fn parses_iana_zone_names() {
+        assert_eq!(
+            AssumedZone::parse("UTC"),
+            parse_fixed_offset("UTC").map(AssumedZone::Fixed)
+        );
+        assert_eq!(
+            AssumedZone::parse("Asia/Tokyo").map(|zone| zone.to_string()),
+            Some("Asia/Tokyo".to_string())
+        );
+        assert_eq!(AssumedZone::parse("Mars/Olympus"), None);
+    }
+
+    #[test]
+    fn named_zones_follow_dst_and_reject_ambiguous_local_times() {
+        let normalizer = TimeNormalizer {
+            formats: vec![TimeInputFormat::parse("%Y-%m-%d %H:%M").expect("valid pattern")],
+            assume_zone: AssumedZone::parse("Europe/Berlin").expect("valid zone"),
+            ..TimeNormalizer::default()
+        };
+        assert_eq!(
+            normalizer.normalize(&json!("2026-01-15 12:00")),
+            Ok(Some(json!("2026-01-15T11:00:00Z")))
+        );
+        assert_eq!(
+            normalizer.normalize(&json!("2026-07-15 12:00")),
+            Ok(Some(json!("2026-07-15T10:00:00Z")))
+        );
+        assert!(matches!(
+            normalizer.normalize(&json!("2026-10-25 02:30")),
+            Err(LocalTimeError::Ambiguous { .. })
+        ));
+        assert!(matches!(
+            normalizer.normalize(&json!("2026-03-29 02:30")),
+            Err(LocalTimeError::Nonexistent { .. })
+        ));
+    }
+
+    #[test]
+    fn normalizes_epoch_and_naive_patterns_to_rfc3339() {
+        let normalizer = TimeNormalizer {
+            formats: vec![
+                TimeInputFormat::EpochSeconds,
+                TimeInputFormat::parse("%Y/%m/%d %H:%M").expect("valid pattern"),
+            ],
+            assume_zone: AssumedZone::parse("+09:00").expect("valid offset"),
+            precision: TimePrecision::Millis,
+            target: TimeTarget::Rfc3339,
+        };
+        assert_eq!(
+            normalizer.normalize(&json!(1_700_000_000)),
+            Ok(Some(json!("2023-11-14T22:13:20.000Z")))
+        );
+        assert_eq!(
+            normalizer.normalize(&json!("2026/02/23 20:15")),
+            Ok(Some(json!("2026-02-23T11:15:00.000Z")))
+        );
+        assert_eq!(normalizer.normalize(&json!("not a time")), Ok(None));
+    }
+
+    #[test]
+    fn emits_epoch_millis_target() {
+        let normalizer = TimeNormalizer {
+            target: TimeTarget::EpochMillis,
+            ..TimeNormalizer::default()
+        };
+        assert_eq!(
+            normalizer.normalize(&json!("2023-11-14T22:13:20.123+00:00")),
+            Ok(Some(json!(1_700_000_000_123_i64)))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_formats() {
+        assert!(TimeInputFormat::parse("iso").is_err());
+        assert!(TimeInputFormat::parse("%Q").is_err());
+    }
+}
@@ -4,6 +4,10 @@ mod assert_cli;
 mod canon_cli;
 #[path = "cli/codex_cli.rs"]
 mod codex_cli;
+#[path = "cli/columnar_cli.rs"]
+mod columnar_cli;
+#[path = "cli/compression_cli.rs"]
+mod compression_cli;
 #[path = "cli/diff_source_cli.rs"]
 mod diff_source_cli;
 #[path = "cli/doctor_cli.rs"]
@@ -14,6 +18,8 @@ mod emit_cli;
 mod entry_cli;
 #[path = "cli/gate_cli.rs"]
 mod gate_cli;
+#[path = "cli/help_cli.rs"]
+mod help_cli;
 #[path = "cli/ingest_api_cli.rs"]
 mod ingest_api_cli;
 #[path = "cli/ingest_cli.rs"]
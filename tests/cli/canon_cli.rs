@@ -1,8 +1,10 @@
+use std::fs;
 use std::io::Cursor;
 
 use dataq::cmd::canon::{CanonCommandOptions, run};
 use dataq::io::Format;
-use serde_json::json;
+use serde_json::{Value, json};
+use tempfile::tempdir;
 
 #[test]
 fn canon_command_pipeline_success_path() {
@@ -16,6 +18,7 @@ fn canon_command_pipeline_success_path() {
         CanonCommandOptions {
             sort_keys: true,
             normalize_time: true,
+            ..CanonCommandOptions::default()
         },
     )
     .expect("canon command should succeed");
@@ -39,6 +42,7 @@ fn canon_command_is_deterministic() {
     let options = CanonCommandOptions {
         sort_keys: true,
         normalize_time: true,
+        ..CanonCommandOptions::default()
     };
 
     let mut first = Vec::new();
@@ -47,7 +51,7 @@ fn canon_command_is_deterministic() {
         &mut first,
         Format::Json,
         Format::Json,
-        options,
+        options.clone(),
     )
     .expect("first run should succeed");
 
@@ -77,6 +81,7 @@ fn canon_command_preserves_fractional_seconds_and_numeric_precision() {
         CanonCommandOptions {
             sort_keys: true,
             normalize_time: true,
+            ..CanonCommandOptions::default()
         },
     )
     .expect("canon command should succeed");
@@ -92,3 +97,237 @@ fn canon_command_preserves_fractional_seconds_and_numeric_precision() {
         })
     );
 }
+
+#[test]
+fn canon_coerce_none_keeps_string_scalars() {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["canon", "--from", "json", "--coerce", "none"])
+        .write_stdin(r#"{"zip":"01234","flag":"true"}"#)
+        .output()
+        .expect("run canon");
+    assert_eq!(output.status.code(), Some(0));
+    let out: Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    assert_eq!(out, json!({"flag":"true","zip":"01234"}));
+}
+
+#[test]
+fn canon_coerce_exempt_protects_selected_paths() {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["canon", "--from", "json", "--coerce-exempt", r#"$["zip"]"#])
+        .write_stdin(r#"[{"zip":"01234","qty":"3"}]"#)
+        .output()
+        .expect("run canon");
+    assert_eq!(output.status.code(), Some(0));
+    let out: Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    assert_eq!(out, json!({"qty":3,"zip":"01234"}));
+}
+
+#[test]
+fn canon_coerce_schema_uses_declared_field_types() {
+    let dir = tempdir().expect("tempdir");
+    let schema_path = dir.path().join("schema.json");
+    fs::write(
+        &schema_path,
+        r#"{"type":"object","properties":{"qty":{"type":"integer"},"zip":{"type":"string"}}}"#,
+    )
+    .expect("write schema");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "canon",
+            "--from",
+            "json",
+            "--coerce",
+            "schema",
+            "--coerce-schema",
+        ])
+        .arg(&schema_path)
+        .write_stdin(r#"{"zip":"01234","qty":"3","other":"false"}"#)
+        .output()
+        .expect("run canon");
+    assert_eq!(output.status.code(), Some(0));
+    let out: Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    assert_eq!(out, json!({"other":"false","qty":3,"zip":"01234"}));
+}
+
+#[test]
+fn canon_coerce_schema_without_source_is_input_usage_error() {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["canon", "--from", "json", "--coerce", "schema"])
+        .write_stdin(r#"{"a":"1"}"#)
+        .output()
+        .expect("run canon");
+    assert_eq!(output.status.code(), Some(3));
+    let stderr: Value = serde_json::from_slice(&output.stderr).expect("stderr json");
+    assert_eq!(stderr["error"], json!("input_usage_error"));
+}
+
+#[test]
+fn canon_rejects_non_canonical_coerce_exempt_path() {
+    assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["canon", "--from", "json", "--coerce-exempt", "zip"])
+        .write_stdin(r#"{"zip":"01234"}"#)
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn canon_normalization_passes_compose_in_order() {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "canon",
+            "--from",
+            "json",
+            "--key-case",
+            "snake",
+            "--whitespace",
+            "collapse",
+            "--drop-nulls",
+            "--sort-array",
+            r#"$["tag_list"]"#,
+        ])
+        .write_stdin(r#"{"tagList":["b","a"],"displayName":"  Ada   Lovelace ","deletedAt":null,"count":" 12 "}"#)
+        .output()
+        .expect("run canon");
+    assert_eq!(output.status.code(), Some(0));
+    let out = String::from_utf8(output.stdout).expect("utf8 stdout");
+    assert_eq!(
+        out.trim_end(),
+        r#"{"count":12,"display_name":"Ada Lovelace","tag_list":["a","b"]}"#
+    );
+}
+
+#[test]
+fn canon_emit_pipeline_reports_normalization_guards() {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "--emit-pipeline",
+            "canon",
+            "--from",
+            "json",
+            "--unicode",
+            "nfc",
+            "--drop-empty",
+        ])
+        .write_stdin(r#"{"a":""}"#)
+        .output()
+        .expect("run canon");
+    assert_eq!(output.status.code(), Some(0));
+    let stderr: Value = serde_json::from_slice(&output.stderr).expect("stderr json");
+    let guards = stderr["deterministic_guards"]
+        .as_array()
+        .expect("guards array");
+    assert!(guards.contains(&json!("strings_unicode_nfc")));
+    assert!(guards.contains(&json!("empty_fields_dropped")));
+}
+
+#[test]
+fn canon_time_paths_normalize_epoch_and_naive_timestamps() {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "canon",
+            "--from",
+            "json",
+            "--time-path",
+            r#"$["created"]"#,
+            "--time-path",
+            r#"$["local"]"#,
+            "--time-format",
+            "epoch_s",
+            "--time-format",
+            "%Y/%m/%d %H:%M",
+            "--time-zone",
+            "+09:00",
+            "--time-precision",
+            "ms",
+        ])
+        .write_stdin(r#"{"created":1700000000,"local":"2026/02/23 20:15","count":1700000000}"#)
+        .output()
+        .expect("run canon");
+    assert_eq!(output.status.code(), Some(0));
+    let out: Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    assert_eq!(
+        out,
+        json!({
+            "count": 1700000000,
+            "created": "2023-11-14T22:13:20.000Z",
+            "local": "2026-02-23T11:15:00.000Z"
+        })
+    );
+}
+
+#[test]
+fn canon_time_zone_accepts_iana_names_and_rejects_dst_gaps() {
+    let run = |input: &str| {
+        assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .args([
+                "canon",
+                "--from",
+                "json",
+                "--time-path",
+                r#"$["at"]"#,
+                "--time-format",
+                "%Y-%m-%d %H:%M",
+                "--time-zone",
+                "America/New_York",
+            ])
+            .write_stdin(input)
+            .output()
+            .expect("run canon")
+    };
+
+    let output = run(r#"[{"at":"2026-01-15 12:00"},{"at":"2026-07-15 12:00"}]"#);
+    assert_eq!(output.status.code(), Some(0));
+    let out: Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    assert_eq!(
+        out,
+        json!([{"at": "2026-01-15T17:00:00Z"}, {"at": "2026-07-15T16:00:00Z"}])
+    );
+
+    let output = run(r#"{"at":"2026-03-08 02:30"}"#);
+    assert_eq!(output.status.code(), Some(3));
+    let stderr: Value = serde_json::from_slice(&output.stderr).expect("stderr json");
+    assert_eq!(stderr["error"], json!("input_usage_error"));
+    let message = stderr["message"].as_str().expect("message");
+    assert!(message.contains("does not exist"), "{message}");
+    assert!(message.contains("America/New_York"), "{message}");
+
+    let output = run(r#"{"at":"2026-11-01 01:30"}"#);
+    assert_eq!(output.status.code(), Some(3));
+    let stderr: Value = serde_json::from_slice(&output.stderr).expect("stderr json");
+    assert!(
+        stderr["message"]
+            .as_str()
+            .expect("message")
+            .contains("ambiguous")
+    );
+}
+
+#[test]
+fn canon_time_target_epoch_ms() {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "canon",
+            "--from",
+            "json",
+            "--time-path",
+            r#"$["ts"]"#,
+            "--time-target",
+            "epoch_ms",
+        ])
+        .write_stdin(r#"{"ts":"2023-11-14T22:13:20.5Z"}"#)
+        .output()
+        .expect("run canon");
+    assert_eq!(output.status.code(), Some(0));
+    let out: Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    assert_eq!(out, json!({"ts": 1700000000500_i64}));
+}
+
+#[test]
+fn canon_time_settings_without_path_are_input_usage_error() {
+    assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["canon", "--from", "json", "--time-format", "epoch_s"])
+        .write_stdin(r#"{"ts":1}"#)
+        .assert()
+        .code(3);
+}
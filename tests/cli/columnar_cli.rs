@@ -0,0 +1,118 @@
+#![cfg(feature = "columnar")]
+
+use std::fs;
+use std::path::Path;
+
+use dataq::io::Format;
+use dataq::io::writer::write_values;
+use serde_json::{Value, json};
+use tempfile::tempdir;
+
+fn write_columnar(path: &Path, format: Format, values: &[Value]) {
+    let mut bytes = Vec::new();
+    write_values(&mut bytes, format, values).expect("write columnar fixture");
+    fs::write(path, bytes).expect("write fixture file");
+}
+
+#[test]
+fn canon_reads_parquet_input_by_extension() {
+    let dir = tempdir().expect("tempdir");
+    let input = dir.path().join("snapshot.parquet");
+    write_columnar(
+        &input,
+        Format::Parquet,
+        &[json!({"id": 2, "name": "b"}), json!({"id": 1, "name": "a"})],
+    );
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "canon",
+            "--input",
+            input.to_str().expect("utf8"),
+            "--to",
+            "jsonl",
+        ])
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+    let text = String::from_utf8(output).expect("utf8 stdout");
+    assert_eq!(
+        text,
+        "{\"id\":2,\"name\":\"b\"}\n{\"id\":1,\"name\":\"a\"}\n"
+    );
+}
+
+#[test]
+fn profile_reads_arrow_ipc_from_stdin() {
+    let mut bytes = Vec::new();
+    write_values(
+        &mut bytes,
+        Format::ArrowIpc,
+        &[json!({"score": 1.5}), json!({"score": null})],
+    )
+    .expect("write arrow");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["profile", "--from", "arrow"])
+        .write_stdin(bytes)
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+    let report: Value = serde_json::from_slice(&output).expect("profile json");
+    assert_eq!(report["record_count"], json!(2));
+    assert_eq!(report["fields"]["$[\"score\"]"]["null_ratio"], json!(0.5));
+}
+
+#[test]
+fn sdiff_and_assert_accept_parquet_paths() {
+    let dir = tempdir().expect("tempdir");
+    let left = dir.path().join("left.parquet");
+    let right = dir.path().join("right.parquet");
+    write_columnar(
+        &left,
+        Format::Parquet,
+        &[json!({"id": 1, "status": "active"})],
+    );
+    write_columnar(
+        &right,
+        Format::Parquet,
+        &[json!({"id": 1, "status": "archived"})],
+    );
+
+    let sdiff_output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "sdiff",
+            "--left",
+            left.to_str().expect("utf8"),
+            "--right",
+            right.to_str().expect("utf8"),
+        ])
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+    let report: Value = serde_json::from_slice(&sdiff_output).expect("sdiff json");
+    assert_eq!(report["values"]["total"], json!(1));
+
+    let rules = dir.path().join("rules.yaml");
+    fs::write(
+        &rules,
+        "required_keys: [id]\nfields:\n  status:\n    enum: [active]\n",
+    )
+    .expect("write rules");
+    assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "assert",
+            "--rules",
+            rules.to_str().expect("utf8"),
+            "--input",
+            right.to_str().expect("utf8"),
+        ])
+        .assert()
+        .code(2);
+}
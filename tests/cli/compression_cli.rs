@@ -0,0 +1,77 @@
+use std::fs;
+
+use dataq::io::writer::write_values_compressed;
+use dataq::io::{Compression, Format};
+use serde_json::{Value, json};
+use tempfile::tempdir;
+
+fn compressed_bytes(format: Format, compression: Compression, values: &[Value]) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_values_compressed(&mut out, format, compression, values).expect("compress fixture");
+    out
+}
+
+#[test]
+fn canon_autodetects_gzip_jsonl_on_stdin() {
+    let input = compressed_bytes(
+        Format::Jsonl,
+        Compression::Gzip,
+        &[json!({"b": 1, "a": 2}), json!({"b": 3, "a": 4})],
+    );
+
+    assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["canon", "--to", "jsonl"])
+        .write_stdin(input)
+        .assert()
+        .code(0)
+        .stdout("{\"a\":2,\"b\":1}\n{\"a\":4,\"b\":3}\n");
+}
+
+#[test]
+fn sdiff_and_profile_read_compressed_paths() {
+    let dir = tempdir().expect("tempdir");
+    let left = dir.path().join("left.jsonl.gz");
+    let right = dir.path().join("right.json.zst");
+    fs::write(
+        &left,
+        compressed_bytes(Format::Jsonl, Compression::Gzip, &[json!({"id": 1})]),
+    )
+    .expect("write left");
+    fs::write(
+        &right,
+        compressed_bytes(Format::Json, Compression::Zstd, &[json!({"id": 2})]),
+    )
+    .expect("write right");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "sdiff",
+            "--left",
+            left.to_str().expect("utf8"),
+            "--right",
+            right.to_str().expect("utf8"),
+        ])
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+    let report: Value = serde_json::from_slice(&output).expect("sdiff json");
+    assert_eq!(report["values"]["total"], json!(1));
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "profile",
+            "--from",
+            "jsonl",
+            "--input",
+            left.to_str().expect("utf8"),
+        ])
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+    let report: Value = serde_json::from_slice(&output).expect("profile json");
+    assert_eq!(report["record_count"], json!(1));
+}
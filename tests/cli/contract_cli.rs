@@ -68,6 +68,7 @@ fn contract_all_returns_deterministic_order() {
             "doctor",
             "recipe-run",
             "recipe-lock",
+            "recipe-replay",
         ]
     );
     for entry in contracts {
@@ -192,7 +193,11 @@ fn contract_recipe_run_command_matches_recipe_run_shape() {
 
     let payload: Value = serde_json::from_slice(&output.stdout).expect("stdout json");
     assert_eq!(payload["command"], json!("recipe-run"));
-    assert_eq!(payload["schema"], json!("dataq.recipe.run.output.v1"));
+    assert_eq!(payload["schema"], json!("dataq.recipe.run.output.v2"));
+    assert_eq!(
+        payload["supported_versions"],
+        json!(["dataq.recipe.run.output.v1", "dataq.recipe.run.output.v2"])
+    );
     assert_eq!(
         payload["output_fields"],
         json!(["matched", "exit_code", "steps"])
@@ -244,8 +249,419 @@ fn contract_recipe_lock_command_reports_lock_output_shape() {
     assert_eq!(
         payload["notes"],
         json!([
-            "`tool_versions` keys are deterministically sorted by tool name (`jq`, `mlr`, `yq`).",
-            "Lock output is canonicalized before write/emit."
+            "`tool_versions` always records `jq`, `mlr`, `yq` and adds `pandoc`/`rg` when `ingest_doc`/`scan_text` steps are present; keys are sorted by tool name.",
+            "Lock output is canonicalized before write/emit.",
+            "`input_hashes` (file content hashes keyed by recipe-relative path) is present only when steps read files."
         ])
     );
 }
+
+#[test]
+fn contract_json_schema_format_documents_stdout_error_and_pipeline() {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["contract", "--command", "sdiff", "--format", "json-schema"])
+        .output()
+        .expect("run contract --format json-schema");
+    assert_eq!(output.status.code(), Some(0));
+
+    let document: Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    assert_eq!(
+        document["$schema"],
+        json!("https://json-schema.org/draft/2020-12/schema")
+    );
+    assert_eq!(document["title"], json!("dataq.sdiff.output.v1"));
+    assert_eq!(document["$ref"], json!("#/$defs/success"));
+    assert_eq!(
+        document["$defs"]["success"]["required"],
+        json!(["counts", "keys", "ignored_paths", "values"])
+    );
+    assert_eq!(
+        document["$defs"]["error"]["required"],
+        json!(["error", "message"])
+    );
+    assert!(document["$defs"]["pipeline"]["properties"]["steps"].is_object());
+    assert_eq!(
+        document["x-dataq-exit-codes"],
+        json!({
+            "0": {"stream": "stdout", "$ref": "#/$defs/success"},
+            "2": {"stream": "stdout", "$ref": "#/$defs/success"},
+            "3": {"stream": "stderr", "$ref": "#/$defs/error"},
+            "1": {"stream": "stderr", "$ref": "#/$defs/error"}
+        })
+    );
+
+    let all = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["contract", "--all", "--format", "json-schema"])
+        .output()
+        .expect("run contract --all --format json-schema");
+    assert_eq!(all.status.code(), Some(0));
+    let documents: Value = serde_json::from_slice(&all.stdout).expect("stdout json");
+    let documents = documents.as_array().expect("document array");
+    assert_eq!(documents.len(), 19);
+    for document in documents {
+        jsonschema::validator_for(document)
+            .unwrap_or_else(|error| panic!("{}: {error}", document["title"]));
+    }
+}
+
+#[test]
+fn contract_verify_validates_captured_outputs() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let input_path = dir.path().join("input.json");
+    std::fs::write(&input_path, r#"[{"id":1},{"id":2}]"#).expect("write input");
+
+    let profile = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["--emit-pipeline", "profile", "--from", "json", "--input"])
+        .arg(&input_path)
+        .output()
+        .expect("run profile");
+    assert_eq!(profile.status.code(), Some(0));
+    let stdout_path = dir.path().join("stdout.json");
+    let stderr_path = dir.path().join("stderr.json");
+    std::fs::write(&stdout_path, &profile.stdout).expect("write stdout capture");
+    std::fs::write(&stderr_path, &profile.stderr).expect("write stderr capture");
+
+    let verify = |payload: &str, input: &std::path::Path| {
+        assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .args([
+                "contract",
+                "verify",
+                "--command",
+                "profile",
+                "--payload",
+                payload,
+            ])
+            .arg("--input")
+            .arg(input)
+            .output()
+            .expect("run contract verify")
+    };
+
+    let success = verify("success", &stdout_path);
+    assert_eq!(success.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&success.stdout).expect("verify json");
+    assert_eq!(
+        report,
+        json!({
+            "command": "profile",
+            "schema": "dataq.profile.output.v1",
+            "payload": "success",
+            "valid": true,
+            "errors": []
+        })
+    );
+    assert_eq!(verify("pipeline", &stderr_path).status.code(), Some(0));
+
+    let broken_path = dir.path().join("broken.json");
+    std::fs::write(&broken_path, r#"{"record_count":"2","fields":{}}"#).expect("write broken");
+    let mismatch = verify("success", &broken_path);
+    assert_eq!(mismatch.status.code(), Some(2));
+    let report: Value = serde_json::from_slice(&mismatch.stdout).expect("verify json");
+    assert_eq!(report["valid"], json!(false));
+    let errors = report["errors"].as_array().expect("errors array");
+    assert!(
+        errors
+            .iter()
+            .any(|error| error["path"] == json!("/record_count"))
+    );
+    assert!(
+        errors
+            .iter()
+            .any(|error| error["message"] == json!("\"field_count\" is a required property"))
+    );
+
+    let cli_error = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["profile", "--from", "json", "--input"])
+        .arg(dir.path().join("missing.json"))
+        .output()
+        .expect("run failing profile");
+    assert_eq!(cli_error.status.code(), Some(3));
+    let error_path = dir.path().join("error.json");
+    std::fs::write(&error_path, &cli_error.stderr).expect("write error capture");
+    assert_eq!(verify("error", &error_path).status.code(), Some(0));
+    assert_eq!(verify("success", &error_path).status.code(), Some(2));
+
+    assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["contract", "verify", "--command", "profile", "--input"])
+        .arg(input_path.with_extension("missing"))
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("\"error\":\"input_usage_error\""));
+}
+
+#[test]
+fn contract_verify_checks_each_json_lines_document() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let verify = |command: &str, payload: &str, input: &std::path::Path| {
+        assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .args([
+                "contract",
+                "verify",
+                "--command",
+                command,
+                "--payload",
+                payload,
+            ])
+            .arg("--input")
+            .arg(input)
+            .output()
+            .expect("run contract verify")
+    };
+
+    let lines_path = dir.path().join("profile.jsonl");
+    std::fs::write(
+        &lines_path,
+        concat!(
+            r#"{"record_count":0,"field_count":0,"fields":{}}"#,
+            "\n\n",
+            r#"{"record_count":"2","field_count":0,"fields":{}}"#,
+            "\n",
+        ),
+    )
+    .expect("write jsonl");
+    let mismatch = verify("profile", "success", &lines_path);
+    assert_eq!(mismatch.status.code(), Some(2));
+    let report: Value = serde_json::from_slice(&mismatch.stdout).expect("verify json");
+    let errors = report["errors"].as_array().expect("errors array");
+    assert!(!errors.is_empty());
+    assert!(errors.iter().all(|error| error["line"] == json!(3)));
+    assert!(
+        errors
+            .iter()
+            .any(|error| error["path"] == json!("/record_count"))
+    );
+
+    let torn_path = dir.path().join("torn.jsonl");
+    std::fs::write(
+        &torn_path,
+        concat!(r#"{"record_count":0,"field_count":0,"fields":{}}"#, "\n{\n"),
+    )
+    .expect("write torn jsonl");
+    let torn = verify("profile", "success", &torn_path);
+    assert_eq!(torn.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&torn.stderr).contains("line 2"));
+}
+
+#[test]
+fn contract_verify_rejects_success_payload_without_schema() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let input_path = dir.path().join("any.json");
+    std::fs::write(&input_path, r#"{"anything":true}"#).expect("write input");
+
+    for command in ["canon", "merge"] {
+        assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .args(["contract", "verify", "--command", command, "--input"])
+            .arg(&input_path)
+            .assert()
+            .code(3)
+            .stderr(predicate::str::contains("has no success payload schema"));
+    }
+
+    let error_path = dir.path().join("error.json");
+    std::fs::write(
+        &error_path,
+        r#"{"error":"input_usage_error","message":"bad input"}"#,
+    )
+    .expect("write error capture");
+    assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "contract",
+            "verify",
+            "--command",
+            "canon",
+            "--payload",
+            "error",
+            "--input",
+        ])
+        .arg(&error_path)
+        .assert()
+        .code(0);
+}
+
+#[test]
+fn contract_diff_reports_breaking_and_additive_changes_against_snapshot() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let snapshot = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["contract", "--all", "--format", "json-schema"])
+        .output()
+        .expect("run contract snapshot");
+    assert_eq!(snapshot.status.code(), Some(0));
+    let current_path = dir.path().join("current.json");
+    std::fs::write(&current_path, &snapshot.stdout).expect("write snapshot");
+
+    let diff = |from: &std::path::Path| {
+        assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .args(["contract", "diff", "--from"])
+            .arg(from)
+            .output()
+            .expect("run contract diff")
+    };
+
+    let unchanged = diff(&current_path);
+    assert_eq!(unchanged.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&unchanged.stdout).expect("diff json");
+    assert_eq!(report["format"], json!("json-schema"));
+    assert_eq!(report["compatible"], json!(true));
+    assert_eq!(report["changes"], json!([]));
+
+    let mut documents: Vec<Value> = serde_json::from_slice(&snapshot.stdout).expect("documents");
+    let sdiff = documents
+        .iter_mut()
+        .find(|document| document["x-dataq-command"] == json!("sdiff"))
+        .expect("sdiff document");
+    sdiff["$defs"]["success"]["required"]
+        .as_array_mut()
+        .expect("required")
+        .push(json!("legacy"));
+    sdiff["$defs"]["success"]["properties"]
+        .as_object_mut()
+        .expect("properties")
+        .remove("ignored_paths");
+    let old_path = dir.path().join("old.json");
+    std::fs::write(&old_path, Value::Array(documents).to_string()).expect("write old snapshot");
+
+    let changed = diff(&old_path);
+    assert_eq!(changed.status.code(), Some(2));
+    let report: Value = serde_json::from_slice(&changed.stdout).expect("diff json");
+    assert_eq!(report["compatible"], json!(false));
+    assert_eq!(report["summary"], json!({"breaking": 1, "additive": 0}));
+    assert_eq!(
+        report["changes"],
+        json!([{
+            "command": "sdiff",
+            "payload": "success",
+            "path": "/legacy",
+            "change": "field_removed",
+            "severity": "breaking"
+        }])
+    );
+
+    let not_json = dir.path().join("not.json");
+    std::fs::write(&not_json, "nope").expect("write invalid snapshot");
+    assert_eq!(diff(&not_json).status.code(), Some(3));
+}
+
+#[test]
+fn contract_version_flag_accepts_supported_versions_only() {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["contract", "--command", "sdiff"])
+        .output()
+        .expect("run contract");
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    assert_eq!(
+        payload["supported_versions"],
+        json!(["dataq.sdiff.output.v1"])
+    );
+
+    for version in ["v1", "dataq.profile.output.v1"] {
+        assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .args(["--contract-version", version, "profile", "--from", "json"])
+            .write_stdin(r#"[{"id":1}]"#)
+            .assert()
+            .code(0);
+    }
+
+    assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["profile", "--from", "json", "--contract-version", "v2"])
+        .write_stdin(r#"[{"id":1}]"#)
+        .assert()
+        .code(3)
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains(
+            "contract version `v2` is not supported by `profile`",
+        ));
+
+    assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "--contract-version",
+            "v1",
+            "emit",
+            "plan",
+            "--command",
+            "canon",
+        ])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains(
+            "`--contract-version` requires a subcommand with a versioned output contract",
+        ));
+}
+
+#[test]
+fn contract_version_v1_emits_original_recipe_step_shape() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    std::fs::write(dir.path().join("input.json"), r#"[{"id":1}]"#).expect("write input");
+    let recipe_path = dir.path().join("recipe.yaml");
+    std::fs::write(
+        &recipe_path,
+        r#"
+version: dataq.recipe.v1
+steps:
+  - id: rows
+    kind: canon
+    args:
+      input: input.json
+  - kind: sdiff
+    inputs: [rows]
+    when:
+      step: rows
+      matched: false
+    args:
+      right: input.json
+"#,
+    )
+    .expect("write recipe");
+
+    // A non-strict replay reports the mismatching lock and still runs the steps.
+    let lock_path = dir.path().join("recipe.lock.json");
+    std::fs::write(
+        &lock_path,
+        r#"{"version":"dataq.recipe.lock.v1","command_graph_hash":"x","args_hash":"x","tool_versions":{},"dataq_version":"0"}"#,
+    )
+    .expect("write lock");
+
+    let invoke = |version: Option<&str>, subcommand: &str| {
+        let mut command = assert_cmd::cargo::cargo_bin_cmd!("dataq");
+        if let Some(version) = version {
+            command.args(["--contract-version", version]);
+        }
+        command
+            .args(["recipe", subcommand, "--file"])
+            .arg(&recipe_path);
+        if subcommand == "replay" {
+            command.arg("--lock").arg(&lock_path);
+        }
+        let output = command.output().expect("run recipe");
+        assert_eq!(output.status.code(), Some(0));
+        serde_json::from_slice::<Value>(&output.stdout).expect("stdout json")
+    };
+    let run = |version: Option<&str>| invoke(version, "run");
+    let assert_v1_steps = |payload: &Value| {
+        for step in payload["steps"].as_array().expect("steps array") {
+            let keys: Vec<&str> = step
+                .as_object()
+                .expect("step object")
+                .keys()
+                .map(String::as_str)
+                .collect();
+            assert_eq!(keys, ["index", "kind", "matched", "exit_code", "summary"]);
+        }
+    };
+
+    let latest = run(None);
+    assert_eq!(run(Some("v2")), latest);
+    assert_eq!(latest["steps"][0]["id"], json!("rows"));
+    assert_eq!(latest["steps"][1]["skipped"], json!(true));
+
+    let v1 = run(Some("dataq.recipe.run.output.v1"));
+    assert_eq!(run(Some("v1")), v1);
+    assert_v1_steps(&v1);
+    assert_eq!(v1["matched"], latest["matched"]);
+    assert_eq!(v1["steps"][0]["summary"], latest["steps"][0]["summary"]);
+
+    let replay_latest = invoke(None, "replay");
+    assert_eq!(replay_latest["steps"], latest["steps"]);
+    let replay_v1 = invoke(Some("dataq.recipe.replay.output.v1"), "replay");
+    assert_v1_steps(&replay_v1);
+    assert_eq!(replay_v1["lock_check"], replay_latest["lock_check"]);
+}
@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde_json::{Value, json};
+
+/// CLI arguments intentionally absent from the MCP tool input schemas.
+const CLI_ONLY_ARGUMENTS: [(&str, &str); 2] = [("assert", "rules_help"), ("assert", "schema_help")];
+
+fn run_help(args: &[&str]) -> std::process::Output {
+    assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .arg("help")
+        .args(args)
+        .output()
+        .expect("run help")
+}
+
+fn parse_json(bytes: &[u8]) -> Value {
+    serde_json::from_slice(bytes).expect("json output")
+}
+
+fn collect_commands(command: &Value, into: &mut BTreeMap<String, Value>) {
+    into.insert(
+        command["command"].as_str().expect("command").to_string(),
+        command.clone(),
+    );
+    for subcommand in command["subcommands"].as_array().expect("subcommands") {
+        collect_commands(subcommand, into);
+    }
+}
+
+fn find_argument<'a>(command: &'a Value, name: &str) -> &'a Value {
+    command["arguments"]
+        .as_array()
+        .expect("arguments")
+        .iter()
+        .find(|argument| argument["name"] == name)
+        .unwrap_or_else(|| panic!("missing argument `{name}` in {}", command["command"]))
+}
+
+/// Resolves the enum declared on a property, looking through array items and `oneOf` branches.
+fn schema_enum(property: &Value) -> Option<Vec<Value>> {
+    if let Some(values) = property["enum"].as_array() {
+        return Some(values.clone());
+    }
+    if property["items"].is_object() {
+        return schema_enum(&property["items"]);
+    }
+    ["oneOf", "anyOf"].iter().find_map(|keyword| {
+        property[*keyword]
+            .as_array()
+            .and_then(|branches| branches.iter().find_map(schema_enum))
+    })
+}
+
+#[test]
+fn help_json_describes_every_subcommand() {
+    let output = run_help(&["--json"]);
+    assert_eq!(output.status.code(), Some(0));
+    let payload = parse_json(&output.stdout);
+
+    assert_eq!(payload["name"], json!("dataq"));
+    assert_eq!(payload["version"], json!(env!("CARGO_PKG_VERSION")));
+    let globals: Vec<&str> = payload["global_arguments"]
+        .as_array()
+        .expect("global arguments")
+        .iter()
+        .map(|argument| argument["name"].as_str().expect("name"))
+        .collect();
+    assert_eq!(globals, vec!["emit_pipeline", "contract_version"]);
+
+    let mut commands = BTreeMap::new();
+    for command in payload["commands"].as_array().expect("commands") {
+        collect_commands(command, &mut commands);
+    }
+    for expected in [
+        "canon",
+        "ingest api",
+        "gate policy",
+        "contract verify",
+        "help",
+    ] {
+        assert!(commands.contains_key(expected), "missing {expected}");
+    }
+
+    let canon = &commands["canon"];
+    let coerce = find_argument(canon, "coerce");
+    assert_eq!(coerce["type"], json!("enum"));
+    assert_eq!(coerce["values"], json!(["none", "all", "schema"]));
+    assert_eq!(coerce["default"], json!("all"));
+    assert_eq!(find_argument(canon, "sort_keys")["type"], json!("boolean"));
+    assert_eq!(find_argument(canon, "sort_keys")["default"], json!(true));
+    assert_eq!(
+        find_argument(canon, "coerce_schema")["conflicts_with"],
+        json!(["coerce_rules"])
+    );
+    assert_eq!(
+        find_argument(canon, "coerce_rules")["conflicts_with"],
+        json!(["coerce_schema"])
+    );
+    assert_eq!(
+        find_argument(canon, "coerce_exempt")["multiple"],
+        json!(true)
+    );
+
+    let sdiff = &commands["sdiff"];
+    assert_eq!(find_argument(sdiff, "left")["required"], json!(true));
+    assert_eq!(find_argument(sdiff, "left")["type"], json!("path"));
+    assert_eq!(
+        find_argument(sdiff, "value_diff_cap")["type"],
+        json!("integer")
+    );
+    assert_eq!(find_argument(sdiff, "fail_on_diff")["type"], json!("flag"));
+
+    assert_eq!(
+        commands["assert"]["groups"],
+        json!([{
+            "name": "assert_source",
+            "args": ["rules", "schema", "rules_help", "schema_help"],
+            "required": true,
+            "multiple": false
+        }])
+    );
+    assert_eq!(
+        commands["contract"]["args_conflict_with_subcommands"],
+        json!(true)
+    );
+}
+
+#[test]
+fn help_json_for_one_command_and_unknown_command() {
+    let output = run_help(&["--json", "ingest", "api"]);
+    assert_eq!(output.status.code(), Some(0));
+    let payload = parse_json(&output.stdout);
+    let commands = payload["commands"].as_array().expect("commands");
+    assert_eq!(commands.len(), 1);
+    assert_eq!(commands[0]["command"], json!("ingest api"));
+    assert_eq!(find_argument(&commands[0], "url")["required"], json!(true));
+    assert_eq!(
+        payload["global_arguments"][0]["name"],
+        json!("emit_pipeline")
+    );
+
+    let output = run_help(&["--json", "ingest", "nope"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(output.stdout.is_empty());
+    let error = parse_json(&output.stderr);
+    assert_eq!(error["error"], json!("input_usage_error"));
+    assert!(
+        error["message"]
+            .as_str()
+            .expect("message")
+            .contains("ingest nope")
+    );
+}
+
+#[test]
+fn help_without_json_prints_clap_text_help() {
+    let output = run_help(&["sdiff"]);
+    assert_eq!(output.status.code(), Some(0));
+    let text = String::from_utf8(output.stdout).expect("utf8");
+    assert!(text.contains("Usage: dataq sdiff"));
+    assert!(text.contains("--value-diff-cap"));
+}
+
+#[test]
+fn help_json_stays_in_sync_with_mcp_tool_input_schemas() {
+    let output = run_help(&["--json"]);
+    assert_eq!(output.status.code(), Some(0));
+    let payload = parse_json(&output.stdout);
+    let mut commands = BTreeMap::new();
+    for command in payload["commands"].as_array().expect("commands") {
+        collect_commands(command, &mut commands);
+    }
+
+    let tools_output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .arg("mcp")
+        .write_stdin(json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}).to_string())
+        .output()
+        .expect("run mcp");
+    assert_eq!(tools_output.status.code(), Some(0));
+    let tools = parse_json(&tools_output.stdout)["result"]["tools"].clone();
+
+    for tool in tools.as_array().expect("tools") {
+        let name = tool["name"].as_str().expect("tool name");
+        let path = name
+            .trim_start_matches("dataq.")
+            .replace('.', " ")
+            .replace('_', "-");
+        let command = commands
+            .get(&path)
+            .unwrap_or_else(|| panic!("{name} has no CLI command `{path}`"));
+        let properties = tool["inputSchema"]["properties"]
+            .as_object()
+            .expect("input schema properties");
+        assert!(
+            properties.contains_key("emit_pipeline"),
+            "{name} lacks emit_pipeline"
+        );
+
+        for argument in command["arguments"].as_array().expect("arguments") {
+            let argument_name = argument["name"].as_str().expect("argument name");
+            if CLI_ONLY_ARGUMENTS.contains(&(path.as_str(), argument_name)) {
+                continue;
+            }
+            let property = [
+                argument_name.to_string(),
+                format!("{argument_name}_path"),
+                format!("{argument_name}_paths"),
+            ]
+            .iter()
+            .find_map(|key| properties.get(key))
+            .unwrap_or_else(|| panic!("{name} has no property for `{path} --{argument_name}`"));
+
+            if let Some(values) = argument["values"].as_array() {
+                match schema_enum(property) {
+                    Some(declared) => assert_eq!(
+                        &declared, values,
+                        "{name}.{argument_name} enum differs from CLI"
+                    ),
+                    None => {
+                        let pattern = Regex::new(
+                            property["pattern"]
+                                .as_str()
+                                .unwrap_or_else(|| panic!("{name}.{argument_name} has no enum")),
+                        )
+                        .expect("pattern");
+                        for value in values {
+                            assert!(
+                                pattern.is_match(value.as_str().expect("value")),
+                                "{name}.{argument_name} rejects CLI value {value}"
+                            );
+                        }
+                    }
+                }
+            }
+            if !argument["default"].is_null() {
+                assert_eq!(
+                    property["default"], argument["default"],
+                    "{name}.{argument_name} default differs from CLI"
+                );
+            }
+        }
+    }
+}
@@ -71,6 +71,700 @@ fn initialize_returns_expected_shape() {
     assert!(response["result"]["capabilities"]["tools"].is_object());
 }
 
+#[test]
+fn serve_mode_handles_session_with_notifications_and_batches() {
+    let session = [
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+        json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+        json!([
+            {"jsonrpc": "2.0", "id": 2, "method": "tools/list"},
+            {"jsonrpc": "2.0", "id": "p", "method": "ping"}
+        ]),
+        tool_call_request(
+            3,
+            "dataq.canon",
+            json!({"input_inline": [{"b": 1, "a": 2}]}),
+        ),
+    ]
+    .iter()
+    .map(Value::to_string)
+    .collect::<Vec<_>>()
+    .join("\n");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["mcp", "--serve"])
+        .write_stdin(session)
+        .output()
+        .expect("run mcp --serve");
+
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).expect("stdout utf8");
+    let responses: Vec<Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("response line json"))
+        .collect();
+    assert_eq!(responses.len(), 3);
+    assert_eq!(responses[0]["id"], Value::from(1));
+    assert_eq!(
+        responses[0]["result"]["serverInfo"]["name"],
+        Value::from("dataq")
+    );
+
+    let batch = responses[1].as_array().expect("batch response array");
+    assert_eq!(batch.len(), 2);
+    assert_eq!(batch[0]["id"], Value::from(2));
+    assert!(batch[0]["result"]["tools"].is_array());
+    assert_eq!(batch[1]["id"], Value::from("p"));
+    assert_eq!(batch[1]["result"], json!({}));
+
+    assert_eq!(responses[2]["id"], Value::from(3));
+    assert_eq!(responses[2]["result"]["isError"], Value::from(false));
+}
+
+#[test]
+fn serve_emits_progress_notifications_for_tool_calls_with_progress_token() {
+    let toolchain = FakeToolchain::new();
+    let rowset_args = json!({
+        "input": [{"team":"a","price":10.0}],
+        "jq_filter": ".",
+        "mlr": ["sort", "-f", "team"]
+    });
+    let mut with_token = tool_call_request(1, "dataq.transform.rowset", rowset_args.clone());
+    with_token["params"]["_meta"] = json!({"progressToken": "rowset-1"});
+    let session = [
+        with_token,
+        tool_call_request(2, "dataq.transform.rowset", rowset_args),
+    ]
+    .iter()
+    .map(Value::to_string)
+    .collect::<Vec<_>>()
+    .join("\n");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["mcp", "--serve"])
+        .env("DATAQ_MLR_BIN", &toolchain.mlr_bin)
+        .env("PATH", toolchain.path_with_current())
+        .write_stdin(session)
+        .output()
+        .expect("run mcp --serve");
+    assert_eq!(output.status.code(), Some(0));
+
+    let messages: Vec<Value> = String::from_utf8(output.stdout)
+        .expect("stdout utf8")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("message line json"))
+        .collect();
+    let progress: Vec<&Value> = messages
+        .iter()
+        .filter(|message| message["method"] == "notifications/progress")
+        .collect();
+    assert_eq!(
+        progress
+            .iter()
+            .map(|message| message["params"].clone())
+            .collect::<Vec<_>>(),
+        vec![
+            json!({"progressToken": "rowset-1", "progress": 1, "message": "transform_rowset_jq"}),
+            json!({"progressToken": "rowset-1", "progress": 2, "message": "transform_rowset_mlr"}),
+        ]
+    );
+
+    let position = |id: i64| {
+        messages
+            .iter()
+            .position(|message| message["id"] == id)
+            .expect("response present")
+    };
+    let last_progress = messages
+        .iter()
+        .rposition(|message| message["method"] == "notifications/progress")
+        .expect("progress present");
+    assert!(last_progress < position(1));
+    assert_eq!(
+        messages[position(1)]["result"]["isError"],
+        Value::from(false)
+    );
+    assert_eq!(
+        messages[position(2)]["result"]["isError"],
+        Value::from(false)
+    );
+    assert_eq!(messages.len(), 4);
+}
+
+#[cfg(unix)]
+#[test]
+fn serve_cancelled_notification_kills_running_tool_and_suppresses_response() {
+    let toolchain = FakeToolchain::new();
+    let dir = tempdir().expect("tempdir");
+    let slow_mlr = dir.path().join("slow-mlr");
+    fs::write(&slow_mlr, "#!/bin/sh\nexec sleep 30\n").expect("write slow mlr");
+    set_executable(&slow_mlr);
+
+    let session = [
+        tool_call_request(
+            7,
+            "dataq.transform.rowset",
+            json!({
+                "input": [{"team":"a"}],
+                "jq_filter": ".",
+                "mlr": ["sort", "-f", "team"]
+            }),
+        ),
+        json!({"jsonrpc": "2.0", "method": "notifications/cancelled", "params": {"requestId": 7, "reason": "user abort"}}),
+        json!({"jsonrpc": "2.0", "id": 8, "method": "ping"}),
+    ]
+    .iter()
+    .map(Value::to_string)
+    .collect::<Vec<_>>()
+    .join("\n");
+
+    let started = std::time::Instant::now();
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["mcp", "--serve"])
+        .env("DATAQ_MLR_BIN", &slow_mlr)
+        .env("PATH", toolchain.path_with_current())
+        .write_stdin(session)
+        .output()
+        .expect("run mcp --serve");
+    assert!(started.elapsed() < std::time::Duration::from_secs(20));
+    assert_eq!(output.status.code(), Some(0));
+
+    let responses: Vec<Value> = String::from_utf8(output.stdout)
+        .expect("stdout utf8")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("response line json"))
+        .collect();
+    assert_eq!(
+        responses,
+        vec![json!({"jsonrpc": "2.0", "id": 8, "result": {}})]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn serve_caps_concurrent_tool_calls_and_queues_the_rest() {
+    let toolchain = FakeToolchain::new();
+    let dir = tempdir().expect("tempdir");
+    let running = dir.path().join("running");
+    fs::create_dir_all(&running).expect("create running dir");
+    let tracking_mlr = dir.path().join("tracking-mlr");
+    fs::write(
+        &tracking_mlr,
+        format!(
+            "#!/bin/sh\nmkdir \"{running}/$$\"\nls \"{running}\" | wc -l >> \"{peaks}\"\nsleep 0.2\nrmdir \"{running}/$$\"\nexec \"{mlr}\" \"$@\"\n",
+            running = running.display(),
+            peaks = dir.path().join("peaks").display(),
+            mlr = toolchain.mlr_bin.display(),
+        ),
+    )
+    .expect("write tracking mlr");
+    set_executable(&tracking_mlr);
+
+    let session = (1..=20)
+        .map(|id| {
+            tool_call_request(
+                id,
+                "dataq.transform.rowset",
+                json!({
+                    "input": [{"team":"a"}],
+                    "jq_filter": ".",
+                    "mlr": ["sort", "-f", "team"]
+                }),
+            )
+            .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["mcp", "--serve"])
+        .env("DATAQ_MLR_BIN", &tracking_mlr)
+        .env("PATH", toolchain.path_with_current())
+        .write_stdin(session)
+        .output()
+        .expect("run mcp --serve");
+    assert_eq!(output.status.code(), Some(0));
+
+    let mut ids: Vec<i64> = String::from_utf8(output.stdout)
+        .expect("stdout utf8")
+        .lines()
+        .map(|line| {
+            let response: Value = serde_json::from_str(line).expect("response line json");
+            assert_eq!(response["result"]["isError"], json!(false));
+            response["id"].as_i64().expect("response id")
+        })
+        .collect();
+    ids.sort_unstable();
+    assert_eq!(ids, (1..=20).collect::<Vec<_>>());
+
+    let peak = fs::read_to_string(dir.path().join("peaks"))
+        .expect("read peaks")
+        .lines()
+        .map(|line| line.trim().parse::<usize>().expect("peak count"))
+        .max()
+        .expect("at least one mlr run");
+    assert!(peak <= 8, "peak concurrency {peak} exceeds the cap");
+    assert!(peak > 1, "tool calls no longer run concurrently");
+}
+
+#[test]
+fn resources_list_and_read_expose_contracts_help_and_workspace_files() {
+    let workspace = tempdir().expect("tempdir");
+    fs::create_dir_all(workspace.path().join("recipes")).expect("create recipes dir");
+    let recipe = r#"{"version":"dataq.recipe.v1","steps":[]}"#;
+    fs::write(workspace.path().join("recipes/ci.json"), recipe).expect("write recipe");
+    fs::write(
+        workspace.path().join("rules.json"),
+        r#"{"required_keys":["id"]}"#,
+    )
+    .expect("write rules");
+    fs::write(workspace.path().join("data.json"), r#"[{"id":1}]"#).expect("write data");
+
+    let session = [
+        json!({"jsonrpc": "2.0", "id": 1, "method": "resources/list"}),
+        json!({"jsonrpc": "2.0", "id": 2, "method": "resources/read", "params": {"uri": "dataq://contract/all"}}),
+        json!({"jsonrpc": "2.0", "id": 3, "method": "resources/read", "params": {"uri": "dataq://assert/rules-help"}}),
+        json!({"jsonrpc": "2.0", "id": 4, "method": "resources/read", "params": {"uri": "dataq://workspace/recipes/ci.json"}}),
+        json!({"jsonrpc": "2.0", "id": 5, "method": "resources/read", "params": {"uri": "dataq://workspace/data.json"}}),
+        json!({"jsonrpc": "2.0", "id": 6, "method": "resources/read", "params": {"uri": "dataq://workspace/../rules.json"}}),
+    ]
+    .iter()
+    .map(Value::to_string)
+    .collect::<Vec<_>>()
+    .join("\n");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["mcp", "--serve"])
+        .current_dir(workspace.path())
+        .write_stdin(session)
+        .output()
+        .expect("run mcp --serve");
+    assert_eq!(output.status.code(), Some(0));
+    let responses: Vec<Value> = String::from_utf8(output.stdout)
+        .expect("stdout utf8")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("response line json"))
+        .collect();
+    assert_eq!(responses.len(), 6);
+
+    let uris: Vec<&str> = responses[0]["result"]["resources"]
+        .as_array()
+        .expect("resources array")
+        .iter()
+        .map(|resource| resource["uri"].as_str().expect("uri"))
+        .collect();
+    assert_eq!(
+        uris,
+        vec![
+            "dataq://contract/all",
+            "dataq://assert/rules-help",
+            "dataq://assert/schema-help",
+            "dataq://workspace/recipes/ci.json",
+            "dataq://workspace/rules.json",
+        ]
+    );
+
+    let contracts: Value = serde_json::from_str(
+        responses[1]["result"]["contents"][0]["text"]
+            .as_str()
+            .expect("contract text"),
+    )
+    .expect("contract json");
+    assert_eq!(contracts[0]["command"], json!("canon"));
+    let rules_help: Value = serde_json::from_str(
+        responses[2]["result"]["contents"][0]["text"]
+            .as_str()
+            .expect("rules help text"),
+    )
+    .expect("rules help json");
+    assert!(rules_help.is_object());
+    assert_eq!(responses[3]["result"]["contents"][0]["text"], json!(recipe));
+    assert_eq!(
+        responses[3]["result"]["contents"][0]["mimeType"],
+        json!("application/json")
+    );
+    assert_eq!(responses[4]["error"]["code"], json!(-32002));
+    assert_eq!(responses[5]["error"]["code"], json!(-32002));
+}
+
+#[test]
+fn prompts_list_and_get_render_workflow_templates() {
+    let list = run_mcp(
+        &json!({"jsonrpc": "2.0", "id": 1, "method": "prompts/list"}),
+        None,
+    );
+    assert_eq!(list.status.code(), Some(0));
+    let list = parse_stdout_json(&list.stdout);
+    let prompts = list["result"]["prompts"].as_array().expect("prompts array");
+    let names: Vec<&str> = prompts
+        .iter()
+        .map(|prompt| prompt["name"].as_str().expect("prompt name"))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "dataq.gate_ci_workflow",
+            "dataq.explain_sdiff",
+            "dataq.draft_rules"
+        ]
+    );
+    assert_eq!(prompts[1]["meta"]["tools"], json!(["dataq.sdiff"]));
+    assert_eq!(
+        prompts[1]["arguments"][0],
+        json!({"name": "left_path", "description": "Path to the baseline dataset", "required": true})
+    );
+
+    let get = run_mcp(
+        &json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "prompts/get",
+            "params": {
+                "name": "dataq.explain_sdiff",
+                "arguments": {"left_path": "before.json", "right_path": "after.json", "key": "$[\"id\"]"}
+            }
+        }),
+        None,
+    );
+    assert_eq!(get.status.code(), Some(0));
+    let get = parse_stdout_json(&get.stdout);
+    let message = &get["result"]["messages"][0];
+    assert_eq!(message["role"], json!("user"));
+    let text = message["content"]["text"].as_str().expect("prompt text");
+    assert!(text.contains(
+        r#"Call `dataq.sdiff` with {"left_path":"before.json","right_path":"after.json","key":"$[\"id\"]"}"#
+    ));
+    assert!(text.contains("Tool input schemas:\n- `dataq.sdiff`: {"));
+
+    let missing = run_mcp(
+        &json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "prompts/get",
+            "params": {"name": "dataq.draft_rules"}
+        }),
+        None,
+    );
+    let missing = parse_stdout_json(&missing.stdout);
+    assert_eq!(missing["error"]["code"], json!(-32602));
+    assert_eq!(
+        missing["error"]["message"],
+        json!("missing required prompt argument `input_path`")
+    );
+}
+
+#[test]
+fn root_allowlist_rejects_paths_outside_workspace_and_in_recipes() {
+    let temp = tempdir().expect("tempdir");
+    let workspace = temp.path().join("workspace");
+    fs::create_dir_all(&workspace).expect("create workspace");
+    fs::write(workspace.join("data.json"), r#"[{"b":1,"a":2}]"#).expect("write data");
+    let secret = temp.path().join("secret.json");
+    fs::write(&secret, r#"[{"token":"x"}]"#).expect("write secret");
+    let secret = secret.display().to_string();
+
+    let session = [
+        tool_call_request(1, "dataq.canon", json!({"input_path": "data.json"})),
+        tool_call_request(2, "dataq.canon", json!({"input_path": "../secret.json"})),
+        tool_call_request(
+            3,
+            "dataq.sdiff",
+            json!({"left_path": "data.json", "right_file": secret}),
+        ),
+        tool_call_request(
+            4,
+            "dataq.recipe.run",
+            json!({"recipe": {
+                "version": "dataq.recipe.v1",
+                "steps": [{"kind": "canon", "args": {"input": secret}}]
+            }}),
+        ),
+    ]
+    .iter()
+    .map(Value::to_string)
+    .collect::<Vec<_>>()
+    .join("\n");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["mcp", "--serve", "--root"])
+        .arg(&workspace)
+        .current_dir(&workspace)
+        .write_stdin(session)
+        .output()
+        .expect("run mcp --serve --root");
+    assert_eq!(output.status.code(), Some(0));
+    let mut responses: Vec<Value> = String::from_utf8(output.stdout)
+        .expect("stdout utf8")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("response line json"))
+        .collect();
+    assert_eq!(responses.len(), 4);
+    // tools/call runs concurrently, so check responses in id order rather than completion order.
+    responses.sort_by_key(|response| response["id"].as_i64());
+
+    let structured = |index: usize| &responses[index]["result"]["structuredContent"];
+    assert_eq!(structured(0)["exit_code"], json!(0));
+    assert_eq!(structured(0)["payload"], json!({"a": 2, "b": 1}));
+
+    for (index, name, reason) in [
+        (1, "input_path", "path_traversal"),
+        (2, "right_file", "outside_root"),
+        (3, "recipe", "outside_root"),
+    ] {
+        assert_eq!(structured(index)["exit_code"], json!(3));
+        assert_eq!(
+            structured(index)["payload"]["error"],
+            json!("input_usage_error")
+        );
+        assert_eq!(
+            structured(index)["payload"]["invalid_params"],
+            json!([{"name": name, "reason": reason}])
+        );
+    }
+
+    let missing_root = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["mcp", "--root"])
+        .arg(temp.path().join("missing"))
+        .write_stdin("")
+        .output()
+        .expect("run mcp with missing root");
+    assert_eq!(missing_root.status.code(), Some(3));
+    let stderr: Value = serde_json::from_slice(&missing_root.stderr).expect("stderr json");
+    assert_eq!(stderr["error"], json!("input_usage_error"));
+}
+
+#[test]
+fn root_allowlist_checks_rules_extends_and_recipe_includes() {
+    let temp = tempdir().expect("tempdir");
+    let workspace = temp.path().join("workspace");
+    fs::create_dir_all(&workspace).expect("create workspace");
+    fs::write(workspace.join("data.json"), r#"[{"id":1}]"#).expect("write data");
+    let secret_rules = temp.path().join("secret.rules.json");
+    fs::write(&secret_rules, r#"{"required_keys":["id"]}"#).expect("write secret rules");
+    let secret_recipe = temp.path().join("secret.recipe.json");
+    fs::write(
+        &secret_recipe,
+        r#"{"version":"dataq.recipe.v1","steps":[]}"#,
+    )
+    .expect("write secret recipe");
+    fs::write(
+        workspace.join("rules.json"),
+        json!({"extends": secret_rules.display().to_string()}).to_string(),
+    )
+    .expect("write rules");
+
+    let session = [
+        tool_call_request(
+            1,
+            "dataq.assert",
+            json!({"input_path": "data.json", "rules_path": "rules.json"}),
+        ),
+        tool_call_request(
+            2,
+            "dataq.gate.policy",
+            json!({"input_path": "data.json", "rules_path": "rules.json"}),
+        ),
+        tool_call_request(
+            3,
+            "dataq.recipe.run",
+            json!({"recipe": {
+                "version": "dataq.recipe.v1",
+                "steps": [
+                    {"kind": "canon", "args": {"input": "data.json"}},
+                    {"kind": "assert", "args": {"rules_file": "rules.json"}}
+                ]
+            }}),
+        ),
+        tool_call_request(
+            4,
+            "dataq.recipe.run",
+            json!({"recipe": {
+                "version": "dataq.recipe.v1",
+                "steps": [{"kind": "include", "args": {"path": secret_recipe.display().to_string()}}]
+            }}),
+        ),
+        tool_call_request(
+            5,
+            "dataq.recipe.run",
+            json!({"recipe": {"version": "dataq.recipe.v0", "steps": []}}),
+        ),
+    ]
+    .iter()
+    .map(Value::to_string)
+    .collect::<Vec<_>>()
+    .join("\n");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["mcp", "--serve", "--root"])
+        .arg(&workspace)
+        .current_dir(&workspace)
+        .write_stdin(session)
+        .output()
+        .expect("run mcp --serve --root");
+    assert_eq!(output.status.code(), Some(0));
+    let mut responses: Vec<Value> = String::from_utf8(output.stdout)
+        .expect("stdout utf8")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("response line json"))
+        .collect();
+    assert_eq!(responses.len(), 5);
+    responses.sort_by_key(|response| response["id"].as_i64());
+
+    let structured = |index: usize| &responses[index]["result"]["structuredContent"];
+    for (index, name) in [
+        (0, "rules_path"),
+        (1, "rules_path"),
+        (2, "recipe"),
+        (3, "recipe"),
+    ] {
+        assert_eq!(structured(index)["exit_code"], json!(3), "call {index}");
+        assert_eq!(
+            structured(index)["payload"]["invalid_params"],
+            json!([{"name": name, "reason": "outside_root"}]),
+            "call {index}"
+        );
+    }
+
+    assert_eq!(structured(4)["exit_code"], json!(3));
+    assert_eq!(
+        structured(4)["payload"]["error"],
+        json!("input_usage_error")
+    );
+    assert!(
+        structured(4)["payload"]["message"]
+            .as_str()
+            .expect("message")
+            .starts_with("failed to resolve recipe paths under the configured `--root`")
+    );
+}
+
+#[test]
+fn root_allowlist_checks_default_scan_path_when_path_is_omitted() {
+    let temp = tempdir().expect("tempdir");
+    let workspace = temp.path().join("workspace");
+    let outside = temp.path().join("outside");
+    fs::create_dir_all(&workspace).expect("create workspace");
+    fs::create_dir_all(&outside).expect("create outside");
+    fs::write(outside.join("notes.txt"), "secret\n").expect("write outside file");
+
+    let call = |current_dir: &std::path::Path| {
+        let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .args(["mcp", "--root"])
+            .arg(&workspace)
+            .current_dir(current_dir)
+            .write_stdin(
+                tool_call_request(1, "dataq.scan.text", json!({"pattern": "secret"})).to_string(),
+            )
+            .output()
+            .expect("run mcp --root");
+        assert_eq!(output.status.code(), Some(0));
+        parse_stdout_json(&output.stdout)["result"]["structuredContent"].clone()
+    };
+
+    let escaped = call(&outside);
+    assert_eq!(escaped["exit_code"], json!(3));
+    assert_eq!(
+        escaped["payload"]["invalid_params"],
+        json!([{"name": "path", "reason": "outside_root"}])
+    );
+
+    // Inside the root the call passes the sandbox (it may still fail later when `rg` is missing).
+    let inside = call(&workspace);
+    assert!(
+        !inside["payload"]["message"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("`--root`")
+    );
+}
+
+#[test]
+fn recipe_tools_pin_contract_version() {
+    let dir = tempdir().expect("tempdir");
+    fs::write(dir.path().join("input.json"), r#"[{"id":1}]"#).expect("write input");
+    let recipe_path = dir.path().join("recipe.json");
+    fs::write(
+        &recipe_path,
+        r#"{"version":"dataq.recipe.v1","steps":[{"id":"rows","kind":"canon","args":{"input":"input.json"}}]}"#,
+    )
+    .expect("write recipe");
+    let lock_path = dir.path().join("recipe.lock.json");
+    fs::write(
+        &lock_path,
+        r#"{"version":"dataq.recipe.lock.v1","command_graph_hash":"x","args_hash":"x","tool_versions":{},"dataq_version":"0"}"#,
+    )
+    .expect("write lock");
+
+    let call = |tool: &str, arguments: Value| {
+        let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .arg("mcp")
+            .write_stdin(tool_call_request(1, tool, arguments).to_string())
+            .output()
+            .expect("run mcp");
+        assert_eq!(output.status.code(), Some(0));
+        parse_stdout_json(&output.stdout)["result"]["structuredContent"].clone()
+    };
+    let recipe = recipe_path.display().to_string();
+    let lock = lock_path.display().to_string();
+
+    let latest = call("dataq.recipe.run", json!({"file_path": recipe}));
+    assert_eq!(latest["payload"]["steps"][0]["id"], json!("rows"));
+    for (tool, arguments) in [
+        (
+            "dataq.recipe.run",
+            json!({"file_path": recipe, "contract_version": "v1"}),
+        ),
+        (
+            "dataq.recipe.replay",
+            json!({"file_path": recipe, "lock_path": lock, "contract_version": "dataq.recipe.replay.output.v1"}),
+        ),
+    ] {
+        let pinned = call(tool, arguments);
+        assert_eq!(pinned["exit_code"], json!(0), "{tool}");
+        assert_eq!(
+            pinned["payload"]["steps"][0],
+            json!({
+                "index": 0,
+                "kind": "canon",
+                "matched": true,
+                "exit_code": 0,
+                "summary": latest["payload"]["steps"][0]["summary"]
+            }),
+            "{tool}"
+        );
+    }
+
+    let unsupported = call(
+        "dataq.recipe.lock",
+        json!({"file_path": recipe, "contract_version": "v2"}),
+    );
+    assert_eq!(unsupported["exit_code"], json!(3));
+    assert!(
+        unsupported["payload"]["message"]
+            .as_str()
+            .expect("message")
+            .contains("contract version `v2` is not supported by `recipe-lock`")
+    );
+}
+
+#[test]
+fn http_mode_reports_bind_failures_and_conflicts_with_serve() {
+    let bad_addr = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["mcp", "--http", "not-an-address"])
+        .output()
+        .expect("run mcp --http");
+    assert_eq!(bad_addr.status.code(), Some(3));
+    let stderr: Value = serde_json::from_slice(&bad_addr.stderr).expect("stderr json");
+    assert_eq!(stderr["error"], json!("input_usage_error"));
+    assert_eq!(stderr["details"]["http"], json!("not-an-address"));
+
+    let conflict = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["mcp", "--serve", "--http", "0"])
+        .output()
+        .expect("run mcp --serve --http");
+    assert_eq!(conflict.status.code(), Some(3));
+}
+
 #[test]
 fn tools_list_is_deterministic_and_in_fixed_order() {
     let request = json!({
@@ -355,6 +1049,7 @@ fn tools_call_minimal_success_for_all_tools() {
         ),
     ];
 
+    let output_schemas = published_output_schemas();
     for (index, (tool_name, arguments)) in requests.into_iter().enumerate() {
         let request = tool_call_request(index as i64, tool_name, arguments);
         let output = run_mcp(&request, Some(&toolchain));
@@ -372,6 +1067,74 @@ fn tools_call_minimal_success_for_all_tools() {
             Value::from(0),
             "tool: {tool_name}",
         );
+        assert_matches_output_schema(
+            &output_schemas,
+            tool_name,
+            &response["result"]["structuredContent"],
+        );
+    }
+}
+
+#[test]
+fn tools_call_error_and_mismatch_payloads_match_output_schemas() {
+    let toolchain = FakeToolchain::new();
+    let dir = tempdir().expect("tempdir");
+    let mut requests: Vec<(String, Value)> = TOOL_ORDER
+        .iter()
+        .map(|tool_name| (tool_name.to_string(), json!({"unexpected_argument": true})))
+        .collect();
+    requests.push((
+        "dataq.scan.text".to_string(),
+        json!({"pattern": "token", "path": dir.path(), "policy_mode": true}),
+    ));
+    requests.push((
+        "dataq.assert".to_string(),
+        json!({"input": [{"id": "x"}], "rules": {"required_keys": ["id", "name"]}}),
+    ));
+    requests.push((
+        "dataq.sdiff".to_string(),
+        json!({"left": [{"id": 1}], "right": [{"id": 2}], "fail_on_diff": true}),
+    ));
+    let session = requests
+        .iter()
+        .enumerate()
+        .map(|(index, (tool_name, arguments))| {
+            tool_call_request(index as i64, tool_name, arguments.clone()).to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["mcp", "--serve"])
+        .env("DATAQ_MLR_BIN", &toolchain.mlr_bin)
+        .env("PATH", toolchain.path_with_current())
+        .write_stdin(session)
+        .output()
+        .expect("run mcp --serve");
+    assert_eq!(output.status.code(), Some(0));
+
+    let output_schemas = published_output_schemas();
+    let mut responses: Vec<Value> = String::from_utf8(output.stdout)
+        .expect("stdout utf8")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("response line json"))
+        .collect();
+    responses.sort_by_key(|response| response["id"].as_i64());
+    assert_eq!(responses.len(), requests.len());
+
+    for (response, (tool_name, _)) in responses.iter().zip(&requests) {
+        let structured = &response["result"]["structuredContent"];
+        let expected_exit_code = if response["id"].as_u64() < Some(TOOL_ORDER.len() as u64) {
+            3
+        } else {
+            2
+        };
+        assert_eq!(
+            structured["exit_code"],
+            json!(expected_exit_code),
+            "tool: {tool_name}"
+        );
+        assert_matches_output_schema(&output_schemas, tool_name, structured);
     }
 }
 
@@ -464,6 +1227,28 @@ fn tools_call_alias_arguments_emit_deprecation_warnings() {
     assert_eq!(warnings[0]["canonical"], Value::from("input"));
 }
 
+#[test]
+fn canon_tool_honors_coerce_arguments() {
+    let request = tool_call_request(
+        106,
+        "dataq.canon",
+        json!({
+            "input": [{"zip": "01234", "qty": "3"}],
+            "coerce_exempt": ["$[\"zip\"]"]
+        }),
+    );
+
+    let output = run_mcp(&request, None);
+    assert_eq!(output.status.code(), Some(0));
+
+    let response = parse_stdout_json(&output.stdout);
+    assert_eq!(response["result"]["isError"], Value::Bool(false));
+    assert_eq!(
+        response["result"]["structuredContent"]["payload"],
+        json!({"qty": 3, "zip": "01234"})
+    );
+}
+
 #[test]
 fn tools_call_rejects_unknown_arguments() {
     let request = tool_call_request(
@@ -1077,6 +1862,39 @@ fn contract_supports_recipe_lock_command() {
     );
 }
 
+#[test]
+fn contract_json_schema_format_returns_schema_documents() {
+    let request = tool_call_request(
+        12,
+        "dataq.contract",
+        json!({
+            "command": "recipe-lock",
+            "format": "json-schema"
+        }),
+    );
+    let output = run_mcp(&request, None);
+    assert_eq!(output.status.code(), Some(0));
+    let payload = &parse_stdout_json(&output.stdout)["result"]["structuredContent"]["payload"];
+    assert_eq!(payload["title"], Value::from("dataq.recipe.lock.output.v1"));
+    assert_eq!(payload["$ref"], Value::from("#/$defs/success"));
+
+    let invalid = tool_call_request(
+        13,
+        "dataq.contract",
+        json!({
+            "all": true,
+            "format": "yaml"
+        }),
+    );
+    let output = run_mcp(&invalid, None);
+    let structured = &parse_stdout_json(&output.stdout)["result"]["structuredContent"];
+    assert_eq!(structured["exit_code"], Value::from(3));
+    assert_eq!(
+        structured["payload"]["message"],
+        Value::from("`format` must be one of `contract`, `json-schema`")
+    );
+}
+
 #[test]
 fn recipe_lock_invalid_step_args_return_exit_three() {
     let toolchain = FakeToolchain::new();
@@ -1286,6 +2104,42 @@ fn run_mcp_raw(raw: &str, toolchain: Option<&FakeToolchain>) -> std::process::Ou
     command.output().expect("run mcp")
 }
 
+fn published_output_schemas() -> serde_json::Map<String, Value> {
+    let request = json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"});
+    let output = run_mcp(&request, None);
+    assert_eq!(output.status.code(), Some(0));
+    parse_stdout_json(&output.stdout)["result"]["tools"]
+        .as_array()
+        .expect("tools array")
+        .iter()
+        .map(|tool| {
+            (
+                tool["name"].as_str().expect("tool name").to_string(),
+                tool["outputSchema"].clone(),
+            )
+        })
+        .collect()
+}
+
+fn assert_matches_output_schema(
+    output_schemas: &serde_json::Map<String, Value>,
+    tool_name: &str,
+    structured: &Value,
+) {
+    let schema = output_schemas
+        .get(tool_name)
+        .unwrap_or_else(|| panic!("outputSchema for {tool_name}"));
+    let validator = jsonschema::validator_for(schema).expect("compile outputSchema");
+    let errors: Vec<String> = validator
+        .iter_errors(structured)
+        .map(|error| format!("{}: {error}", error.instance_path().as_str()))
+        .collect();
+    assert!(
+        errors.is_empty(),
+        "tool: {tool_name}: {errors:?}\n{structured}"
+    );
+}
+
 fn parse_stdout_json(stdout: &[u8]) -> Value {
     serde_json::from_slice(stdout).expect("stdout json")
 }
@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use dataq::util::hash::DeterministicHasher;
+use dataq::util::hash::{DeterministicHasher, DigestHasher};
 use dataq::util::sort::sort_value_keys;
 use serde_json::{Value, json};
 use tempfile::{TempDir, tempdir};
@@ -69,6 +69,514 @@ steps:
     assert_eq!(summary["steps"][1]["matched"], Value::Bool(true));
 }
 
+#[test]
+fn recipe_run_param_overrides_default_input_path() {
+    let dir = tempdir().expect("temp dir");
+    fs::write(dir.path().join("good.json"), r#"[{"id":"1"}]"#).expect("write good");
+    fs::write(dir.path().join("bad.json"), r#"[{"name":"x"}]"#).expect("write bad");
+    let recipe_path = dir.path().join("recipe.yaml");
+    fs::write(
+        &recipe_path,
+        r#"
+version: dataq.recipe.v1
+params:
+  source:
+    type: string
+    default: good.json
+steps:
+  - kind: canon
+    args:
+      input: "${params.source}"
+      from: json
+  - kind: assert
+    args:
+      rules:
+        required_keys: [id]
+"#,
+    )
+    .expect("write recipe");
+
+    let run = |params: &[&str]| {
+        assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .args([
+                "recipe",
+                "run",
+                "--file",
+                recipe_path.to_str().expect("utf8 path"),
+            ])
+            .args(params)
+            .output()
+            .expect("run recipe")
+    };
+
+    assert_eq!(run(&[]).status.code(), Some(0));
+    assert_eq!(run(&["--param", "source=bad.json"]).status.code(), Some(2));
+
+    let unknown = run(&["--param", "target=bad.json"]);
+    assert_eq!(unknown.status.code(), Some(3));
+    let stderr_json = parse_last_stderr_json(&unknown.stderr);
+    assert!(
+        stderr_json["message"]
+            .as_str()
+            .expect("message")
+            .contains("unknown recipe param `target`")
+    );
+}
+
+#[test]
+fn recipe_run_cache_dir_skips_unchanged_steps() {
+    let dir = tempdir().expect("temp dir");
+    fs::write(dir.path().join("input.json"), r#"[{"id":"1"}]"#).expect("write input");
+    let recipe_path = dir.path().join("recipe.json");
+    fs::write(
+        &recipe_path,
+        r#"{"version":"dataq.recipe.v1","steps":[{"kind":"canon","args":{"input":"input.json"}},{"kind":"profile","args":{}}]}"#,
+    )
+    .expect("write recipe");
+    let cache_dir = dir.path().join("cache");
+
+    let run = || {
+        let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .args([
+                "recipe",
+                "run",
+                "--file",
+                recipe_path.to_str().expect("utf8 path"),
+                "--cache-dir",
+                cache_dir.to_str().expect("utf8 path"),
+            ])
+            .output()
+            .expect("run recipe");
+        assert_eq!(output.status.code(), Some(0));
+        serde_json::from_slice::<Value>(&output.stdout).expect("stdout json")
+    };
+
+    let first = run();
+    assert!(first["steps"][0].get("cached").is_none());
+    assert!(first["steps"][1].get("cached").is_none());
+    let second = run();
+    assert_eq!(second["steps"][0]["cached"], Value::Bool(true));
+    assert_eq!(second["steps"][1]["cached"], Value::Bool(true));
+    assert_eq!(second["steps"][1]["summary"], first["steps"][1]["summary"]);
+
+    fs::write(dir.path().join("input.json"), r#"[{"id":"1"},{"id":"2"}]"#).expect("rewrite input");
+    let third = run();
+    assert!(third["steps"][0].get("cached").is_none());
+    assert_eq!(third["steps"][1]["summary"]["record_count"], Value::from(2));
+}
+
+#[test]
+fn recipe_run_cache_key_covers_rules_extends_and_tool_versions() {
+    let dir = tempdir().expect("temp dir");
+    fs::write(dir.path().join("input.json"), r#"[{"id":"1"}]"#).expect("write input");
+    fs::create_dir_all(dir.path().join("rules")).expect("create rules dir");
+    fs::write(
+        dir.path().join("rules/base.json"),
+        r#"{"required_keys":["id"]}"#,
+    )
+    .expect("write base rules");
+    fs::write(
+        dir.path().join("rules/policy.json"),
+        r#"{"extends":"base.json"}"#,
+    )
+    .expect("write rules");
+    let jq_version = dir.path().join("jq.version");
+    fs::write(&jq_version, "jq-1.7\n").expect("write jq version");
+    let pass_through = |name: &str, version: String| {
+        let path = dir.path().join(name);
+        write_exec_script(
+            &path,
+            &format!("#!/bin/sh\nif [ \"$1\" = \"--version\" ]; then {version}; exit 0; fi\ncat\n"),
+        );
+        path
+    };
+    let jq_bin = pass_through("jq", format!("cat '{}'", jq_version.display()));
+    let mlr_bin = pass_through("mlr", "echo 'mlr 6.13.0'".to_string());
+    let recipe_path = dir.path().join("recipe.yaml");
+    fs::write(
+        &recipe_path,
+        r#"
+version: dataq.recipe.v1
+steps:
+  - kind: canon
+    args:
+      input: input.json
+  - kind: transform_rowset
+    args:
+      jq_filter: "."
+      mlr: [cat]
+  - kind: assert
+    args:
+      rules_file: rules/policy.json
+"#,
+    )
+    .expect("write recipe");
+    let cache_dir = dir.path().join("cache");
+
+    let run = || {
+        let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .env("DATAQ_JQ_BIN", &jq_bin)
+            .env("DATAQ_MLR_BIN", &mlr_bin)
+            .args([
+                "recipe",
+                "run",
+                "--file",
+                recipe_path.to_str().expect("utf8 path"),
+                "--cache-dir",
+                cache_dir.to_str().expect("utf8 path"),
+            ])
+            .output()
+            .expect("run recipe");
+        assert_eq!(output.status.code(), Some(0), "{output:?}");
+        let summary: Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+        summary["steps"]
+            .as_array()
+            .expect("steps")
+            .iter()
+            .map(|step| step["cached"] == Value::Bool(true))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(run(), vec![false, false, false]);
+    assert_eq!(run(), vec![true, true, true]);
+
+    fs::write(
+        dir.path().join("rules/base.json"),
+        r#"{"required_keys":["id"],"forbid_keys":["secret"]}"#,
+    )
+    .expect("rewrite base rules");
+    assert_eq!(run(), vec![true, true, false]);
+
+    fs::write(&jq_version, "jq-1.8\n").expect("upgrade jq");
+    assert_eq!(run(), vec![true, false, true]);
+}
+
+#[test]
+fn recipe_validate_reports_missing_paths_and_invalid_rules_without_running() {
+    let dir = tempdir().expect("temp dir");
+    fs::write(dir.path().join("input.json"), r#"[{"id":"1"}]"#).expect("write input");
+    fs::write(
+        dir.path().join("rules.json"),
+        r#"{"fields":{"id":{"type":"strin"}}}"#,
+    )
+    .expect("write rules");
+    let recipe_path = dir.path().join("recipe.json");
+    fs::write(
+        &recipe_path,
+        r#"{"version":"dataq.recipe.v1","steps":[
+            {"kind":"canon","args":{"input":"input.json"}},
+            {"kind":"write","args":{"path":"out.json"}},
+            {"kind":"gate_policy","args":{"rules":"rules.json"}},
+            {"kind":"assert","args":{"rules_file":"missing.json"}},
+            {"kind":"canon","args":{"input":"out.json"}}
+        ]}"#,
+    )
+    .expect("write recipe");
+
+    let validate = |path: &Path| {
+        assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .args([
+                "recipe",
+                "validate",
+                "--file",
+                path.to_str().expect("utf8 path"),
+            ])
+            .output()
+            .expect("run recipe validate")
+    };
+
+    let output = validate(&recipe_path);
+    assert_eq!(output.status.code(), Some(2));
+    let report: Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    assert_eq!(report["valid"], Value::Bool(false));
+    assert_eq!(report["step_count"], Value::from(5));
+    let errors = report["errors"].as_array().expect("errors");
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0]["step"], Value::from(2));
+    assert_eq!(errors[0]["check"], Value::from("compile"));
+    assert_eq!(errors[1]["step"], Value::from(3));
+    assert_eq!(errors[1]["check"], Value::from("path"));
+    assert!(
+        errors[1]["message"]
+            .as_str()
+            .expect("message")
+            .contains("missing.json")
+    );
+    assert!(!dir.path().join("out.json").exists());
+
+    fs::write(dir.path().join("rules.json"), r#"{"required_keys":["id"]}"#).expect("rewrite rules");
+    fs::write(
+        dir.path().join("missing.json"),
+        r#"{"required_keys":["id"]}"#,
+    )
+    .expect("write assert rules");
+    let output = validate(&recipe_path);
+    assert_eq!(output.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    assert_eq!(report["valid"], Value::Bool(true));
+    assert_eq!(report["errors"], json!([]));
+
+    let output = validate(&dir.path().join("absent.json"));
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn recipe_validate_reports_arg_errors_per_step() {
+    let dir = tempdir().expect("temp dir");
+    let recipe_path = dir.path().join("recipe.json");
+    fs::write(
+        &recipe_path,
+        r#"{"version":"dataq.recipe.v1","steps":[
+            {"kind":"profile","args":{}},
+            {"kind":"canon","args":{"input":"input.json"}},
+            {"kind":"scan_text","args":{"pattern":" "}},
+            {"kind":"aggregate","args":{"group_by":"team","metric":"median","target":"price"}}
+        ]}"#,
+    )
+    .expect("write recipe");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["recipe", "validate", "--file"])
+        .arg(&recipe_path)
+        .output()
+        .expect("run recipe validate");
+    assert_eq!(output.status.code(), Some(2));
+    let report: Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    let errors = report["errors"].as_array().expect("errors");
+    let steps: Vec<&Value> = errors.iter().map(|issue| &issue["step"]).collect();
+    assert_eq!(steps, vec![&json!(0), &json!(2), &json!(3)]);
+    assert!(errors.iter().all(|issue| issue["check"] == json!("args")));
+    assert!(
+        errors[1]["message"]
+            .as_str()
+            .expect("message")
+            .contains("pattern")
+    );
+}
+
+#[test]
+fn recipe_graph_emits_step_dag_as_json_and_dot() {
+    let dir = tempdir().expect("temp dir");
+    let recipe_path = dir.path().join("recipe.json");
+    fs::write(
+        &recipe_path,
+        r#"{"version":"dataq.recipe.v1","steps":[
+            {"id":"rows","kind":"canon","args":{"input":"input.json"}},
+            {"id":"check","kind":"assert","on_failure":"continue","args":{"rules":{"required_keys":["id"]}}},
+            {"kind":"transform_rowset","inputs":["rows"],"when":{"step":"check","matched":true},"args":{"jq_filter":".","mlr":["cat"]}}
+        ]}"#,
+    )
+    .expect("write recipe");
+
+    let graph = |format: &str| {
+        assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .args([
+                "recipe",
+                "graph",
+                "--file",
+                recipe_path.to_str().expect("utf8 path"),
+                "--format",
+                format,
+            ])
+            .output()
+            .expect("run recipe graph")
+    };
+
+    let output = graph("json");
+    assert_eq!(output.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    assert_eq!(report["order"], json!([0, 1, 2]));
+    assert_eq!(
+        report["edges"],
+        json!([
+            {"from": 0, "to": 1, "kind": "input"},
+            {"from": 0, "to": 2, "kind": "input"},
+            {"from": 1, "to": 2, "kind": "when", "matched": true}
+        ])
+    );
+    let tools: Vec<&str> = report["nodes"][2]["stages"]
+        .as_array()
+        .expect("stages")
+        .iter()
+        .map(|stage| stage["tool"].as_str().expect("tool"))
+        .collect();
+    assert_eq!(tools, vec!["jq", "mlr"]);
+
+    let output = graph("dot");
+    assert_eq!(output.status.code(), Some(0));
+    let dot = String::from_utf8(output.stdout).expect("utf8 dot");
+    assert!(dot.starts_with("digraph recipe {"));
+    assert!(dot.contains("subgraph cluster_step_0"));
+    assert!(dot.contains("label=\"0: canon (rows)\""));
+    assert!(dot.contains(
+        "\"step_1_stage_4\" -> \"step_2_stage_1\" [style=dashed, label=\"when matched=true\"];"
+    ));
+}
+
+#[test]
+fn recipe_run_write_step_persists_dataset_with_hash() {
+    let dir = tempdir().expect("temp dir");
+    fs::write(dir.path().join("input.json"), r#"[{"b":"2","a":"1"}]"#).expect("write input");
+    let recipe_path = dir.path().join("recipe.yaml");
+    fs::write(
+        &recipe_path,
+        r#"
+version: dataq.recipe.v1
+steps:
+  - kind: canon
+    args:
+      input: input.json
+  - kind: write
+    args:
+      path: out/data.csv
+"#,
+    )
+    .expect("write recipe");
+    fs::create_dir_all(dir.path().join("out")).expect("create out dir");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "recipe",
+            "run",
+            "--file",
+            recipe_path.to_str().expect("utf8 path"),
+        ])
+        .output()
+        .expect("run recipe");
+    assert_eq!(output.status.code(), Some(0));
+
+    let written = fs::read(dir.path().join("out/data.csv")).expect("read written csv");
+    assert_eq!(written, b"a,b\n1,2\n");
+    let mut hasher = DigestHasher::sha256();
+    hasher.update(written.as_slice());
+    let summary: Value = serde_json::from_slice(&output.stdout).expect("summary json");
+    assert_eq!(summary["steps"][1]["kind"], Value::from("write"));
+    assert_eq!(
+        summary["steps"][1]["summary"]["hash"],
+        Value::from(hasher.finish())
+    );
+    assert_eq!(summary["steps"][1]["summary"]["format"], Value::from("csv"));
+}
+
+#[test]
+fn recipe_run_write_step_io_failure_is_execution_error() {
+    let dir = tempdir().expect("temp dir");
+    fs::write(dir.path().join("input.json"), r#"[{"a":"1"}]"#).expect("write input");
+    let recipe_path = dir.path().join("recipe.yaml");
+    fs::write(
+        &recipe_path,
+        r#"
+version: dataq.recipe.v1
+steps:
+  - kind: canon
+    args:
+      input: input.json
+  - kind: write
+    args:
+      path: missing/data.json
+"#,
+    )
+    .expect("write recipe");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["recipe", "run", "--file"])
+        .arg(&recipe_path)
+        .output()
+        .expect("run recipe");
+    assert_eq!(output.status.code(), Some(1));
+    let error: Value = serde_json::from_slice(&output.stderr).expect("stderr json");
+    assert_eq!(error["error"], Value::from("internal_error"));
+    assert!(
+        error["message"]
+            .as_str()
+            .expect("message")
+            .contains("failed to write output file")
+    );
+
+    fs::write(
+        &recipe_path,
+        r#"
+version: dataq.recipe.v1
+steps:
+  - kind: canon
+    args:
+      input: input.json
+  - kind: write
+    args:
+      path: data.out
+      format: xml
+"#,
+    )
+    .expect("write recipe");
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args(["recipe", "run", "--file"])
+        .arg(&recipe_path)
+        .output()
+        .expect("run recipe");
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn recipe_run_continue_on_failure_reports_every_gate() {
+    let dir = tempdir().expect("temp dir");
+    fs::write(dir.path().join("input.json"), r#"[{"id":"x"}]"#).expect("write input");
+    let recipe_path = dir.path().join("recipe.yaml");
+    fs::write(
+        &recipe_path,
+        r#"
+version: dataq.recipe.v1
+steps:
+  - id: rows
+    kind: canon
+    args:
+      input: input.json
+  - id: typed
+    kind: assert
+    inputs: [rows]
+    on_failure: continue
+    args:
+      rules:
+        fields:
+          id:
+            type: integer
+  - id: named
+    kind: assert
+    inputs: [rows]
+    on_failure: continue
+    args:
+      rules:
+        required_keys: [name]
+  - kind: sdiff
+    inputs: [rows]
+    when:
+      step: typed
+      matched: true
+    args:
+      right: input.json
+"#,
+    )
+    .expect("write recipe");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "recipe",
+            "run",
+            "--file",
+            recipe_path.to_str().expect("utf8 path"),
+        ])
+        .output()
+        .expect("run recipe");
+
+    assert_eq!(output.status.code(), Some(2));
+    let summary: Value = serde_json::from_slice(&output.stdout).expect("summary json");
+    assert_eq!(summary["exit_code"], Value::from(2));
+    assert_eq!(summary["steps"][1]["matched"], Value::Bool(false));
+    assert_eq!(summary["steps"][2]["matched"], Value::Bool(false));
+    assert_eq!(summary["steps"][3]["kind"], Value::from("sdiff"));
+    assert_eq!(summary["steps"][3]["skipped"], Value::Bool(true));
+    assert!(summary["steps"][0].get("skipped").is_none());
+}
+
 #[test]
 fn recipe_run_resolves_relative_paths_from_recipe_directory() {
     let dir = tempdir().expect("temp dir");
@@ -111,6 +619,275 @@ steps:
     assert_eq!(summary["steps"][0]["kind"], Value::from("canon"));
 }
 
+#[test]
+fn recipe_run_canon_step_applies_coerce_policy() {
+    let dir = tempdir().expect("temp dir");
+    let input_path = dir.path().join("input.json");
+    let rules_path = dir.path().join("types.json");
+    let recipe_path = dir.path().join("recipe.yaml");
+    fs::write(&input_path, r#"[{"id":"1","zip":"01234"}]"#).expect("write input");
+    fs::write(&rules_path, r#"{"fields":{"id":{"type":"integer"}}}"#).expect("write rules");
+    fs::write(
+        &recipe_path,
+        r#"
+version: dataq.recipe.v1
+steps:
+  - kind: canon
+    args:
+      input: ./input.json
+      coerce: schema
+      coerce_rules: ./types.json
+      key_case: snake
+      whitespace: trim
+  - kind: assert
+    args:
+      rules:
+        fields:
+          id:
+            type: integer
+          zip:
+            type: string
+"#,
+    )
+    .expect("write recipe");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "recipe",
+            "run",
+            "--file",
+            recipe_path.to_str().expect("utf8 path"),
+        ])
+        .output()
+        .expect("run command");
+
+    assert_eq!(output.status.code(), Some(0));
+    let summary: Value = serde_json::from_slice(&output.stdout).expect("summary json");
+    assert_eq!(summary["matched"], Value::Bool(true));
+    assert_eq!(
+        summary["steps"][0]["summary"]["coerce"],
+        Value::from("schema")
+    );
+}
+
+#[test]
+fn recipe_run_canon_step_reports_coerce_errors_like_cli() {
+    let dir = tempdir().expect("temp dir");
+    let rules_path = dir.path().join("types.json");
+    let recipe_path = dir.path().join("recipe.yaml");
+    fs::write(&rules_path, r#"{"fields":{"id":{"type":"integer"}}}"#).expect("write rules");
+
+    let cli_output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "canon",
+            "--from",
+            "json",
+            "--coerce",
+            "all",
+            "--coerce-rules",
+        ])
+        .arg(&rules_path)
+        .write_stdin("{}")
+        .output()
+        .expect("run canon");
+    assert_eq!(cli_output.status.code(), Some(3));
+    let cli_error: Value = serde_json::from_slice(&cli_output.stderr).expect("canon stderr json");
+
+    for (coerce, exempt) in [("all", "$[\"id\"]"), ("schema", "not-a-path")] {
+        fs::write(
+            &recipe_path,
+            format!(
+                r#"
+version: dataq.recipe.v1
+steps:
+  - kind: canon
+    args:
+      input: ./types.json
+      coerce: {coerce}
+      coerce_exempt: ['{exempt}']
+      coerce_rules: ./types.json
+"#
+            ),
+        )
+        .expect("write recipe");
+
+        let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .args(["recipe", "run", "--file"])
+            .arg(&recipe_path)
+            .output()
+            .expect("run recipe");
+        assert_eq!(output.status.code(), Some(3));
+        let error: Value = serde_json::from_slice(&output.stderr).expect("recipe stderr json");
+        let message = error["message"].as_str().expect("message");
+        if coerce == "all" {
+            assert!(
+                message.contains(cli_error["message"].as_str().expect("cli message")),
+                "{message}"
+            );
+        } else {
+            assert!(
+                message.contains("invalid coerce exempt path `not-a-path`"),
+                "{message}"
+            );
+        }
+    }
+}
+
+#[test]
+fn recipe_run_join_step_feeds_gate_policy_step() {
+    let dir = tempdir().expect("temp dir");
+    let mlr_bin = dir.path().join("fake-mlr");
+    write_exec_script(
+        &mlr_bin,
+        "#!/bin/sh\ncat >/dev/null\nprintf '[{\"id\":2,\"l\":\"L2\",\"r\":\"R2\"},{\"id\":1,\"l\":\"L1\",\"r\":\"R1\"}]'\n",
+    );
+    fs::write(
+        dir.path().join("left.json"),
+        r#"[{"id":1,"l":"L1"},{"id":2,"l":"L2"}]"#,
+    )
+    .expect("write left");
+    fs::write(
+        dir.path().join("right.json"),
+        r#"[{"id":1,"r":"R1"},{"id":2,"r":"R2"}]"#,
+    )
+    .expect("write right");
+    fs::write(
+        dir.path().join("rules.json"),
+        r#"{"fields":{"r":{"enum":["R1"]}}}"#,
+    )
+    .expect("write rules");
+    let recipe_path = dir.path().join("recipe.yaml");
+    fs::write(
+        &recipe_path,
+        r#"
+version: dataq.recipe.v1
+steps:
+  - kind: join
+    args:
+      left: ./left.json
+      right: ./right.json
+      on: id
+  - kind: gate_policy
+    args:
+      rules: ./rules.json
+"#,
+    )
+    .expect("write recipe");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .env("DATAQ_MLR_BIN", &mlr_bin)
+        .args([
+            "recipe",
+            "run",
+            "--file",
+            recipe_path.to_str().expect("utf8 path"),
+        ])
+        .output()
+        .expect("run command");
+
+    assert_eq!(output.status.code(), Some(2));
+    let summary: Value = serde_json::from_slice(&output.stdout).expect("summary json");
+    assert_eq!(summary["steps"][0]["kind"], Value::from("join"));
+    assert_eq!(summary["steps"][0]["summary"]["record_count"], json!(2));
+    assert_eq!(summary["steps"][1]["kind"], Value::from("gate_policy"));
+    assert_eq!(summary["steps"][1]["matched"], Value::Bool(false));
+    assert_eq!(summary["steps"][1]["summary"]["violations"], json!(1));
+}
+
+#[test]
+fn recipe_run_dag_diffs_two_canonicalized_sources() {
+    let dir = tempdir().expect("temp dir");
+    fs::write(dir.path().join("a.json"), r#"[{"id":"1","v":"x"}]"#).expect("write a");
+    fs::write(dir.path().join("b.json"), r#"[{"id":1,"v":"y"}]"#).expect("write b");
+    let recipe_path = dir.path().join("recipe.yaml");
+    fs::write(
+        &recipe_path,
+        r#"
+version: dataq.recipe.v1
+steps:
+  - id: diff
+    kind: sdiff
+    inputs: [left, right]
+    args:
+      key: '$["id"]'
+  - id: left
+    kind: canon
+    inputs: []
+    args:
+      input: ./a.json
+  - id: right
+    kind: canon
+    inputs: []
+    args:
+      input: ./b.json
+"#,
+    )
+    .expect("write recipe");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "recipe",
+            "run",
+            "--file",
+            recipe_path.to_str().expect("utf8 path"),
+        ])
+        .output()
+        .expect("run command");
+
+    assert_eq!(output.status.code(), Some(2));
+    let summary: Value = serde_json::from_slice(&output.stdout).expect("summary json");
+    let order: Vec<&str> = summary["steps"]
+        .as_array()
+        .expect("steps")
+        .iter()
+        .map(|step| step["id"].as_str().expect("step id"))
+        .collect();
+    assert_eq!(order, vec!["left", "right", "diff"]);
+    assert_eq!(summary["steps"][2]["index"], json!(0));
+    assert_eq!(summary["steps"][2]["matched"], Value::Bool(false));
+}
+
+#[test]
+fn recipe_run_rejects_cyclic_inputs() {
+    let dir = tempdir().expect("temp dir");
+    let recipe_path = dir.path().join("recipe.yaml");
+    fs::write(
+        &recipe_path,
+        r#"
+version: dataq.recipe.v1
+steps:
+  - id: a
+    kind: profile
+    inputs: [b]
+    args: {}
+  - id: b
+    kind: profile
+    inputs: [a]
+    args: {}
+"#,
+    )
+    .expect("write recipe");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .args([
+            "recipe",
+            "run",
+            "--file",
+            recipe_path.to_str().expect("utf8 path"),
+        ])
+        .output()
+        .expect("run command");
+
+    assert_eq!(output.status.code(), Some(3));
+    let error: Value = serde_json::from_slice(&output.stderr).expect("stderr json");
+    assert!(
+        error["message"]
+            .as_str()
+            .expect("message")
+            .contains("form a cycle")
+    );
+}
+
 #[test]
 fn recipe_run_invalid_schema_returns_exit_three() {
     let dir = tempdir().expect("temp dir");
@@ -390,6 +1167,67 @@ fn recipe_lock_regenerates_byte_identically() {
     assert_eq!(bytes_first, bytes_second);
 }
 
+#[test]
+fn recipe_lock_compresses_out_by_extension_and_replay_reads_it() {
+    let dir = tempdir().expect("temp dir");
+    let toolchain = FakeToolchain::new("jq-1.7", "yq 4.35.2", "mlr 6.13.0");
+    let recipe_path = dir.path().join("recipe.json");
+    fs::write(&recipe_path, r#"{"version":"dataq.recipe.v1","steps":[]}"#).expect("write recipe");
+
+    let plain = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .env("DATAQ_JQ_BIN", &toolchain.jq_bin)
+        .env("DATAQ_YQ_BIN", &toolchain.yq_bin)
+        .env("DATAQ_MLR_BIN", &toolchain.mlr_bin)
+        .args(["recipe", "lock", "--file"])
+        .arg(&recipe_path)
+        .output()
+        .expect("run plain lock");
+    assert_eq!(plain.status.code(), Some(0));
+
+    for (name, magic) in [
+        ("recipe.lock.json.gz", &[0x1f, 0x8b][..]),
+        ("recipe.lock.json.zst", &[0x28, 0xb5, 0x2f, 0xfd][..]),
+    ] {
+        let lock_path = dir.path().join(name);
+        let lock = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .env("DATAQ_JQ_BIN", &toolchain.jq_bin)
+            .env("DATAQ_YQ_BIN", &toolchain.yq_bin)
+            .env("DATAQ_MLR_BIN", &toolchain.mlr_bin)
+            .args(["recipe", "lock", "--file"])
+            .arg(&recipe_path)
+            .arg("--out")
+            .arg(&lock_path)
+            .output()
+            .expect("run compressed lock");
+        assert_eq!(lock.status.code(), Some(0), "{name}");
+        assert!(lock.stdout.is_empty());
+
+        let bytes = fs::read(&lock_path).expect("read compressed lock");
+        assert!(bytes.starts_with(magic), "{name}");
+        let decompressed =
+            dataq::io::compression::decompress_bytes(&bytes).expect("decompress lock");
+        assert_eq!(
+            decompressed.as_ref(),
+            plain.stdout.trim_ascii_end(),
+            "{name}"
+        );
+
+        let replay = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .env("DATAQ_JQ_BIN", &toolchain.jq_bin)
+            .env("DATAQ_YQ_BIN", &toolchain.yq_bin)
+            .env("DATAQ_MLR_BIN", &toolchain.mlr_bin)
+            .args(["recipe", "replay", "--strict", "--file"])
+            .arg(&recipe_path)
+            .arg("--lock")
+            .arg(&lock_path)
+            .output()
+            .expect("run replay");
+        assert_eq!(replay.status.code(), Some(0), "{name}");
+        let summary: Value = serde_json::from_slice(&replay.stdout).expect("summary json");
+        assert_eq!(summary["lock_check"]["matched"], Value::Bool(true));
+    }
+}
+
 #[test]
 fn recipe_lock_invalid_recipe_returns_exit_three() {
     let dir = tempdir().expect("temp dir");
@@ -851,6 +1689,294 @@ fn write_exec_script(path: &Path, body: &str) {
         fs::set_permissions(path, fs::Permissions::from_mode(0o755)).expect("chmod");
     }
 }
+#[test]
+fn recipe_lock_records_and_replay_verifies_step_external_tools() {
+    let dir = tempdir().expect("temp dir");
+    let toolchain = FakeToolchain::new("jq-1.7", "yq 4.35.2", "mlr 6.13.0");
+    let rg_bin = write_fake_version_script(dir.path().join("rg"), "ripgrep 14.1.0");
+    let upgraded_rg_bin = write_fake_version_script(dir.path().join("rg-new"), "ripgrep 15.0.0");
+    let recipe_path = dir.path().join("recipe.json");
+    let lock_path = dir.path().join("recipe.lock.json");
+    fs::write(
+        &recipe_path,
+        r#"{"version":"dataq.recipe.v1","steps":[{"kind":"scan_text","args":{"pattern":"TODO"}}]}"#,
+    )
+    .expect("write recipe");
+
+    let lock = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .env("DATAQ_JQ_BIN", &toolchain.jq_bin)
+        .env("DATAQ_YQ_BIN", &toolchain.yq_bin)
+        .env("DATAQ_MLR_BIN", &toolchain.mlr_bin)
+        .env("DATAQ_RG_BIN", &rg_bin)
+        .args(["recipe", "lock", "--file"])
+        .arg(&recipe_path)
+        .arg("--out")
+        .arg(&lock_path)
+        .output()
+        .expect("run lock");
+    assert_eq!(lock.status.code(), Some(0));
+    let lock_json: Value =
+        serde_json::from_slice(&fs::read(&lock_path).expect("read lock")).expect("lock json");
+    assert_eq!(
+        lock_json["tool_versions"],
+        json!({"jq": "jq-1.7", "mlr": "mlr 6.13.0", "rg": "ripgrep 14.1.0", "yq": "yq 4.35.2"})
+    );
+
+    let replay = assert_cmd::cargo::cargo_bin_cmd!("dataq")
+        .env("DATAQ_JQ_BIN", &toolchain.jq_bin)
+        .env("DATAQ_YQ_BIN", &toolchain.yq_bin)
+        .env("DATAQ_MLR_BIN", &toolchain.mlr_bin)
+        .env("DATAQ_RG_BIN", &upgraded_rg_bin)
+        .args(["recipe", "replay", "--strict", "--file"])
+        .arg(&recipe_path)
+        .arg("--lock")
+        .arg(&lock_path)
+        .output()
+        .expect("run replay");
+    assert_eq!(replay.status.code(), Some(2));
+    let summary: Value = serde_json::from_slice(&replay.stdout).expect("summary json");
+    assert_eq!(
+        summary["lock_check"]["mismatches"],
+        json!([{
+            "constraint": "lock.tool_versions.rg",
+            "expected": "ripgrep 14.1.0",
+            "actual": "ripgrep 15.0.0"
+        }])
+    );
+}
+
+#[test]
+fn recipe_replay_reports_input_drift_from_lock_hashes() {
+    let dir = tempdir().expect("temp dir");
+    let toolchain = FakeToolchain::new("jq-1.7", "yq 4.35.2", "mlr 6.13.0");
+    let recipe_path = dir.path().join("recipe.yaml");
+    let lock_path = dir.path().join("recipe.lock.json");
+    fs::write(dir.path().join("input.json"), r#"[{"id":"1"}]"#).expect("write input");
+    fs::write(dir.path().join("rules.json"), r#"{"required_keys":["id"]}"#).expect("write rules");
+    fs::write(
+        &recipe_path,
+        r#"
+version: dataq.recipe.v1
+steps:
+  - kind: canon
+    args:
+      input: input.json
+  - kind: assert
+    args:
+      rules_file: rules.json
+"#,
+    )
+    .expect("write recipe");
+
+    let run_dataq = |args: &[&str]| {
+        assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .env("DATAQ_JQ_BIN", &toolchain.jq_bin)
+            .env("DATAQ_YQ_BIN", &toolchain.yq_bin)
+            .env("DATAQ_MLR_BIN", &toolchain.mlr_bin)
+            .args(args)
+            .output()
+            .expect("run dataq")
+    };
+    let recipe_arg = recipe_path.to_str().expect("utf8 path");
+    let lock_arg = lock_path.to_str().expect("utf8 path");
+
+    let lock_output = run_dataq(&["recipe", "lock", "--file", recipe_arg, "--out", lock_arg]);
+    assert_eq!(lock_output.status.code(), Some(0));
+    let lock_json: Value =
+        serde_json::from_slice(&fs::read(&lock_path).expect("read lock")).expect("lock json");
+    assert!(
+        lock_json["command_graph_hash"]
+            .as_str()
+            .expect("graph hash")
+            .starts_with("sha256:")
+    );
+    let hashes = lock_json["input_hashes"].as_object().expect("input hashes");
+    assert_eq!(
+        hashes.keys().collect::<Vec<_>>(),
+        vec!["input.json", "rules.json"]
+    );
+
+    let replay_args = [
+        "recipe", "replay", "--file", recipe_arg, "--lock", lock_arg, "--strict",
+    ];
+    assert_eq!(run_dataq(&replay_args).status.code(), Some(0));
+
+    fs::write(dir.path().join("input.json"), r#"[{"id":"2"}]"#).expect("rewrite input");
+    let drifted = run_dataq(&replay_args);
+    assert_eq!(drifted.status.code(), Some(2));
+    let summary: Value = serde_json::from_slice(&drifted.stdout).expect("summary json");
+    assert_eq!(summary["lock_check"]["mismatch_count"], Value::from(1));
+    assert_eq!(
+        summary["lock_check"]["mismatches"][0]["constraint"],
+        Value::from("lock.input_hashes.input.json")
+    );
+    assert_eq!(
+        summary["lock_check"]["mismatches"][0]["expected"],
+        hashes["input.json"]
+    );
+}
+
+#[test]
+fn recipe_include_inlines_shared_prelude_and_locks_its_hash() {
+    let dir = tempdir().expect("temp dir");
+    let toolchain = FakeToolchain::new("jq-1.7", "yq 4.35.2", "mlr 6.13.0");
+    fs::create_dir_all(dir.path().join("shared")).expect("create shared dir");
+    fs::write(dir.path().join("input.json"), r#"[{"id":"1"}]"#).expect("write input");
+    let prelude = r#"
+version: dataq.recipe.v1
+params:
+  source:
+    type: string
+steps:
+  - kind: canon
+    args:
+      input: "${params.source}"
+  - kind: assert
+    args:
+      rules:
+        required_keys: [id]
+"#;
+    fs::write(dir.path().join("shared/prelude.yaml"), prelude).expect("write prelude");
+    let recipe_path = dir.path().join("recipe.yaml");
+    let lock_path = dir.path().join("recipe.lock.json");
+    fs::write(
+        &recipe_path,
+        r#"
+version: dataq.recipe.v1
+steps:
+  - kind: include
+    args:
+      path: shared/prelude.yaml
+      params:
+        source: input.json
+  - kind: profile
+    args: {}
+"#,
+    )
+    .expect("write recipe");
+
+    let run_dataq = |args: &[&str]| {
+        assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .env("DATAQ_JQ_BIN", &toolchain.jq_bin)
+            .env("DATAQ_YQ_BIN", &toolchain.yq_bin)
+            .env("DATAQ_MLR_BIN", &toolchain.mlr_bin)
+            .args(args)
+            .output()
+            .expect("run dataq")
+    };
+    let recipe_arg = recipe_path.to_str().expect("utf8 path");
+    let lock_arg = lock_path.to_str().expect("utf8 path");
+
+    let run = run_dataq(&["recipe", "run", "--file", recipe_arg]);
+    assert_eq!(run.status.code(), Some(0));
+    let summary: Value = serde_json::from_slice(&run.stdout).expect("summary json");
+    let kinds: Vec<&str> = summary["steps"]
+        .as_array()
+        .expect("steps")
+        .iter()
+        .map(|step| step["kind"].as_str().expect("kind"))
+        .collect();
+    assert_eq!(kinds, vec!["canon", "assert", "profile"]);
+
+    let lock_output = run_dataq(&["recipe", "lock", "--file", recipe_arg, "--out", lock_arg]);
+    assert_eq!(lock_output.status.code(), Some(0));
+    let lock_json: Value =
+        serde_json::from_slice(&fs::read(&lock_path).expect("read lock")).expect("lock json");
+    let hashes = lock_json["input_hashes"].as_object().expect("input hashes");
+    assert_eq!(
+        hashes.keys().collect::<Vec<_>>(),
+        vec!["input.json", "shared/prelude.yaml"]
+    );
+
+    fs::write(
+        dir.path().join("shared/prelude.yaml"),
+        format!("# reviewed\n{prelude}"),
+    )
+    .expect("rewrite prelude");
+    let replay = run_dataq(&[
+        "recipe", "replay", "--file", recipe_arg, "--lock", lock_arg, "--strict",
+    ]);
+    assert_eq!(replay.status.code(), Some(2));
+    let summary: Value = serde_json::from_slice(&replay.stdout).expect("summary json");
+    assert_eq!(summary["lock_check"]["mismatch_count"], Value::from(1));
+    assert_eq!(
+        summary["lock_check"]["mismatches"][0]["constraint"],
+        Value::from("lock.input_hashes.shared/prelude.yaml")
+    );
+}
+
+#[test]
+fn recipe_lock_hashes_rules_extends_chain() {
+    let dir = tempdir().expect("temp dir");
+    let toolchain = FakeToolchain::new("jq-1.7", "yq 4.35.2", "mlr 6.13.0");
+    fs::create_dir_all(dir.path().join("rules")).expect("create rules dir");
+    fs::write(dir.path().join("input.json"), r#"[{"id":"1"}]"#).expect("write input");
+    fs::write(
+        dir.path().join("rules/base.json"),
+        r#"{"required_keys":["id"]}"#,
+    )
+    .expect("write base rules");
+    fs::write(
+        dir.path().join("rules/policy.json"),
+        r#"{"extends":"base.json"}"#,
+    )
+    .expect("write rules");
+    let recipe_path = dir.path().join("recipe.yaml");
+    let lock_path = dir.path().join("recipe.lock.json");
+    fs::write(
+        &recipe_path,
+        r#"
+version: dataq.recipe.v1
+steps:
+  - kind: canon
+    args:
+      input: input.json
+  - kind: assert
+    args:
+      rules_file: rules/policy.json
+"#,
+    )
+    .expect("write recipe");
+
+    let run_dataq = |args: &[&str]| {
+        assert_cmd::cargo::cargo_bin_cmd!("dataq")
+            .env("DATAQ_JQ_BIN", &toolchain.jq_bin)
+            .env("DATAQ_YQ_BIN", &toolchain.yq_bin)
+            .env("DATAQ_MLR_BIN", &toolchain.mlr_bin)
+            .args(args)
+            .output()
+            .expect("run dataq")
+    };
+    let recipe_arg = recipe_path.to_str().expect("utf8 path");
+    let lock_arg = lock_path.to_str().expect("utf8 path");
+
+    let lock_output = run_dataq(&["recipe", "lock", "--file", recipe_arg, "--out", lock_arg]);
+    assert_eq!(lock_output.status.code(), Some(0));
+    let lock_json: Value =
+        serde_json::from_slice(&fs::read(&lock_path).expect("read lock")).expect("lock json");
+    let hashes = lock_json["input_hashes"].as_object().expect("input hashes");
+    assert_eq!(
+        hashes.keys().collect::<Vec<_>>(),
+        vec!["input.json", "rules/base.json", "rules/policy.json"]
+    );
+
+    fs::write(
+        dir.path().join("rules/base.json"),
+        r#"{"required_keys":["id","name"]}"#,
+    )
+    .expect("rewrite base rules");
+    let replay = run_dataq(&[
+        "recipe", "replay", "--file", recipe_arg, "--lock", lock_arg, "--strict",
+    ]);
+    assert_eq!(replay.status.code(), Some(2));
+    let summary: Value = serde_json::from_slice(&replay.stdout).expect("summary json");
+    assert_eq!(summary["lock_check"]["mismatch_count"], Value::from(1));
+    assert_eq!(
+        summary["lock_check"]["mismatches"][0]["constraint"],
+        Value::from("lock.input_hashes.rules/base.json")
+    );
+}
+
 #[test]
 fn recipe_replay_matching_tool_versions_with_strict_succeeds() {
     let dir = tempdir().expect("temp dir");
@@ -26,12 +26,18 @@ mod ingest_doc_roundtrip;
 mod ingest_notes_flow;
 #[path = "integration/ingest_yaml_jobs_flow.rs"]
 mod ingest_yaml_jobs_flow;
+#[path = "integration/io_columnar_roundtrip.rs"]
+mod io_columnar_roundtrip;
+#[path = "integration/io_compression.rs"]
+mod io_compression;
 #[path = "integration/io_format_resolution.rs"]
 mod io_format_resolution;
 #[path = "integration/io_roundtrip.rs"]
 mod io_roundtrip;
 #[path = "integration/mcp_flow.rs"]
 mod mcp_flow;
+#[path = "integration/mcp_http_flow.rs"]
+mod mcp_http_flow;
 #[path = "integration/merge_flow.rs"]
 mod merge_flow;
 #[path = "integration/profile_determinism.rs"]
@@ -5,6 +5,7 @@ use std::thread;
 use std::time::Duration;
 
 use dataq::cmd::canon::{CanonCommandOptions, run};
+use dataq::engine::canon::coerce::CoercePolicy;
 use dataq::io::Format;
 use predicates::prelude::predicate;
 
@@ -36,6 +37,7 @@ fn canon_flow_is_idempotent() {
     let options = CanonCommandOptions {
         sort_keys: true,
         normalize_time: true,
+        ..CanonCommandOptions::default()
     };
 
     let mut first = Vec::new();
@@ -44,7 +46,7 @@ fn canon_flow_is_idempotent() {
         &mut first,
         Format::Json,
         Format::Json,
-        options,
+        options.clone(),
     )
     .expect("first flow should succeed");
 
@@ -74,6 +76,7 @@ fn canon_flow_preserves_fractional_seconds_and_precision_sensitive_numbers() {
         CanonCommandOptions {
             sort_keys: true,
             normalize_time: true,
+            ..CanonCommandOptions::default()
         },
     )
     .expect("canon flow should succeed");
@@ -188,3 +191,23 @@ fn canon_cli_stdin_autodetect_failure_returns_exit_three() {
             "could not autodetect stdin input format",
         ));
 }
+
+#[test]
+fn canon_flow_with_coercion_disabled_preserves_strings() {
+    let input = br#"{"id":"007","ok":"false"}"#;
+    let mut output = Vec::new();
+    run(
+        Cursor::new(input),
+        &mut output,
+        Format::Json,
+        Format::Json,
+        CanonCommandOptions {
+            coerce: CoercePolicy::none(),
+            ..CanonCommandOptions::default()
+        },
+    )
+    .expect("canon flow should succeed");
+
+    let out = String::from_utf8(output).expect("utf8 output");
+    assert_eq!(out, r#"{"id":"007","ok":"false"}"#);
+}
@@ -0,0 +1,134 @@
+#![cfg(feature = "columnar")]
+
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::{
+    BinaryArray, Date32Array, Decimal128Array, Float32Array, RecordBatch, StringArray,
+    TimestampMillisecondArray,
+};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use dataq::io::reader::read_values;
+use dataq::io::writer::write_values;
+use dataq::io::{Format, autodetect_stdin_input_format, resolve_input_format};
+use parquet::arrow::ArrowWriter;
+use serde_json::json;
+
+#[test]
+fn parquet_roundtrip_for_object_rows() {
+    let values = vec![
+        json!({"active": true, "id": 1, "score": 1.5, "tags": ["a", "b"], "user": {"name": "x"}}),
+        json!({"active": false, "id": 2, "score": null, "tags": [], "user": {"name": "y"}}),
+    ];
+    let mut out = Vec::new();
+    write_values(&mut out, Format::Parquet, &values).expect("write parquet");
+    let read_back = read_values(Cursor::new(out), Format::Parquet).expect("read parquet");
+    assert_eq!(read_back, values);
+}
+
+#[test]
+fn arrow_ipc_roundtrip_fills_missing_fields_with_null() {
+    let values = vec![json!({"b": "x", "a": 1}), json!({"a": 2})];
+    let mut out = Vec::new();
+    write_values(&mut out, Format::ArrowIpc, &values).expect("write arrow");
+    let read_back = read_values(Cursor::new(out), Format::ArrowIpc).expect("read arrow");
+    assert_eq!(
+        read_back,
+        vec![json!({"a": 1, "b": "x"}), json!({"a": 2, "b": null})]
+    );
+}
+
+#[test]
+fn columnar_writer_rejects_non_object_rows() {
+    let err = write_values(Vec::new(), Format::Parquet, &[json!(1)]).expect_err("must fail");
+    assert_eq!(err.to_string(), "columnar row 0 must be an object");
+}
+
+#[test]
+fn parquet_logical_types_map_to_fixed_json_forms() {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new(
+            "at",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("+09:00".into())),
+            true,
+        ),
+        Field::new("day", DataType::Date32, true),
+        Field::new("amount", DataType::Decimal128(10, 2), true),
+        Field::new("ratio", DataType::Float32, true),
+        Field::new("blob", DataType::Binary, true),
+        Field::new("note", DataType::Utf8, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        Arc::clone(&schema),
+        vec![
+            Arc::new(
+                TimestampMillisecondArray::from(vec![Some(1_704_067_200_123), None])
+                    .with_timezone("+09:00"),
+            ),
+            Arc::new(Date32Array::from(vec![Some(19_723), None])),
+            Arc::new(
+                Decimal128Array::from(vec![Some(12_345), Some(-5)])
+                    .with_precision_and_scale(10, 2)
+                    .expect("decimal"),
+            ),
+            Arc::new(Float32Array::from(vec![Some(0.1), Some(f32::NAN)])),
+            Arc::new(BinaryArray::from(vec![Some(&[0xde, 0xad][..]), None])),
+            Arc::new(StringArray::from(vec![Some("01234"), None])),
+        ],
+    )
+    .expect("batch");
+    let mut bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut bytes, schema, None).expect("writer");
+    writer.write(&batch).expect("write batch");
+    writer.close().expect("close");
+
+    let values = read_values(Cursor::new(bytes), Format::Parquet).expect("read parquet");
+    assert_eq!(
+        values,
+        vec![
+            json!({
+                "at": "2024-01-01T00:00:00.123Z",
+                "day": "2024-01-01",
+                "amount": "123.45",
+                "ratio": 0.1,
+                "blob": "dead",
+                "note": "01234"
+            }),
+            json!({
+                "at": null,
+                "day": null,
+                "amount": "-0.05",
+                "ratio": "NaN",
+                "blob": null,
+                "note": null
+            }),
+        ]
+    );
+}
+
+#[test]
+fn columnar_formats_resolve_from_extension_and_magic_bytes() {
+    assert_eq!(
+        resolve_input_format(None, Some(Path::new("snapshot.parquet"))).expect("parquet"),
+        Format::Parquet
+    );
+    assert_eq!(
+        resolve_input_format(None, Some(Path::new("snapshot.arrow"))).expect("arrow"),
+        Format::ArrowIpc
+    );
+
+    let mut parquet = Vec::new();
+    write_values(&mut parquet, Format::Parquet, &[json!({"a": 1})]).expect("write parquet");
+    assert_eq!(
+        autodetect_stdin_input_format(&parquet).expect("detect parquet"),
+        Format::Parquet
+    );
+
+    let mut arrow = Vec::new();
+    write_values(&mut arrow, Format::ArrowIpc, &[json!({"a": 1})]).expect("write arrow");
+    assert_eq!(
+        autodetect_stdin_input_format(&arrow).expect("detect arrow"),
+        Format::ArrowIpc
+    );
+}
@@ -0,0 +1,81 @@
+use std::io::Cursor;
+use std::path::Path;
+
+use dataq::io::reader::{read_jsonl_stream, read_values};
+use dataq::io::writer::write_values_compressed;
+use dataq::io::{
+    Compression, Format, autodetect_stdin_input_format, resolve_input_format,
+    resolve_output_compression,
+};
+use serde_json::json;
+
+#[test]
+fn compound_extensions_resolve_format_and_compression() {
+    assert_eq!(
+        resolve_input_format(None, Some(Path::new("events.jsonl.gz"))).expect("jsonl.gz"),
+        Format::Jsonl
+    );
+    assert_eq!(
+        resolve_input_format(None, Some(Path::new("export.json.zst"))).expect("json.zst"),
+        Format::Json
+    );
+    assert_eq!(
+        resolve_output_compression(Some(Path::new("out.csv.gz"))),
+        Compression::Gzip
+    );
+    assert_eq!(
+        resolve_output_compression(Some(Path::new("out.yaml.zst"))),
+        Compression::Zstd
+    );
+    assert_eq!(
+        resolve_output_compression(Some(Path::new("out.json"))),
+        Compression::None
+    );
+    assert!(resolve_input_format(None, Some(Path::new("archive.gz"))).is_err());
+}
+
+#[test]
+fn compressed_roundtrip_for_gzip_and_zstd() {
+    let values = vec![json!({"a": 1}), json!({"a": 2})];
+    for compression in [Compression::Gzip, Compression::Zstd] {
+        let mut out = Vec::new();
+        write_values_compressed(&mut out, Format::Jsonl, compression, &values)
+            .expect("write compressed");
+        assert_eq!(Compression::detect(&out), compression);
+        let read_back = read_values(Cursor::new(out), Format::Jsonl).expect("read compressed");
+        assert_eq!(read_back, values);
+    }
+}
+
+#[test]
+fn gzip_output_is_byte_stable() {
+    let values = vec![json!({"id": "1"})];
+    let mut first = Vec::new();
+    let mut second = Vec::new();
+    write_values_compressed(&mut first, Format::Json, Compression::Gzip, &values)
+        .expect("first write");
+    write_values_compressed(&mut second, Format::Json, Compression::Gzip, &values)
+        .expect("second write");
+    assert_eq!(first, second);
+}
+
+#[test]
+fn autodetect_and_stream_read_look_through_compression() {
+    let values = vec![json!({"id": "2"}), json!({"id": "1"})];
+    let mut compressed = Vec::new();
+    write_values_compressed(&mut compressed, Format::Jsonl, Compression::Zstd, &values)
+        .expect("write zstd");
+
+    assert_eq!(
+        autodetect_stdin_input_format(&compressed).expect("autodetect"),
+        Format::Jsonl
+    );
+
+    let mut streamed = Vec::new();
+    read_jsonl_stream(Cursor::new(compressed), |value| {
+        streamed.push(value);
+        Ok::<(), ()>(())
+    })
+    .expect("stream read");
+    assert_eq!(streamed, values);
+}
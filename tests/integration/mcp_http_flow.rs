@@ -0,0 +1,227 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+
+use dataq::cmd::mcp::{self, McpServerOptions};
+use serde_json::{Value, json};
+
+fn start_server() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind local listener");
+    let addr = listener.local_addr().expect("local addr");
+    std::thread::spawn(move || mcp::serve_http(listener, McpServerOptions::default()));
+    addr
+}
+
+fn send(addr: SocketAddr, raw_request: &str) -> (u16, String, String) {
+    let mut stream = TcpStream::connect(addr).expect("connect");
+    stream
+        .write_all(raw_request.as_bytes())
+        .expect("write request");
+    let mut raw = String::new();
+    stream.read_to_string(&mut raw).expect("read response");
+
+    let (head, body) = raw.split_once("\r\n\r\n").expect("http head/body");
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .expect("status code");
+    (status, head.to_string(), body.to_string())
+}
+
+fn post(addr: SocketAddr, accept: &str, body: &Value) -> (u16, String, String) {
+    let body = body.to_string();
+    send(
+        addr,
+        &format!(
+            "POST /mcp HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\nAccept: {accept}\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        ),
+    )
+}
+
+#[test]
+fn mcp_http_transport_serves_json_and_event_stream_responses() {
+    let addr = start_server();
+    let both = "application/json, text/event-stream";
+
+    let (status, head, body) = post(
+        addr,
+        both,
+        &json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+    );
+    assert_eq!(status, 200);
+    assert!(head.contains("Content-Type: application/json"));
+    let response: Value = serde_json::from_str(&body).expect("json body");
+    assert_eq!(response["result"]["serverInfo"]["name"], json!("dataq"));
+
+    let (status, _, body) = post(
+        addr,
+        both,
+        &json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+    );
+    assert_eq!((status, body.as_str()), (202, ""));
+
+    let (status, head, body) = post(
+        addr,
+        "text/event-stream",
+        &json!([{
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {"name": "dataq.canon", "arguments": {"input": [{"b": 1, "a": 2}]}}
+        }]),
+    );
+    assert_eq!(status, 200);
+    assert!(head.contains("Content-Type: text/event-stream"));
+    let data = body
+        .strip_prefix("event: message\ndata: ")
+        .and_then(|rest| rest.strip_suffix("\n\n"))
+        .expect("single sse event");
+    let batch: Value = serde_json::from_str(data).expect("sse data json");
+    assert_eq!(batch[0]["id"], json!(2));
+    assert_eq!(
+        batch[0]["result"]["structuredContent"]["payload"],
+        json!({"a": 2, "b": 1})
+    );
+}
+
+#[test]
+fn mcp_http_transport_rejects_bad_routes_origins_and_bodies() {
+    let addr = start_server();
+
+    let (status, _, _) = send(addr, "GET /mcp HTTP/1.1\r\nHost: x\r\n\r\n");
+    assert_eq!(status, 405);
+    let (status, _, _) = send(addr, "POST /other HTTP/1.1\r\nContent-Length: 0\r\n\r\n");
+    assert_eq!(status, 404);
+    let (status, _, _) = send(
+        addr,
+        "POST /mcp HTTP/1.1\r\nOrigin: http://evil.example\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n{}",
+    );
+    assert_eq!(status, 403);
+    let (status, _, _) = send(
+        addr,
+        "POST /mcp HTTP/1.1\r\nContent-Type: text/plain\r\nContent-Length: 2\r\n\r\n{}",
+    );
+    assert_eq!(status, 415);
+
+    let (status, _, body) = send(
+        addr,
+        "POST /mcp HTTP/1.1\r\nOrigin: http://localhost:3000\r\nContent-Type: application/json\r\nContent-Length: 1\r\n\r\n{",
+    );
+    assert_eq!(status, 400);
+    let response: Value = serde_json::from_str(&body).expect("parse error body");
+    assert_eq!(response["error"]["code"], json!(-32700));
+}
+
+#[test]
+fn mcp_http_transport_caps_concurrent_connections() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind local listener");
+    let addr = listener.local_addr().expect("local addr");
+    std::thread::spawn(move || {
+        mcp::serve_http(
+            listener,
+            McpServerOptions::default().with_http_max_connections(1),
+        )
+    });
+
+    let idle = TcpStream::connect(addr).expect("connect idle client");
+    let read_rejected = || {
+        let mut stream = TcpStream::connect(addr).expect("connect");
+        let mut raw = String::new();
+        stream.read_to_string(&mut raw).expect("read response");
+        raw
+    };
+    let rejected = read_rejected();
+    assert!(rejected.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+    assert!(rejected.contains("Retry-After: 1\r\n"));
+
+    drop(idle);
+    // The freed slot is released asynchronously; a rejected attempt may also
+    // see a reset because its request was never read.
+    let body = json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}).to_string();
+    let request = format!(
+        "POST /mcp HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    );
+    let served = (0..50).any(|_| {
+        let mut stream = TcpStream::connect(addr).expect("connect");
+        let mut raw = String::new();
+        let ok = stream.write_all(request.as_bytes()).is_ok()
+            && stream.read_to_string(&mut raw).is_ok()
+            && raw.starts_with("HTTP/1.1 200 OK\r\n");
+        if !ok {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        ok
+    });
+    assert!(served, "a freed connection slot must be reused");
+}
+
+#[test]
+fn mcp_http_transport_streams_progress_events_for_event_stream_clients() {
+    let addr = start_server();
+    let dir = tempfile::tempdir().expect("tempdir");
+    let input = dir.path().join("input.json");
+    std::fs::write(&input, r#"[{"b":1,"a":2}]"#).expect("write input");
+    let call = json!({
+        "jsonrpc": "2.0",
+        "id": 5,
+        "method": "tools/call",
+        "params": {
+            "name": "dataq.recipe.run",
+            "arguments": {"recipe": {
+                "version": "dataq.recipe.v1",
+                "steps": [
+                    {"kind": "canon", "args": {"input": input.display().to_string()}},
+                    {"kind": "profile", "args": {}}
+                ]
+            }},
+            "_meta": {"progressToken": "run-5"}
+        }
+    });
+
+    let (status, head, body) = post(addr, "application/json, text/event-stream", &call);
+    assert_eq!(status, 200);
+    assert!(head.contains("Content-Type: text/event-stream"));
+    assert!(!head.contains("Content-Length"));
+    let events: Vec<Value> = body
+        .split_terminator("\n\n")
+        .map(|event| {
+            let data = event
+                .strip_prefix("event: message\ndata: ")
+                .expect("sse message event");
+            serde_json::from_str(data).expect("sse data json")
+        })
+        .collect();
+    assert_eq!(events.len(), 3);
+    assert_eq!(
+        events[..2]
+            .iter()
+            .map(|event| (event["method"].clone(), event["params"].clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                json!("notifications/progress"),
+                json!({"progressToken": "run-5", "progress": 1, "message": "execute_step_0_canon"})
+            ),
+            (
+                json!("notifications/progress"),
+                json!({"progressToken": "run-5", "progress": 2, "message": "execute_step_1_profile"})
+            ),
+        ]
+    );
+    assert_eq!(events[2]["id"], json!(5));
+    assert_eq!(events[2]["result"]["isError"], json!(false));
+
+    // Without a progress token the same call is answered with one JSON body.
+    let mut plain = call.clone();
+    plain["params"]
+        .as_object_mut()
+        .expect("params object")
+        .remove("_meta");
+    let (status, head, body) = post(addr, "application/json, text/event-stream", &plain);
+    assert_eq!(status, 200);
+    assert!(head.contains("Content-Type: application/json"));
+    let response: Value = serde_json::from_str(&body).expect("json body");
+    assert_eq!(response["id"], json!(5));
+}